@@ -0,0 +1,14 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CreatedCommit } from "./CreatedCommit";
+
+export type CommitsCreatedEvent = { 
+/**
+ * Commits created before any failure, oldest first.
+ */
+commits: Array<CreatedCommit>, 
+/**
+ * Why the remaining commits were not created, e.g. a rejecting hook.
+ */
+error?: string, };
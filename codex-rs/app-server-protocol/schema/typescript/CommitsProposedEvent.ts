@@ -0,0 +1,15 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProposedCommit } from "./ProposedCommit";
+
+export type CommitsProposedEvent = { 
+/**
+ * Commits in the order they should be created. Empty when there is
+ * nothing to commit.
+ */
+commits: Array<ProposedCommit>, 
+/**
+ * Changed paths that no proposed commit includes.
+ */
+unassigned_paths: Array<string>, };
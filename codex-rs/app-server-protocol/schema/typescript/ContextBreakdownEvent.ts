@@ -0,0 +1,13 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContextItemSummary } from "./ContextItemSummary";
+
+/**
+ * Response payload for `Op::InspectContext`.
+ */
+export type ContextBreakdownEvent = { 
+/**
+ * Items in prompt order.
+ */
+items: Array<ContextItemSummary>, estimated_total_tokens: number, model_context_window: number | null, };
@@ -0,0 +1,5 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ContextItemCategory = "base_instructions" | "user_instructions" | "skill" | "path_instructions" | "session_context" | "user_message" | "assistant_message" | "developer_message" | "tool_call" | "tool_output" | "image" | "reasoning" | "compaction";
@@ -0,0 +1,15 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContextItemCategory } from "./ContextItemCategory";
+
+export type ContextItemSummary = { 
+/**
+ * Id to pass to `Op::PruneContextItems`. `None` for items that cannot be
+ * pruned, such as the base instructions.
+ */
+id: string | null, category: ContextItemCategory, 
+/**
+ * Short description: a tool name, the first line of a message, etc.
+ */
+label: string, estimated_tokens: number, };
@@ -0,0 +1,9 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ModelFallbackEvent = { from_model_provider: string, from_model: string, model_provider: string, model: string, 
+/**
+ * Error from the previous provider that triggered the switch.
+ */
+reason: string, };
@@ -0,0 +1,16 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One commit in a plan drafted by `Op::ProposeCommits`.
+ */
+export type ProposedCommit = { 
+/**
+ * Full commit message: subject line, then an optional body.
+ */
+message: string, 
+/**
+ * Paths relative to the repository root.
+ */
+paths: Array<string>, };
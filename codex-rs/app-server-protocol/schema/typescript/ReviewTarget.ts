@@ -6,4 +6,9 @@ export type ReviewTarget = { "type": "uncommittedChanges" } | { "type": "baseBra
 /**
  * Optional human-readable label (e.g., commit subject) for UIs.
  */
-title: string | null, } | { "type": "custom", instructions: string, };
+title: string | null, } | { "type": "commitRange", base: string, head: string, 
+/**
+ * Review each commit on its own and tag findings with its SHA,
+ * instead of reviewing the combined diff.
+ */
+perCommit: boolean, } | { "type": "custom", instructions: string, };
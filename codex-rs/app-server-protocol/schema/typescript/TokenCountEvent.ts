@@ -4,4 +4,10 @@
 import type { RateLimitSnapshot } from "./RateLimitSnapshot";
 import type { TokenUsageInfo } from "./TokenUsageInfo";
 
-export type TokenCountEvent = { info: TokenUsageInfo | null, rate_limits: RateLimitSnapshot | null, };
+export type TokenCountEvent = { info: TokenUsageInfo | null, rate_limits: RateLimitSnapshot | null, 
+/**
+ * Tokens the next request is expected to send, counted locally with the
+ * model's tokenizer: the last reported usage plus items recorded since, or
+ * the whole history when the provider has not reported usage.
+ */
+context_tokens?: bigint, };
@@ -0,0 +1,13 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ToolOutputsAgedEvent = { 
+/**
+ * Call ids whose outputs were replaced with stubs, oldest first.
+ */
+call_ids: Array<string>, 
+/**
+ * Estimated number of tokens freed.
+ */
+estimated_tokens_saved: number, };
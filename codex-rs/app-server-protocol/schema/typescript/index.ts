@@ -41,11 +41,19 @@ export type { CollabWaitingBeginEvent } from "./CollabWaitingBeginEvent";
 export type { CollabWaitingEndEvent } from "./CollabWaitingEndEvent";
 export type { CollaborationMode } from "./CollaborationMode";
 export type { CollaborationModeMask } from "./CollaborationModeMask";
+export type { CommitsCreatedEvent } from "./CommitsCreatedEvent";
+export type { CommitsProposedEvent } from "./CommitsProposedEvent";
 export type { ContentItem } from "./ContentItem";
+export type { ContextBreakdownEvent } from "./ContextBreakdownEvent";
 export type { ContextCompactedEvent } from "./ContextCompactedEvent";
 export type { ContextCompactionItem } from "./ContextCompactionItem";
+export type { ContextItemCategory } from "./ContextItemCategory";
+export type { ContextItemSummary } from "./ContextItemSummary";
+export type { ContextPruneAction } from "./ContextPruneAction";
+export type { ContextPrunedEvent } from "./ContextPrunedEvent";
 export type { ConversationGitInfo } from "./ConversationGitInfo";
 export type { ConversationSummary } from "./ConversationSummary";
+export type { CreatedCommit } from "./CreatedCommit";
 export type { CreditsSnapshot } from "./CreditsSnapshot";
 export type { CustomPrompt } from "./CustomPrompt";
 export type { DeprecationNoticeEvent } from "./DeprecationNoticeEvent";
@@ -119,6 +127,7 @@ export type { McpToolCallBeginEvent } from "./McpToolCallBeginEvent";
 export type { McpToolCallEndEvent } from "./McpToolCallEndEvent";
 export type { MessagePhase } from "./MessagePhase";
 export type { ModeKind } from "./ModeKind";
+export type { ModelFallbackEvent } from "./ModelFallbackEvent";
 export type { NetworkAccess } from "./NetworkAccess";
 export type { NewConversationParams } from "./NewConversationParams";
 export type { NewConversationResponse } from "./NewConversationResponse";
@@ -131,6 +140,7 @@ export type { PlanItem } from "./PlanItem";
 export type { PlanItemArg } from "./PlanItemArg";
 export type { PlanType } from "./PlanType";
 export type { Profile } from "./Profile";
+export type { ProposedCommit } from "./ProposedCommit";
 export type { RateLimitSnapshot } from "./RateLimitSnapshot";
 export type { RateLimitWindow } from "./RateLimitWindow";
 export type { RawResponseItemEvent } from "./RawResponseItemEvent";
@@ -195,7 +205,9 @@ export type { TokenCountEvent } from "./TokenCountEvent";
 export type { TokenUsage } from "./TokenUsage";
 export type { TokenUsageInfo } from "./TokenUsageInfo";
 export type { Tool } from "./Tool";
+export type { ToolOutputsAgedEvent } from "./ToolOutputsAgedEvent";
 export type { Tools } from "./Tools";
+export type { TouchedPathsUpdatedEvent } from "./TouchedPathsUpdatedEvent";
 export type { TurnAbortReason } from "./TurnAbortReason";
 export type { TurnAbortedEvent } from "./TurnAbortedEvent";
 export type { TurnCompleteEvent } from "./TurnCompleteEvent";
@@ -0,0 +1,10 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContextItemCategory } from "./ContextItemCategory";
+
+export type ThreadContextItem = { 
+/**
+ * Id to pass to `thread/context/prune`; null for items that cannot be pruned.
+ */
+id: string | null, category: ContextItemCategory, label: string, estimatedTokens: number, };
@@ -0,0 +1,11 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContextPruneAction } from "./ContextPruneAction";
+
+export type ThreadContextPruneParams = { threadId: string, 
+/**
+ * Ids from `thread/context/read`. The request fails without changing anything if any
+ * id is unknown.
+ */
+ids: Array<string>, action: ContextPruneAction, };
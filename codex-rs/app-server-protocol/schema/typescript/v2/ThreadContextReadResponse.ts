@@ -0,0 +1,10 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ThreadContextItem } from "./ThreadContextItem";
+
+export type ThreadContextReadResponse = { 
+/**
+ * Items in prompt order, starting with the base instructions.
+ */
+items: Array<ThreadContextItem>, estimatedTotalTokens: number, modelContextWindow: number | null, };
@@ -2,5 +2,10 @@
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Thread } from "./Thread";
+import type { ThreadPlan } from "./ThreadPlan";
 
-export type ThreadReadResponse = { thread: Thread, };
+export type ThreadReadResponse = { thread: Thread, 
+/**
+ * Latest checklist the agent recorded with `update_plan`, if any.
+ */
+plan: ThreadPlan | null, };
@@ -44,6 +44,8 @@ export type { ConfigValueWriteParams } from "./ConfigValueWriteParams";
 export type { ConfigWarningNotification } from "./ConfigWarningNotification";
 export type { ConfigWriteResponse } from "./ConfigWriteResponse";
 export type { ContextCompactedNotification } from "./ContextCompactedNotification";
+export type { ContextItemCategory } from "./ContextItemCategory";
+export type { ContextPruneAction } from "./ContextPruneAction";
 export type { CreditsSnapshot } from "./CreditsSnapshot";
 export type { DeprecationNoticeNotification } from "./DeprecationNoticeNotification";
 export type { DynamicToolCallParams } from "./DynamicToolCallParams";
@@ -128,6 +130,11 @@ export type { TextRange } from "./TextRange";
 export type { Thread } from "./Thread";
 export type { ThreadArchiveParams } from "./ThreadArchiveParams";
 export type { ThreadArchiveResponse } from "./ThreadArchiveResponse";
+export type { ThreadContextItem } from "./ThreadContextItem";
+export type { ThreadContextPruneParams } from "./ThreadContextPruneParams";
+export type { ThreadContextPruneResponse } from "./ThreadContextPruneResponse";
+export type { ThreadContextReadParams } from "./ThreadContextReadParams";
+export type { ThreadContextReadResponse } from "./ThreadContextReadResponse";
 export type { ThreadForkParams } from "./ThreadForkParams";
 export type { ThreadForkResponse } from "./ThreadForkResponse";
 export type { ThreadItem } from "./ThreadItem";
@@ -136,6 +143,7 @@ export type { ThreadListResponse } from "./ThreadListResponse";
 export type { ThreadLoadedListParams } from "./ThreadLoadedListParams";
 export type { ThreadLoadedListResponse } from "./ThreadLoadedListResponse";
 export type { ThreadNameUpdatedNotification } from "./ThreadNameUpdatedNotification";
+export type { ThreadPlan } from "./ThreadPlan";
 export type { ThreadReadParams } from "./ThreadReadParams";
 export type { ThreadReadResponse } from "./ThreadReadResponse";
 export type { ThreadResumeParams } from "./ThreadResumeParams";
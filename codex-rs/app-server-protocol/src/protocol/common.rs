@@ -212,6 +212,14 @@ client_request_definitions! {
         params: v2::ThreadRollbackParams,
         response: v2::ThreadRollbackResponse,
     },
+    ThreadContextRead => "thread/context/read" {
+        params: v2::ThreadContextReadParams,
+        response: v2::ThreadContextReadResponse,
+    },
+    ThreadContextPrune => "thread/context/prune" {
+        params: v2::ThreadContextPruneParams,
+        response: v2::ThreadContextPruneResponse,
+    },
     ThreadList => "thread/list" {
         params: v2::ThreadListParams,
         response: v2::ThreadListResponse,
@@ -25,6 +25,7 @@ use codex_protocol::openai_models::default_input_modalities;
 use codex_protocol::parse_command::ParsedCommand as CoreParsedCommand;
 use codex_protocol::plan_tool::PlanItemArg as CorePlanItemArg;
 use codex_protocol::plan_tool::StepStatus as CorePlanStepStatus;
+use codex_protocol::plan_tool::UpdatePlanArgs as CoreUpdatePlanArgs;
 use codex_protocol::protocol::AgentStatus as CoreAgentStatus;
 use codex_protocol::protocol::AskForApproval as CoreAskForApproval;
 use codex_protocol::protocol::CodexErrorInfo as CoreCodexErrorInfo;
@@ -226,6 +227,30 @@ v2_enum_from_core!(
     }
 );
 
+v2_enum_from_core!(
+    pub enum ContextItemCategory from codex_protocol::protocol::ContextItemCategory {
+        BaseInstructions,
+        UserInstructions,
+        Skill,
+        PathInstructions,
+        SessionContext,
+        UserMessage,
+        AssistantMessage,
+        DeveloperMessage,
+        ToolCall,
+        ToolOutput,
+        Image,
+        Reasoning,
+        Compaction
+    }
+);
+
+v2_enum_from_core!(
+    pub enum ContextPruneAction from codex_protocol::protocol::ContextPruneAction {
+        Drop, Truncate
+    }
+);
+
 v2_enum_from_core!(
     pub enum McpAuthStatus from codex_protocol::protocol::McpAuthStatus {
         Unsupported,
@@ -269,6 +294,17 @@ pub enum ConfigLayerSource {
         file: AbsolutePathBuf,
     },
 
+    /// A `[[conditional]]` block from the user config.toml whose conditions
+    /// matched the session's working directory.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    Conditional {
+        /// The user config.toml that declares the block.
+        file: AbsolutePathBuf,
+        /// Zero-based position of the block among the `[[conditional]]` entries.
+        index: u32,
+    },
+
     /// Path to a .codex/ folder within a project. There could be multiple of
     /// these between `cwd` and the project/repo root.
     #[serde(rename_all = "camelCase")]
@@ -301,6 +337,7 @@ impl ConfigLayerSource {
             ConfigLayerSource::Mdm { .. } => 0,
             ConfigLayerSource::System { .. } => 10,
             ConfigLayerSource::User { .. } => 20,
+            ConfigLayerSource::Conditional { .. } => 22,
             ConfigLayerSource::Project { .. } => 25,
             ConfigLayerSource::SessionFlags => 30,
             ConfigLayerSource::LegacyManagedConfigTomlFromFile { .. } => 40,
@@ -1432,6 +1469,64 @@ pub struct ThreadRollbackResponse {
     pub thread: Thread,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextReadParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextReadResponse {
+    /// Items in prompt order, starting with the base instructions.
+    pub items: Vec<ThreadContextItem>,
+    #[ts(type = "number")]
+    pub estimated_total_tokens: i64,
+    #[ts(type = "number | null")]
+    pub model_context_window: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextItem {
+    /// Id to pass to `thread/context/prune`; null for items that cannot be pruned.
+    pub id: Option<String>,
+    pub category: ContextItemCategory,
+    pub label: String,
+    #[ts(type = "number")]
+    pub estimated_tokens: i64,
+}
+
+impl From<codex_protocol::protocol::ContextItemSummary> for ThreadContextItem {
+    fn from(value: codex_protocol::protocol::ContextItemSummary) -> Self {
+        Self {
+            id: value.id,
+            category: value.category.into(),
+            label: value.label,
+            estimated_tokens: value.estimated_tokens,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextPruneParams {
+    pub thread_id: String,
+    /// Ids from `thread/context/read`. The request fails without changing anything if any
+    /// id is unknown.
+    pub ids: Vec<String>,
+    pub action: ContextPruneAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextPruneResponse {}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1533,6 +1628,25 @@ pub struct ThreadReadParams {
 #[ts(export_to = "v2/")]
 pub struct ThreadReadResponse {
     pub thread: Thread,
+    /// Latest checklist the agent recorded with `update_plan`, if any.
+    pub plan: Option<ThreadPlan>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPlan {
+    pub explanation: Option<String>,
+    pub steps: Vec<TurnPlanStep>,
+}
+
+impl From<CoreUpdatePlanArgs> for ThreadPlan {
+    fn from(value: CoreUpdatePlanArgs) -> Self {
+        Self {
+            explanation: value.explanation,
+            steps: value.plan.into_iter().map(Into::into).collect(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
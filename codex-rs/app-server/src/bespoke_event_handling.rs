@@ -1372,7 +1372,9 @@ async fn handle_token_count_event(
     token_count_event: TokenCountEvent,
     outgoing: &OutgoingMessageSender,
 ) {
-    let TokenCountEvent { info, rate_limits } = token_count_event;
+    let TokenCountEvent {
+        info, rate_limits, ..
+    } = token_count_event;
     if let Some(token_usage) = info.map(ThreadTokenUsage::from) {
         let notification = ThreadTokenUsageUpdatedNotification {
             thread_id: conversation_id.to_string(),
@@ -2110,6 +2112,7 @@ mod tests {
             TokenCountEvent {
                 info: Some(info),
                 rate_limits: Some(rate_limits),
+                context_tokens: None,
             },
             &outgoing,
         )
@@ -2163,6 +2166,7 @@ mod tests {
             TokenCountEvent {
                 info: None,
                 rate_limits: None,
+                context_tokens: None,
             },
             &outgoing,
         )
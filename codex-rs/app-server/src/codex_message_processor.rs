@@ -104,6 +104,10 @@ use codex_app_server_protocol::SkillsRemoteWriteResponse;
 use codex_app_server_protocol::Thread;
 use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadArchiveResponse;
+use codex_app_server_protocol::ThreadContextPruneParams;
+use codex_app_server_protocol::ThreadContextPruneResponse;
+use codex_app_server_protocol::ThreadContextReadParams;
+use codex_app_server_protocol::ThreadContextReadResponse;
 use codex_app_server_protocol::ThreadForkParams;
 use codex_app_server_protocol::ThreadForkResponse;
 use codex_app_server_protocol::ThreadItem;
@@ -111,6 +115,7 @@ use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadListResponse;
 use codex_app_server_protocol::ThreadLoadedListParams;
 use codex_app_server_protocol::ThreadLoadedListResponse;
+use codex_app_server_protocol::ThreadPlan;
 use codex_app_server_protocol::ThreadReadParams;
 use codex_app_server_protocol::ThreadReadResponse;
 use codex_app_server_protocol::ThreadResumeParams;
@@ -183,6 +188,7 @@ use codex_core::sandboxing::SandboxPermissions;
 use codex_core::skills::remote::download_remote_skill;
 use codex_core::skills::remote::list_remote_skills;
 use codex_core::state_db::get_state_db;
+use codex_core::state_db::get_thread_plan;
 use codex_core::token_data::parse_id_token;
 use codex_core::windows_sandbox::WindowsSandboxLevelExt;
 use codex_feedback::CodexFeedback;
@@ -196,6 +202,7 @@ use codex_protocol::config_types::WindowsSandboxLevel;
 use codex_protocol::dynamic_tools::DynamicToolSpec as CoreDynamicToolSpec;
 use codex_protocol::items::TurnItem;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::plan_tool::UpdatePlanArgs;
 use codex_protocol::protocol::AgentStatus;
 use codex_protocol::protocol::GitInfo as CoreGitInfo;
 use codex_protocol::protocol::McpAuthStatus as CoreMcpAuthStatus;
@@ -458,6 +465,12 @@ impl CodexMessageProcessor {
             ClientRequest::ThreadRollback { request_id, params } => {
                 self.thread_rollback(request_id, params).await;
             }
+            ClientRequest::ThreadContextRead { request_id, params } => {
+                self.thread_context_read(request_id, params).await;
+            }
+            ClientRequest::ThreadContextPrune { request_id, params } => {
+                self.thread_context_prune(request_id, params).await;
+            }
             ClientRequest::ThreadList { request_id, params } => {
                 self.thread_list(request_id, params).await;
             }
@@ -1876,6 +1889,55 @@ impl CodexMessageProcessor {
             .await;
     }
 
+    async fn thread_context_read(&self, request_id: RequestId, params: ThreadContextReadParams) {
+        let (_, thread) = match self.load_thread(&params.thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let breakdown = thread.context_breakdown().await;
+        let response = ThreadContextReadResponse {
+            items: breakdown.items.into_iter().map(Into::into).collect(),
+            estimated_total_tokens: breakdown.estimated_total_tokens,
+            model_context_window: breakdown.model_context_window,
+        };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
+    async fn thread_context_prune(&self, request_id: RequestId, params: ThreadContextPruneParams) {
+        let ThreadContextPruneParams {
+            thread_id,
+            ids,
+            action,
+        } = params;
+        if ids.is_empty() {
+            self.send_invalid_request_error(request_id, "ids must not be empty".to_string())
+                .await;
+            return;
+        }
+
+        let (_, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread.prune_context_items(ids, action.to_core()).await {
+            self.send_invalid_request_error(request_id, err.to_string())
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadContextPruneResponse {})
+            .await;
+    }
+
     async fn thread_unarchive(&mut self, request_id: RequestId, params: ThreadUnarchiveParams) {
         // TODO(jif) mostly rewrite this using sqlite after phase 1
         let thread_id = match ThreadId::from_string(&params.thread_id) {
@@ -2277,10 +2339,37 @@ impl CodexMessageProcessor {
             }
         }
 
-        let response = ThreadReadResponse { thread };
+        let plan = self
+            .read_thread_plan(thread_uuid, rollout_path.as_deref())
+            .await
+            .map(ThreadPlan::from);
+        let response = ThreadReadResponse { thread, plan };
         self.outgoing.send_response(request_id, response).await;
     }
 
+    /// Latest `update_plan` checklist for a thread: live state for loaded
+    /// threads, otherwise the state DB, otherwise the last `PlanUpdate` event
+    /// in the rollout.
+    async fn read_thread_plan(
+        &self,
+        thread_id: ThreadId,
+        rollout_path: Option<&Path>,
+    ) -> Option<UpdatePlanArgs> {
+        if let Ok(thread) = self.thread_manager.get_thread(thread_id).await {
+            return thread.plan().await;
+        }
+        let state_db_ctx = get_state_db(&self.config, None).await;
+        if let Some(plan) = get_thread_plan(state_db_ctx.as_deref(), thread_id, "thread_read").await
+        {
+            return Some(plan);
+        }
+        let events = read_event_msgs_from_rollout(rollout_path?).await.ok()?;
+        events.into_iter().rev().find_map(|event| match event {
+            EventMsg::PlanUpdate(plan) => Some(plan),
+            _ => None,
+        })
+    }
+
     pub(crate) fn thread_created_receiver(&self) -> broadcast::Receiver<ThreadId> {
         self.thread_manager.subscribe_thread_created()
     }
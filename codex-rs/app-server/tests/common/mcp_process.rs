@@ -50,6 +50,8 @@ use codex_app_server_protocol::SendUserTurnParams;
 use codex_app_server_protocol::ServerRequest;
 use codex_app_server_protocol::SetDefaultModelParams;
 use codex_app_server_protocol::ThreadArchiveParams;
+use codex_app_server_protocol::ThreadContextPruneParams;
+use codex_app_server_protocol::ThreadContextReadParams;
 use codex_app_server_protocol::ThreadForkParams;
 use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadLoadedListParams;
@@ -427,6 +429,24 @@ impl McpProcess {
         self.send_request("thread/rollback", params).await
     }
 
+    /// Send a `thread/context/read` JSON-RPC request.
+    pub async fn send_thread_context_read_request(
+        &mut self,
+        params: ThreadContextReadParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/context/read", params).await
+    }
+
+    /// Send a `thread/context/prune` JSON-RPC request.
+    pub async fn send_thread_context_prune_request(
+        &mut self,
+        params: ThreadContextPruneParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/context/prune", params).await
+    }
+
     /// Send a `thread/list` JSON-RPC request.
     pub async fn send_thread_list_request(
         &mut self,
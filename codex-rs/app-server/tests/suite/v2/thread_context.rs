@@ -0,0 +1,174 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_final_assistant_message_sse_response;
+use app_test_support::create_mock_responses_server_sequence_unchecked;
+use app_test_support::to_response;
+use codex_app_server_protocol::ContextItemCategory;
+use codex_app_server_protocol::ContextPruneAction;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadContextPruneParams;
+use codex_app_server_protocol::ThreadContextPruneResponse;
+use codex_app_server_protocol::ThreadContextReadParams;
+use codex_app_server_protocol::ThreadContextReadResponse;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use codex_app_server_protocol::TurnStartParams;
+use codex_app_server_protocol::UserInput as V2UserInput;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_context_read_and_prune_individual_items() -> Result<()> {
+    let responses = vec![create_final_assistant_message_sse_response("Done")?];
+    let server = create_mock_responses_server_sequence_unchecked(responses).await;
+
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let start_id = mcp
+        .send_thread_start_request(ThreadStartParams {
+            model: Some("mock-model".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    let start_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(start_id)),
+    )
+    .await??;
+    let ThreadStartResponse { thread, .. } = to_response::<ThreadStartResponse>(start_resp)?;
+
+    let turn_id = mcp
+        .send_turn_start_request(TurnStartParams {
+            thread_id: thread.id.clone(),
+            input: vec![V2UserInput::Text {
+                text: "Please remember the magic word".to_string(),
+                text_elements: Vec::new(),
+            }],
+            ..Default::default()
+        })
+        .await?;
+    let _turn_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(turn_id)),
+    )
+    .await??;
+    let _completed = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("turn/completed"),
+    )
+    .await??;
+
+    let context = read_context(&mut mcp, &thread.id).await?;
+    let base = &context.items[0];
+    assert_eq!(base.category, ContextItemCategory::BaseInstructions);
+    assert_eq!(base.id, None);
+    assert!(base.estimated_tokens > 0);
+    assert_eq!(
+        context.estimated_total_tokens,
+        context
+            .items
+            .iter()
+            .map(|item| item.estimated_tokens)
+            .sum::<i64>()
+    );
+    let user_message = context
+        .items
+        .iter()
+        .find(|item| item.label == "Please remember the magic word")
+        .expect("user message is listed");
+    assert_eq!(user_message.category, ContextItemCategory::UserMessage);
+    let user_message_id = user_message
+        .id
+        .clone()
+        .expect("user messages can be pruned");
+
+    // Unknown ids are rejected without touching the history.
+    let bad_id = mcp
+        .send_thread_context_prune_request(ThreadContextPruneParams {
+            thread_id: thread.id.clone(),
+            ids: vec![user_message_id.clone(), "item:999".to_string()],
+            action: ContextPruneAction::Drop,
+        })
+        .await?;
+    let error: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(bad_id)),
+    )
+    .await??;
+    assert!(error.error.message.contains("item:999"));
+    assert_eq!(
+        read_context(&mut mcp, &thread.id).await?.items,
+        context.items
+    );
+
+    let prune_id = mcp
+        .send_thread_context_prune_request(ThreadContextPruneParams {
+            thread_id: thread.id.clone(),
+            ids: vec![user_message_id],
+            action: ContextPruneAction::Drop,
+        })
+        .await?;
+    let prune_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(prune_id)),
+    )
+    .await??;
+    let ThreadContextPruneResponse {} = to_response::<ThreadContextPruneResponse>(prune_resp)?;
+
+    let pruned = read_context(&mut mcp, &thread.id).await?;
+    assert_eq!(pruned.items.len(), context.items.len() - 1);
+    assert!(
+        !pruned
+            .items
+            .iter()
+            .any(|item| item.label == "Please remember the magic word")
+    );
+
+    Ok(())
+}
+
+async fn read_context(mcp: &mut McpProcess, thread_id: &str) -> Result<ThreadContextReadResponse> {
+    let read_id = mcp
+        .send_thread_context_read_request(ThreadContextReadParams {
+            thread_id: thread_id.to_string(),
+        })
+        .await?;
+    let read_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(read_id)),
+    )
+    .await??;
+    to_response::<ThreadContextReadResponse>(read_resp)
+}
+
+fn create_config_toml(codex_home: &std::path::Path, server_uri: &str) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "responses"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
@@ -0,0 +1,472 @@
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_app_server_protocol::ConfigLayerSource;
+use codex_app_server_protocol::ConfigValueWriteParams;
+use codex_app_server_protocol::MergeStrategy;
+use codex_common::CliConfigOverrides;
+use codex_core::config::CONFIG_TOML_FILE;
+use codex_core::config::find_codex_home;
+use codex_core::config::schema::unknown_config_keys;
+use codex_core::config::service::ConfigService;
+use codex_core::config_loader::CloudRequirementsLoader;
+use codex_core::config_loader::ConfigLayerStack;
+use codex_core::config_loader::ConfigLoadError;
+use codex_core::config_loader::LoaderOverrides;
+use codex_core::config_loader::format_config_error_with_source;
+use codex_core::config_loader::load_config_layers_state;
+use codex_core::config_loader::validate_config_layers;
+use codex_utils_absolute_path::AbsolutePathBuf;
+use serde_json::Value as JsonValue;
+use toml::Value as TomlValue;
+
+/// Subcommands:
+/// - `get`      — print the effective value of a key
+/// - `set`      — write a key to `~/.codex/config.toml`
+/// - `unset`    — remove a key from `~/.codex/config.toml`
+/// - `list`     — print every key set in some config layer
+/// - `validate` — check config files for syntax errors, unknown keys and type errors
+#[derive(Debug, clap::Parser)]
+pub struct ConfigCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: ConfigSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ConfigSubcommand {
+    Get(GetArgs),
+    Set(SetArgs),
+    Unset(UnsetArgs),
+    List(ListArgs),
+    Validate,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct GetArgs {
+    /// Dotted key to read (for example: sandbox_mode or features.unified_exec).
+    pub key: String,
+
+    /// Also print which config layer provided each value.
+    #[arg(long)]
+    pub show_origin: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SetArgs {
+    /// Dotted key to write (for example: model or tools.view_image).
+    pub key: String,
+
+    /// Value to store. Parsed as TOML, falling back to a plain string, like `-c`.
+    pub value: String,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct UnsetArgs {
+    /// Dotted key to remove from the user config.
+    pub key: String,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ListArgs {
+    /// Print which config layer provided each value.
+    #[arg(long)]
+    pub show_origin: bool,
+}
+
+impl ConfigCli {
+    pub async fn run(self) -> Result<()> {
+        let ConfigCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        match subcommand {
+            ConfigSubcommand::Get(args) => {
+                run_get(&config_overrides, args).await?;
+            }
+            ConfigSubcommand::Set(args) => {
+                run_set(&config_overrides, args).await?;
+            }
+            ConfigSubcommand::Unset(args) => {
+                run_unset(&config_overrides, args).await?;
+            }
+            ConfigSubcommand::List(args) => {
+                run_list(&config_overrides, args).await?;
+            }
+            ConfigSubcommand::Validate => {
+                run_validate(&config_overrides).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn run_get(config_overrides: &CliConfigOverrides, get_args: GetArgs) -> Result<()> {
+    let GetArgs { key, show_origin } = get_args;
+    let segments = parse_key(&key)?;
+    let layers = load_layers(config_overrides).await?;
+    let effective = layers.effective_config();
+
+    let Some(value) = value_at_path(&effective, &segments) else {
+        bail!("`{key}` is not set in any config layer.");
+    };
+
+    if !value.is_table() {
+        let rendered = match value {
+            TomlValue::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        if show_origin {
+            println!("{rendered}\t{}", origin_label(&layers, &segments));
+        } else {
+            println!("{rendered}");
+        }
+        return Ok(());
+    }
+
+    print_entries(&layers, &leaf_entries(value, &segments), show_origin);
+    Ok(())
+}
+
+async fn run_set(config_overrides: &CliConfigOverrides, set_args: SetArgs) -> Result<()> {
+    let SetArgs { key, value } = set_args;
+    let segments = parse_key(&key)?;
+    let value = parse_value(&key, &value)?;
+    if let Some(unknown) = unknown_config_keys(&nest_value(&segments, value.clone())).first() {
+        bail!("unknown configuration key `{}`", unknown.join("."));
+    }
+
+    let service = config_service(config_overrides)?;
+    let response = service
+        .write_value(ConfigValueWriteParams {
+            key_path: key.clone(),
+            value: serde_json::to_value(&value).context("failed to encode value")?,
+            merge_strategy: MergeStrategy::Replace,
+            file_path: None,
+            expected_version: None,
+        })
+        .await?;
+
+    println!("Set `{key}` in {}.", response.file_path.display());
+    if let Some(overridden) = response.overridden_metadata {
+        eprintln!("Warning: {}", overridden.message);
+    }
+    Ok(())
+}
+
+async fn run_unset(config_overrides: &CliConfigOverrides, unset_args: UnsetArgs) -> Result<()> {
+    let UnsetArgs { key } = unset_args;
+    let segments = parse_key(&key)?;
+
+    let layers = load_layers(config_overrides).await?;
+    let in_user_layer = layers
+        .get_user_layer()
+        .is_some_and(|layer| value_at_path(&layer.config, &segments).is_some());
+    if !in_user_layer {
+        match value_at_path(&layers.effective_config(), &segments) {
+            Some(_) => bail!(
+                "`{key}` is not set in the user config; it comes from {}.",
+                origin_label(&layers, &segments)
+            ),
+            None => bail!("`{key}` is not set in the user config."),
+        }
+    }
+
+    let service = config_service(config_overrides)?;
+    let response = service
+        .write_value(ConfigValueWriteParams {
+            key_path: key.clone(),
+            value: JsonValue::Null,
+            merge_strategy: MergeStrategy::Replace,
+            file_path: None,
+            expected_version: None,
+        })
+        .await?;
+
+    println!("Removed `{key}` from {}.", response.file_path.display());
+    if let Some(overridden) = response.overridden_metadata {
+        eprintln!("Note: {}", overridden.message);
+    }
+    Ok(())
+}
+
+async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) -> Result<()> {
+    let layers = load_layers(config_overrides).await?;
+    let effective = layers.effective_config();
+    let entries = leaf_entries(&effective, &[]);
+    if entries.is_empty() {
+        println!("No settings are configured.");
+        return Ok(());
+    }
+    print_entries(&layers, &entries, list_args.show_origin);
+    Ok(())
+}
+
+async fn run_validate(config_overrides: &CliConfigOverrides) -> Result<()> {
+    let layers = match load_layers(config_overrides).await {
+        Ok(layers) => layers,
+        Err(err) => {
+            // Syntax errors stop the loader before we can inspect any layer.
+            if let Some(load_error) = err
+                .downcast_ref::<std::io::Error>()
+                .and_then(|err| err.get_ref())
+                .and_then(|err| err.downcast_ref::<ConfigLoadError>())
+            {
+                eprintln!(
+                    "{}",
+                    format_config_error_with_source(load_error.config_error())
+                );
+                bail!("configuration is invalid");
+            }
+            return Err(err);
+        }
+    };
+
+    let errors = validate_config_layers(&layers).await;
+    if errors.is_empty() {
+        println!("Configuration is valid.");
+        return Ok(());
+    }
+
+    for error in &errors {
+        eprintln!("{}\n", format_config_error_with_source(error));
+    }
+    let noun = if errors.len() == 1 {
+        "problem"
+    } else {
+        "problems"
+    };
+    bail!("found {} {noun} in configuration", errors.len());
+}
+
+async fn load_layers(config_overrides: &CliConfigOverrides) -> Result<ConfigLayerStack> {
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+    let cwd = AbsolutePathBuf::current_dir().context("failed to resolve current directory")?;
+    let layers = load_config_layers_state(
+        &codex_home,
+        Some(cwd),
+        &overrides,
+        LoaderOverrides::default(),
+        CloudRequirementsLoader::default(),
+    )
+    .await?;
+    Ok(layers)
+}
+
+fn config_service(config_overrides: &CliConfigOverrides) -> Result<ConfigService> {
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+    Ok(ConfigService::new(
+        codex_home,
+        overrides,
+        LoaderOverrides::default(),
+        CloudRequirementsLoader::default(),
+    ))
+}
+
+fn parse_key(key: &str) -> Result<Vec<String>> {
+    let segments: Vec<String> = key.split('.').map(str::to_string).collect();
+    if segments.iter().any(String::is_empty) {
+        bail!("invalid key `{key}`: expected dotted segments such as `tools.view_image`");
+    }
+    Ok(segments)
+}
+
+/// Parse a value exactly like `-c key=value` does.
+fn parse_value(key: &str, raw: &str) -> Result<TomlValue> {
+    let overrides = CliConfigOverrides {
+        raw_overrides: vec![format!("{key}={raw}")],
+    };
+    let mut parsed = overrides.parse_overrides().map_err(anyhow::Error::msg)?;
+    match parsed.pop() {
+        Some((_, value)) => Ok(value),
+        None => bail!("missing value for `{key}`"),
+    }
+}
+
+fn nest_value(segments: &[String], value: TomlValue) -> TomlValue {
+    segments.iter().rev().fold(value, |value, segment| {
+        let mut table = toml::map::Map::new();
+        table.insert(segment.clone(), value);
+        TomlValue::Table(table)
+    })
+}
+
+fn value_at_path<'a>(root: &'a TomlValue, segments: &[String]) -> Option<&'a TomlValue> {
+    segments
+        .iter()
+        .try_fold(root, |current, segment| current.as_table()?.get(segment))
+}
+
+/// Flatten `value` into `(key path, value)` pairs. Arrays are kept whole
+/// because layers replace them rather than merging element by element.
+fn leaf_entries<'a>(value: &'a TomlValue, prefix: &[String]) -> Vec<(Vec<String>, &'a TomlValue)> {
+    let mut entries = Vec::new();
+    let mut path = prefix.to_vec();
+    collect_leaf_entries(value, &mut path, &mut entries);
+    entries
+}
+
+fn collect_leaf_entries<'a>(
+    value: &'a TomlValue,
+    path: &mut Vec<String>,
+    entries: &mut Vec<(Vec<String>, &'a TomlValue)>,
+) {
+    match value {
+        TomlValue::Table(table) => {
+            for (key, child) in table {
+                path.push(key.clone());
+                collect_leaf_entries(child, path, entries);
+                path.pop();
+            }
+        }
+        other => entries.push((path.clone(), other)),
+    }
+}
+
+fn print_entries(
+    layers: &ConfigLayerStack,
+    entries: &[(Vec<String>, &TomlValue)],
+    show_origin: bool,
+) {
+    for (segments, value) in entries {
+        let key = segments.join(".");
+        if show_origin {
+            println!("{key} = {value}\t{}", origin_label(layers, segments));
+        } else {
+            println!("{key} = {value}");
+        }
+    }
+}
+
+/// Describe the highest-precedence layer that sets `segments`.
+fn origin_label(layers: &ConfigLayerStack, segments: &[String]) -> String {
+    layers
+        .layers_high_to_low()
+        .into_iter()
+        .find(|layer| value_at_path(&layer.config, segments).is_some())
+        .map(|layer| describe_layer_source(&layer.name))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn describe_layer_source(source: &ConfigLayerSource) -> String {
+    match source {
+        ConfigLayerSource::Mdm { domain, key } => format!("mdm:{domain}/{key}"),
+        ConfigLayerSource::System { file } => format!("system:{}", file.display()),
+        ConfigLayerSource::User { file } => format!("user:{}", file.display()),
+        ConfigLayerSource::Conditional { file, index } => {
+            format!("conditional[{index}]:{}", file.display())
+        }
+        ConfigLayerSource::Project { dot_codex_folder } => format!(
+            "project:{}",
+            dot_codex_folder.as_path().join(CONFIG_TOML_FILE).display()
+        ),
+        ConfigLayerSource::SessionFlags => "session-flags".to_string(),
+        ConfigLayerSource::LegacyManagedConfigTomlFromFile { file } => {
+            format!("managed-config:{}", file.display())
+        }
+        ConfigLayerSource::LegacyManagedConfigTomlFromMdm => "managed-config:mdm".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_value_matches_config_override_semantics() {
+        assert_eq!(
+            parse_value("model", "gpt-5").expect("parse"),
+            TomlValue::String("gpt-5".to_string())
+        );
+        assert_eq!(
+            parse_value("tools.view_image", "true").expect("parse"),
+            TomlValue::Boolean(true)
+        );
+        assert_eq!(
+            parse_value("sandbox_workspace_write.writable_roots", "[\"/tmp\"]").expect("parse"),
+            TomlValue::Array(vec![TomlValue::String("/tmp".to_string())])
+        );
+    }
+
+    #[test]
+    fn parse_key_rejects_empty_segments() {
+        assert_eq!(
+            parse_key("tools.view_image").expect("parse"),
+            vec!["tools".to_string(), "view_image".to_string()]
+        );
+        assert!(parse_key("tools..view_image").is_err());
+        assert!(parse_key("").is_err());
+    }
+
+    #[test]
+    fn leaf_entries_flattens_tables_but_keeps_arrays() {
+        let value: TomlValue = toml::from_str(
+            r#"
+model = "gpt-5"
+
+[sandbox_workspace_write]
+writable_roots = ["/tmp", "/var/tmp"]
+network_access = true
+"#,
+        )
+        .expect("parse");
+
+        let keys: Vec<String> = leaf_entries(&value, &[])
+            .into_iter()
+            .map(|(segments, _)| segments.join("."))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "model".to_string(),
+                "sandbox_workspace_write.network_access".to_string(),
+                "sandbox_workspace_write.writable_roots".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn origin_label_reports_highest_precedence_layer() {
+        let user_file = AbsolutePathBuf::current_dir()
+            .and_then(|cwd| cwd.join(CONFIG_TOML_FILE))
+            .expect("path");
+        let layers = ConfigLayerStack::new(
+            vec![
+                codex_core::config_loader::ConfigLayerEntry::new(
+                    ConfigLayerSource::User {
+                        file: user_file.clone(),
+                    },
+                    toml::from_str("sandbox_mode = \"read-only\"\nmodel = \"gpt-5\"")
+                        .expect("parse"),
+                ),
+                codex_core::config_loader::ConfigLayerEntry::new(
+                    ConfigLayerSource::SessionFlags,
+                    toml::from_str("sandbox_mode = \"workspace-write\"").expect("parse"),
+                ),
+            ],
+            Default::default(),
+            Default::default(),
+        )
+        .expect("layers");
+
+        assert_eq!(
+            origin_label(&layers, &["sandbox_mode".to_string()]),
+            "session-flags"
+        );
+        assert_eq!(
+            origin_label(&layers, &["model".to_string()]),
+            format!("user:{}", user_file.display())
+        );
+    }
+}
@@ -0,0 +1,1087 @@
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Output;
+use std::process::Stdio;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use clap::Parser;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::find_thread_path_by_id_str;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::RolloutItem;
+use codex_core::protocol::RolloutLine;
+use codex_exec::exec_events::ThreadEvent;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncSeekExt;
+use tokio::io::BufReader;
+use tokio::process::Command;
+use tokio::time::sleep_until;
+use tokio::time::timeout_at;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 600;
+const USAGE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const STDERR_GRACE: Duration = Duration::from_secs(5);
+
+/// Run a suite of tasks against one or more models and report pass rates.
+#[derive(Debug, Parser)]
+pub struct EvalCommand {
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Suite file (TOML) listing the tasks to run.
+    #[arg(value_name = "SUITE")]
+    pub suite: PathBuf,
+
+    /// Model to evaluate. Repeat to compare several models.
+    #[arg(long = "model", short = 'm', value_name = "MODEL")]
+    pub models: Vec<String>,
+
+    /// Configuration profile to evaluate. Repeat to compare several profiles.
+    #[arg(long = "profile", short = 'p', value_name = "PROFILE")]
+    pub profiles: Vec<String>,
+
+    /// Only run tasks whose name contains this string.
+    #[arg(long = "filter", value_name = "TEXT")]
+    pub filter: Option<String>,
+
+    /// Directory for `report.json`, `report.md`, and per-run logs.
+    /// Defaults to `codex-eval-<timestamp>` in the current directory.
+    #[arg(short = 'o', long = "output", value_name = "DIR")]
+    pub output: Option<PathBuf>,
+
+    /// Keep each run's working directory instead of deleting it.
+    #[arg(long = "keep-workdirs", default_value_t = false)]
+    pub keep_workdirs: bool,
+}
+
+/// A suite file: shared budgets plus a list of `[[task]]` tables.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EvalSuite {
+    /// Default wall-clock budget for each run, in seconds.
+    timeout_secs: Option<u64>,
+    /// Default token budget for each run. Runs that use more tokens fail.
+    max_tokens: Option<i64>,
+    #[serde(default, rename = "task")]
+    tasks: Vec<EvalTask>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EvalTask {
+    name: String,
+    prompt: String,
+    /// Directory copied into a fresh working directory for each run.
+    fixture: Option<PathBuf>,
+    /// Git revision checked out into a detached worktree for each run.
+    git_ref: Option<String>,
+    /// Repository that `git_ref` is resolved in. Defaults to the current directory.
+    repo: Option<PathBuf>,
+    /// Shell command run in the working directory before Codex starts.
+    setup: Option<String>,
+    /// Shell command run after Codex finishes. Exit status 0 means the task passed.
+    verify: String,
+    timeout_secs: Option<u64>,
+    max_tokens: Option<i64>,
+}
+
+enum TaskSource {
+    Fixture(PathBuf),
+    GitRef { repo: PathBuf, git_ref: String },
+}
+
+impl EvalSuite {
+    fn parse(contents: &str, suite_dir: &Path, cwd: &Path) -> Result<Self> {
+        let mut suite: EvalSuite = toml::from_str(contents).context("invalid suite file")?;
+        if suite.tasks.is_empty() {
+            bail!("suite has no [[task]] entries");
+        }
+
+        let mut names = HashSet::new();
+        for task in &mut suite.tasks {
+            if task.name.trim().is_empty() {
+                bail!("every task needs a non-empty name");
+            }
+            if !names.insert(task.name.clone()) {
+                bail!("duplicate task name `{}`", task.name);
+            }
+            if task.prompt.trim().is_empty() {
+                bail!("task `{}` has an empty prompt", task.name);
+            }
+            match (&task.fixture, &task.git_ref) {
+                (Some(_), Some(_)) => {
+                    bail!("task `{}` sets both fixture and git_ref", task.name)
+                }
+                (None, None) => bail!("task `{}` needs a fixture or a git_ref", task.name),
+                _ => {}
+            }
+            // Fixtures are relative to the suite file; repos to where eval runs.
+            task.fixture = task.fixture.take().map(|path| suite_dir.join(path));
+            task.repo = task.repo.take().map(|path| cwd.join(path));
+        }
+        Ok(suite)
+    }
+}
+
+impl EvalTask {
+    fn source(&self, cwd: &Path) -> TaskSource {
+        match (&self.fixture, &self.git_ref) {
+            (Some(fixture), _) => TaskSource::Fixture(fixture.clone()),
+            (None, git_ref) => TaskSource::GitRef {
+                repo: self.repo.clone().unwrap_or_else(|| cwd.to_path_buf()),
+                git_ref: git_ref.clone().unwrap_or_default(),
+            },
+        }
+    }
+}
+
+/// A model/profile combination that every task runs against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Variant {
+    model: Option<String>,
+    profile: Option<String>,
+}
+
+impl Variant {
+    fn all(models: &[String], profiles: &[String]) -> Vec<Self> {
+        let models: Vec<Option<String>> = if models.is_empty() {
+            vec![None]
+        } else {
+            models.iter().cloned().map(Some).collect()
+        };
+        let profiles: Vec<Option<String>> = if profiles.is_empty() {
+            vec![None]
+        } else {
+            profiles.iter().cloned().map(Some).collect()
+        };
+        models
+            .iter()
+            .flat_map(|model| {
+                profiles.iter().map(move |profile| Variant {
+                    model: model.clone(),
+                    profile: profile.clone(),
+                })
+            })
+            .collect()
+    }
+
+    fn label(&self) -> String {
+        match (&self.model, &self.profile) {
+            (Some(model), Some(profile)) => format!("{model} (profile {profile})"),
+            (Some(model), None) => model.clone(),
+            (None, Some(profile)) => format!("profile {profile}"),
+            (None, None) => "default".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RunOutcome {
+    Passed,
+    Failed,
+    TimedOut,
+    OverBudget,
+    Error,
+}
+
+impl RunOutcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            RunOutcome::Passed => "passed",
+            RunOutcome::Failed => "failed",
+            RunOutcome::TimedOut => "timed out",
+            RunOutcome::OverBudget => "over budget",
+            RunOutcome::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+struct TokenUsage {
+    input_tokens: i64,
+    cached_input_tokens: i64,
+    output_tokens: i64,
+}
+
+impl TokenUsage {
+    fn total(&self) -> i64 {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct RunResult {
+    task: String,
+    variant: String,
+    model: Option<String>,
+    profile: Option<String>,
+    outcome: RunOutcome,
+    duration_secs: f64,
+    usage: TokenUsage,
+    thread_id: Option<String>,
+    rollout_path: Option<PathBuf>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct VariantSummary {
+    variant: String,
+    passed: usize,
+    total: usize,
+    pass_rate: f64,
+    total_tokens: i64,
+    duration_secs: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct EvalReport {
+    suite: PathBuf,
+    summary: Vec<VariantSummary>,
+    runs: Vec<RunResult>,
+}
+
+/// What we learn about a session from the `codex exec --json` event stream.
+#[derive(Debug, Default, PartialEq)]
+struct SessionStats {
+    thread_id: Option<String>,
+    usage: TokenUsage,
+    error: Option<String>,
+}
+
+impl SessionStats {
+    fn from_jsonl(stdout: &str) -> Self {
+        let mut stats = SessionStats::default();
+        for line in stdout.lines() {
+            let Ok(event) = serde_json::from_str::<ThreadEvent>(line) else {
+                continue;
+            };
+            match event {
+                ThreadEvent::ThreadStarted(started) => stats.thread_id = Some(started.thread_id),
+                ThreadEvent::TurnCompleted(completed) => {
+                    stats.usage.input_tokens += completed.usage.input_tokens;
+                    stats.usage.cached_input_tokens += completed.usage.cached_input_tokens;
+                    stats.usage.output_tokens += completed.usage.output_tokens;
+                }
+                ThreadEvent::TurnFailed(failed) => stats.error = Some(failed.error.message),
+                ThreadEvent::Error(error) => stats.error = Some(error.message),
+                _ => {}
+            }
+        }
+        stats
+    }
+}
+
+/// Follows a session's rollout for its latest token count. The file is read
+/// from where the previous poll stopped.
+#[derive(Default)]
+struct RolloutUsage {
+    path: Option<PathBuf>,
+    offset: u64,
+    partial: Vec<u8>,
+    usage: TokenUsage,
+}
+
+impl RolloutUsage {
+    async fn poll(&mut self, codex_home: &Path, thread_id: &str) -> Result<TokenUsage> {
+        if self.path.is_none() {
+            self.path = find_thread_path_by_id_str(codex_home, thread_id).await?;
+        }
+        let Some(path) = &self.path else {
+            return Ok(self.usage);
+        };
+        let mut file = tokio::fs::File::open(path)
+            .await
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        file.seek(SeekFrom::Start(self.offset)).await?;
+        let mut chunk = Vec::new();
+        file.read_to_end(&mut chunk).await?;
+        self.offset += chunk.len() as u64;
+        self.partial.extend_from_slice(&chunk);
+        let Some(end) = self.partial.iter().rposition(|byte| *byte == b'\n') else {
+            return Ok(self.usage);
+        };
+        let complete: Vec<u8> = self.partial.drain(..=end).collect();
+        for line in String::from_utf8_lossy(&complete).lines() {
+            let Ok(line) = serde_json::from_str::<RolloutLine>(line) else {
+                continue;
+            };
+            if let RolloutItem::EventMsg(EventMsg::TokenCount(event)) = line.item
+                && let Some(info) = event.info
+            {
+                let total = info.total_token_usage;
+                self.usage = TokenUsage {
+                    input_tokens: total.input_tokens,
+                    cached_input_tokens: total.cached_input_tokens,
+                    output_tokens: total.output_tokens,
+                };
+            }
+        }
+        Ok(self.usage)
+    }
+}
+
+pub async fn run_eval_command(cmd: EvalCommand) -> Result<()> {
+    let overrides = cmd
+        .config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(overrides)
+        .await
+        .context("failed to load configuration")?;
+
+    let cwd = std::env::current_dir().context("failed to read current directory")?;
+    let suite_path = cwd.join(&cmd.suite);
+    let contents = std::fs::read_to_string(&suite_path)
+        .with_context(|| format!("failed to read {}", suite_path.display()))?;
+    let suite_dir = suite_path.parent().unwrap_or(&cwd).to_path_buf();
+    let suite = EvalSuite::parse(&contents, &suite_dir, &cwd)
+        .with_context(|| format!("failed to load suite {}", suite_path.display()))?;
+
+    let tasks: Vec<&EvalTask> = suite
+        .tasks
+        .iter()
+        .filter(|task| {
+            cmd.filter
+                .as_deref()
+                .is_none_or(|filter| task.name.contains(filter))
+        })
+        .collect();
+    if tasks.is_empty() {
+        bail!("no tasks match the filter");
+    }
+
+    let output_dir = match &cmd.output {
+        Some(dir) => cwd.join(dir),
+        None => {
+            let secs = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default();
+            cwd.join(format!("codex-eval-{secs}"))
+        }
+    };
+    std::fs::create_dir_all(&output_dir)
+        .with_context(|| format!("failed to create {}", output_dir.display()))?;
+
+    let codex_exe = std::env::current_exe().context("failed to locate the codex executable")?;
+    let variants = Variant::all(&cmd.models, &cmd.profiles);
+    let total = tasks.len() * variants.len();
+    let mut runs = Vec::with_capacity(total);
+    for variant in &variants {
+        for task in &tasks {
+            eprintln!(
+                "[{}/{total}] {} with {}",
+                runs.len() + 1,
+                task.name,
+                variant.label()
+            );
+            let budget = RunBudget {
+                timeout: Duration::from_secs(
+                    task.timeout_secs
+                        .or(suite.timeout_secs)
+                        .unwrap_or(DEFAULT_TIMEOUT_SECS),
+                ),
+                max_tokens: task.max_tokens.or(suite.max_tokens),
+            };
+            let run = EvalRun {
+                codex_exe: &codex_exe,
+                codex_home: &config.codex_home,
+                config_overrides: &cmd.config_overrides.raw_overrides,
+                cwd: &cwd,
+                log_dir: &output_dir,
+                keep_workdir: cmd.keep_workdirs,
+            };
+            let result = run.run(task, variant, budget).await;
+            eprintln!(
+                "  {} in {:.1}s",
+                result.outcome.as_str(),
+                result.duration_secs
+            );
+            runs.push(result);
+        }
+    }
+
+    let report = EvalReport {
+        suite: suite_path,
+        summary: summarize(&variants, &runs),
+        runs,
+    };
+    let json_path = output_dir.join("report.json");
+    std::fs::write(&json_path, serde_json::to_string_pretty(&report)?)
+        .with_context(|| format!("failed to write {}", json_path.display()))?;
+    let markdown_path = output_dir.join("report.md");
+    std::fs::write(&markdown_path, render_markdown(&report))
+        .with_context(|| format!("failed to write {}", markdown_path.display()))?;
+
+    for summary in &report.summary {
+        eprintln!(
+            "{}: {}/{} passed ({:.0}%)",
+            summary.variant,
+            summary.passed,
+            summary.total,
+            summary.pass_rate * 100.0
+        );
+    }
+    eprintln!("Wrote {}", markdown_path.display());
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RunBudget {
+    timeout: Duration,
+    max_tokens: Option<i64>,
+}
+
+impl RunBudget {
+    fn exceeded(&self, step: &str) -> String {
+        format!("{step} exceeded {}s budget", self.timeout.as_secs())
+    }
+}
+
+struct EvalRun<'a> {
+    codex_exe: &'a Path,
+    codex_home: &'a Path,
+    config_overrides: &'a [String],
+    cwd: &'a Path,
+    log_dir: &'a Path,
+    keep_workdir: bool,
+}
+
+impl EvalRun<'_> {
+    async fn run(&self, task: &EvalTask, variant: &Variant, budget: RunBudget) -> RunResult {
+        let started = Instant::now();
+        let mut result = RunResult {
+            task: task.name.clone(),
+            variant: variant.label(),
+            model: variant.model.clone(),
+            profile: variant.profile.clone(),
+            outcome: RunOutcome::Error,
+            duration_secs: 0.0,
+            usage: TokenUsage::default(),
+            thread_id: None,
+            rollout_path: None,
+            error: None,
+        };
+        if let Err(err) = self.run_inner(task, variant, budget, &mut result).await {
+            result.outcome = RunOutcome::Error;
+            result.error = Some(format!("{err:#}"));
+        }
+        result.duration_secs = started.elapsed().as_secs_f64();
+        result
+    }
+
+    async fn run_inner(
+        &self,
+        task: &EvalTask,
+        variant: &Variant,
+        budget: RunBudget,
+        result: &mut RunResult,
+    ) -> Result<()> {
+        // Setup, the session and verify share one deadline.
+        let deadline = Instant::now() + budget.timeout;
+        let workdir = Workdir::create(&task.source(self.cwd), self.keep_workdir).await?;
+        let dir = &workdir.path();
+        let log_stem = format!(
+            "{}-{}",
+            sanitize_file_name(&task.name),
+            sanitize_file_name(&variant.label())
+        );
+
+        if let Some(setup) = &task.setup {
+            let Some(output) = run_until(shell(setup, dir), deadline).await? else {
+                result.outcome = RunOutcome::TimedOut;
+                result.error = Some(budget.exceeded("setup"));
+                return Ok(());
+            };
+            self.write_log(&log_stem, "setup", &output)?;
+            if !output.status.success() {
+                bail!("setup command failed with {}", output.status);
+            }
+        }
+
+        let mut exec = Command::new(self.codex_exe);
+        exec.arg("exec")
+            .args(["--json", "--skip-git-repo-check", "--full-auto", "--cd"])
+            .arg(dir);
+        for raw in self.config_overrides {
+            exec.arg("-c").arg(raw);
+        }
+        if let Some(model) = &variant.model {
+            exec.arg("--model").arg(model);
+        }
+        if let Some(profile) = &variant.profile {
+            exec.arg("--profile").arg(profile);
+        }
+        exec.arg("--")
+            .arg(&task.prompt)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let mut child = exec.spawn().context("failed to start codex exec")?;
+        let stdout = child
+            .stdout
+            .take()
+            .context("codex exec stdout was not piped")?;
+        let mut stderr = child
+            .stderr
+            .take()
+            .context("codex exec stderr was not piped")?;
+        let stderr = tokio::spawn(async move {
+            let mut buf = Vec::new();
+            let _ = stderr.read_to_end(&mut buf).await;
+            buf
+        });
+
+        // `codex exec --json` reports usage only when the turn ends, so the
+        // token budget is enforced from the rollout while the session runs.
+        let mut lines = BufReader::new(stdout).lines();
+        let mut session_stdout = String::new();
+        let mut thread_id = None;
+        let mut rollout_usage = RolloutUsage::default();
+        let mut poll = tokio::time::interval(USAGE_POLL_INTERVAL);
+        let stopped = loop {
+            tokio::select! {
+                line = lines.next_line() => {
+                    let Some(line) = line.context("failed to read codex exec output")? else {
+                        break None;
+                    };
+                    if thread_id.is_none()
+                        && let Ok(ThreadEvent::ThreadStarted(started)) =
+                            serde_json::from_str::<ThreadEvent>(&line)
+                    {
+                        thread_id = Some(started.thread_id);
+                    }
+                    session_stdout.push_str(&line);
+                    session_stdout.push('\n');
+                }
+                _ = poll.tick(), if budget.max_tokens.is_some() => {
+                    let (Some(max_tokens), Some(thread_id)) = (budget.max_tokens, &thread_id) else {
+                        continue;
+                    };
+                    let usage = rollout_usage.poll(self.codex_home, thread_id).await?;
+                    if usage.total() > max_tokens {
+                        break Some(RunOutcome::OverBudget);
+                    }
+                }
+                _ = sleep_until(deadline.into()) => break Some(RunOutcome::TimedOut),
+            }
+        };
+        let mut timed_out = stopped == Some(RunOutcome::TimedOut);
+        if stopped.is_some() {
+            child.kill().await.context("failed to stop codex exec")?;
+        }
+        // stdout can close before the process exits, so waiting is bounded too.
+        let status = match timeout_at(deadline.into(), child.wait()).await {
+            Ok(status) => status.context("failed to wait for codex exec")?,
+            Err(_) => {
+                timed_out = true;
+                child.kill().await.context("failed to stop codex exec")?;
+                child
+                    .wait()
+                    .await
+                    .context("failed to wait for codex exec")?
+            }
+        };
+        let output = Output {
+            status,
+            stdout: session_stdout.into_bytes(),
+            // Processes the session left behind may still hold stderr open.
+            stderr: tokio::time::timeout(STDERR_GRACE, stderr)
+                .await
+                .ok()
+                .and_then(Result::ok)
+                .unwrap_or_default(),
+        };
+        self.write_log(&log_stem, "session", &output)?;
+
+        let stats = SessionStats::from_jsonl(&String::from_utf8_lossy(&output.stdout));
+        // A stopped session never reports `turn.completed`; fall back to the
+        // rollout's count.
+        result.usage = if stats.usage == TokenUsage::default() {
+            rollout_usage.usage
+        } else {
+            stats.usage
+        };
+        if let Some(thread_id) = &stats.thread_id {
+            result.rollout_path = find_thread_path_by_id_str(self.codex_home, thread_id).await?;
+        }
+        result.thread_id = stats.thread_id;
+
+        if timed_out {
+            result.outcome = RunOutcome::TimedOut;
+            result.error = Some(budget.exceeded("session"));
+            return Ok(());
+        }
+        if stopped.is_none() && !output.status.success() {
+            result.outcome = RunOutcome::Error;
+            result.error = Some(
+                stats
+                    .error
+                    .unwrap_or_else(|| format!("codex exec exited with {}", output.status)),
+            );
+            return Ok(());
+        }
+        if let Some(max_tokens) = budget.max_tokens
+            && result.usage.total() > max_tokens
+        {
+            result.outcome = RunOutcome::OverBudget;
+            result.error = Some(format!(
+                "used {} tokens, budget is {max_tokens}",
+                result.usage.total()
+            ));
+            return Ok(());
+        }
+
+        let Some(output) = run_until(shell(&task.verify, dir), deadline).await? else {
+            result.outcome = RunOutcome::TimedOut;
+            result.error = Some(budget.exceeded("verify"));
+            return Ok(());
+        };
+        self.write_log(&log_stem, "verify", &output)?;
+        result.outcome = if output.status.success() {
+            RunOutcome::Passed
+        } else {
+            RunOutcome::Failed
+        };
+        Ok(())
+    }
+
+    fn write_log(&self, stem: &str, step: &str, output: &Output) -> Result<()> {
+        let path = self.log_dir.join(format!("{stem}.{step}.log"));
+        let mut contents = output.stdout.clone();
+        contents.extend_from_slice(&output.stderr);
+        std::fs::write(&path, contents)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+/// A scratch working directory for one run, removed on drop unless kept.
+struct Workdir {
+    root: tempfile::TempDir,
+    worktree_repo: Option<PathBuf>,
+}
+
+impl Workdir {
+    async fn create(source: &TaskSource, keep: bool) -> Result<Self> {
+        let mut root = tempfile::Builder::new()
+            .prefix("codex-eval-")
+            .tempdir()
+            .context("failed to create a working directory")?;
+        root.disable_cleanup(keep);
+        let path = root.path().join("work");
+        let worktree_repo = match source {
+            TaskSource::Fixture(fixture) => {
+                copy_dir(fixture, &path)
+                    .with_context(|| format!("failed to copy fixture {}", fixture.display()))?;
+                None
+            }
+            TaskSource::GitRef { repo, git_ref } => {
+                let output = Command::new("git")
+                    .arg("-C")
+                    .arg(repo)
+                    .args(["worktree", "add", "--detach"])
+                    .arg(&path)
+                    .arg(git_ref)
+                    .output()
+                    .await
+                    .context("failed to run git worktree add")?;
+                if !output.status.success() {
+                    bail!(
+                        "git worktree add {git_ref} failed: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    );
+                }
+                Some(repo.clone())
+            }
+        };
+        let worktree_repo = if keep { None } else { worktree_repo };
+        Ok(Self {
+            root,
+            worktree_repo,
+        })
+    }
+
+    fn path(&self) -> PathBuf {
+        self.root.path().join("work")
+    }
+}
+
+impl Drop for Workdir {
+    fn drop(&mut self) {
+        if let Some(repo) = &self.worktree_repo {
+            let _ = std::process::Command::new("git")
+                .arg("-C")
+                .arg(repo)
+                .args(["worktree", "remove", "--force"])
+                .arg(self.path())
+                .output();
+        }
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Output of `command`, or `None` if it is still running at `deadline`, in
+/// which case it is killed.
+async fn run_until(mut command: Command, deadline: Instant) -> Result<Option<Output>> {
+    match timeout_at(deadline.into(), command.output()).await {
+        Ok(output) => Ok(Some(output.context("failed to run command")?)),
+        Err(_) => Ok(None),
+    }
+}
+
+fn shell(command: &str, cwd: &Path) -> Command {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    cmd.arg(command)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+    cmd
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn summarize(variants: &[Variant], runs: &[RunResult]) -> Vec<VariantSummary> {
+    variants
+        .iter()
+        .map(|variant| {
+            let label = variant.label();
+            let runs: Vec<&RunResult> = runs.iter().filter(|run| run.variant == label).collect();
+            let passed = runs
+                .iter()
+                .filter(|run| run.outcome == RunOutcome::Passed)
+                .count();
+            let total = runs.len();
+            VariantSummary {
+                variant: label,
+                passed,
+                total,
+                pass_rate: if total == 0 {
+                    0.0
+                } else {
+                    passed as f64 / total as f64
+                },
+                total_tokens: runs.iter().map(|run| run.usage.total()).sum(),
+                duration_secs: runs.iter().map(|run| run.duration_secs).sum(),
+            }
+        })
+        .collect()
+}
+
+fn render_markdown(report: &EvalReport) -> String {
+    let mut out = format!("# Eval report: {}\n\n", report.suite.display());
+
+    out.push_str("## Summary\n\n");
+    out.push_str("| Variant | Passed | Pass rate | Tokens | Duration |\n");
+    out.push_str("| --- | --- | --- | --- | --- |\n");
+    for summary in &report.summary {
+        out.push_str(&format!(
+            "| {} | {}/{} | {:.0}% | {} | {:.1}s |\n",
+            escape_cell(&summary.variant),
+            summary.passed,
+            summary.total,
+            summary.pass_rate * 100.0,
+            summary.total_tokens,
+            summary.duration_secs
+        ));
+    }
+
+    // One row per task with a column per variant makes regressions easy to spot.
+    let mut by_task: BTreeMap<&str, BTreeMap<&str, &RunResult>> = BTreeMap::new();
+    for run in &report.runs {
+        by_task
+            .entry(run.task.as_str())
+            .or_default()
+            .insert(run.variant.as_str(), run);
+    }
+    out.push_str("\n## Tasks\n\n| Task |");
+    for summary in &report.summary {
+        out.push_str(&format!(" {} |", escape_cell(&summary.variant)));
+    }
+    out.push_str("\n| --- |");
+    for _ in &report.summary {
+        out.push_str(" --- |");
+    }
+    out.push('\n');
+    for (task, runs) in &by_task {
+        out.push_str(&format!("| {} |", escape_cell(task)));
+        for summary in &report.summary {
+            let cell = runs
+                .get(summary.variant.as_str())
+                .map(|run| run.outcome.as_str())
+                .unwrap_or("-");
+            out.push_str(&format!(" {cell} |"));
+        }
+        out.push('\n');
+    }
+
+    out.push_str("\n## Runs\n\n");
+    out.push_str("| Task | Variant | Outcome | Tokens | Duration | Rollout |\n");
+    out.push_str("| --- | --- | --- | --- | --- | --- |\n");
+    for run in &report.runs {
+        let mut outcome = run.outcome.as_str().to_string();
+        if let Some(error) = &run.error {
+            outcome.push_str(&format!(": {error}"));
+        }
+        let rollout = run
+            .rollout_path
+            .as_ref()
+            .map(|path| format!("`{}`", path.display()))
+            .unwrap_or_else(|| "-".to_string());
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {:.1}s | {} |\n",
+            escape_cell(&run.task),
+            escape_cell(&run.variant),
+            escape_cell(&outcome),
+            run.usage.total(),
+            run.duration_secs,
+            rollout
+        ));
+    }
+    out
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SUITE: &str = r#"
+timeout_secs = 300
+
+[[task]]
+name = "fix-bug"
+prompt = "Fix the failing test."
+fixture = "fixtures/bug"
+setup = "npm ci"
+verify = "npm test"
+
+[[task]]
+name = "from-ref"
+prompt = "Add a changelog entry."
+git_ref = "v1.2.0"
+verify = "test -f CHANGELOG.md"
+timeout_secs = 60
+"#;
+
+    fn run(task: &str, variant: &str, outcome: RunOutcome, tokens: i64) -> RunResult {
+        RunResult {
+            task: task.to_string(),
+            variant: variant.to_string(),
+            model: Some(variant.to_string()),
+            profile: None,
+            outcome,
+            duration_secs: 2.0,
+            usage: TokenUsage {
+                input_tokens: tokens,
+                cached_input_tokens: 0,
+                output_tokens: 0,
+            },
+            thread_id: None,
+            rollout_path: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn parses_suite_and_resolves_fixture_paths() {
+        let suite =
+            EvalSuite::parse(SUITE, Path::new("/suites"), Path::new("/work")).expect("parse");
+
+        assert_eq!(suite.timeout_secs, Some(300));
+        assert_eq!(suite.tasks.len(), 2);
+        assert_eq!(
+            suite.tasks[0].fixture,
+            Some(PathBuf::from("/suites/fixtures/bug"))
+        );
+        assert_eq!(suite.tasks[0].setup.as_deref(), Some("npm ci"));
+        assert_eq!(suite.tasks[1].git_ref.as_deref(), Some("v1.2.0"));
+        assert_eq!(suite.tasks[1].timeout_secs, Some(60));
+        assert!(matches!(
+            suite.tasks[1].source(Path::new("/work")),
+            TaskSource::GitRef { repo, .. } if repo == Path::new("/work")
+        ));
+    }
+
+    #[test]
+    fn rejects_tasks_without_exactly_one_source() {
+        let both = r#"
+[[task]]
+name = "t"
+prompt = "p"
+fixture = "f"
+git_ref = "main"
+verify = "true"
+"#;
+        let neither = r#"
+[[task]]
+name = "t"
+prompt = "p"
+verify = "true"
+"#;
+        for contents in [both, neither] {
+            assert!(EvalSuite::parse(contents, Path::new("/"), Path::new("/")).is_err());
+        }
+    }
+
+    #[test]
+    fn variants_cover_every_model_and_profile() {
+        let variants = Variant::all(
+            &["gpt-a".to_string(), "gpt-b".to_string()],
+            &["fast".to_string()],
+        );
+        let labels: Vec<String> = variants.iter().map(Variant::label).collect();
+        assert_eq!(labels, vec!["gpt-a (profile fast)", "gpt-b (profile fast)"]);
+        assert_eq!(Variant::all(&[], &[])[0].label(), "default");
+    }
+
+    #[test]
+    fn session_stats_sum_usage_across_turns() {
+        let stdout = [
+            r#"{"type":"thread.started","thread_id":"abc"}"#,
+            r#"{"type":"turn.completed","usage":{"input_tokens":10,"cached_input_tokens":4,"output_tokens":5}}"#,
+            "not json",
+            r#"{"type":"turn.completed","usage":{"input_tokens":1,"cached_input_tokens":0,"output_tokens":2}}"#,
+        ]
+        .join("\n");
+
+        assert_eq!(
+            SessionStats::from_jsonl(&stdout),
+            SessionStats {
+                thread_id: Some("abc".to_string()),
+                usage: TokenUsage {
+                    input_tokens: 11,
+                    cached_input_tokens: 4,
+                    output_tokens: 7,
+                },
+                error: None,
+            }
+        );
+    }
+
+    #[test]
+    fn markdown_report_compares_variants() {
+        let variants = Variant::all(&["gpt-a".to_string(), "gpt-b".to_string()], &[]);
+        let runs = vec![
+            run("fix-bug", "gpt-a", RunOutcome::Passed, 100),
+            run("fix-bug", "gpt-b", RunOutcome::Failed, 50),
+            run("from-ref", "gpt-a", RunOutcome::Passed, 100),
+            run("from-ref", "gpt-b", RunOutcome::TimedOut, 0),
+        ];
+        let report = EvalReport {
+            suite: PathBuf::from("suite.toml"),
+            summary: summarize(&variants, &runs),
+            runs,
+        };
+
+        let markdown = render_markdown(&report);
+        assert!(markdown.contains("| gpt-a | 2/2 | 100% | 200 | 4.0s |"));
+        assert!(markdown.contains("| gpt-b | 0/2 | 0% | 50 | 4.0s |"));
+        assert!(markdown.contains("| Task | gpt-a | gpt-b |"));
+        assert!(markdown.contains("| from-ref | passed | timed out |"));
+    }
+
+    fn token_count_line(input_tokens: i64, output_tokens: i64) -> String {
+        let usage = codex_core::protocol::TokenUsage {
+            input_tokens,
+            output_tokens,
+            total_tokens: input_tokens + output_tokens,
+            ..Default::default()
+        };
+        let line = RolloutLine {
+            timestamp: "2026-10-19T00:00:00Z".to_string(),
+            item: RolloutItem::EventMsg(EventMsg::TokenCount(
+                codex_core::protocol::TokenCountEvent {
+                    info: Some(codex_core::protocol::TokenUsageInfo {
+                        total_token_usage: usage.clone(),
+                        last_token_usage: usage,
+                        model_context_window: None,
+                    }),
+                    rate_limits: None,
+                    context_tokens: None,
+                },
+            )),
+        };
+        format!("{}\n", serde_json::to_string(&line).expect("serialize"))
+    }
+
+    #[tokio::test]
+    async fn rollout_usage_follows_appended_token_counts() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("rollout.jsonl");
+        let first = token_count_line(100, 10);
+        let second = token_count_line(300, 30);
+        let (head, tail) = second.split_at(20);
+        std::fs::write(&path, format!("{first}{head}")).expect("write");
+        let mut rollout_usage = RolloutUsage {
+            path: Some(path.clone()),
+            ..Default::default()
+        };
+
+        let usage = rollout_usage
+            .poll(dir.path(), "unused")
+            .await
+            .expect("poll");
+        assert_eq!(usage.total(), 110);
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open");
+        std::io::Write::write_all(&mut file, tail.as_bytes()).expect("append");
+        let usage = rollout_usage
+            .poll(dir.path(), "unused")
+            .await
+            .expect("poll");
+        assert_eq!(usage.total(), 330);
+    }
+}
@@ -0,0 +1,135 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use clap::Parser;
+use clap::ValueEnum;
+use codex_common::CliConfigOverrides;
+use codex_core::INTERACTIVE_SESSION_SOURCES;
+use codex_core::RolloutRecorder;
+use codex_core::ThreadSortKey;
+use codex_core::TranscriptExportOptions;
+use codex_core::TranscriptFormat;
+use codex_core::config::Config;
+use codex_core::export_rollout_transcript;
+use codex_core::find_thread_path_by_id_str;
+use codex_core::find_thread_path_by_name_str;
+use codex_protocol::ThreadId;
+
+/// Export a recorded session as a Markdown or HTML transcript.
+#[derive(Debug, Parser)]
+pub struct ExportCommand {
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Conversation/session id (UUID) or thread name. UUIDs take precedence if it parses.
+    /// If omitted, use --last to export the most recent recorded session.
+    #[arg(value_name = "SESSION_ID", required_unless_present = "last")]
+    pub session_id: Option<String>,
+
+    /// Export the most recent session.
+    #[arg(long = "last", default_value_t = false, conflicts_with = "session_id")]
+    pub last: bool,
+
+    /// With --last, consider sessions from any directory instead of only the current one.
+    #[arg(long = "all", default_value_t = false, requires = "last")]
+    pub all: bool,
+
+    /// Transcript format.
+    #[arg(long = "format", value_enum, default_value_t = ExportFormatArg::Md)]
+    pub format: ExportFormatArg,
+
+    /// Write the transcript to this file instead of stdout.
+    #[arg(short = 'o', long = "output", value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Replace the session working directory and home directory in the transcript.
+    #[arg(long = "redact-paths", default_value_t = false)]
+    pub redact_paths: bool,
+
+    /// Leave out command and tool output, keeping only the invocations.
+    #[arg(long = "no-tool-output", default_value_t = false)]
+    pub no_tool_output: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormatArg {
+    #[value(alias = "markdown")]
+    Md,
+    Html,
+}
+
+impl From<ExportFormatArg> for TranscriptFormat {
+    fn from(value: ExportFormatArg) -> Self {
+        match value {
+            ExportFormatArg::Md => TranscriptFormat::Markdown,
+            ExportFormatArg::Html => TranscriptFormat::Html,
+        }
+    }
+}
+
+pub async fn run_export_command(cmd: ExportCommand) -> Result<()> {
+    let overrides = cmd
+        .config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(overrides)
+        .await
+        .context("failed to load configuration")?;
+
+    let path = match cmd.session_id.as_deref() {
+        Some(id_str) => {
+            let path = if ThreadId::from_string(id_str).is_ok() {
+                find_thread_path_by_id_str(&config.codex_home, id_str).await?
+            } else {
+                find_thread_path_by_name_str(&config.codex_home, id_str).await?
+            };
+            match path {
+                Some(path) => path,
+                None => bail!("No saved session found with ID or name {id_str}."),
+            }
+        }
+        None => {
+            let provider_filter = vec![config.model_provider_id.clone()];
+            let filter_cwd = if cmd.all {
+                None
+            } else {
+                Some(config.cwd.as_path())
+            };
+            RolloutRecorder::find_latest_thread_path(
+                &config.codex_home,
+                1,
+                None,
+                ThreadSortKey::UpdatedAt,
+                INTERACTIVE_SESSION_SOURCES,
+                Some(provider_filter.as_slice()),
+                &config.model_provider_id,
+                filter_cwd,
+            )
+            .await?
+            .context("No recorded sessions found.")?
+        }
+    };
+
+    let options = TranscriptExportOptions {
+        redact_paths: cmd.redact_paths,
+        omit_tool_output: cmd.no_tool_output,
+    };
+    let transcript = export_rollout_transcript(&path, cmd.format.into(), &options)
+        .await
+        .with_context(|| format!("failed to read session {}", path.display()))?;
+
+    match cmd.output {
+        Some(output) => {
+            std::fs::write(&output, transcript)
+                .with_context(|| format!("failed to write {}", output.display()))?;
+            eprintln!("Exported session to {}", output.display());
+        }
+        None => {
+            std::io::stdout().write_all(transcript.as_bytes())?;
+        }
+    }
+    Ok(())
+}
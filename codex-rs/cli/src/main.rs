@@ -19,6 +19,7 @@ use codex_cloud_tasks::Cli as CloudTasksCli;
 use codex_common::CliConfigOverrides;
 use codex_exec::Cli as ExecCli;
 use codex_exec::Command as ExecCommand;
+use codex_exec::CommitArgs;
 use codex_exec::ReviewArgs;
 use codex_execpolicy::ExecPolicyCheckCommand;
 use codex_responses_api_proxy::Args as ResponsesApiProxyArgs;
@@ -33,12 +34,18 @@ use supports_color::Stream;
 
 #[cfg(target_os = "macos")]
 mod app_cmd;
+mod config_cmd;
 #[cfg(target_os = "macos")]
 mod desktop_app;
+mod eval_cmd;
+mod export_cmd;
 mod mcp_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
+use crate::config_cmd::ConfigCli;
+use crate::eval_cmd::EvalCommand;
+use crate::export_cmd::ExportCommand;
 use crate::mcp_cmd::McpCli;
 
 use codex_core::config::Config;
@@ -87,6 +94,9 @@ enum Subcommand {
     /// Run a code review non-interactively.
     Review(ReviewArgs),
 
+    /// Split the uncommitted changes into commits with drafted messages.
+    Commit(CommitArgs),
+
     /// Manage login.
     Login(LoginCommand),
 
@@ -129,6 +139,12 @@ enum Subcommand {
     /// Fork a previous interactive session (picker by default; use --last to fork the most recent).
     Fork(ForkCommand),
 
+    /// Export a recorded session as a Markdown or HTML transcript.
+    Export(ExportCommand),
+
+    /// Run a suite of tasks against one or more models and report pass rates.
+    Eval(EvalCommand),
+
     /// [EXPERIMENTAL] Browse tasks from Codex Cloud and apply changes locally.
     #[clap(name = "cloud", alias = "cloud-tasks")]
     Cloud(CloudTasksCli),
@@ -143,6 +159,9 @@ enum Subcommand {
 
     /// Inspect feature flags.
     Features(FeaturesCli),
+
+    /// Get, set, list, and validate configuration settings.
+    Config(ConfigCli),
 }
 
 #[derive(Debug, Parser)]
@@ -577,6 +596,15 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             codex_exec::run_main(exec_cli, codex_linux_sandbox_exe).await?;
         }
+        Some(Subcommand::Commit(commit_args)) => {
+            let mut exec_cli = ExecCli::try_parse_from(["codex", "exec"])?;
+            exec_cli.command = Some(ExecCommand::Commit(commit_args));
+            prepend_config_flags(
+                &mut exec_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            codex_exec::run_main(exec_cli, codex_linux_sandbox_exe).await?;
+        }
         Some(Subcommand::McpServer) => {
             codex_mcp_server::run_main(codex_linux_sandbox_exe, root_config_overrides).await?;
         }
@@ -651,6 +679,20 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             let exit_info = run_interactive_tui(interactive, codex_linux_sandbox_exe).await?;
             handle_app_exit(exit_info)?;
         }
+        Some(Subcommand::Export(mut export_cli)) => {
+            prepend_config_flags(
+                &mut export_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            export_cmd::run_export_command(export_cli).await?;
+        }
+        Some(Subcommand::Eval(mut eval_cli)) => {
+            prepend_config_flags(
+                &mut eval_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            eval_cmd::run_eval_command(eval_cli).await?;
+        }
         Some(Subcommand::Login(mut login_cli)) => {
             prepend_config_flags(
                 &mut login_cli.config_overrides,
@@ -758,6 +800,13 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             tokio::task::spawn_blocking(move || codex_stdio_to_uds::run(socket_path.as_path()))
                 .await??;
         }
+        Some(Subcommand::Config(mut config_cli)) => {
+            prepend_config_flags(
+                &mut config_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            config_cli.run().await?;
+        }
         Some(Subcommand::Features(FeaturesCli { sub })) => match sub {
             FeaturesSubcommand::List => {
                 // Respect root-level `-c` overrides plus top-level flags like `--profile`.
@@ -1324,6 +1373,59 @@ mod tests {
         assert!(interactive.fork_show_all);
     }
 
+    #[test]
+    fn export_parses_format_and_flags() {
+        let cli = MultitoolCli::try_parse_from([
+            "codex",
+            "export",
+            "--last",
+            "--format",
+            "html",
+            "--redact-paths",
+        ])
+        .expect("parse should succeed");
+        let Some(Subcommand::Export(export)) = cli.subcommand else {
+            panic!("expected export subcommand");
+        };
+        assert!(export.last);
+        assert_eq!(export.session_id, None);
+        assert_eq!(export.format, export_cmd::ExportFormatArg::Html);
+        assert!(export.redact_paths);
+        assert!(!export.no_tool_output);
+    }
+
+    #[test]
+    fn eval_parses_repeated_models_and_profiles() {
+        let cli = MultitoolCli::try_parse_from([
+            "codex",
+            "eval",
+            "suite.toml",
+            "-m",
+            "gpt-a",
+            "--model",
+            "gpt-b",
+            "-p",
+            "fast",
+            "--output",
+            "out",
+        ])
+        .expect("parse should succeed");
+        let Some(Subcommand::Eval(eval)) = cli.subcommand else {
+            panic!("expected eval subcommand");
+        };
+        assert_eq!(eval.suite, PathBuf::from("suite.toml"));
+        assert_eq!(eval.models, vec!["gpt-a".to_string(), "gpt-b".to_string()]);
+        assert_eq!(eval.profiles, vec!["fast".to_string()]);
+        assert_eq!(eval.output, Some(PathBuf::from("out")));
+        assert!(!eval.keep_workdirs);
+    }
+
+    #[test]
+    fn export_requires_session_id_or_last() {
+        let result = MultitoolCli::try_parse_from(["codex", "export"]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn app_server_analytics_default_disabled_without_flag() {
         let app_server = app_server_from_args(["codex", "app-server"].as_ref());
@@ -1337,6 +1439,39 @@ mod tests {
         assert!(app_server.analytics_default_enabled);
     }
 
+    #[test]
+    fn config_list_parses_show_origin() {
+        let cli = MultitoolCli::try_parse_from(["codex", "config", "list", "--show-origin"])
+            .expect("parse should succeed");
+        let Some(Subcommand::Config(config_cli)) = cli.subcommand else {
+            panic!("expected config subcommand");
+        };
+        let config_cmd::ConfigSubcommand::List(list) = config_cli.subcommand else {
+            panic!("expected config list");
+        };
+        assert!(list.show_origin);
+    }
+
+    #[test]
+    fn config_set_parses_key_and_value() {
+        let cli = MultitoolCli::try_parse_from([
+            "codex",
+            "config",
+            "set",
+            "sandbox_mode",
+            "workspace-write",
+        ])
+        .expect("parse should succeed");
+        let Some(Subcommand::Config(config_cli)) = cli.subcommand else {
+            panic!("expected config subcommand");
+        };
+        let config_cmd::ConfigSubcommand::Set(set) = config_cli.subcommand else {
+            panic!("expected config set");
+        };
+        assert_eq!(set.key, "sandbox_mode");
+        assert_eq!(set.value, "workspace-write");
+    }
+
     #[test]
     fn features_enable_parses_feature_name() {
         let cli = MultitoolCli::try_parse_from(["codex", "features", "enable", "unified_exec"])
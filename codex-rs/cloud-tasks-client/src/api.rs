@@ -107,6 +107,37 @@ pub struct DiffSummary {
     pub lines_removed: usize,
 }
 
+impl DiffSummary {
+    /// Count files and added/removed lines in a unified git diff.
+    pub fn from_unified_diff(diff: &str) -> Self {
+        let mut files_changed = 0usize;
+        let mut lines_added = 0usize;
+        let mut lines_removed = 0usize;
+        for line in diff.lines() {
+            if line.starts_with("diff --git ") {
+                files_changed += 1;
+                continue;
+            }
+            if line.starts_with("+++") || line.starts_with("---") || line.starts_with("@@") {
+                continue;
+            }
+            match line.as_bytes().first() {
+                Some(b'+') => lines_added += 1,
+                Some(b'-') => lines_removed += 1,
+                _ => {}
+            }
+        }
+        if files_changed == 0 && !diff.trim().is_empty() {
+            files_changed = 1;
+        }
+        Self {
+            files_changed,
+            lines_added,
+            lines_removed,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TaskText {
     pub prompt: Option<String>,
@@ -207,7 +207,7 @@ mod api {
                 && summary.lines_removed == 0
                 && let Some(diff) = details.unified_diff()
             {
-                summary = diff_summary_from_diff(&diff);
+                summary = DiffSummary::from_unified_diff(&diff);
             }
             let updated_at_raw = task_obj
                 .get("updated_at")
@@ -772,34 +772,6 @@ mod api {
             .map(str::to_string)
     }
 
-    fn diff_summary_from_diff(diff: &str) -> DiffSummary {
-        let mut files_changed = 0usize;
-        let mut lines_added = 0usize;
-        let mut lines_removed = 0usize;
-        for line in diff.lines() {
-            if line.starts_with("diff --git ") {
-                files_changed += 1;
-                continue;
-            }
-            if line.starts_with("+++") || line.starts_with("---") || line.starts_with("@@") {
-                continue;
-            }
-            match line.as_bytes().first() {
-                Some(b'+') => lines_added += 1,
-                Some(b'-') => lines_removed += 1,
-                _ => {}
-            }
-        }
-        if files_changed == 0 && !diff.trim().is_empty() {
-            files_changed = 1;
-        }
-        DiffSummary {
-            files_changed,
-            lines_added,
-            lines_removed,
-        }
-    }
-
     fn diff_summary_from_status_display(v: Option<&HashMap<String, Value>>) -> DiffSummary {
         let mut out = DiffSummary::default();
         let Some(map) = v else { return out };
@@ -0,0 +1,1314 @@
+//! A [`CloudBackend`] that runs tasks on this machine.
+//!
+//! Each task is a directory under the client's root holding `task.json` and the prompt. Every
+//! attempt gets its own detached git worktree at the task's base commit and a headless
+//! `codex exec --json` process whose event stream is written to `events.jsonl`. Status, messages
+//! and diffs are derived from those files on read, so attempts keep running after the process
+//! that queued them exits and any later `codex cloud` invocation can pick up the results.
+//!
+//! At most a few attempts run at once; the rest wait in a bounded on-disk queue and are started
+//! by whichever call next lists or summarizes tasks. An attempt whose process died without
+//! finishing its turn is reported as failed, and a finished attempt's worktree is removed once
+//! its diff is cached. All file and git work runs on the blocking thread pool.
+
+use crate::ApplyOutcome;
+use crate::ApplyStatus;
+use crate::AttemptStatus;
+use crate::CloudBackend;
+use crate::CloudTaskError;
+use crate::CreatedTask;
+use crate::DiffSummary;
+use crate::Result;
+use crate::TaskId;
+use crate::TaskListPage;
+use crate::TaskStatus;
+use crate::TaskSummary;
+use crate::TurnAttempt;
+use crate::api::TaskText;
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::SystemTime;
+
+const TASK_FILE: &str = "task.json";
+const PROMPT_FILE: &str = "prompt.md";
+const EVENTS_FILE: &str = "events.jsonl";
+const STDERR_FILE: &str = "stderr.log";
+const DIFF_FILE: &str = "diff.patch";
+const DIFF_INDEX_FILE: &str = "diff.index";
+const APPLIED_FILE: &str = "applied";
+const WORKTREE_DIR: &str = "worktree";
+/// Marks an attempt waiting for a free slot.
+const QUEUED_FILE: &str = "queued";
+/// Marks an attempt claimed from the queue whose process has not recorded its pid yet.
+const STARTING_FILE: &str = "starting";
+const PID_FILE: &str = "pid";
+/// Exit status of the attempt's process, written once it is reaped.
+const EXIT_FILE: &str = "exit";
+const MAX_TITLE_CHARS: usize = 80;
+/// Attempts that may run at once across all local tasks.
+const DEFAULT_MAX_RUNNING_ATTEMPTS: usize = 4;
+/// Attempts that may wait for a free slot before new tasks are refused.
+const DEFAULT_MAX_QUEUED_ATTEMPTS: usize = 32;
+/// How long a claimed attempt may take to start before it is treated as lost.
+const START_GRACE: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug)]
+pub struct LocalClient {
+    root: PathBuf,
+    exec_program: PathBuf,
+    exec_args: Vec<String>,
+    max_running: usize,
+    max_queued: usize,
+    /// Serializes queue decisions made by this process. Other `codex cloud` processes only ever
+    /// claim an attempt through an atomic rename, so an attempt never starts twice.
+    queue_lock: Arc<Mutex<()>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct LocalTask {
+    id: String,
+    title: String,
+    repo_root: PathBuf,
+    git_ref: String,
+    base_sha: String,
+    qa_mode: bool,
+    attempts: usize,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct AttemptState {
+    status: AttemptStatus,
+    messages: Vec<String>,
+    error: Option<String>,
+}
+
+struct LoadedAttempt {
+    number: usize,
+    state: AttemptState,
+    diff: Option<String>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+/// Where an attempt's `codex exec` process is, independent of what its event log says.
+#[derive(Debug, PartialEq, Eq)]
+enum Launch {
+    Queued,
+    Starting,
+    Running,
+    Exited(String),
+    /// Claimed from the queue but never started, e.g. because the claiming process died.
+    Lost,
+}
+
+#[derive(Default)]
+struct QueueSnapshot {
+    running: usize,
+    /// Queued attempts, oldest task first.
+    queued: Vec<(LocalTask, usize)>,
+}
+
+impl LocalClient {
+    /// Store tasks under `root`, launching attempts with the `codex` found on `PATH`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            exec_program: PathBuf::from("codex"),
+            exec_args: Vec::new(),
+            max_running: DEFAULT_MAX_RUNNING_ATTEMPTS,
+            max_queued: DEFAULT_MAX_QUEUED_ATTEMPTS,
+            queue_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Override the program (and leading arguments) used to launch `exec` for each attempt.
+    pub fn with_exec_command(mut self, program: impl Into<PathBuf>, args: Vec<String>) -> Self {
+        self.exec_program = program.into();
+        self.exec_args = args;
+        self
+    }
+
+    /// Override how many attempts may run at once and how many may wait for a free slot.
+    pub fn with_limits(mut self, max_running: usize, max_queued: usize) -> Self {
+        self.max_running = max_running.max(1);
+        self.max_queued = max_queued;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Run `f` on the blocking pool: every backend call reads files and runs git.
+    async fn blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(LocalClient) -> Result<T> + Send + 'static,
+    {
+        let client = self.clone();
+        tokio::task::spawn_blocking(move || f(client))
+            .await
+            .map_err(|e| CloudTaskError::Msg(format!("local task worker failed: {e}")))?
+    }
+
+    /// Directory of task `id`. Ids come from callers, so anything other than an id
+    /// `create_task_dir` could have generated is rejected before it reaches the filesystem.
+    fn task_dir(&self, id: &str) -> Result<PathBuf> {
+        if !is_task_id(id) {
+            return Err(CloudTaskError::Msg(format!("Task {id} not found")));
+        }
+        Ok(self.root.join(id))
+    }
+
+    /// Directory of a task returned by `load_task` or `create`, whose id was already checked.
+    fn loaded_task_dir(&self, task: &LocalTask) -> PathBuf {
+        self.root.join(&task.id)
+    }
+
+    fn load_task(&self, id: &str) -> Result<LocalTask> {
+        let path = self.task_dir(id)?.join(TASK_FILE);
+        let raw = std::fs::read_to_string(&path)
+            .map_err(|_| CloudTaskError::Msg(format!("Task {id} not found")))?;
+        let task: LocalTask = serde_json::from_str(&raw)
+            .map_err(|e| CloudTaskError::Msg(format!("failed to parse {}: {e}", path.display())))?;
+        // Later paths are built from the stored id, so it must name this directory.
+        if task.id != id {
+            return Err(CloudTaskError::Msg(format!(
+                "{} belongs to task {}, not {id}",
+                path.display(),
+                task.id
+            )));
+        }
+        Ok(task)
+    }
+
+    fn load_tasks(&self) -> Result<Vec<LocalTask>> {
+        let entries = match std::fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(CloudTaskError::Io(e.to_string())),
+        };
+        let mut tasks: Vec<LocalTask> = entries
+            .filter_map(std::result::Result::ok)
+            .filter_map(|entry| {
+                let id = entry.file_name().to_str()?.to_string();
+                self.load_task(&id).ok()
+            })
+            .collect();
+        tasks.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(tasks)
+    }
+
+    fn attempt_dir(&self, task: &LocalTask, number: usize) -> PathBuf {
+        self.loaded_task_dir(task).join(format!("attempt-{number}"))
+    }
+
+    fn load_attempt(&self, task: &LocalTask, number: usize) -> LoadedAttempt {
+        let dir = self.attempt_dir(task, number);
+        let events_path = dir.join(EVENTS_FILE);
+        let events = std::fs::read_to_string(&events_path).unwrap_or_default();
+        let mut state = attempt_state_from_events(&events);
+        if matches!(
+            state.status,
+            AttemptStatus::Pending | AttemptStatus::InProgress
+        ) {
+            // The event log only records what the process got to say; a killed or crashed
+            // process leaves it mid-turn.
+            let error = match launch_state(&dir) {
+                Launch::Queued | Launch::Starting | Launch::Running => None,
+                Launch::Exited(status) => Some(format!(
+                    "codex exec exited ({status}) before the turn finished{}",
+                    stderr_hint(&dir)
+                )),
+                Launch::Lost => Some("the attempt was never started".to_string()),
+            };
+            if let Some(error) = error {
+                state.status = AttemptStatus::Failed;
+                state.error = Some(error);
+            }
+        }
+        let diff = if state.status == AttemptStatus::Completed {
+            self.attempt_diff(task, &dir)
+        } else {
+            None
+        };
+        let updated_at = std::fs::metadata(&events_path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .map(DateTime::<Utc>::from);
+        LoadedAttempt {
+            number,
+            state,
+            diff,
+            updated_at,
+        }
+    }
+
+    fn load_attempts(&self, task: &LocalTask) -> Vec<LoadedAttempt> {
+        (1..=task.attempts)
+            .map(|number| self.load_attempt(task, number))
+            .collect()
+    }
+
+    /// The diff an attempt produced against the task's base commit, including new files.
+    /// Computed once the attempt completes and cached next to its event log.
+    fn attempt_diff(&self, task: &LocalTask, dir: &Path) -> Option<String> {
+        let cached = dir.join(DIFF_FILE);
+        if let Ok(diff) = std::fs::read_to_string(&cached) {
+            return (!diff.trim().is_empty()).then_some(diff);
+        }
+        let diff = worktree_diff(&dir.join(WORKTREE_DIR), &dir.join(DIFF_INDEX_FILE), task).ok()?;
+        // Write then rename so a concurrent reader never sees a partial patch.
+        let partial = dir.join(format!("{DIFF_FILE}.tmp"));
+        if std::fs::write(&partial, &diff).is_ok() {
+            let _ = std::fs::rename(&partial, &cached);
+        }
+        (!diff.trim().is_empty()).then_some(diff)
+    }
+
+    fn summarize(&self, task: &LocalTask) -> TaskSummary {
+        let attempts = self.load_attempts(task);
+        let applied = self.loaded_task_dir(task).join(APPLIED_FILE).exists();
+        let status = if applied {
+            TaskStatus::Applied
+        } else if attempts.iter().any(|attempt| {
+            matches!(
+                attempt.state.status,
+                AttemptStatus::Pending | AttemptStatus::InProgress
+            )
+        }) {
+            TaskStatus::Pending
+        } else if attempts
+            .iter()
+            .any(|attempt| attempt.state.status == AttemptStatus::Completed)
+        {
+            TaskStatus::Ready
+        } else {
+            TaskStatus::Error
+        };
+        let summary = primary_attempt(&attempts)
+            .and_then(|attempt| attempt.diff.as_deref())
+            .map(DiffSummary::from_unified_diff)
+            .unwrap_or_default();
+        let updated_at = attempts
+            .iter()
+            .filter_map(|attempt| attempt.updated_at)
+            .max()
+            .unwrap_or(task.created_at);
+        TaskSummary {
+            id: TaskId(task.id.clone()),
+            title: task.title.clone(),
+            status,
+            updated_at,
+            environment_id: Some(task.repo_root.display().to_string()),
+            environment_label: task
+                .repo_root
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string()),
+            summary,
+            is_review: false,
+            attempt_total: Some(task.attempts),
+        }
+    }
+
+    /// Count running attempts and collect queued ones. Finished attempts get their worktree
+    /// removed along the way, once their diff is cached.
+    fn queue_snapshot(&self) -> Result<QueueSnapshot> {
+        let mut snapshot = QueueSnapshot::default();
+        let mut tasks = self.load_tasks()?;
+        tasks.reverse();
+        for task in tasks {
+            for number in 1..=task.attempts {
+                let dir = self.attempt_dir(&task, number);
+                match launch_state(&dir) {
+                    Launch::Queued => snapshot.queued.push((task.clone(), number)),
+                    Launch::Starting | Launch::Running => snapshot.running += 1,
+                    Launch::Exited(_) | Launch::Lost => {
+                        if dir.join(WORKTREE_DIR).exists() {
+                            // Loading caches the diff of a completed attempt.
+                            let attempt = self.load_attempt(&task, number);
+                            if attempt.state.status != AttemptStatus::Completed
+                                || dir.join(DIFF_FILE).exists()
+                            {
+                                remove_worktree(&task.repo_root, &dir.join(WORKTREE_DIR));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Start queued attempts, oldest first, while there are free slots.
+    fn pump_queue(&self) -> Result<()> {
+        let _guard = self
+            .queue_lock
+            .lock()
+            .map_err(|_| CloudTaskError::Msg("local task queue lock poisoned".to_string()))?;
+        let snapshot = self.queue_snapshot()?;
+        let mut running = snapshot.running;
+        for (task, number) in snapshot.queued {
+            if running >= self.max_running {
+                break;
+            }
+            let dir = self.attempt_dir(&task, number);
+            if !claim_attempt(&dir) {
+                continue;
+            }
+            running += 1;
+            if let Err(err) = self.spawn_attempt(&task, number) {
+                // The task already exists, so record the failure on the attempt instead.
+                let line = serde_json::json!({ "type": "error", "message": err.to_string() });
+                let _ = std::fs::write(dir.join(EVENTS_FILE), format!("{line}\n"));
+                let _ = std::fs::write(dir.join(EXIT_FILE), "not started");
+                let _ = std::fs::remove_file(dir.join(STARTING_FILE));
+                running -= 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Launch a claimed attempt in a fresh worktree. On error nothing is left running and the
+    /// worktree is removed again. Returns the child's pid.
+    fn spawn_attempt(&self, task: &LocalTask, number: usize) -> Result<u32> {
+        let dir = self.attempt_dir(task, number);
+        let worktree = dir.join(WORKTREE_DIR);
+        let worktree_arg = worktree.to_string_lossy().to_string();
+        run_git(
+            &task.repo_root,
+            &[
+                "worktree",
+                "add",
+                "--detach",
+                worktree_arg.as_str(),
+                task.base_sha.as_str(),
+            ],
+        )?;
+        let result = self.launch_exec(task, &dir, &worktree);
+        if result.is_err() {
+            remove_worktree(&task.repo_root, &worktree);
+        }
+        result
+    }
+
+    fn launch_exec(&self, task: &LocalTask, dir: &Path, worktree: &Path) -> Result<u32> {
+        let io = |e: std::io::Error| CloudTaskError::Io(e.to_string());
+        let events = File::create(dir.join(EVENTS_FILE)).map_err(io)?;
+        let stderr = File::create(dir.join(STDERR_FILE)).map_err(io)?;
+        let prompt = File::open(self.loaded_task_dir(task).join(PROMPT_FILE)).map_err(io)?;
+
+        let mut cmd = Command::new(&self.exec_program);
+        cmd.args(&self.exec_args)
+            .arg("exec")
+            .arg("--json")
+            .arg("--cd")
+            .arg(worktree);
+        if task.qa_mode {
+            cmd.args(["--sandbox", "read-only"]);
+        } else {
+            cmd.arg("--full-auto");
+        }
+        cmd.arg("-")
+            .stdin(Stdio::from(prompt))
+            .stdout(Stdio::from(events))
+            .stderr(Stdio::from(stderr));
+        let mut child = cmd.spawn().map_err(|e| {
+            CloudTaskError::Io(format!(
+                "failed to launch {}: {e}",
+                self.exec_program.display()
+            ))
+        })?;
+        let pid = child.id();
+        if let Err(e) = std::fs::write(dir.join(PID_FILE), pid.to_string()) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(CloudTaskError::Io(e.to_string()));
+        }
+        let _ = std::fs::remove_file(dir.join(STARTING_FILE));
+
+        // Attempts outlive this process and report back through their event log. While this
+        // process is alive it reaps the child and records how it exited; afterwards the pid is
+        // checked directly.
+        let exit_path = dir.join(EXIT_FILE);
+        std::thread::spawn(move || {
+            let status = match child.wait() {
+                Ok(status) => status.to_string(),
+                Err(e) => format!("unknown status: {e}"),
+            };
+            let _ = std::fs::write(exit_path, status);
+        });
+        Ok(pid)
+    }
+
+    /// Undo a task whose attempts could not all be started.
+    fn roll_back_task(&self, task: &LocalTask, started: &[(usize, u32)]) {
+        for (number, pid) in started {
+            kill_process(*pid);
+            remove_worktree(
+                &task.repo_root,
+                &self.attempt_dir(task, *number).join(WORKTREE_DIR),
+            );
+        }
+        let _ = std::fs::remove_dir_all(self.loaded_task_dir(task));
+    }
+
+    fn create_task_dir(&self) -> Result<(String, PathBuf)> {
+        std::fs::create_dir_all(&self.root).map_err(|e| CloudTaskError::Io(e.to_string()))?;
+        let base = format!("local_{}", Utc::now().format("%Y%m%d%H%M%S%3f"));
+        for suffix in 0.. {
+            let id = if suffix == 0 {
+                base.clone()
+            } else {
+                format!("{base}_{suffix}")
+            };
+            let dir = self.task_dir(&id)?;
+            match std::fs::create_dir(&dir) {
+                Ok(()) => return Ok((id, dir)),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(CloudTaskError::Io(e.to_string())),
+            }
+        }
+        unreachable!("unbounded range always yields a candidate id")
+    }
+
+    fn create(
+        &self,
+        env_id: &str,
+        prompt: &str,
+        git_ref: &str,
+        qa_mode: bool,
+        best_of_n: usize,
+    ) -> Result<CreatedTask> {
+        let repo_root = PathBuf::from(env_id);
+        if !repo_root.is_dir() {
+            return Err(CloudTaskError::Msg(format!(
+                "local environment {env_id} is not a directory"
+            )));
+        }
+        let spec = format!("{git_ref}^{{commit}}");
+        let base_sha = run_git(&repo_root, &["rev-parse", "--verify", spec.as_str()])
+            .map_err(|_| {
+                CloudTaskError::Msg(format!(
+                    "git ref '{git_ref}' does not resolve to a commit in {env_id}"
+                ))
+            })?
+            .trim()
+            .to_string();
+        let attempts = best_of_n.max(1);
+
+        let _guard = self
+            .queue_lock
+            .lock()
+            .map_err(|_| CloudTaskError::Msg("local task queue lock poisoned".to_string()))?;
+        let snapshot = self.queue_snapshot()?;
+        let free = self.max_running.saturating_sub(snapshot.running);
+        let waiting = snapshot.queued.len() + attempts.saturating_sub(free);
+        if waiting > self.max_queued {
+            return Err(CloudTaskError::Msg(format!(
+                "the local task queue is full ({} attempts running, {} waiting); try again once some finish",
+                snapshot.running,
+                snapshot.queued.len()
+            )));
+        }
+
+        let (id, dir) = self.create_task_dir()?;
+        let task = LocalTask {
+            id: id.clone(),
+            title: title_from_prompt(prompt),
+            repo_root,
+            git_ref: git_ref.to_string(),
+            base_sha,
+            qa_mode,
+            attempts,
+            created_at: Utc::now(),
+        };
+        let write_files = || -> std::io::Result<()> {
+            std::fs::write(dir.join(PROMPT_FILE), prompt)?;
+            for number in 1..=task.attempts {
+                let attempt_dir = self.attempt_dir(&task, number);
+                std::fs::create_dir_all(&attempt_dir)?;
+                std::fs::write(attempt_dir.join(QUEUED_FILE), "")?;
+            }
+            let json = serde_json::to_string_pretty(&task)?;
+            std::fs::write(dir.join(TASK_FILE), json)
+        };
+        if let Err(e) = write_files() {
+            self.roll_back_task(&task, &[]);
+            return Err(CloudTaskError::Io(e.to_string()));
+        }
+
+        // Start what fits now; the rest is picked up by later calls once slots free up.
+        let mut started = Vec::new();
+        for number in 1..=task.attempts.min(free) {
+            if !claim_attempt(&self.attempt_dir(&task, number)) {
+                continue;
+            }
+            match self.spawn_attempt(&task, number) {
+                Ok(pid) => started.push((number, pid)),
+                Err(err) => {
+                    self.roll_back_task(&task, &started);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(CreatedTask { id: TaskId(id) })
+    }
+
+    fn apply(
+        &self,
+        id: TaskId,
+        diff_override: Option<String>,
+        preflight: bool,
+    ) -> Result<ApplyOutcome> {
+        let task = self.load_task(&id.0)?;
+        let diff = match diff_override {
+            Some(diff) => diff,
+            None => primary_attempt(&self.load_attempts(&task))
+                .and_then(|attempt| attempt.diff.clone())
+                .ok_or_else(|| {
+                    CloudTaskError::Msg(format!("No diff available for task {}", id.0))
+                })?,
+        };
+
+        let req = codex_git::ApplyGitRequest {
+            cwd: task.repo_root.clone(),
+            diff,
+            revert: false,
+            preflight,
+        };
+        let r = codex_git::apply_git_patch(&req)
+            .map_err(|e| CloudTaskError::Io(format!("git apply failed to run: {e}")))?;
+        let status = if r.exit_code == 0 {
+            ApplyStatus::Success
+        } else if !r.applied_paths.is_empty() || !r.conflicted_paths.is_empty() {
+            ApplyStatus::Partial
+        } else {
+            ApplyStatus::Error
+        };
+        let applied = status == ApplyStatus::Success && !preflight;
+        if applied {
+            let _ = std::fs::write(self.loaded_task_dir(&task).join(APPLIED_FILE), "");
+        }
+
+        let verb = if preflight { "Preflight" } else { "Apply" };
+        let message = match status {
+            ApplyStatus::Success if preflight => {
+                format!("Preflight passed for task {} (applies cleanly)", id.0)
+            }
+            ApplyStatus::Success => format!(
+                "Applied task {} to {} ({} files)",
+                id.0,
+                task.repo_root.display(),
+                r.applied_paths.len()
+            ),
+            ApplyStatus::Partial | ApplyStatus::Error => format!(
+                "{verb} {} for task {} (applied={}, skipped={}, conflicts={})",
+                if status == ApplyStatus::Partial {
+                    "did not fully apply"
+                } else {
+                    "failed"
+                },
+                id.0,
+                r.applied_paths.len(),
+                r.skipped_paths.len(),
+                r.conflicted_paths.len()
+            ),
+        };
+
+        Ok(ApplyOutcome {
+            applied,
+            status,
+            message,
+            skipped_paths: r.skipped_paths,
+            conflict_paths: r.conflicted_paths,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl CloudBackend for LocalClient {
+    async fn list_tasks(
+        &self,
+        env: Option<&str>,
+        limit: Option<i64>,
+        _cursor: Option<&str>,
+    ) -> Result<TaskListPage> {
+        let env = env.map(PathBuf::from);
+        let limit = limit
+            .and_then(|limit| usize::try_from(limit).ok())
+            .unwrap_or(usize::MAX);
+        self.blocking(move |client| {
+            client.pump_queue()?;
+            let tasks = client
+                .load_tasks()?
+                .into_iter()
+                .filter(|task| env.as_ref().is_none_or(|env| &task.repo_root == env))
+                .take(limit)
+                .map(|task| client.summarize(&task))
+                .collect();
+            Ok(TaskListPage {
+                tasks,
+                cursor: None,
+            })
+        })
+        .await
+    }
+
+    async fn get_task_summary(&self, id: TaskId) -> Result<TaskSummary> {
+        self.blocking(move |client| {
+            client.pump_queue()?;
+            let task = client.load_task(&id.0)?;
+            Ok(client.summarize(&task))
+        })
+        .await
+    }
+
+    async fn get_task_diff(&self, id: TaskId) -> Result<Option<String>> {
+        self.blocking(move |client| {
+            let task = client.load_task(&id.0)?;
+            let attempts = client.load_attempts(&task);
+            Ok(primary_attempt(&attempts).and_then(|attempt| attempt.diff.clone()))
+        })
+        .await
+    }
+
+    async fn get_task_messages(&self, id: TaskId) -> Result<Vec<String>> {
+        self.blocking(move |client| {
+            let task = client.load_task(&id.0)?;
+            let attempts = client.load_attempts(&task);
+            Ok(primary_attempt(&attempts)
+                .map(attempt_messages)
+                .unwrap_or_default())
+        })
+        .await
+    }
+
+    async fn get_task_text(&self, id: TaskId) -> Result<TaskText> {
+        self.blocking(move |client| {
+            let task = client.load_task(&id.0)?;
+            let prompt =
+                std::fs::read_to_string(client.loaded_task_dir(&task).join(PROMPT_FILE)).ok();
+            let attempts = client.load_attempts(&task);
+            let Some(primary) = primary_attempt(&attempts) else {
+                return Ok(TaskText {
+                    prompt,
+                    ..Default::default()
+                });
+            };
+            Ok(TaskText {
+                prompt,
+                messages: attempt_messages(primary),
+                turn_id: Some(turn_id(&task.id, primary.number)),
+                sibling_turn_ids: attempts
+                    .iter()
+                    .filter(|attempt| attempt.number != primary.number)
+                    .map(|attempt| turn_id(&task.id, attempt.number))
+                    .collect(),
+                attempt_placement: Some(placement(primary.number)),
+                attempt_status: primary.state.status,
+            })
+        })
+        .await
+    }
+
+    async fn list_sibling_attempts(
+        &self,
+        task: TaskId,
+        turn_id_arg: String,
+    ) -> Result<Vec<TurnAttempt>> {
+        self.blocking(move |client| {
+            let task = client.load_task(&task.0)?;
+            Ok(client
+                .load_attempts(&task)
+                .iter()
+                .filter(|attempt| turn_id(&task.id, attempt.number) != turn_id_arg)
+                .map(|attempt| TurnAttempt {
+                    turn_id: turn_id(&task.id, attempt.number),
+                    attempt_placement: Some(placement(attempt.number)),
+                    created_at: Some(task.created_at),
+                    status: attempt.state.status,
+                    diff: attempt.diff.clone(),
+                    messages: attempt_messages(attempt),
+                })
+                .collect())
+        })
+        .await
+    }
+
+    async fn apply_task_preflight(
+        &self,
+        id: TaskId,
+        diff_override: Option<String>,
+    ) -> Result<ApplyOutcome> {
+        self.blocking(move |client| client.apply(id, diff_override, true))
+            .await
+    }
+
+    async fn apply_task(&self, id: TaskId, diff_override: Option<String>) -> Result<ApplyOutcome> {
+        self.blocking(move |client| client.apply(id, diff_override, false))
+            .await
+    }
+
+    async fn create_task(
+        &self,
+        env_id: &str,
+        prompt: &str,
+        git_ref: &str,
+        qa_mode: bool,
+        best_of_n: usize,
+    ) -> Result<CreatedTask> {
+        let env_id = env_id.to_string();
+        let prompt = prompt.to_string();
+        let git_ref = git_ref.to_string();
+        self.blocking(move |client| client.create(&env_id, &prompt, &git_ref, qa_mode, best_of_n))
+            .await
+    }
+}
+
+fn turn_id(task_id: &str, number: usize) -> String {
+    format!("{task_id}-attempt-{number}")
+}
+
+fn placement(number: usize) -> i64 {
+    i64::try_from(number.saturating_sub(1)).unwrap_or(i64::MAX)
+}
+
+/// The attempt shown by default: the first one that completed, else the first attempt.
+fn primary_attempt(attempts: &[LoadedAttempt]) -> Option<&LoadedAttempt> {
+    attempts
+        .iter()
+        .find(|attempt| attempt.state.status == AttemptStatus::Completed)
+        .or_else(|| attempts.first())
+}
+
+fn attempt_messages(attempt: &LoadedAttempt) -> Vec<String> {
+    let mut messages = attempt.state.messages.clone();
+    if let Some(error) = attempt.state.error.as_ref() {
+        messages.push(format!("Attempt failed: {error}"));
+    }
+    messages
+}
+
+fn title_from_prompt(prompt: &str) -> String {
+    let line = prompt
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("Untitled task");
+    if line.chars().count() > MAX_TITLE_CHARS {
+        let truncated: String = line.chars().take(MAX_TITLE_CHARS - 1).collect();
+        format!("{truncated}…")
+    } else {
+        line.to_string()
+    }
+}
+
+/// Fold the JSONL events written by `codex exec --json` into the attempt's status and the
+/// assistant messages it produced.
+fn attempt_state_from_events(events: &str) -> AttemptState {
+    let mut state = AttemptState {
+        status: AttemptStatus::Pending,
+        ..Default::default()
+    };
+    for line in events.lines() {
+        let Ok(event) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        match event.get("type").and_then(Value::as_str) {
+            Some("thread.started" | "turn.started") => {
+                if state.status == AttemptStatus::Pending {
+                    state.status = AttemptStatus::InProgress;
+                }
+            }
+            Some("item.completed") => {
+                let item = event.get("item");
+                if item
+                    .and_then(|item| item.get("type"))
+                    .and_then(Value::as_str)
+                    == Some("agent_message")
+                    && let Some(text) = item
+                        .and_then(|item| item.get("text"))
+                        .and_then(Value::as_str)
+                {
+                    state.messages.push(text.to_string());
+                }
+            }
+            Some("turn.completed") => state.status = AttemptStatus::Completed,
+            Some("turn.failed") => {
+                state.status = AttemptStatus::Failed;
+                state.error = event
+                    .get("error")
+                    .and_then(|error| error.get("message"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+            }
+            Some("error") => {
+                state.status = AttemptStatus::Failed;
+                state.error = event
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+            }
+            _ => {}
+        }
+    }
+    state
+}
+
+/// Where the attempt in `dir` is in its lifecycle, judged from its marker files and pid.
+fn launch_state(dir: &Path) -> Launch {
+    if dir.join(QUEUED_FILE).exists() {
+        return Launch::Queued;
+    }
+    if let Ok(meta) = std::fs::metadata(dir.join(STARTING_FILE)) {
+        let claimed_for = meta
+            .modified()
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .unwrap_or_default();
+        return if claimed_for < START_GRACE {
+            Launch::Starting
+        } else {
+            Launch::Lost
+        };
+    }
+    if let Ok(status) = std::fs::read_to_string(dir.join(EXIT_FILE)) {
+        return Launch::Exited(status.trim().to_string());
+    }
+    match std::fs::read_to_string(dir.join(PID_FILE))
+        .ok()
+        .and_then(|pid| pid.trim().parse::<u32>().ok())
+    {
+        Some(pid) if process_alive(pid) => Launch::Running,
+        Some(_) => Launch::Exited("status unknown".to_string()),
+        None => Launch::Lost,
+    }
+}
+
+/// Take a queued attempt for this process. The rename is atomic, so concurrent `codex cloud`
+/// processes never start the same attempt twice.
+fn claim_attempt(dir: &Path) -> bool {
+    std::fs::rename(dir.join(QUEUED_FILE), dir.join(STARTING_FILE)).is_ok()
+}
+
+/// The last non-empty stderr line of an attempt, formatted as a suffix for error messages.
+fn stderr_hint(dir: &Path) -> String {
+    std::fs::read_to_string(dir.join(STDERR_FILE))
+        .ok()
+        .and_then(|stderr| {
+            stderr
+                .lines()
+                .rev()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(|line| format!(": {line}"))
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // Signal 0 only checks that the process exists and may be signalled.
+    let rc = unsafe { libc::kill(pid, 0) };
+    rc == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_alive(pid: u32) -> bool {
+    Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {pid}"), "/NH"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+        .unwrap_or(true)
+}
+
+#[cfg(unix)]
+fn kill_process(pid: u32) {
+    if let Ok(pid) = libc::pid_t::try_from(pid) {
+        unsafe {
+            libc::kill(pid, libc::SIGKILL);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process(pid: u32) {
+    let _ = Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/F"])
+        .output();
+}
+
+/// Diff the worktree against the task's base commit, new files included. Staging goes through
+/// a throwaway index so the worktree's own index is left alone.
+fn worktree_diff(worktree: &Path, index: &Path, task: &LocalTask) -> Result<String> {
+    let env = [("GIT_INDEX_FILE", index.as_os_str())];
+    let base = task.base_sha.as_str();
+    let result = run_git_with_env(worktree, &["read-tree", base], &env)
+        .and_then(|_| run_git_with_env(worktree, &["add", "-A"], &env))
+        .and_then(|_| run_git_with_env(worktree, &["diff", "--cached", "--binary", base], &env));
+    let _ = std::fs::remove_file(index);
+    result
+}
+
+fn remove_worktree(repo_root: &Path, worktree: &Path) {
+    if !worktree.exists() {
+        return;
+    }
+    let worktree_arg = worktree.to_string_lossy().to_string();
+    if run_git(
+        repo_root,
+        &["worktree", "remove", "--force", worktree_arg.as_str()],
+    )
+    .is_err()
+    {
+        let _ = std::fs::remove_dir_all(worktree);
+        let _ = run_git(repo_root, &["worktree", "prune"]);
+    }
+}
+
+fn run_git(cwd: &Path, args: &[&str]) -> Result<String> {
+    run_git_with_env(cwd, args, &[])
+}
+
+fn run_git_with_env(cwd: &Path, args: &[&str], env: &[(&str, &OsStr)]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .envs(env.iter().copied())
+        .current_dir(cwd)
+        .output()
+        .map_err(|e| CloudTaskError::Io(format!("failed to run git: {e}")))?;
+    if !output.status.success() {
+        return Err(CloudTaskError::Msg(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Whether `id` has the shape `create_task_dir` generates: `local_` followed by a timestamp
+/// and an optional `_<n>` suffix. Rules out path separators and `..`.
+fn is_task_id(id: &str) -> bool {
+    id.strip_prefix("local_").is_some_and(|rest| {
+        rest.starts_with(|c: char| c.is_ascii_digit())
+            && rest.chars().all(|c| c.is_ascii_digit() || c == '_')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn completed_attempt_collects_agent_messages() {
+        let events = [
+            r#"{"type":"thread.started","thread_id":"t"}"#,
+            r#"{"type":"turn.started"}"#,
+            r#"{"type":"item.completed","item":{"id":"0","type":"reasoning","text":"thinking"}}"#,
+            r#"{"type":"item.completed","item":{"id":"1","type":"agent_message","text":"Done."}}"#,
+            r#"{"type":"turn.completed","usage":{"input_tokens":1,"cached_input_tokens":0,"output_tokens":1}}"#,
+        ]
+        .join("\n");
+
+        assert_eq!(
+            attempt_state_from_events(&events),
+            AttemptState {
+                status: AttemptStatus::Completed,
+                messages: vec!["Done.".to_string()],
+                error: None,
+            }
+        );
+    }
+
+    #[test]
+    fn attempt_status_tracks_progress_and_failure() {
+        assert_eq!(attempt_state_from_events("").status, AttemptStatus::Pending);
+        assert_eq!(
+            attempt_state_from_events(r#"{"type":"turn.started"}"#).status,
+            AttemptStatus::InProgress
+        );
+
+        let failed = [
+            r#"{"type":"turn.started"}"#,
+            r#"{"type":"turn.failed","error":{"message":"stream disconnected"}}"#,
+        ]
+        .join("\n");
+        assert_eq!(
+            attempt_state_from_events(&failed),
+            AttemptState {
+                status: AttemptStatus::Failed,
+                messages: Vec::new(),
+                error: Some("stream disconnected".to_string()),
+            }
+        );
+    }
+
+    /// A git repository with one commit, used as a local environment.
+    fn init_repo(dir: &Path) {
+        for args in [
+            &["init", "--quiet"][..],
+            &["config", "user.email", "test@example.com"][..],
+            &["config", "user.name", "Test"][..],
+        ] {
+            run_git(dir, args).expect("git setup");
+        }
+        std::fs::write(dir.join("README.md"), "hello\n").expect("write readme");
+        run_git(dir, &["add", "-A"]).expect("git add");
+        run_git(dir, &["commit", "--quiet", "-m", "init"]).expect("git commit");
+    }
+
+    /// A client whose attempts run `script` with `sh`. The worktree is `$4` and the attempt
+    /// directory is its parent.
+    fn client_running(root: &Path, script: &str) -> LocalClient {
+        LocalClient::new(root).with_exec_command(
+            "sh",
+            vec!["-c".to_string(), script.to_string(), "sh".to_string()],
+        )
+    }
+
+    async fn wait_for_status(client: &LocalClient, id: &TaskId, status: TaskStatus) {
+        for _ in 0..200 {
+            let summary = client.get_task_summary(id.clone()).await.expect("summary");
+            if summary.status == status {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("task {} never reached {status:?}", id.0);
+    }
+
+    const COMPLETE_TURN: &str = r#"printf '%s\n' '{"type":"turn.started"}' '{"type":"item.completed","item":{"id":"1","type":"agent_message","text":"Added a file."}}' '{"type":"turn.completed"}'"#;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn create_diff_and_apply_round_trip() {
+        let repo = tempfile::tempdir().expect("repo");
+        let root = tempfile::tempdir().expect("root");
+        init_repo(repo.path());
+        let client = client_running(
+            root.path(),
+            &format!(r#"cd "$4" && echo added > new.txt && {COMPLETE_TURN}"#),
+        );
+
+        let created = client
+            .create_task(
+                &repo.path().display().to_string(),
+                "Add a file",
+                "HEAD",
+                false,
+                1,
+            )
+            .await
+            .expect("create task");
+        wait_for_status(&client, &created.id, TaskStatus::Ready).await;
+
+        let diff = client
+            .get_task_diff(created.id.clone())
+            .await
+            .expect("diff")
+            .expect("attempt produced a diff");
+        assert!(diff.contains("+++ b/new.txt"), "{diff}");
+        assert_eq!(
+            client
+                .get_task_messages(created.id.clone())
+                .await
+                .expect("messages"),
+            vec!["Added a file.".to_string()]
+        );
+        // Reading the diff leaves the worktree's index alone, and the next queue pass removes
+        // the finished worktree.
+        let attempt_dir = root.path().join(&created.id.0).join("attempt-1");
+        assert!(!attempt_dir.join(DIFF_INDEX_FILE).exists());
+        for _ in 0..200 {
+            client.list_tasks(None, None, None).await.expect("list");
+            if !attempt_dir.join(WORKTREE_DIR).exists() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert!(!attempt_dir.join(WORKTREE_DIR).exists());
+        assert_eq!(
+            run_git(repo.path(), &["worktree", "list", "--porcelain"])
+                .expect("worktree list")
+                .matches("worktree ")
+                .count(),
+            1
+        );
+
+        let preflight = client
+            .apply_task_preflight(created.id.clone(), None)
+            .await
+            .expect("preflight");
+        assert_eq!(preflight.status, ApplyStatus::Success);
+        assert!(!repo.path().join("new.txt").exists());
+
+        let applied = client
+            .apply_task(created.id.clone(), None)
+            .await
+            .expect("apply");
+        assert_eq!(applied.status, ApplyStatus::Success);
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("new.txt")).expect("applied file"),
+            "added\n"
+        );
+        wait_for_status(&client, &created.id, TaskStatus::Applied).await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn killed_attempt_is_reported_as_failed() {
+        let repo = tempfile::tempdir().expect("repo");
+        let root = tempfile::tempdir().expect("root");
+        init_repo(repo.path());
+        let client = client_running(
+            root.path(),
+            r#"printf '%s\n' '{"type":"turn.started"}'; echo "worker crashed" >&2; kill -9 $$"#,
+        );
+
+        let created = client
+            .create_task(
+                &repo.path().display().to_string(),
+                "Crash",
+                "HEAD",
+                false,
+                1,
+            )
+            .await
+            .expect("create task");
+        wait_for_status(&client, &created.id, TaskStatus::Error).await;
+
+        let messages = client
+            .get_task_messages(created.id.clone())
+            .await
+            .expect("messages");
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("worker crashed"), "{messages:?}");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn attempts_beyond_the_limit_wait_in_a_bounded_queue() {
+        let repo = tempfile::tempdir().expect("repo");
+        let root = tempfile::tempdir().expect("root");
+        init_repo(repo.path());
+        // Each attempt runs until a `release` file shows up in its attempt directory.
+        let client = client_running(
+            root.path(),
+            &format!(r#"while [ ! -f "$4/../release" ]; do sleep 0.05; done; {COMPLETE_TURN}"#),
+        )
+        .with_limits(1, 1);
+        let env = repo.path().display().to_string();
+
+        let created = client
+            .create_task(&env, "Two attempts", "HEAD", false, 2)
+            .await
+            .expect("create task");
+        let task_dir = root.path().join(&created.id.0);
+        assert!(task_dir.join("attempt-1").join(PID_FILE).exists());
+        assert!(task_dir.join("attempt-2").join(QUEUED_FILE).exists());
+
+        let err = client
+            .create_task(&env, "One more", "HEAD", false, 1)
+            .await
+            .expect_err("queue is full");
+        assert!(err.to_string().contains("queue is full"), "{err}");
+
+        std::fs::write(task_dir.join("attempt-1").join("release"), "").expect("release");
+        for _ in 0..200 {
+            client
+                .get_task_summary(created.id.clone())
+                .await
+                .expect("summary");
+            if task_dir.join("attempt-2").join(PID_FILE).exists() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        std::fs::write(task_dir.join("attempt-2").join("release"), "").expect("release");
+        wait_for_status(&client, &created.id, TaskStatus::Ready).await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn failed_launch_rolls_back_the_task() {
+        let repo = tempfile::tempdir().expect("repo");
+        let root = tempfile::tempdir().expect("root");
+        init_repo(repo.path());
+        let client =
+            LocalClient::new(root.path()).with_exec_command("/nonexistent/codex", Vec::new());
+
+        let err = client
+            .create_task(
+                &repo.path().display().to_string(),
+                "Never runs",
+                "HEAD",
+                false,
+                2,
+            )
+            .await
+            .expect_err("launch fails");
+        assert!(err.to_string().contains("failed to launch"), "{err}");
+
+        let page = client.list_tasks(None, None, None).await.expect("list");
+        assert!(page.tasks.is_empty());
+        assert_eq!(
+            run_git(repo.path(), &["worktree", "list", "--porcelain"])
+                .expect("worktree list")
+                .matches("worktree ")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn task_ids_outside_the_generated_alphabet_are_rejected() {
+        let root = tempfile::tempdir().expect("root");
+        let client = LocalClient::new(root.path());
+
+        assert_eq!(
+            client.task_dir("local_20261019120000123_2").ok(),
+            Some(root.path().join("local_20261019120000123_2"))
+        );
+        for id in [
+            "../local_1",
+            "local_1/../../etc",
+            "local_1\\..",
+            "local_..",
+            "local_",
+            "other_1",
+            "",
+        ] {
+            assert!(client.task_dir(id).is_err(), "{id:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn title_uses_first_non_empty_prompt_line() {
+        assert_eq!(
+            title_from_prompt("\n  Fix the flaky test  \nmore detail"),
+            "Fix the flaky test"
+        );
+        assert_eq!(
+            title_from_prompt("x".repeat(100).as_str()).chars().count(),
+            80
+        );
+    }
+}
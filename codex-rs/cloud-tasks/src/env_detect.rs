@@ -26,6 +26,16 @@ pub async fn autodetect_environment_id(
     headers: &HeaderMap,
     desired_label: Option<String>,
 ) -> anyhow::Result<AutodetectSelection> {
+    if crate::util::local_mode() {
+        let cwd = std::env::current_dir()?;
+        let row = local_environment(&cwd)
+            .ok_or_else(|| anyhow::anyhow!("not inside a git repository"))?;
+        return Ok(AutodetectSelection {
+            id: row.id,
+            label: row.label,
+        });
+    }
+
     // 1) Try repo-specific environments based on local git origins (GitHub only, like VSCode)
     let origins = get_git_origins();
     crate::append_error_log(format!("env: git origins: {origins:?}"));
@@ -106,6 +116,21 @@ pub async fn autodetect_environment_id(
     anyhow::bail!("no environments available")
 }
 
+/// The local environment for `path`: the root of the git checkout containing it. Local tasks
+/// use the repository root as their environment id.
+pub fn local_environment(path: &std::path::Path) -> Option<crate::app::EnvironmentRow> {
+    let path = std::fs::canonicalize(path).ok()?;
+    let root = codex_core::git_info::get_git_repo_root(&path)?;
+    Some(crate::app::EnvironmentRow {
+        id: root.display().to_string(),
+        label: root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string()),
+        is_pinned: true,
+        repo_hints: None,
+    })
+}
+
 fn pick_environment_row(
     envs: &[CodeEnvironment],
     desired_label: Option<&str>,
@@ -256,6 +281,11 @@ pub async fn list_environments(
     base_url: &str,
     headers: &HeaderMap,
 ) -> anyhow::Result<Vec<crate::app::EnvironmentRow>> {
+    if crate::util::local_mode() {
+        let cwd = std::env::current_dir()?;
+        return Ok(local_environment(&cwd).into_iter().collect());
+    }
+
     let mut map: HashMap<String, crate::app::EnvironmentRow> = HashMap::new();
 
     // 1) By-repo lookup for each parsed GitHub origin
@@ -35,6 +35,18 @@ struct ApplyJob {
 struct BackendContext {
     backend: Arc<dyn codex_cloud_tasks_client::CloudBackend>,
     base_url: String,
+    /// Set when tasks run on this machine instead of Codex Cloud.
+    local_root: Option<PathBuf>,
+}
+
+impl BackendContext {
+    /// Where a task can be inspected: its web URL, or its directory for local tasks.
+    fn task_location(&self, task_id: &str) -> String {
+        match self.local_root.as_ref() {
+            Some(root) => root.join(task_id).display().to_string(),
+            None => util::task_url(&self.base_url, task_id),
+        }
+    }
 }
 
 async fn init_backend(user_agent_suffix: &str) -> anyhow::Result<BackendContext> {
@@ -51,6 +63,20 @@ async fn init_backend(user_agent_suffix: &str) -> anyhow::Result<BackendContext>
         return Ok(BackendContext {
             backend: Arc::new(codex_cloud_tasks_client::MockClient),
             base_url,
+            local_root: None,
+        });
+    }
+
+    if util::local_mode() {
+        let root = codex_core::config::find_codex_home()?.join("local-tasks");
+        let exe = std::env::current_exe()?;
+        append_error_log(format!("startup: local tasks root={}", root.display()));
+        let local = codex_cloud_tasks_client::LocalClient::new(root.clone())
+            .with_exec_command(exe, Vec::new());
+        return Ok(BackendContext {
+            backend: Arc::new(local),
+            base_url,
+            local_root: Some(root),
         });
     }
 
@@ -104,6 +130,7 @@ async fn init_backend(user_agent_suffix: &str) -> anyhow::Result<BackendContext>
     Ok(BackendContext {
         backend: Arc::new(http),
         base_url,
+        local_root: None,
     })
 }
 
@@ -175,8 +202,7 @@ async fn run_exec_command(args: crate::cli::ExecCommand) -> anyhow::Result<()> {
         attempts,
     )
     .await?;
-    let url = util::task_url(&ctx.base_url, &created.id.0);
-    println!("{url}");
+    println!("{}", ctx.task_location(&created.id.0));
     Ok(())
 }
 
@@ -185,6 +211,11 @@ async fn resolve_environment_id(ctx: &BackendContext, requested: &str) -> anyhow
     if trimmed.is_empty() {
         return Err(anyhow!("environment id must not be empty"));
     }
+    if ctx.local_root.is_some() {
+        return crate::env_detect::local_environment(std::path::Path::new(trimmed))
+            .map(|row| row.id)
+            .ok_or_else(|| anyhow!("'{trimmed}' is not inside a git repository"));
+    }
     let normalized = util::normalize_base_url(&ctx.base_url);
     let headers = util::build_chatgpt_headers().await;
     let environments = crate::env_detect::list_environments(&normalized, &headers).await?;
@@ -474,13 +505,13 @@ fn format_task_status_lines(
 
 fn format_task_list_lines(
     tasks: &[codex_cloud_tasks_client::TaskSummary],
-    base_url: &str,
+    task_location: impl Fn(&str) -> String,
     now: chrono::DateTime<Utc>,
     colorize: bool,
 ) -> Vec<String> {
     let mut lines = Vec::new();
     for (idx, task) in tasks.iter().enumerate() {
-        lines.push(util::task_url(base_url, &task.id.0));
+        lines.push(task_location(&task.id.0));
         for line in format_task_status_lines(task, now, colorize) {
             lines.push(format!("  {line}"));
         }
@@ -528,7 +559,7 @@ async fn run_list_command(args: crate::cli::ListCommand) -> anyhow::Result<()> {
             .map(|task| {
                 serde_json::json!({
                     "id": task.id.0,
-                    "url": util::task_url(&ctx.base_url, &task.id.0),
+                    "url": ctx.task_location(&task.id.0),
                     "title": task.title,
                     "status": task.status,
                     "updated_at": task.updated_at,
@@ -557,7 +588,7 @@ async fn run_list_command(args: crate::cli::ListCommand) -> anyhow::Result<()> {
     }
     let now = Utc::now();
     let colorize = supports_color::on(SupportStream::Stdout).is_some();
-    for line in format_task_list_lines(&page.tasks, &ctx.base_url, now, colorize) {
+    for line in format_task_list_lines(&page.tasks, |id| ctx.task_location(id), now, colorize) {
         println!("{line}");
     }
     if let Some(cursor) = page.cursor {
@@ -2298,7 +2329,12 @@ mod tests {
                 attempt_total: Some(1),
             },
         ];
-        let lines = format_task_list_lines(&tasks, "https://chatgpt.com/backend-api", now, false);
+        let lines = format_task_list_lines(
+            &tasks,
+            |id| util::task_url("https://chatgpt.com/backend-api", id),
+            now,
+            false,
+        );
         assert_eq!(
             lines,
             vec![
@@ -13,6 +13,14 @@ pub fn set_user_agent_suffix(suffix: &str) {
     }
 }
 
+/// True when `CODEX_CLOUD_TASKS_MODE=local` asks for tasks to run on this machine.
+pub fn local_mode() -> bool {
+    matches!(
+        std::env::var("CODEX_CLOUD_TASKS_MODE").ok().as_deref(),
+        Some("local") | Some("LOCAL")
+    )
+}
+
 pub fn append_error_log(message: impl AsRef<str>) {
     let ts = Utc::now().to_rfc3339();
     if let Ok(mut f) = std::fs::OpenOptions::new()
@@ -15,6 +15,7 @@ use crate::error::RetryLimitReachedError;
 use crate::error::UnexpectedResponseError;
 use crate::error::UsageLimitReachedError;
 use crate::model_provider_info::ModelProviderInfo;
+use crate::provider_auth::auth_command_token;
 use crate::token_data::PlanType;
 
 pub(crate) fn map_api_error(err: ApiError) -> CodexErr {
@@ -171,7 +172,7 @@ fn extract_header(headers: Option<&HeaderMap>, name: &str) -> Option<String> {
     })
 }
 
-pub(crate) fn auth_provider_from_auth(
+pub(crate) async fn auth_provider_from_auth(
     auth: Option<CodexAuth>,
     provider: &ModelProviderInfo,
 ) -> crate::error::Result<CoreAuthProvider> {
@@ -189,6 +190,13 @@ pub(crate) fn auth_provider_from_auth(
         });
     }
 
+    if let Some(command) = &provider.auth_command {
+        return Ok(CoreAuthProvider {
+            token: Some(auth_command_token(command).await?),
+            account_id: None,
+        });
+    }
+
     if let Some(auth) = auth {
         let token = auth.get_token()?;
         Ok(CoreAuthProvider {
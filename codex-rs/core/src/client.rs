@@ -7,6 +7,7 @@ use crate::api_bridge::CoreAuthProvider;
 use crate::api_bridge::auth_provider_from_auth;
 use crate::api_bridge::map_api_error;
 use crate::auth::UnauthorizedRecovery;
+use crate::provider_auth::AuthCommandRecovery;
 use crate::turn_metadata::build_turn_metadata_header;
 use codex_api::CompactClient as ApiCompactClient;
 use codex_api::CompactionInput as ApiCompactionInput;
@@ -257,20 +258,6 @@ impl ModelClient {
             return Ok(Vec::new());
         }
         let auth_manager = self.state.auth_manager.clone();
-        let auth = match auth_manager.as_ref() {
-            Some(manager) => manager.auth().await,
-            None => None,
-        };
-        let api_provider = self
-            .state
-            .provider
-            .to_api_provider(auth.as_ref().map(CodexAuth::internal_auth_mode))?;
-        let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
-        let transport = ReqwestTransport::new(build_reqwest_client());
-        let request_telemetry = self.build_request_telemetry();
-        let client = ApiCompactClient::new(transport, api_provider, api_auth)
-            .with_telemetry(Some(request_telemetry));
-
         let instructions = prompt.base_instructions.text.clone();
         let payload = ApiCompactionInput {
             model: &self.state.model_info.slug,
@@ -290,10 +277,41 @@ impl ModelClient {
                 extra_headers.insert("x-openai-subagent", val);
             }
         }
-        client
-            .compact_input(&payload, extra_headers)
-            .await
-            .map_err(map_api_error)
+
+        let mut auth_recovery = auth_manager
+            .as_ref()
+            .map(super::auth::AuthManager::unauthorized_recovery);
+        let mut auth_command_recovery = AuthCommandRecovery::new(&self.state.provider);
+        loop {
+            let auth = match auth_manager.as_ref() {
+                Some(manager) => manager.auth().await,
+                None => None,
+            };
+            let api_provider = self
+                .state
+                .provider
+                .to_api_provider(auth.as_ref().map(CodexAuth::internal_auth_mode))?;
+            let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider).await?;
+            let transport = ReqwestTransport::new(build_reqwest_client());
+            let request_telemetry = self.build_request_telemetry();
+            let client = ApiCompactClient::new(transport, api_provider, api_auth)
+                .with_telemetry(Some(request_telemetry));
+
+            match client.compact_input(&payload, extra_headers.clone()).await {
+                Ok(items) => return Ok(items),
+                Err(ApiError::Transport(
+                    unauthorized_transport @ TransportError::Http { status, .. },
+                )) if status == StatusCode::UNAUTHORIZED => {
+                    handle_unauthorized(
+                        unauthorized_transport,
+                        &mut auth_command_recovery,
+                        &mut auth_recovery,
+                    )
+                    .await?;
+                }
+                Err(err) => return Err(map_api_error(err)),
+            }
+        }
     }
 }
 
@@ -540,6 +558,7 @@ impl ModelClientSession {
         let mut auth_recovery = auth_manager
             .as_ref()
             .map(super::auth::AuthManager::unauthorized_recovery);
+        let mut auth_command_recovery = AuthCommandRecovery::new(&self.state.provider);
         loop {
             let auth = match auth_manager.as_ref() {
                 Some(manager) => manager.auth().await,
@@ -549,7 +568,7 @@ impl ModelClientSession {
                 .state
                 .provider
                 .to_api_provider(auth.as_ref().map(CodexAuth::internal_auth_mode))?;
-            let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
+            let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider).await?;
             let transport = ReqwestTransport::new(build_reqwest_client());
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let compression = self.responses_request_compression(auth.as_ref());
@@ -570,7 +589,12 @@ impl ModelClientSession {
                 Err(ApiError::Transport(
                     unauthorized_transport @ TransportError::Http { status, .. },
                 )) if status == StatusCode::UNAUTHORIZED => {
-                    handle_unauthorized(unauthorized_transport, &mut auth_recovery).await?;
+                    handle_unauthorized(
+                        unauthorized_transport,
+                        &mut auth_command_recovery,
+                        &mut auth_recovery,
+                    )
+                    .await?;
                     continue;
                 }
                 Err(err) => return Err(map_api_error(err)),
@@ -586,6 +610,7 @@ impl ModelClientSession {
         let mut auth_recovery = auth_manager
             .as_ref()
             .map(super::auth::AuthManager::unauthorized_recovery);
+        let mut auth_command_recovery = AuthCommandRecovery::new(&self.state.provider);
         loop {
             let auth = match auth_manager.as_ref() {
                 Some(manager) => manager.auth().await,
@@ -595,7 +620,7 @@ impl ModelClientSession {
                 .state
                 .provider
                 .to_api_provider(auth.as_ref().map(CodexAuth::internal_auth_mode))?;
-            let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
+            let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider).await?;
             let compression = self.responses_request_compression(auth.as_ref());
 
             let options = self.build_responses_options(prompt, compression);
@@ -609,7 +634,12 @@ impl ModelClientSession {
                 Err(ApiError::Transport(
                     unauthorized_transport @ TransportError::Http { status, .. },
                 )) if status == StatusCode::UNAUTHORIZED => {
-                    handle_unauthorized(unauthorized_transport, &mut auth_recovery).await?;
+                    handle_unauthorized(
+                        unauthorized_transport,
+                        &mut auth_command_recovery,
+                        &mut auth_recovery,
+                    )
+                    .await?;
                     continue;
                 }
                 Err(err) => return Err(map_api_error(err)),
@@ -775,14 +805,26 @@ where
     ResponseStream { rx_event }
 }
 
-/// Handles a 401 response by optionally refreshing ChatGPT tokens once.
+/// Handles a 401 response by optionally refreshing the provider's
+/// `auth_command` token or ChatGPT tokens once.
 ///
 /// When refresh succeeds, the caller should retry the API call; otherwise
 /// the mapped `CodexErr` is returned to the caller.
 async fn handle_unauthorized(
     transport: TransportError,
+    auth_command_recovery: &mut Option<AuthCommandRecovery>,
     auth_recovery: &mut Option<UnauthorizedRecovery>,
 ) -> Result<()> {
+    if let Some(recovery) = auth_command_recovery {
+        // ChatGPT tokens are not sent to providers with an `auth_command`,
+        // so refreshing them would not help.
+        return if recovery.refresh().await {
+            Ok(())
+        } else {
+            Err(map_api_error(ApiError::Transport(transport)))
+        };
+    }
+
     if let Some(recovery) = auth_recovery
         && recovery.has_next()
     {
@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt::Debug;
@@ -23,9 +24,11 @@ use crate::exec_policy::ExecPolicyManager;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::features::maybe_push_unstable_features_warning;
+use crate::fetch_url::FetchUrlStore;
 use crate::models_manager::manager::ModelsManager;
 use crate::parse_command::parse_command;
 use crate::parse_turn_item;
+use crate::path_instructions::PathInstructionsStore;
 use crate::rollout::session_index;
 use crate::stream_events_utils::HandleOutputCtx;
 use crate::stream_events_utils::handle_non_tool_response_item;
@@ -52,15 +55,21 @@ use codex_protocol::mcp::CallToolResult;
 use codex_protocol::models::BaseInstructions;
 use codex_protocol::models::format_allow_prefixes;
 use codex_protocol::openai_models::ModelInfo;
+use codex_protocol::plan_tool::UpdatePlanArgs;
+use codex_protocol::protocol::ContextBreakdownEvent;
+use codex_protocol::protocol::ContextPruneAction;
+use codex_protocol::protocol::ContextPrunedEvent;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::HasLegacyEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ItemStartedEvent;
+use codex_protocol::protocol::ModelFallbackEvent;
 use codex_protocol::protocol::RawResponseItemEvent;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::SubAgentSource;
+use codex_protocol::protocol::ToolOutputsAgedEvent;
 use codex_protocol::protocol::TurnAbortReason;
 use codex_protocol::protocol::TurnContextItem;
 use codex_protocol::protocol::TurnStartedEvent;
@@ -106,9 +115,13 @@ use crate::config::ConstraintResult;
 use crate::config::GhostSnapshotConfig;
 use crate::config::resolve_web_search_mode_for_turn;
 use crate::config::types::McpServerConfig;
+use crate::config::types::ModelFallback;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::context_manager::ContextManager;
 use crate::environment_context::EnvironmentContext;
+use crate::environment_facts::EnvironmentFacts;
+use crate::environment_facts::EnvironmentFactsCollector;
+use crate::environment_facts::FactCommandSandbox;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
 #[cfg(test)]
@@ -161,6 +174,7 @@ use crate::protocol::Submission;
 use crate::protocol::TokenCountEvent;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
+use crate::protocol::TouchedPathsUpdatedEvent;
 use crate::protocol::TurnDiffEvent;
 use crate::protocol::WarningEvent;
 use crate::rollout::RolloutRecorder;
@@ -180,6 +194,7 @@ use crate::skills::injection::ToolMentionKind;
 use crate::skills::injection::app_id_from_path;
 use crate::skills::injection::tool_kind_for_path;
 use crate::skills::resolve_skill_dependencies_for_turn;
+use crate::spill::SpillStore;
 use crate::state::ActiveTurn;
 use crate::state::SessionServices;
 use crate::state::SessionState;
@@ -195,6 +210,7 @@ use crate::tools::sandboxing::ApprovalStore;
 use crate::tools::spec::ToolsConfig;
 use crate::tools::spec::ToolsConfigParams;
 use crate::turn_diff_tracker::TurnDiffTracker;
+use crate::unified_exec::ServiceManager;
 use crate::unified_exec::UnifiedExecProcessManager;
 use crate::user_notification::UserNotification;
 use crate::util::backoff;
@@ -214,6 +230,7 @@ use codex_protocol::protocol::InitialHistory;
 use codex_protocol::user_input::UserInput;
 use codex_utils_readiness::Readiness;
 use codex_utils_readiness::ReadinessFlag;
+use codex_utils_tokenizer::Tokenizer;
 use tokio::sync::watch;
 
 /// The high-level interface to the Codex system.
@@ -454,6 +471,24 @@ impl Codex {
         state.session_configuration.thread_config_snapshot()
     }
 
+    pub(crate) async fn current_plan(&self) -> Option<UpdatePlanArgs> {
+        self.session.current_plan().await
+    }
+
+    pub(crate) async fn context_breakdown(&self) -> ContextBreakdownEvent {
+        let turn_context = self.session.new_default_turn().await;
+        self.session.context_breakdown(turn_context.as_ref()).await
+    }
+
+    pub(crate) async fn prune_context_items(
+        &self,
+        ids: Vec<String>,
+        action: ContextPruneAction,
+    ) -> CodexResult<()> {
+        let sub_id = self.session.next_internal_sub_id();
+        self.session.prune_context_items(sub_id, ids, action).await
+    }
+
     pub(crate) fn state_db(&self) -> Option<state_db::StateDbHandle> {
         self.session.state_db()
     }
@@ -500,6 +535,10 @@ pub(crate) struct TurnContext {
     pub(crate) codex_linux_sandbox_exe: Option<PathBuf>,
     pub(crate) tool_call_gate: Arc<ReadinessFlag>,
     pub(crate) truncation_policy: TruncationPolicy,
+    /// Counts tokens for this turn's model in context accounting.
+    pub(crate) tokenizer: Tokenizer,
+    /// Git state, toolchains and the like reported in `<environment_context>`.
+    pub(crate) environment_facts: EnvironmentFacts,
     pub(crate) dynamic_tools: Vec<DynamicToolSpec>,
 }
 impl TurnContext {
@@ -682,6 +721,8 @@ impl Session {
             web_search_mode: per_turn_config.web_search_mode,
         });
 
+        let tokenizer = Tokenizer::for_model(&model_info.slug);
+
         TurnContext {
             sub_id,
             client,
@@ -700,7 +741,10 @@ impl Session {
             final_output_json_schema: None,
             codex_linux_sandbox_exe: per_turn_config.codex_linux_sandbox_exe.clone(),
             tool_call_gate: Arc::new(ReadinessFlag::new()),
-            truncation_policy: model_info.truncation_policy.into(),
+            truncation_policy: TruncationPolicy::from(model_info.truncation_policy)
+                .with_tokenizer(tokenizer),
+            tokenizer,
+            environment_facts: EnvironmentFacts::default(),
             dynamic_tools: session_configuration.dynamic_tools.clone(),
         }
     }
@@ -902,6 +946,7 @@ impl Session {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: Mutex::new(CancellationToken::new()),
             unified_exec_manager: UnifiedExecProcessManager::default(),
+            service_manager: ServiceManager::default(),
             analytics_events_client: AnalyticsEventsClient::new(
                 Arc::clone(&config),
                 Arc::clone(&auth_manager),
@@ -919,6 +964,19 @@ impl Session {
             agent_control,
             state_db: state_db_ctx.clone(),
             transport_manager: TransportManager::new(),
+            output_spill: config
+                .features
+                .enabled(Feature::OutputSpill)
+                .then(|| SpillStore::new(&config.codex_home, conversation_id)),
+            fetch_url: config
+                .features
+                .enabled(Feature::FetchUrl)
+                .then(|| FetchUrlStore::new(&config.config_layer_stack)),
+            path_instructions: PathInstructionsStore::discover(&config).await,
+            environment_facts: EnvironmentFactsCollector::new(
+                config.environment_context.clone(),
+                config.active_project.is_trusted(),
+            ),
         };
 
         let sess = Arc::new(Session {
@@ -1016,9 +1074,9 @@ impl Session {
         format!("auto-compact-{id}")
     }
 
-    async fn get_total_token_usage(&self) -> i64 {
+    async fn get_total_token_usage(&self, turn_context: &TurnContext) -> i64 {
         let state = self.state.lock().await;
-        state.get_total_token_usage(state.server_reasoning_included())
+        state.get_total_token_usage(state.server_reasoning_included(), turn_context.tokenizer)
     }
 
     async fn get_estimated_token_count(&self, turn_context: &TurnContext) -> Option<i64> {
@@ -1095,6 +1153,9 @@ impl Session {
                     let mut state = self.state.lock().await;
                     state.set_token_info(Some(info));
                 }
+                self.restore_plan_from_rollout(&rollout_items).await;
+                self.restore_touched_paths_from_rollout(&rollout_items)
+                    .await;
 
                 // Defer seeding the session's initial context until the first turn starts so
                 // turn/start overrides can be merged before we write to the rollout.
@@ -1116,6 +1177,9 @@ impl Session {
                     let mut state = self.state.lock().await;
                     state.set_token_info(Some(info));
                 }
+                self.restore_plan_from_rollout(&rollout_items).await;
+                self.restore_touched_paths_from_rollout(&rollout_items)
+                    .await;
 
                 // If persisting, persist all rollout items as-is (recorder filters)
                 if !rollout_items.is_empty() {
@@ -1123,7 +1187,8 @@ impl Session {
                 }
 
                 // Append the current session's initial context after the reconstructed history.
-                let initial_context = self.build_initial_context(&turn_context).await;
+                let mut initial_context = self.build_initial_context(&turn_context).await;
+                initial_context.extend(self.plan_context_item_if_missing().await);
                 self.record_conversation_items(&turn_context, &initial_context)
                     .await;
                 {
@@ -1136,6 +1201,28 @@ impl Session {
         }
     }
 
+    async fn restore_plan_from_rollout(&self, rollout_items: &[RolloutItem]) {
+        let plan = rollout_items.iter().rev().find_map(|item| match item {
+            RolloutItem::EventMsg(EventMsg::PlanUpdate(plan)) => Some(plan.clone()),
+            _ => None,
+        });
+        if plan.is_some() {
+            let mut state = self.state.lock().await;
+            state.plan = plan;
+        }
+    }
+
+    async fn restore_touched_paths_from_rollout(&self, rollout_items: &[RolloutItem]) {
+        let touched = rollout_items.iter().rev().find_map(|item| match item {
+            RolloutItem::EventMsg(EventMsg::TouchedPathsUpdated(ev)) => Some(ev.paths.clone()),
+            _ => None,
+        });
+        if let Some(paths) = touched {
+            let mut state = self.state.lock().await;
+            state.record_touched_paths(paths.into_iter().collect());
+        }
+    }
+
     fn last_token_info_from_rollout(rollout_items: &[RolloutItem]) -> Option<TokenUsageInfo> {
         rollout_items.iter().rev().find_map(|item| match item {
             RolloutItem::EventMsg(EventMsg::TokenCount(ev)) => ev.info.clone(),
@@ -1196,6 +1283,7 @@ impl Session {
                 session_configuration,
                 updates.final_output_json_schema,
                 sandbox_policy_changed,
+                true,
             )
             .await)
     }
@@ -1206,6 +1294,7 @@ impl Session {
         session_configuration: SessionConfiguration,
         final_output_json_schema: Option<Option<Value>>,
         sandbox_policy_changed: bool,
+        collect_environment_facts: bool,
     ) -> Arc<TurnContext> {
         let per_turn_config = Self::build_per_turn_config(&session_configuration);
 
@@ -1249,6 +1338,15 @@ impl Session {
         if let Some(final_schema) = final_output_json_schema {
             turn_context.final_output_json_schema = final_schema;
         }
+        // Only user turns run the fact providers; internal turns reuse what the
+        // last user turn saw so their diffs against it stay empty.
+        let environment_facts = &self.services.environment_facts;
+        turn_context.environment_facts = if collect_environment_facts {
+            let sandbox = FactCommandSandbox::for_turn(&turn_context, self.conversation_id);
+            environment_facts.collect(&turn_context.cwd, &sandbox).await
+        } else {
+            environment_facts.last_collected().await
+        };
         Arc::new(turn_context)
     }
 
@@ -1270,7 +1368,7 @@ impl Session {
             let state = self.state.lock().await;
             state.session_configuration.clone()
         };
-        self.new_turn_from_configuration(sub_id, session_configuration, None, false)
+        self.new_turn_from_configuration(sub_id, session_configuration, None, false, false)
             .await
     }
 
@@ -1758,6 +1856,7 @@ impl Session {
         rollout_items: &[RolloutItem],
     ) -> Vec<ResponseItem> {
         let mut history = ContextManager::new();
+        let rollout_path = self.rollout_path().await;
         for item in rollout_items {
             match item {
                 RolloutItem::ResponseItem(response_item) => {
@@ -1782,6 +1881,16 @@ impl Session {
                 RolloutItem::EventMsg(EventMsg::ThreadRolledBack(rollback)) => {
                     history.drop_last_n_user_turns(rollback.num_turns);
                 }
+                RolloutItem::EventMsg(EventMsg::ContextPruned(pruned)) => {
+                    history.prune_items(&pruned.ids, pruned.action);
+                }
+                RolloutItem::EventMsg(EventMsg::ToolOutputsAged(aged)) => {
+                    history.age_tool_outputs(
+                        &aged.call_ids,
+                        rollout_path.as_deref(),
+                        turn_context.tokenizer,
+                    );
+                }
                 _ => {}
             }
         }
@@ -1815,11 +1924,198 @@ impl Session {
         self.record_conversation_items(ctx, &[item]).await;
     }
 
+    /// Inject path-scoped instructions for files touched since the last
+    /// model request that the conversation has not seen yet.
+    pub(crate) async fn record_path_instructions(&self, turn_context: &TurnContext) {
+        let touched = self.services.path_instructions.take_touched().await;
+        if touched.is_empty() {
+            return;
+        }
+        let items = {
+            let state = self.state.lock().await;
+            self.services
+                .path_instructions
+                .injections(&touched, state.history.raw_items())
+        };
+        if !items.is_empty() {
+            self.record_conversation_items(turn_context, &items).await;
+        }
+    }
+
+    pub(crate) async fn set_plan(&self, plan: UpdatePlanArgs) {
+        let mut state = self.state.lock().await;
+        state.plan = Some(plan);
+    }
+
+    pub(crate) async fn current_plan(&self) -> Option<UpdatePlanArgs> {
+        let state = self.state.lock().await;
+        state.plan.clone()
+    }
+
+    /// Per-item breakdown of the prompt the next turn would send.
+    pub(crate) async fn context_breakdown(
+        &self,
+        turn_context: &TurnContext,
+    ) -> ContextBreakdownEvent {
+        let items = self.clone_history().await.breakdown(turn_context);
+        let estimated_total_tokens = items
+            .iter()
+            .fold(0i64, |acc, item| acc.saturating_add(item.estimated_tokens));
+        ContextBreakdownEvent {
+            items,
+            estimated_total_tokens,
+            model_context_window: turn_context.client.get_model_context_window(),
+        }
+    }
+
+    /// Drops or shortens the given history items and records a `ContextPruned` event, which
+    /// is replayed when the thread is resumed. Nothing changes if any id is unknown.
+    pub(crate) async fn prune_context_items(
+        &self,
+        sub_id: String,
+        ids: Vec<String>,
+        action: ContextPruneAction,
+    ) -> CodexResult<()> {
+        // Holding the active turn slot keeps a turn from starting, and holding the state lock
+        // keeps other writers out, between resolving the ids and replacing the history.
+        let active = self.active_turn.lock().await;
+        if active.is_some() {
+            return Err(CodexErr::InvalidRequest(
+                "Cannot prune context while a turn is in progress.".to_string(),
+            ));
+        }
+        let turn_context = self.new_default_turn_with_sub_id(sub_id).await;
+        {
+            let mut state = self.state.lock().await;
+            let mut history = state.clone_history();
+            let unknown = history.prune_items(&ids, action);
+            if !unknown.is_empty() {
+                return Err(CodexErr::InvalidRequest(format!(
+                    "Unknown context item ids: {}",
+                    unknown.join(", ")
+                )));
+            }
+            state.replace_history(history.raw_items().to_vec());
+        }
+        self.recompute_token_usage(turn_context.as_ref()).await;
+        drop(active);
+
+        self.send_event_raw_flushed(Event {
+            id: turn_context.sub_id.clone(),
+            msg: EventMsg::ContextPruned(ContextPrunedEvent { ids, action }),
+        })
+        .await;
+        Ok(())
+    }
+
+    /// Replaces tool outputs older than the last `keep_recent_turns` user turns with stubs
+    /// and records a `ToolOutputsAged` event, which is replayed when the thread is resumed.
+    /// Returns whether any output was aged.
+    pub(crate) async fn age_stale_tool_outputs(
+        &self,
+        turn_context: &TurnContext,
+        keep_recent_turns: usize,
+    ) -> bool {
+        let rollout_path = self.rollout_path().await;
+        let mut history = self.clone_history().await;
+        let call_ids = history.stale_tool_output_call_ids(
+            keep_recent_turns,
+            rollout_path.as_deref(),
+            turn_context.tokenizer,
+        );
+        if call_ids.is_empty() {
+            return false;
+        }
+
+        let estimated_tokens_saved =
+            history.age_tool_outputs(&call_ids, rollout_path.as_deref(), turn_context.tokenizer);
+        self.replace_history(history.raw_items().to_vec()).await;
+        self.recompute_token_usage(turn_context).await;
+
+        self.send_event(
+            turn_context,
+            EventMsg::ToolOutputsAged(ToolOutputsAgedEvent {
+                call_ids,
+                estimated_tokens_saved,
+            }),
+        )
+        .await;
+        true
+    }
+
+    async fn rollout_path(&self) -> Option<PathBuf> {
+        let guard = self.services.rollout.lock().await;
+        guard.as_ref().map(|rec| rec.rollout_path.clone())
+    }
+
+    /// The latest plan as a context message, for histories that no longer
+    /// contain the `update_plan` calls (after compaction or on resume).
+    pub(crate) async fn plan_context_item(&self) -> Option<ResponseItem> {
+        let state = self.state.lock().await;
+        state
+            .plan
+            .as_ref()
+            .filter(|plan| !plan.plan.is_empty())
+            .map(crate::tools::handlers::plan_context_item)
+    }
+
+    /// `plan_context_item` for a resumed or forked history, unless that history
+    /// still shows the plan through an `update_plan` call or a plan context
+    /// message left by compaction.
+    async fn plan_context_item_if_missing(&self) -> Option<ResponseItem> {
+        let item = self.plan_context_item().await?;
+        let history = self.clone_history().await;
+        let shows_plan = history.raw_items().iter().any(|item| match item {
+            ResponseItem::FunctionCall { name, .. } => name == "update_plan",
+            ResponseItem::Message { content, .. } => {
+                crate::session_prefix::is_plan_context_content(content)
+            }
+            _ => false,
+        });
+        (!shows_plan).then_some(item)
+    }
+
     pub(crate) async fn replace_history(&self, items: Vec<ResponseItem>) {
         let mut state = self.state.lock().await;
         state.replace_history(items);
     }
 
+    /// Paths edited with apply_patch this session that have not been
+    /// committed through `Op::CreateCommits` yet.
+    pub(crate) async fn touched_paths(&self) -> BTreeSet<PathBuf> {
+        self.state.lock().await.touched_paths()
+    }
+
+    pub(crate) async fn record_touched_paths(&self, paths: BTreeSet<PathBuf>) {
+        if paths.is_empty() {
+            return;
+        }
+        let touched = {
+            let mut state = self.state.lock().await;
+            state.record_touched_paths(paths);
+            state.touched_paths()
+        };
+        self.persist_touched_paths(touched).await;
+    }
+
+    pub(crate) async fn forget_touched_paths(&self, paths: &[PathBuf]) {
+        let touched = {
+            let mut state = self.state.lock().await;
+            state.forget_touched_paths(paths.iter().map(PathBuf::as_path));
+            state.touched_paths()
+        };
+        self.persist_touched_paths(touched).await;
+    }
+
+    /// Records the full set in the rollout only; clients have no use for it.
+    async fn persist_touched_paths(&self, paths: BTreeSet<PathBuf>) {
+        let event = EventMsg::TouchedPathsUpdated(TouchedPathsUpdatedEvent {
+            paths: paths.into_iter().collect(),
+        });
+        self.persist_rollout_items(&[RolloutItem::EventMsg(event)])
+            .await;
+    }
+
     pub(crate) async fn seed_initial_context_if_needed(&self, turn_context: &TurnContext) {
         {
             let mut state = self.state.lock().await;
@@ -1829,7 +2125,9 @@ impl Session {
             state.initial_context_seeded = true;
         }
 
-        let initial_context = self.build_initial_context(turn_context).await;
+        // A resumed session restores its plan along with the initial context.
+        let mut initial_context = self.build_initial_context(turn_context).await;
+        initial_context.extend(self.plan_context_item_if_missing().await);
         self.record_conversation_items(turn_context, &initial_context)
             .await;
         self.flush_rollout().await;
@@ -1923,9 +2221,9 @@ impl Session {
                 .into(),
             );
         }
-        items.push(ResponseItem::from(EnvironmentContext::new(
-            Some(turn_context.cwd.clone()),
-            shell.as_ref().clone(),
+        items.push(ResponseItem::from(EnvironmentContext::from_turn_context(
+            turn_context,
+            shell.as_ref(),
         )));
         items
     }
@@ -2038,11 +2336,27 @@ impl Session {
     }
 
     async fn send_token_count_event(&self, turn_context: &TurnContext) {
-        let (info, rate_limits) = {
+        let (info, rate_limits, context_tokens) = {
             let state = self.state.lock().await;
-            state.token_info_and_rate_limits()
+            let (info, rate_limits) = state.token_info_and_rate_limits();
+            let usage_reported = info
+                .as_ref()
+                .is_some_and(|info| info.last_token_usage.total_tokens > 0);
+            let context_tokens = if usage_reported {
+                Some(state.get_total_token_usage(
+                    state.server_reasoning_included(),
+                    turn_context.tokenizer,
+                ))
+            } else {
+                state.history.estimate_token_count(turn_context)
+            };
+            (info, rate_limits, context_tokens)
         };
-        let event = EventMsg::TokenCount(TokenCountEvent { info, rate_limits });
+        let event = EventMsg::TokenCount(TokenCountEvent {
+            info,
+            rate_limits,
+            context_tokens,
+        });
         self.send_event(turn_context, event).await;
     }
 
@@ -2464,7 +2778,7 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                 handlers::refresh_mcp_servers(&sess, config).await;
             }
             Op::ListCustomPrompts => {
-                handlers::list_custom_prompts(&sess, sub.id.clone()).await;
+                handlers::list_custom_prompts(&sess, &config, sub.id.clone()).await;
             }
             Op::ListSkills { cwds, force_reload } => {
                 handlers::list_skills(&sess, sub.id.clone(), cwds, force_reload).await;
@@ -2494,6 +2808,12 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::ThreadRollback { num_turns } => {
                 handlers::thread_rollback(&sess, sub.id.clone(), num_turns).await;
             }
+            Op::InspectContext => {
+                handlers::inspect_context(&sess, sub.id.clone()).await;
+            }
+            Op::PruneContextItems { ids, action } => {
+                handlers::prune_context_items(&sess, sub.id.clone(), ids, action).await;
+            }
             Op::SetThreadName { name } => {
                 handlers::set_thread_name(&sess, sub.id.clone(), name).await;
             }
@@ -2521,6 +2841,12 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::Review { review_request } => {
                 handlers::review(&sess, &config, sub.id.clone(), review_request).await;
             }
+            Op::ProposeCommits => {
+                handlers::propose_commits(&sess, sub.id.clone()).await;
+            }
+            Op::CreateCommits { commits } => {
+                handlers::create_commits(&sess, sub.id.clone(), commits).await;
+            }
             _ => {} // Ignore unknown ops; enum is non_exhaustive to allow extensions.
         }
     }
@@ -2542,11 +2868,14 @@ mod handlers {
     use crate::review_prompts::resolve_review_request;
     use crate::rollout::session_index;
     use crate::tasks::CompactTask;
+    use crate::tasks::CreateCommitsTask;
+    use crate::tasks::ProposeCommitsTask;
     use crate::tasks::RegularTask;
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandTask;
     use codex_protocol::custom_prompts::CustomPrompt;
     use codex_protocol::protocol::CodexErrorInfo;
+    use codex_protocol::protocol::ContextPruneAction;
     use codex_protocol::protocol::ErrorEvent;
     use codex_protocol::protocol::Event;
     use codex_protocol::protocol::EventMsg;
@@ -2555,6 +2884,7 @@ mod handlers {
     use codex_protocol::protocol::ListSkillsResponseEvent;
     use codex_protocol::protocol::McpServerRefreshConfig;
     use codex_protocol::protocol::Op;
+    use codex_protocol::protocol::ProposedCommit;
     use codex_protocol::protocol::RemoteSkillDownloadedEvent;
     use codex_protocol::protocol::RemoteSkillSummary;
     use codex_protocol::protocol::ReviewDecision;
@@ -2864,13 +3194,13 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
-    pub async fn list_custom_prompts(sess: &Session, sub_id: String) {
-        let custom_prompts: Vec<CustomPrompt> =
-            if let Some(dir) = crate::custom_prompts::default_prompts_dir() {
-                crate::custom_prompts::discover_prompts_in(&dir).await
-            } else {
-                Vec::new()
-            };
+    pub async fn list_custom_prompts(sess: &Session, config: &Arc<Config>, sub_id: String) {
+        let user_dir = crate::custom_prompts::default_prompts_dir();
+        let custom_prompts: Vec<CustomPrompt> = crate::custom_prompts::discover_prompts(
+            &config.config_layer_stack,
+            user_dir.as_deref(),
+        )
+        .await;
 
         let event = Event {
             id: sub_id,
@@ -2991,6 +3321,18 @@ mod handlers {
             .await;
     }
 
+    pub async fn propose_commits(sess: &Arc<Session>, sub_id: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(turn_context, Vec::new(), ProposeCommitsTask::new())
+            .await;
+    }
+
+    pub async fn create_commits(sess: &Arc<Session>, sub_id: String, commits: Vec<ProposedCommit>) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(turn_context, Vec::new(), CreateCommitsTask::new(commits))
+            .await;
+    }
+
     pub async fn compact(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
 
@@ -3049,6 +3391,34 @@ mod handlers {
         .await;
     }
 
+    pub async fn inspect_context(sess: &Arc<Session>, sub_id: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        let breakdown = sess.context_breakdown(turn_context.as_ref()).await;
+        sess.send_event_raw(Event {
+            id: turn_context.sub_id.clone(),
+            msg: EventMsg::ContextBreakdown(breakdown),
+        })
+        .await;
+    }
+
+    pub async fn prune_context_items(
+        sess: &Arc<Session>,
+        sub_id: String,
+        ids: Vec<String>,
+        action: ContextPruneAction,
+    ) {
+        if let Err(err) = sess.prune_context_items(sub_id.clone(), ids, action).await {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: err.to_string(),
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                }),
+            })
+            .await;
+        }
+    }
+
     /// Persists the thread name in the session index, updates in-memory state, and emits
     /// a `ThreadNameUpdated` event on success.
     ///
@@ -3121,6 +3491,7 @@ mod handlers {
             .unified_exec_manager
             .terminate_all_processes()
             .await;
+        sess.services.service_manager.stop_all().await;
         info!("Shutting down Codex instance");
         let history = sess.clone_history().await;
         let turn_count = history
@@ -3254,6 +3625,7 @@ async fn spawn_review_thread(
         parent_turn_context.client.transport_manager(),
     );
 
+    let tokenizer = Tokenizer::for_model(&model_info.slug);
     let review_turn_context = TurnContext {
         sub_id: sub_id.to_string(),
         client,
@@ -3273,7 +3645,10 @@ async fn spawn_review_thread(
         codex_linux_sandbox_exe: parent_turn_context.codex_linux_sandbox_exe.clone(),
         tool_call_gate: Arc::new(ReadinessFlag::new()),
         dynamic_tools: parent_turn_context.dynamic_tools.clone(),
-        truncation_policy: model_info.truncation_policy.into(),
+        truncation_policy: TruncationPolicy::from(model_info.truncation_policy)
+            .with_tokenizer(tokenizer),
+        tokenizer,
+        environment_facts: parent_turn_context.environment_facts.clone(),
     };
 
     // Seed the child task with the review prompt as the initial user message.
@@ -3283,7 +3658,12 @@ async fn spawn_review_thread(
         text_elements: Vec::new(),
     }];
     let tc = Arc::new(review_turn_context);
-    sess.spawn_task(tc.clone(), input, ReviewTask::new()).await;
+    let task = if resolved.commit_reviews.is_empty() {
+        ReviewTask::new()
+    } else {
+        ReviewTask::per_commit(resolved.commit_reviews)
+    };
+    sess.spawn_task(tc.clone(), input, task).await;
 
     // Announce entering review mode so UIs can switch modes.
     let review_request = ReviewRequest {
@@ -3364,7 +3744,7 @@ fn errors_to_info(errors: &[SkillError]) -> Vec<SkillErrorInfo> {
 ///
 pub(crate) async fn run_turn(
     sess: Arc<Session>,
-    turn_context: Arc<TurnContext>,
+    mut turn_context: Arc<TurnContext>,
     input: Vec<UserInput>,
     cancellation_token: CancellationToken,
 ) -> Option<String> {
@@ -3374,15 +3754,16 @@ pub(crate) async fn run_turn(
 
     let model_info = turn_context.client.get_model_info();
     let auto_compact_limit = model_info.auto_compact_token_limit().unwrap_or(i64::MAX);
-    let total_usage_tokens = sess.get_total_token_usage().await;
+    let context_limit = context_reduction_limit(&turn_context, auto_compact_limit);
+    let total_usage_tokens = sess.get_total_token_usage(turn_context.as_ref()).await;
 
     let event = EventMsg::TurnStarted(TurnStartedEvent {
         model_context_window: turn_context.client.get_model_context_window(),
         collaboration_mode_kind: turn_context.collaboration_mode.mode,
     });
     sess.send_event(&turn_context, event).await;
-    if total_usage_tokens >= auto_compact_limit {
-        run_auto_compact(&sess, &turn_context).await;
+    if total_usage_tokens >= context_limit {
+        reduce_context_usage(&sess, &turn_context, auto_compact_limit).await;
     }
 
     let skills_outcome = Some(
@@ -3481,6 +3862,10 @@ pub(crate) async fn run_turn(
     let mut client_session = turn_context
         .client
         .new_session(Some(turn_context.cwd.clone()));
+    // Once the turn falls back it stays on that backend; the next turn starts
+    // on the configured provider again.
+    let model_fallbacks = turn_context.client.config().model_fallbacks.clone();
+    let mut next_fallback = 0;
 
     loop {
         // Note that pending_input would be something like a message the user
@@ -3497,6 +3882,7 @@ pub(crate) async fn run_turn(
         let sampling_request_input: Vec<ResponseItem> = {
             sess.record_conversation_items(&turn_context, &pending_input)
                 .await;
+            sess.record_path_instructions(&turn_context).await;
             sess.clone_history().await.for_prompt()
         };
 
@@ -3528,8 +3914,8 @@ pub(crate) async fn run_turn(
                     needs_follow_up,
                     last_agent_message: sampling_request_last_agent_message,
                 } = sampling_request_output;
-                let total_usage_tokens = sess.get_total_token_usage().await;
-                let token_limit_reached = total_usage_tokens >= auto_compact_limit;
+                let total_usage_tokens = sess.get_total_token_usage(turn_context.as_ref()).await;
+                let token_limit_reached = total_usage_tokens >= context_limit;
 
                 let estimated_token_count =
                     sess.get_estimated_token_count(turn_context.as_ref()).await;
@@ -3546,7 +3932,7 @@ pub(crate) async fn run_turn(
 
                 // as long as compaction works well in getting us way below the token limit, we shouldn't worry about being in an infinite loop.
                 if token_limit_reached && needs_follow_up {
-                    run_auto_compact(&sess, &turn_context).await;
+                    reduce_context_usage(&sess, &turn_context, auto_compact_limit).await;
                     continue;
                 }
 
@@ -3584,6 +3970,16 @@ pub(crate) async fn run_turn(
                 sess.send_event(&turn_context, event).await;
                 break;
             }
+            Err(e) if e.is_provider_unavailable() && next_fallback < model_fallbacks.len() => {
+                let fallback = &model_fallbacks[next_fallback];
+                next_fallback += 1;
+                turn_context =
+                    Arc::new(switch_to_model_fallback(&sess, &turn_context, fallback, &e).await);
+                client_session = turn_context
+                    .client
+                    .new_session(Some(turn_context.cwd.clone()));
+                continue;
+            }
             Err(e) => {
                 info!("Turn error: {e:#}");
                 let event = EventMsg::Error(e.to_error_event(None));
@@ -3594,9 +3990,121 @@ pub(crate) async fn run_turn(
         }
     }
 
+    let changed_paths = turn_diff_tracker.lock().await.changed_paths();
+    sess.record_touched_paths(changed_paths).await;
+
     last_agent_message
 }
 
+/// Rebuilds `turn_context` so the rest of the turn is served by `fallback`,
+/// and reports the switch to the user and to telemetry.
+async fn switch_to_model_fallback(
+    sess: &Session,
+    turn_context: &TurnContext,
+    fallback: &ModelFallback,
+    err: &CodexErr,
+) -> TurnContext {
+    let config = turn_context.client.config();
+    let from_model_provider = config.model_provider_id.clone();
+    let from_model = turn_context.collaboration_mode.model().to_string();
+    let model = fallback.model.clone().unwrap_or_else(|| from_model.clone());
+    warn!(
+        "model provider `{from_model_provider}` unavailable ({err}); falling back to `{model}` on `{}`",
+        fallback.model_provider_id
+    );
+
+    // Rebuild the turn the way `new_turn_from_configuration` does, swapping
+    // only the provider and model.
+    let mut session_configuration = {
+        let state = sess.state.lock().await;
+        state.session_configuration.clone()
+    };
+    session_configuration.provider = fallback.model_provider.clone();
+    session_configuration.collaboration_mode =
+        turn_context
+            .collaboration_mode
+            .with_updates(Some(model.clone()), None, None);
+    let mut per_turn_config = Session::build_per_turn_config(&session_configuration);
+    per_turn_config.model = Some(model.clone());
+    per_turn_config.model_provider_id = fallback.model_provider_id.clone();
+    per_turn_config.model_provider = fallback.model_provider.clone();
+    let model_info = sess
+        .services
+        .models_manager
+        .get_model_info(&model, &per_turn_config)
+        .await;
+    let previous_otel_manager = turn_context.client.get_otel_manager();
+    previous_otel_manager.record_model_fallback(
+        &from_model_provider,
+        &fallback.model_provider_id,
+        &model,
+    );
+
+    let mut fallback_turn_context = Session::make_turn_context(
+        turn_context.client.get_auth_manager(),
+        &previous_otel_manager,
+        fallback.model_provider.clone(),
+        &session_configuration,
+        per_turn_config,
+        model_info,
+        sess.conversation_id,
+        turn_context.sub_id.clone(),
+        turn_context.client.transport_manager(),
+    );
+    // State that belongs to the turn rather than to the model.
+    fallback_turn_context.final_output_json_schema = turn_context.final_output_json_schema.clone();
+    fallback_turn_context.tool_call_gate = Arc::clone(&turn_context.tool_call_gate);
+    fallback_turn_context.environment_facts = turn_context.environment_facts.clone();
+
+    sess.send_event(
+        &fallback_turn_context,
+        EventMsg::ModelFallback(ModelFallbackEvent {
+            from_model_provider,
+            from_model,
+            model_provider: fallback.model_provider_id.clone(),
+            model,
+            reason: err.to_string(),
+        }),
+    )
+    .await;
+
+    fallback_turn_context
+}
+
+/// Usage at which a turn starts freeing context: the auto-compaction limit, or
+/// the earlier tool output aging limit when aging is enabled.
+fn context_reduction_limit(turn_context: &TurnContext, auto_compact_limit: i64) -> i64 {
+    let config = turn_context.client.config();
+    let aging = &config.tool_output_aging;
+    match aging.token_limit {
+        Some(token_limit) if aging.enabled => token_limit.min(auto_compact_limit),
+        _ => auto_compact_limit,
+    }
+}
+
+/// Ages stale tool outputs when enabled and falls back to compacting the whole
+/// history only while the context is still over the auto-compaction limit.
+async fn reduce_context_usage(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    auto_compact_limit: i64,
+) {
+    let aging = turn_context.client.config().tool_output_aging.clone();
+    if aging.enabled
+        && sess
+            .age_stale_tool_outputs(turn_context.as_ref(), aging.keep_recent_turns)
+            .await
+    {
+        let estimated_token_count = sess.get_estimated_token_count(turn_context.as_ref()).await;
+        if estimated_token_count.is_some_and(|tokens| tokens < auto_compact_limit) {
+            return;
+        }
+    }
+    if sess.get_total_token_usage(turn_context.as_ref()).await >= auto_compact_limit {
+        run_auto_compact(sess, turn_context).await;
+    }
+}
+
 async fn run_auto_compact(sess: &Arc<Session>, turn_context: &Arc<TurnContext>) {
     if should_use_remote_compact_task(sess.as_ref(), &turn_context.client.get_provider()) {
         run_inline_remote_auto_compact_task(Arc::clone(sess), Arc::clone(turn_context)).await;
@@ -4841,24 +5349,28 @@ mod tests {
             TokenCountEvent {
                 info: Some(info1),
                 rate_limits: None,
+                context_tokens: None,
             },
         )));
         rollout_items.push(RolloutItem::EventMsg(EventMsg::TokenCount(
             TokenCountEvent {
                 info: None,
                 rate_limits: None,
+                context_tokens: None,
             },
         )));
         rollout_items.push(RolloutItem::EventMsg(EventMsg::TokenCount(
             TokenCountEvent {
                 info: Some(info2.clone()),
                 rate_limits: None,
+                context_tokens: None,
             },
         )));
         rollout_items.push(RolloutItem::EventMsg(EventMsg::TokenCount(
             TokenCountEvent {
                 info: None,
                 rate_limits: None,
+                context_tokens: None,
             },
         )));
 
@@ -4874,6 +5386,78 @@ mod tests {
         assert_eq!(actual, Some(info2));
     }
 
+    #[tokio::test]
+    async fn record_initial_history_restores_latest_touched_paths() {
+        let (session, turn_context) = make_session_and_context().await;
+        let (mut rollout_items, _expected) = sample_rollout(&session, &turn_context).await;
+        for paths in [vec!["/repo/a.rs", "/repo/b.rs"], vec!["/repo/b.rs"]] {
+            rollout_items.push(RolloutItem::EventMsg(EventMsg::TouchedPathsUpdated(
+                TouchedPathsUpdatedEvent {
+                    paths: paths.into_iter().map(PathBuf::from).collect(),
+                },
+            )));
+        }
+
+        session
+            .record_initial_history(InitialHistory::Resumed(ResumedHistory {
+                conversation_id: ThreadId::default(),
+                history: rollout_items,
+                rollout_path: PathBuf::from("/tmp/resume.jsonl"),
+            }))
+            .await;
+
+        assert_eq!(
+            session.touched_paths().await,
+            BTreeSet::from([PathBuf::from("/repo/b.rs")])
+        );
+    }
+
+    #[tokio::test]
+    async fn resume_restores_plan_only_when_history_lost_it() {
+        let plan = UpdatePlanArgs {
+            explanation: None,
+            plan: vec![codex_protocol::plan_tool::PlanItemArg {
+                step: "ship it".to_string(),
+                status: codex_protocol::plan_tool::StepStatus::InProgress,
+            }],
+        };
+        let plan_call = ResponseItem::FunctionCall {
+            id: None,
+            name: "update_plan".to_string(),
+            arguments: serde_json::to_string(&plan).expect("serialize plan"),
+            call_id: "call-plan".to_string(),
+        };
+
+        for keep_call in [true, false] {
+            let (session, turn_context) = make_session_and_context().await;
+            let (mut rollout_items, _expected) = sample_rollout(&session, &turn_context).await;
+            if keep_call {
+                rollout_items.push(RolloutItem::ResponseItem(plan_call.clone()));
+            }
+            rollout_items.push(RolloutItem::EventMsg(EventMsg::PlanUpdate(plan.clone())));
+
+            session
+                .record_initial_history(InitialHistory::Forked(rollout_items))
+                .await;
+
+            let plan_contexts = session
+                .clone_history()
+                .await
+                .raw_items()
+                .iter()
+                .filter(|item| {
+                    matches!(item, ResponseItem::Message { content, .. }
+                        if crate::session_prefix::is_plan_context_content(content))
+                })
+                .count();
+            assert_eq!(
+                plan_contexts,
+                usize::from(!keep_call),
+                "keep_call={keep_call}"
+            );
+        }
+    }
+
     #[tokio::test]
     async fn record_initial_history_reconstructs_forked_transcript() {
         let (session, turn_context) = make_session_and_context().await;
@@ -4953,6 +5537,132 @@ mod tests {
         assert_eq!(expected, history.raw_items());
     }
 
+    #[tokio::test]
+    async fn prune_context_items_is_replayed_when_resuming() {
+        let (sess, tc, rx) = make_session_and_context_with_rx().await;
+
+        let items = vec![
+            ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: "run the tests".to_string(),
+                }],
+                end_turn: None,
+                phase: None,
+            },
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: "{}".to_string(),
+                call_id: "call-1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "call-1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "test log\n".repeat(500),
+                    ..Default::default()
+                },
+            },
+        ];
+        sess.record_into_history(&items, tc.as_ref()).await;
+
+        let err = sess
+            .prune_context_items(
+                "sub-1".to_string(),
+                vec!["output:missing".to_string()],
+                ContextPruneAction::Drop,
+            )
+            .await
+            .expect_err("unknown ids are rejected");
+        assert_eq!(err.to_string(), "Unknown context item ids: output:missing");
+
+        let ids = vec!["output:call-1".to_string()];
+        sess.prune_context_items("sub-2".to_string(), ids.clone(), ContextPruneAction::Drop)
+            .await
+            .expect("prune succeeds");
+        let pruned = loop {
+            let event = rx.recv().await.expect("event");
+            if let EventMsg::ContextPruned(pruned) = event.msg {
+                break pruned;
+            }
+        };
+        assert_eq!(pruned.ids, ids);
+
+        let live = sess.clone_history().await.raw_items().to_vec();
+        let Some(ResponseItem::FunctionCallOutput { output, .. }) = live.last() else {
+            panic!("expected the tool output to be kept as a placeholder");
+        };
+        assert!(!output.content.contains("test log"));
+
+        let mut rollout_items: Vec<RolloutItem> =
+            items.into_iter().map(RolloutItem::ResponseItem).collect();
+        rollout_items.push(RolloutItem::EventMsg(EventMsg::ContextPruned(pruned)));
+        let reconstructed = sess
+            .reconstruct_history_from_rollout(tc.as_ref(), &rollout_items)
+            .await;
+        assert_eq!(live, reconstructed);
+    }
+
+    #[tokio::test]
+    async fn aged_tool_outputs_are_replayed_when_resuming() {
+        let (sess, tc, rx) = make_session_and_context_with_rx().await;
+
+        let user_message = |text: &str| ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+            phase: None,
+        };
+        let items = vec![
+            user_message("run the tests"),
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["cargo","test"]}"#.to_string(),
+                call_id: "call-1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "call-1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "test log\n".repeat(500),
+                    ..Default::default()
+                },
+            },
+            user_message("now fix it"),
+        ];
+        sess.record_into_history(&items, tc.as_ref()).await;
+
+        assert!(!sess.age_stale_tool_outputs(tc.as_ref(), 2).await);
+        assert!(sess.age_stale_tool_outputs(tc.as_ref(), 1).await);
+        let aged = loop {
+            let event = rx.recv().await.expect("event");
+            if let EventMsg::ToolOutputsAged(aged) = event.msg {
+                break aged;
+            }
+        };
+        assert_eq!(aged.call_ids, vec!["call-1".to_string()]);
+        assert!(aged.estimated_tokens_saved > 0);
+
+        let live = sess.clone_history().await.raw_items().to_vec();
+        let Some(ResponseItem::FunctionCallOutput { output, .. }) = live.get(2) else {
+            panic!("expected the tool output to be kept as a stub");
+        };
+        assert!(output.content.starts_with("[output aged out of context"));
+        assert!(output.content.contains("Command: cargo test"));
+
+        let mut rollout_items: Vec<RolloutItem> =
+            items.into_iter().map(RolloutItem::ResponseItem).collect();
+        rollout_items.push(RolloutItem::EventMsg(EventMsg::ToolOutputsAged(aged)));
+        let reconstructed = sess
+            .reconstruct_history_from_rollout(tc.as_ref(), &rollout_items)
+            .await;
+        assert_eq!(live, reconstructed);
+    }
+
     #[tokio::test]
     async fn thread_rollback_clears_history_when_num_turns_exceeds_existing_turns() {
         let (sess, tc, rx) = make_session_and_context_with_rx().await;
@@ -5434,6 +6144,7 @@ mod tests {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: Mutex::new(CancellationToken::new()),
             unified_exec_manager: UnifiedExecProcessManager::default(),
+            service_manager: ServiceManager::default(),
             analytics_events_client: AnalyticsEventsClient::new(
                 Arc::clone(&config),
                 Arc::clone(&auth_manager),
@@ -5451,6 +6162,10 @@ mod tests {
             agent_control,
             state_db: None,
             transport_manager: TransportManager::new(),
+            output_spill: None,
+            fetch_url: None,
+            path_instructions: PathInstructionsStore::default(),
+            environment_facts: EnvironmentFactsCollector::default(),
         };
 
         let turn_context = Session::make_turn_context(
@@ -5554,6 +6269,7 @@ mod tests {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: Mutex::new(CancellationToken::new()),
             unified_exec_manager: UnifiedExecProcessManager::default(),
+            service_manager: ServiceManager::default(),
             analytics_events_client: AnalyticsEventsClient::new(
                 Arc::clone(&config),
                 Arc::clone(&auth_manager),
@@ -5571,6 +6287,10 @@ mod tests {
             agent_control,
             state_db: None,
             transport_manager: TransportManager::new(),
+            output_spill: None,
+            fetch_url: None,
+            path_instructions: PathInstructionsStore::default(),
+            environment_facts: EnvironmentFactsCollector::default(),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
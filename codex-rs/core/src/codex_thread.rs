@@ -6,7 +6,10 @@ use crate::protocol::Op;
 use crate::protocol::Submission;
 use codex_protocol::config_types::Personality;
 use codex_protocol::openai_models::ReasoningEffort;
+use codex_protocol::plan_tool::UpdatePlanArgs;
 use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::ContextBreakdownEvent;
+use codex_protocol::protocol::ContextPruneAction;
 use codex_protocol::protocol::SandboxPolicy;
 use codex_protocol::protocol::SessionSource;
 use std::path::PathBuf;
@@ -73,4 +76,23 @@ impl CodexThread {
     pub async fn config_snapshot(&self) -> ThreadConfigSnapshot {
         self.codex.thread_config_snapshot().await
     }
+
+    /// Latest checklist recorded with the `update_plan` tool, if any.
+    pub async fn plan(&self) -> Option<UpdatePlanArgs> {
+        self.codex.current_plan().await
+    }
+
+    /// Per-item breakdown of the model context with estimated token costs.
+    pub async fn context_breakdown(&self) -> ContextBreakdownEvent {
+        self.codex.context_breakdown().await
+    }
+
+    /// Drops or shortens history items by the ids reported in [`Self::context_breakdown`].
+    pub async fn prune_context_items(
+        &self,
+        ids: Vec<String>,
+        action: ContextPruneAction,
+    ) -> CodexResult<()> {
+        self.codex.prune_context_items(ids, action).await
+    }
 }
@@ -6,10 +6,12 @@ use crate::codex::TurnContext;
 use crate::context_manager::ContextManager;
 use crate::context_manager::is_codex_generated_item;
 use crate::error::Result as CodexResult;
+use crate::path_instructions::carry_over_path_instructions;
 use crate::protocol::CompactedItem;
 use crate::protocol::EventMsg;
 use crate::protocol::RolloutItem;
 use crate::protocol::TurnStartedEvent;
+use crate::session_prefix::is_plan_context_content;
 use codex_protocol::items::ContextCompactionItem;
 use codex_protocol::items::TurnItem;
 use codex_protocol::models::ResponseItem;
@@ -80,6 +82,12 @@ async fn run_remote_compact_task_inner_impl(
         .client
         .compact_conversation_history(&prompt)
         .await?;
+    carry_over_path_instructions(history.raw_items(), &mut new_history);
+    // Replace any plan the server carried over with the current one.
+    new_history.retain(|item| {
+        !matches!(item, ResponseItem::Message { content, .. } if is_plan_context_content(content))
+    });
+    new_history.extend(sess.plan_context_item().await);
 
     if !ghost_snapshots.is_empty() {
         new_history.extend(ghost_snapshots);
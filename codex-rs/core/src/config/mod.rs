@@ -1,11 +1,16 @@
 use crate::auth::AuthCredentialsStoreMode;
 use crate::config::edit::ConfigEdit;
 use crate::config::edit::ConfigEditsBuilder;
+use crate::config::types::ConditionalConfigToml;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
+use crate::config::types::EnvironmentContextProviders;
+use crate::config::types::EnvironmentContextToml;
 use crate::config::types::History;
 use crate::config::types::McpServerConfig;
 use crate::config::types::McpServerDisabledReason;
 use crate::config::types::McpServerTransportConfig;
+use crate::config::types::ModelFallback;
+use crate::config::types::ModelFallbackToml;
 use crate::config::types::Notice;
 use crate::config::types::NotificationMethod;
 use crate::config::types::Notifications;
@@ -16,6 +21,8 @@ use crate::config::types::SandboxWorkspaceWrite;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
 use crate::config::types::SkillsConfig;
+use crate::config::types::ToolOutputAging;
+use crate::config::types::ToolOutputAgingToml;
 use crate::config::types::Tui;
 use crate::config::types::UriBasedFileOpener;
 use crate::config_loader::CloudRequirementsLoader;
@@ -128,12 +135,22 @@ pub struct Config {
     /// Token usage threshold triggering auto-compaction of conversation history.
     pub model_auto_compact_token_limit: Option<i64>,
 
+    /// Replacing stale tool outputs with stubs before compacting.
+    pub tool_output_aging: ToolOutputAging,
+
+    /// Facts added to the `<environment_context>` message.
+    pub environment_context: EnvironmentContextProviders,
+
     /// Key into the model_providers map that specifies which provider to use.
     pub model_provider_id: String,
 
     /// Info needed to make an API request to the model.
     pub model_provider: ModelProviderInfo,
 
+    /// Providers and models to try, in order, when `model_provider` keeps
+    /// failing during a turn.
+    pub model_fallbacks: Vec<ModelFallback>,
+
     /// Optionally specify the personality of the model
     pub personality: Option<Personality>,
 
@@ -178,6 +195,10 @@ pub struct Config {
     /// Compact prompt override.
     pub compact_prompt: Option<String>,
 
+    /// Commit message conventions for `/commit`, e.g. "Prefix subjects with
+    /// the crate name".
+    pub commit_instructions: Option<String>,
+
     /// Optional external notifier command. When set, Codex will spawn this
     /// program after each completed *turn* (i.e. when the agent finishes
     /// processing a user submission). The value must be the full command
@@ -801,12 +822,25 @@ pub struct ConfigToml {
     /// Provider to use from the model_providers map.
     pub model_provider: Option<String>,
 
+    /// Ordered (provider, model) pairs a turn falls back to when the primary
+    /// provider keeps failing after retries.
+    pub model_fallbacks: Option<Vec<ModelFallbackToml>>,
+
     /// Size of the context window for the model, in tokens.
     pub model_context_window: Option<i64>,
 
     /// Token usage threshold triggering auto-compaction of conversation history.
     pub model_auto_compact_token_limit: Option<i64>,
 
+    /// Replace old tool outputs with short stubs when the context fills up,
+    /// compacting the whole history only when that is not enough.
+    pub tool_output_aging: Option<ToolOutputAgingToml>,
+
+    /// Facts about the workspace and machine to include in the
+    /// `<environment_context>` message, such as git state and toolchains.
+    #[serde(default)]
+    pub environment_context: EnvironmentContextToml,
+
     /// Default approval policy for executing commands.
     pub approval_policy: Option<AskForApproval>,
 
@@ -839,6 +873,10 @@ pub struct ConfigToml {
     /// Compact prompt used for history compaction.
     pub compact_prompt: Option<String>,
 
+    /// Commit message conventions the model follows when `/commit` drafts
+    /// commits.
+    pub commit_instructions: Option<String>,
+
     /// When set, restricts ChatGPT login to a specific workspace identifier.
     #[serde(default)]
     pub forced_chatgpt_workspace_id: Option<String>,
@@ -892,6 +930,11 @@ pub struct ConfigToml {
     #[serde(default)]
     pub profiles: HashMap<String, ConfigProfile>,
 
+    /// Settings that apply automatically when the working directory, git
+    /// remote, or git branch matches. Only honored in the user config.toml.
+    #[serde(default)]
+    pub conditional: Option<Vec<ConditionalConfigToml>>,
+
     /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
     #[serde(default)]
     pub history: Option<History>,
@@ -1078,6 +1121,13 @@ pub struct GhostSnapshotToml {
     pub ignore_large_untracked_dirs: Option<i64>,
     /// Disable all ghost snapshot warning events.
     pub disable_warnings: Option<bool>,
+    /// Snapshot workspaces that are not git repositories by copying changed
+    /// files into a store under CODEX_HOME. Defaults to false.
+    pub file_snapshots: Option<bool>,
+    /// Size limit in bytes for the files each session keeps in the snapshot
+    /// store. The session's oldest snapshots are dropped once it is exceeded.
+    /// Zero or negative removes the limit.
+    pub file_snapshot_max_bytes: Option<i64>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -1258,6 +1308,43 @@ pub fn resolve_oss_provider(
     }
 }
 
+/// Resolve `model_fallbacks` entries against the known providers. Entries
+/// without a provider stay on `current_provider_id`.
+fn resolve_model_fallbacks(
+    entries: Vec<ModelFallbackToml>,
+    current_provider_id: &str,
+    model_providers: &HashMap<String, ModelProviderInfo>,
+) -> std::io::Result<Vec<ModelFallback>> {
+    entries
+        .into_iter()
+        .map(|entry| {
+            let ModelFallbackToml {
+                model_provider,
+                model,
+            } = entry;
+            if model_provider.is_none() && model.is_none() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "each `model_fallbacks` entry must set `model_provider`, `model`, or both",
+                ));
+            }
+            let model_provider_id =
+                model_provider.unwrap_or_else(|| current_provider_id.to_string());
+            let provider = model_providers.get(&model_provider_id).ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Model provider `{model_provider_id}` in `model_fallbacks` not found"),
+                )
+            })?;
+            Ok(ModelFallback {
+                model_provider: provider.clone(),
+                model_provider_id,
+                model,
+            })
+        })
+        .collect()
+}
+
 /// Resolve the web search mode from explicit config and feature flags.
 fn resolve_web_search_mode(
     config_toml: &ConfigToml,
@@ -1458,6 +1545,19 @@ impl Config {
                 std::io::Error::new(std::io::ErrorKind::NotFound, message)
             })?
             .clone();
+        let model_fallbacks = resolve_model_fallbacks(
+            config_profile
+                .model_fallbacks
+                .or(cfg.model_fallbacks)
+                .unwrap_or_default(),
+            &model_provider_id,
+            &model_providers,
+        )?;
+
+        let tool_output_aging = ToolOutputAging::resolve(
+            config_profile.tool_output_aging.as_ref(),
+            cfg.tool_output_aging.as_ref(),
+        );
 
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
@@ -1497,6 +1597,16 @@ impl Config {
             {
                 config.disable_warnings = disable_warnings;
             }
+            if let Some(ghost_snapshot) = cfg.ghost_snapshot.as_ref()
+                && let Some(file_snapshots) = ghost_snapshot.file_snapshots
+            {
+                config.file_snapshots = file_snapshots;
+            }
+            if let Some(ghost_snapshot) = cfg.ghost_snapshot.as_ref()
+                && let Some(max_bytes) = ghost_snapshot.file_snapshot_max_bytes
+            {
+                config.file_snapshot_max_bytes = if max_bytes > 0 { Some(max_bytes) } else { None };
+            }
             config
         };
 
@@ -1585,8 +1695,11 @@ impl Config {
             review_model,
             model_context_window: cfg.model_context_window,
             model_auto_compact_token_limit: cfg.model_auto_compact_token_limit,
+            tool_output_aging,
+            environment_context: cfg.environment_context.into(),
             model_provider_id,
             model_provider,
+            model_fallbacks,
             cwd: resolved_cwd,
             approval_policy: constrained_approval_policy,
             sandbox_policy: constrained_sandbox_policy,
@@ -1600,6 +1713,10 @@ impl Config {
             personality,
             developer_instructions,
             compact_prompt,
+            commit_instructions: cfg
+                .commit_instructions
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty()),
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             cli_auth_credentials_store_mode: cfg.cli_auth_credentials_store.unwrap_or_default(),
@@ -2529,6 +2646,152 @@ profile = "project"
         Ok(())
     }
 
+    #[test]
+    fn profile_model_fallbacks_resolve_against_providers() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+profile = "oncall"
+model_fallbacks = [{ model = "unused" }]
+
+[model_providers.azure]
+name = "Azure"
+base_url = "https://example.openai.azure.com/openai"
+
+[profiles.oncall]
+model_fallbacks = [
+  { model_provider = "azure", model = "gpt-5-deployment" },
+  { model = "gpt-5-mini" },
+]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config
+                .model_fallbacks
+                .iter()
+                .map(|fallback| (
+                    fallback.model_provider_id.as_str(),
+                    fallback.model_provider.name.as_str(),
+                    fallback.model.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("azure", "Azure", Some("gpt-5-deployment")),
+                ("openai", "OpenAI", Some("gpt-5-mini")),
+            ]
+        );
+
+        let cfg =
+            toml::from_str::<ConfigToml>(r#"model_fallbacks = [{ model_provider = "missing" }]"#)
+                .expect("TOML deserialization should succeed");
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("unknown fallback provider");
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+
+        Ok(())
+    }
+
+    #[test]
+    fn environment_context_providers_are_opt_in() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let load = |toml: &str| {
+            let cfg =
+                toml::from_str::<ConfigToml>(toml).expect("TOML deserialization should succeed");
+            Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )
+        };
+
+        assert_eq!(
+            load("")?.environment_context,
+            EnvironmentContextProviders::default()
+        );
+
+        let config = load(
+            r#"
+[environment_context]
+git = true
+date = true
+
+[environment_context.commands]
+kube_context = ["kubectl", "config", "current-context"]
+empty = []
+"#,
+        )?;
+        assert_eq!(
+            config.environment_context,
+            EnvironmentContextProviders {
+                git: true,
+                os: false,
+                date: true,
+                toolchains: false,
+                commands: BTreeMap::from([(
+                    "kube_context".to_string(),
+                    vec![
+                        "kubectl".to_string(),
+                        "config".to_string(),
+                        "current-context".to_string(),
+                    ],
+                )]),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn profile_tool_output_aging_overrides_individual_settings() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let load = |toml: &str| {
+            let cfg =
+                toml::from_str::<ConfigToml>(toml).expect("TOML deserialization should succeed");
+            Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )
+        };
+
+        assert_eq!(load("")?.tool_output_aging, ToolOutputAging::default());
+
+        let config = load(
+            r#"
+profile = "long"
+
+[tool_output_aging]
+enabled = true
+token_limit = 150000
+
+[profiles.long.tool_output_aging]
+keep_recent_turns = 5
+"#,
+        )?;
+        assert_eq!(
+            config.tool_output_aging,
+            ToolOutputAging {
+                enabled: true,
+                token_limit: Some(150_000),
+                keep_recent_turns: 5,
+            }
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn cli_override_takes_precedence_over_profile_sandbox_mode() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -3759,6 +4022,7 @@ model_verbosity = "high"
             wire_api: crate::WireApi::Responses,
             env_key_instructions: None,
             experimental_bearer_token: None,
+            auth_command: None,
             query_params: None,
             http_headers: None,
             env_http_headers: None,
@@ -3821,8 +4085,11 @@ model_verbosity = "high"
                 review_model: None,
                 model_context_window: None,
                 model_auto_compact_token_limit: None,
+                tool_output_aging: ToolOutputAging::default(),
+                environment_context: EnvironmentContextProviders::default(),
                 model_provider_id: "openai".to_string(),
                 model_provider: fixture.openai_provider.clone(),
+                model_fallbacks: Vec::new(),
                 approval_policy: Constrained::allow_any(AskForApproval::Never),
                 sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
                 enforce_residency: Constrained::allow_any(None),
@@ -3858,6 +4125,7 @@ model_verbosity = "high"
                 base_instructions: None,
                 developer_instructions: None,
                 compact_prompt: None,
+                commit_instructions: None,
                 forced_chatgpt_workspace_id: None,
                 forced_login_method: None,
                 include_apply_patch_tool: false,
@@ -3906,8 +4174,11 @@ model_verbosity = "high"
             review_model: None,
             model_context_window: None,
             model_auto_compact_token_limit: None,
+            tool_output_aging: ToolOutputAging::default(),
+            environment_context: EnvironmentContextProviders::default(),
             model_provider_id: "openai-custom".to_string(),
             model_provider: fixture.openai_custom_provider.clone(),
+            model_fallbacks: Vec::new(),
             approval_policy: Constrained::allow_any(AskForApproval::UnlessTrusted),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             enforce_residency: Constrained::allow_any(None),
@@ -3943,6 +4214,7 @@ model_verbosity = "high"
             base_instructions: None,
             developer_instructions: None,
             compact_prompt: None,
+            commit_instructions: None,
             forced_chatgpt_workspace_id: None,
             forced_login_method: None,
             include_apply_patch_tool: false,
@@ -4006,8 +4278,11 @@ model_verbosity = "high"
             review_model: None,
             model_context_window: None,
             model_auto_compact_token_limit: None,
+            tool_output_aging: ToolOutputAging::default(),
+            environment_context: EnvironmentContextProviders::default(),
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            model_fallbacks: Vec::new(),
             approval_policy: Constrained::allow_any(AskForApproval::OnFailure),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             enforce_residency: Constrained::allow_any(None),
@@ -4043,6 +4318,7 @@ model_verbosity = "high"
             base_instructions: None,
             developer_instructions: None,
             compact_prompt: None,
+            commit_instructions: None,
             forced_chatgpt_workspace_id: None,
             forced_login_method: None,
             include_apply_patch_tool: false,
@@ -4092,8 +4368,11 @@ model_verbosity = "high"
             review_model: None,
             model_context_window: None,
             model_auto_compact_token_limit: None,
+            tool_output_aging: ToolOutputAging::default(),
+            environment_context: EnvironmentContextProviders::default(),
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            model_fallbacks: Vec::new(),
             approval_policy: Constrained::allow_any(AskForApproval::OnFailure),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             enforce_residency: Constrained::allow_any(None),
@@ -4129,6 +4408,7 @@ model_verbosity = "high"
             base_instructions: None,
             developer_instructions: None,
             compact_prompt: None,
+            commit_instructions: None,
             forced_chatgpt_workspace_id: None,
             forced_login_method: None,
             include_apply_patch_tool: false,
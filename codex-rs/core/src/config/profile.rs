@@ -20,6 +20,9 @@ pub struct ConfigProfile {
     /// The key in the `model_providers` map identifying the
     /// [`ModelProviderInfo`] to use.
     pub model_provider: Option<String>,
+    /// Ordered (provider, model) pairs to try when the primary provider keeps
+    /// failing. Replaces the top-level `model_fallbacks` list.
+    pub model_fallbacks: Option<Vec<crate::config::types::ModelFallbackToml>>,
     pub approval_policy: Option<AskForApproval>,
     pub sandbox_mode: Option<SandboxMode>,
     pub model_reasoning_effort: Option<ReasoningEffort>,
@@ -46,6 +49,8 @@ pub struct ConfigProfile {
     #[schemars(schema_with = "crate::config::schema::features_schema")]
     pub features: Option<crate::features::FeaturesToml>,
     pub oss_provider: Option<String>,
+    /// Overrides individual `tool_output_aging` settings for this profile.
+    pub tool_output_aging: Option<crate::config::types::ToolOutputAgingToml>,
 }
 
 impl From<ConfigProfile> for codex_app_server_protocol::Profile {
@@ -8,9 +8,12 @@ use schemars::schema::ObjectValidation;
 use schemars::schema::RootSchema;
 use schemars::schema::Schema;
 use schemars::schema::SchemaObject;
+use schemars::schema::SingleOrVec;
 use serde_json::Map;
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::path::Path;
+use toml::Value as TomlValue;
 
 /// Schema for the `[features]` map with known + legacy keys only.
 pub(crate) fn features_schema(schema_gen: &mut SchemaGenerator) -> Schema {
@@ -95,11 +98,180 @@ pub fn write_config_schema(out_path: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Return the dotted key paths in `value` that the `config.toml` schema does
+/// not define. Serde silently ignores unknown keys when loading, so a typo such
+/// as `sandbox_mod` otherwise goes unnoticed.
+pub fn unknown_config_keys(value: &TomlValue) -> Vec<Vec<String>> {
+    let root = config_schema();
+    let schema = Schema::Object(root.schema.clone());
+    let mut unknown = Vec::new();
+    collect_unknown_keys(&root, &schema, value, &mut Vec::new(), &mut unknown);
+    unknown
+}
+
+fn collect_unknown_keys(
+    root: &RootSchema,
+    schema: &Schema,
+    value: &TomlValue,
+    path: &mut Vec<String>,
+    unknown: &mut Vec<Vec<String>>,
+) {
+    match value {
+        TomlValue::Table(table) => {
+            let ObjectShape::Known {
+                properties,
+                additional,
+            } = object_shape(root, schema)
+            else {
+                return;
+            };
+            for (key, child) in table {
+                path.push(key.clone());
+                match properties.get(key).or(additional.as_ref()) {
+                    Some(child_schema) => {
+                        collect_unknown_keys(root, child_schema, child, path, unknown);
+                    }
+                    None => unknown.push(path.clone()),
+                }
+                path.pop();
+            }
+        }
+        TomlValue::Array(items) => {
+            let Some(item_schema) = array_item_schema(root, schema) else {
+                return;
+            };
+            for (index, item) in items.iter().enumerate() {
+                path.push(index.to_string());
+                collect_unknown_keys(root, &item_schema, item, path, unknown);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The keys a schema accepts when the value is a table.
+enum ObjectShape {
+    /// The schema does not describe a table (e.g. a string-only setting).
+    NotObject,
+    /// Any key is accepted.
+    Open,
+    Known {
+        properties: BTreeMap<String, Schema>,
+        /// Schema for keys outside `properties`; `None` rejects them.
+        additional: Option<Schema>,
+    },
+}
+
+fn object_shape(root: &RootSchema, schema: &Schema) -> ObjectShape {
+    let Schema::Object(object) = schema else {
+        return ObjectShape::Open;
+    };
+    if let Some(definition) = resolve_reference(root, object) {
+        return object_shape(root, definition);
+    }
+
+    let mut shapes = Vec::new();
+    if let Some(validation) = object.object.as_deref() {
+        let additional = match validation.additional_properties.as_deref() {
+            None | Some(Schema::Bool(false)) => None,
+            Some(Schema::Bool(true)) => return ObjectShape::Open,
+            Some(schema) => Some(schema.clone()),
+        };
+        shapes.push(ObjectShape::Known {
+            properties: validation.properties.clone().into_iter().collect(),
+            additional,
+        });
+    }
+    if let Some(subschemas) = object.subschemas.as_deref() {
+        for schema in [&subschemas.all_of, &subschemas.any_of, &subschemas.one_of]
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            shapes.push(object_shape(root, schema));
+        }
+    }
+    if shapes.is_empty() {
+        let accepts_object = object
+            .instance_type
+            .as_ref()
+            .is_none_or(|types| types.contains(&InstanceType::Object));
+        return if accepts_object {
+            ObjectShape::Open
+        } else {
+            ObjectShape::NotObject
+        };
+    }
+
+    merge_object_shapes(shapes)
+}
+
+fn merge_object_shapes(shapes: Vec<ObjectShape>) -> ObjectShape {
+    let mut merged: Option<(BTreeMap<String, Schema>, Option<Schema>)> = None;
+    for shape in shapes {
+        match shape {
+            ObjectShape::NotObject => {}
+            ObjectShape::Open => return ObjectShape::Open,
+            ObjectShape::Known {
+                properties,
+                additional,
+            } => {
+                let (merged_properties, merged_additional) =
+                    merged.get_or_insert_with(|| (BTreeMap::new(), None));
+                merged_properties.extend(properties);
+                if merged_additional.is_none() {
+                    *merged_additional = additional;
+                }
+            }
+        }
+    }
+    match merged {
+        Some((properties, additional)) => ObjectShape::Known {
+            properties,
+            additional,
+        },
+        None => ObjectShape::NotObject,
+    }
+}
+
+fn array_item_schema(root: &RootSchema, schema: &Schema) -> Option<Schema> {
+    let Schema::Object(object) = schema else {
+        return None;
+    };
+    if let Some(definition) = resolve_reference(root, object) {
+        return array_item_schema(root, definition);
+    }
+    if let Some(SingleOrVec::Single(items)) = object
+        .array
+        .as_deref()
+        .and_then(|array| array.items.as_ref())
+    {
+        return Some(items.as_ref().clone());
+    }
+    let subschemas = object.subschemas.as_deref()?;
+    [&subschemas.all_of, &subschemas.any_of, &subschemas.one_of]
+        .into_iter()
+        .flatten()
+        .flatten()
+        .find_map(|schema| array_item_schema(root, schema))
+}
+
+fn resolve_reference<'a>(root: &'a RootSchema, object: &SchemaObject) -> Option<&'a Schema> {
+    let name = object
+        .reference
+        .as_deref()?
+        .strip_prefix("#/definitions/")?;
+    root.definitions.get(name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::canonicalize;
     use super::config_schema_json;
+    use super::unknown_config_keys;
 
+    use pretty_assertions::assert_eq;
     use similar::TextDiff;
 
     #[test]
@@ -129,4 +301,45 @@ Run `just write-config-schema` to overwrite with your changes.\n\n{diff}"
             );
         }
     }
+
+    #[test]
+    fn unknown_config_keys_reports_typos_at_every_depth() {
+        let config: toml::Value = toml::from_str(
+            r#"
+model = "gpt-5"
+sandbox_mod = "read-only"
+
+[features]
+unified_exec = true
+not_a_feature = true
+
+[mcp_servers.docs]
+command = "docs-server"
+comand = "typo"
+
+[profiles.fast]
+model_reasoning_effort = "low"
+web_serch = "live"
+"#,
+        )
+        .expect("parse config");
+
+        assert_eq!(
+            unknown_config_keys(&config),
+            vec![
+                vec!["features".to_string(), "not_a_feature".to_string()],
+                vec![
+                    "mcp_servers".to_string(),
+                    "docs".to_string(),
+                    "comand".to_string(),
+                ],
+                vec![
+                    "profiles".to_string(),
+                    "fast".to_string(),
+                    "web_serch".to_string(),
+                ],
+                vec!["sandbox_mod".to_string()],
+            ]
+        );
+    }
 }
@@ -4,6 +4,7 @@
 // definitions that do not contain business logic.
 
 use crate::config_loader::RequirementSource;
+use crate::model_provider_info::ModelProviderInfo;
 pub use codex_protocol::config_types::AltScreenMode;
 pub use codex_protocol::config_types::ModeKind;
 pub use codex_protocol::config_types::Personality;
@@ -532,6 +533,157 @@ pub struct SkillsConfig {
     pub config: Vec<SkillConfig>,
 }
 
+/// A `[[conditional]]` block in the user config.toml. When every condition in
+/// `when` holds for the session's working directory, `config` is merged on top
+/// of the user config as its own layer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ConditionalConfigToml {
+    /// Conditions that must all hold for this block to apply.
+    pub when: ConditionalMatchToml,
+    /// Settings applied when the block matches, using the same keys as config.toml.
+    #[schemars(with = "serde_json::Map<String, serde_json::Value>")]
+    pub config: toml::Table,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ConditionalMatchToml {
+    /// Glob matched against the working directory, e.g. `~/src/monorepo/**`.
+    pub path: Option<String>,
+    /// Glob matched against each git remote URL with the scheme, user, and
+    /// `.git` suffix removed, e.g. `github.com/acme/payments*`.
+    pub remote: Option<String>,
+    /// Glob matched against the current git branch, e.g. `release/*`.
+    pub branch: Option<String>,
+}
+
+/// One step of a `model_fallbacks` chain. Omitted fields keep the value the
+/// turn started with.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ModelFallbackToml {
+    /// Key in the `model_providers` map to fall back to.
+    pub model_provider: Option<String>,
+    /// Model to request from the fallback provider.
+    pub model: Option<String>,
+}
+
+/// A `model_fallbacks` entry resolved against `model_providers`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelFallback {
+    pub model_provider_id: String,
+    pub model_provider: ModelProviderInfo,
+    /// `None` keeps the model the turn started with.
+    pub model: Option<String>,
+}
+
+/// Settings for replacing old tool outputs with short stubs before falling
+/// back to full compaction.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ToolOutputAgingToml {
+    /// Replace stale tool outputs with stubs when the context fills up.
+    /// Defaults to `false`.
+    pub enabled: Option<bool>,
+    /// Token usage at which stale outputs are aged. Defaults to the
+    /// auto-compaction limit.
+    pub token_limit: Option<i64>,
+    /// Number of most recent user turns whose tool outputs are never aged.
+    /// Defaults to 2.
+    pub keep_recent_turns: Option<usize>,
+}
+
+pub const DEFAULT_TOOL_OUTPUT_AGING_KEEP_RECENT_TURNS: usize = 2;
+
+/// `tool_output_aging` with defaults applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolOutputAging {
+    pub enabled: bool,
+    /// `None` ages outputs at the auto-compaction limit.
+    pub token_limit: Option<i64>,
+    pub keep_recent_turns: usize,
+}
+
+impl Default for ToolOutputAging {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            token_limit: None,
+            keep_recent_turns: DEFAULT_TOOL_OUTPUT_AGING_KEEP_RECENT_TURNS,
+        }
+    }
+}
+
+impl ToolOutputAging {
+    /// Resolves the profile table over the top-level one, field by field.
+    pub fn resolve(
+        profile: Option<&ToolOutputAgingToml>,
+        base: Option<&ToolOutputAgingToml>,
+    ) -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: profile
+                .and_then(|aging| aging.enabled)
+                .or_else(|| base.and_then(|aging| aging.enabled))
+                .unwrap_or(defaults.enabled),
+            token_limit: profile
+                .and_then(|aging| aging.token_limit)
+                .or_else(|| base.and_then(|aging| aging.token_limit)),
+            keep_recent_turns: profile
+                .and_then(|aging| aging.keep_recent_turns)
+                .or_else(|| base.and_then(|aging| aging.keep_recent_turns))
+                .unwrap_or(defaults.keep_recent_turns),
+        }
+    }
+}
+
+/// Extra facts included in the `<environment_context>` message sent to the
+/// model. Every provider is off unless enabled here.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct EnvironmentContextToml {
+    /// Current git branch and number of uncommitted files.
+    pub git: Option<bool>,
+    /// Operating system, version and architecture.
+    pub os: Option<bool>,
+    /// Current date and UTC offset.
+    pub date: Option<bool>,
+    /// Versions of cargo, node, python and go found on `PATH`.
+    pub toolchains: Option<bool>,
+    /// Commands whose trimmed output is included under their name, e.g.
+    /// `kube_context = ["kubectl", "config", "current-context"]`.
+    pub commands: Option<BTreeMap<String, Vec<String>>>,
+}
+
+/// `environment_context` with defaults applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvironmentContextProviders {
+    pub git: bool,
+    pub os: bool,
+    pub date: bool,
+    pub toolchains: bool,
+    /// Command name to argv. Commands with an empty argv are dropped.
+    pub commands: BTreeMap<String, Vec<String>>,
+}
+
+impl From<EnvironmentContextToml> for EnvironmentContextProviders {
+    fn from(toml: EnvironmentContextToml) -> Self {
+        Self {
+            git: toml.git.unwrap_or(false),
+            os: toml.os.unwrap_or(false),
+            date: toml.date.unwrap_or(false),
+            toolchains: toml.toolchains.unwrap_or(false),
+            commands: toml
+                .commands
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(_, argv)| !argv.is_empty())
+                .collect(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 #[schemars(deny_unknown_fields)]
 pub struct SandboxWorkspaceWrite {
@@ -3,6 +3,7 @@
 
 use crate::config::CONFIG_TOML_FILE;
 use crate::config::ConfigToml;
+use crate::config::schema::unknown_config_keys;
 use codex_app_server_protocol::ConfigLayerSource;
 use codex_utils_absolute_path::AbsolutePathBufGuard;
 use serde_path_to_error::Path as SerdePath;
@@ -182,6 +183,62 @@ where
     None
 }
 
+/// Validate every file-backed layer in `layers`, including disabled project
+/// layers, and return all problems found in precedence order (lowest first).
+pub async fn validate_config_layers(layers: &ConfigLayerStack) -> Vec<ConfigError> {
+    let mut errors = Vec::new();
+    for layer in layers.get_layers(ConfigLayerStackOrdering::LowestPrecedenceFirst, true) {
+        let Some(path) = config_path_for_layer(layer) else {
+            continue;
+        };
+        let contents = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => {
+                errors.push(ConfigError::new(
+                    path.clone(),
+                    default_range(),
+                    format!("failed to read config file: {err}"),
+                ));
+                continue;
+            }
+        };
+        let Some(parent) = path.parent() else {
+            continue;
+        };
+        let _guard = AbsolutePathBufGuard::new(parent);
+        errors.extend(validate_config_file(&path, &contents));
+    }
+    errors
+}
+
+/// Validate a single `config.toml`: TOML syntax, keys the schema does not
+/// define, and the first value that does not deserialize into [ConfigToml].
+pub fn validate_config_file(path: impl AsRef<Path>, contents: &str) -> Vec<ConfigError> {
+    let path = path.as_ref();
+    let value: toml::Value = match toml::from_str(contents) {
+        Ok(value) => value,
+        Err(err) => return vec![config_error_from_toml(path, contents, err)],
+    };
+
+    let mut errors: Vec<ConfigError> = unknown_config_keys(&value)
+        .into_iter()
+        .map(|segments| {
+            let range = span_for_key(contents, &segments)
+                .map(|span| text_range_from_span(contents, span))
+                .unwrap_or_else(default_range);
+            ConfigError::new(
+                path.to_path_buf(),
+                range,
+                format!("unknown configuration key `{}`", segments.join(".")),
+            )
+        })
+        .collect();
+    errors.extend(config_error_from_config_toml(path, contents));
+    errors.sort_by_key(|error| (error.range.start.line, error.range.start.column));
+    errors
+}
+
 fn config_path_for_layer(layer: &ConfigLayerEntry) -> Option<PathBuf> {
     match &layer.name {
         ConfigLayerSource::System { file } => Some(file.to_path_buf()),
@@ -190,7 +247,10 @@ fn config_path_for_layer(layer: &ConfigLayerEntry) -> Option<PathBuf> {
             Some(dot_codex_folder.as_path().join(CONFIG_TOML_FILE))
         }
         ConfigLayerSource::LegacyManagedConfigTomlFromFile { file } => Some(file.to_path_buf()),
+        // Conditional blocks live in the user config.toml, which the user layer
+        // already covers.
         ConfigLayerSource::Mdm { .. }
+        | ConfigLayerSource::Conditional { .. }
         | ConfigLayerSource::SessionFlags
         | ConfigLayerSource::LegacyManagedConfigTomlFromMdm => None,
     }
@@ -314,6 +374,26 @@ fn span_for_config_path(contents: &str, path: &SerdePath) -> Option<std::ops::Ra
     span_for_path(contents, path)
 }
 
+/// Span of the key (rather than the value) at `segments`, so unknown-key
+/// diagnostics point at the name the user mistyped.
+fn span_for_key(contents: &str, segments: &[String]) -> Option<std::ops::Range<usize>> {
+    let doc = contents.parse::<Document<String>>().ok()?;
+    let (last, parents) = segments.split_last()?;
+    let mut node = TomlNode::Item(doc.as_item());
+    for segment in parents {
+        node = match segment.parse::<usize>() {
+            Ok(index) => seq_child(&node, index).or_else(|| map_child(&node, segment))?,
+            Err(_) => map_child(&node, segment)?,
+        };
+    }
+    match node {
+        TomlNode::Item(item) => item.as_table_like()?.get_key_value(last)?.0.span(),
+        TomlNode::Table(table) => table.get_key_value(last)?.0.span(),
+        TomlNode::Value(Value::InlineTable(table)) => table.get_key_value(last)?.0.span(),
+        TomlNode::Value(_) => None,
+    }
+}
+
 fn is_features_table_path(path: &SerdePath) -> bool {
     let mut segments = path.iter();
     matches!(segments.next(), Some(SerdeSegment::Map { key }) if key == "features")
@@ -1,4 +1,5 @@
 mod cloud_requirements;
+mod conditional;
 mod config_requirements;
 mod diagnostics;
 mod fingerprint;
@@ -50,6 +51,8 @@ pub(crate) use diagnostics::first_layer_config_error_from_entries;
 pub use diagnostics::format_config_error;
 pub use diagnostics::format_config_error_with_source;
 pub(crate) use diagnostics::io_error_from_config_error;
+pub use diagnostics::validate_config_file;
+pub use diagnostics::validate_config_layers;
 pub use merge::merge_toml_values;
 pub(crate) use overrides::build_cli_overrides_layer;
 pub use state::ConfigLayerEntry;
@@ -183,7 +186,16 @@ pub async fn load_config_layers_state(
         )
     })
     .await?;
+    // `[[conditional]]` blocks only apply to sessions with a working directory
+    // and sit directly above the user layer that declares them.
+    let conditional_layers = match cwd.as_ref() {
+        Some(cwd) => {
+            conditional::load_conditional_layers(&user_file, &user_layer.config, cwd).await?
+        }
+        None => Vec::new(),
+    };
     layers.push(user_layer);
+    layers.extend(conditional_layers);
 
     if let Some(cwd) = cwd {
         let mut merged_so_far = TomlValue::Table(toml::map::Map::new());
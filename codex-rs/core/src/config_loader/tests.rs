@@ -81,6 +81,65 @@ async fn returns_config_error_for_invalid_user_config_toml() {
     assert_eq!(config_error, &expected_config_error);
 }
 
+#[tokio::test]
+async fn conditional_blocks_layer_above_user_config_when_cwd_matches() {
+    let tmp = tempdir().expect("tempdir");
+    let work = tmp.path().join("work");
+    std::fs::create_dir_all(&work).expect("create work dir");
+    let config_path = tmp.path().join(CONFIG_TOML_FILE);
+    std::fs::write(
+        &config_path,
+        format!(
+            r#"sandbox_mode = "workspace-write"
+
+[[conditional]]
+when = {{ path = '{}/**' }}
+config = {{ sandbox_mode = "read-only" }}
+"#,
+            work.display()
+        ),
+    )
+    .expect("write config");
+
+    let cwd = AbsolutePathBuf::try_from(work.as_path()).expect("cwd");
+    let layers = load_config_layers_state(
+        tmp.path(),
+        Some(cwd),
+        &[] as &[(String, TomlValue)],
+        LoaderOverrides::default(),
+        CloudRequirementsLoader::default(),
+    )
+    .await
+    .expect("load layers");
+
+    assert_eq!(
+        layers.effective_config().get("sandbox_mode"),
+        Some(&TomlValue::String("read-only".to_string()))
+    );
+    let origin = layers.origins().remove("sandbox_mode").expect("origin");
+    assert_eq!(
+        origin.name,
+        super::ConfigLayerSource::Conditional {
+            file: AbsolutePathBuf::try_from(config_path.as_path()).expect("config path"),
+            index: 0,
+        }
+    );
+
+    let thread_agnostic = load_config_layers_state(
+        tmp.path(),
+        None,
+        &[] as &[(String, TomlValue)],
+        LoaderOverrides::default(),
+        CloudRequirementsLoader::default(),
+    )
+    .await
+    .expect("load layers");
+    assert_eq!(
+        thread_agnostic.effective_config().get("sandbox_mode"),
+        Some(&TomlValue::String("workspace-write".to_string()))
+    );
+}
+
 #[tokio::test]
 async fn returns_config_error_for_invalid_managed_config_toml() {
     let tmp = tempdir().expect("tempdir");
@@ -150,6 +209,33 @@ fn schema_error_points_to_feature_value() {
     assert_eq!(error.range.start.column, value_column);
 }
 
+#[test]
+fn validate_config_file_reports_unknown_keys_and_type_errors() {
+    let tmp = tempdir().expect("tempdir");
+    let contents = "model = \"gpt-5\"\nsandbox_mod = \"read-only\"\n\n[tools]\nview_image = 1\n";
+    let config_path = tmp.path().join(CONFIG_TOML_FILE);
+
+    let _guard = codex_utils_absolute_path::AbsolutePathBufGuard::new(tmp.path());
+    let errors = super::validate_config_file(&config_path, contents);
+
+    let summary: Vec<(usize, usize, &str)> = errors
+        .iter()
+        .map(|error| {
+            (
+                error.range.start.line,
+                error.range.start.column,
+                error.message.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(summary.len(), 2);
+    assert_eq!(
+        summary[0],
+        (2, 1, "unknown configuration key `sandbox_mod`")
+    );
+    assert_eq!((summary[1].0, summary[1].1), (5, 14));
+}
+
 #[tokio::test]
 async fn merges_managed_config_layer_on_top() {
     let tmp = tempdir().expect("tempdir");
@@ -0,0 +1,197 @@
+//! Stubs that replace stale tool outputs when the context fills up. A stub
+//! keeps what the model most likely still needs (the command, its exit code
+//! and the edges of the output) and says where the full output is recorded.
+
+use crate::parse_command::extract_shell_command;
+use crate::parse_command::shlex_join;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use serde_json::Value;
+use std::path::Path;
+
+pub(crate) const AGED_OUTPUT_PREFIX: &str = "[output aged out of context";
+
+/// Stubs saving fewer tokens than this are not worth losing the output for.
+pub(crate) const MIN_SAVED_TOKENS: i64 = 100;
+const HEAD_LINES: usize = 5;
+const TAIL_LINES: usize = 5;
+const LINE_MAX_CHARS: usize = 200;
+
+pub(crate) fn output_call_id(item: &ResponseItem) -> Option<&str> {
+    match item {
+        ResponseItem::FunctionCallOutput { call_id, .. }
+        | ResponseItem::CustomToolCallOutput { call_id, .. } => Some(call_id.as_str()),
+        _ => None,
+    }
+}
+
+/// Stub replacing the tool output `item`, or `None` if `item` is not a tool
+/// output or was aged already. `items` is the full history, used to find the
+/// call that produced the output.
+pub(crate) fn stub(
+    item: &ResponseItem,
+    items: &[ResponseItem],
+    rollout_path: Option<&Path>,
+) -> Option<ResponseItem> {
+    let (call_id, text) = output_text(item)?;
+    if text.starts_with(AGED_OUTPUT_PREFIX) {
+        return None;
+    }
+
+    let mut lines = vec![match rollout_path {
+        Some(path) => format!(
+            "{AGED_OUTPUT_PREFIX}; the full output is recorded under call_id `{call_id}` in {}]",
+            path.display()
+        ),
+        None => format!("{AGED_OUTPUT_PREFIX}]"),
+    }];
+    if let Some(command) = call_command(items, call_id) {
+        lines.push(format!("Command: {}", truncate_line(&command)));
+    }
+    let (exit_code, body) = split_exec_output(&text);
+    if let Some(exit_code) = exit_code {
+        lines.push(format!("Exit code: {exit_code}"));
+    }
+    lines.extend(excerpt(&body));
+    let content = lines.join("\n");
+
+    Some(match item {
+        ResponseItem::FunctionCallOutput { call_id, output } => ResponseItem::FunctionCallOutput {
+            call_id: call_id.clone(),
+            output: FunctionCallOutputPayload {
+                content,
+                content_items: None,
+                success: output.success,
+            },
+        },
+        _ => ResponseItem::CustomToolCallOutput {
+            call_id: call_id.to_string(),
+            output: content,
+        },
+    })
+}
+
+fn output_text(item: &ResponseItem) -> Option<(&str, String)> {
+    match item {
+        ResponseItem::FunctionCallOutput { call_id, output } => {
+            let text = match &output.content_items {
+                Some(content_items) if output.content.is_empty() => content_items
+                    .iter()
+                    .filter_map(|item| match item {
+                        FunctionCallOutputContentItem::InputText { text } => Some(text.as_str()),
+                        FunctionCallOutputContentItem::InputImage { .. } => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                _ => output.content.clone(),
+            };
+            Some((call_id.as_str(), text))
+        }
+        ResponseItem::CustomToolCallOutput { call_id, output } => {
+            Some((call_id.as_str(), output.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// Human-readable command for the call that produced `call_id`, falling back
+/// to the tool name when the arguments carry no command.
+fn call_command(items: &[ResponseItem], call_id: &str) -> Option<String> {
+    items.iter().find_map(|item| match item {
+        ResponseItem::FunctionCall {
+            name,
+            arguments,
+            call_id: id,
+            ..
+        } if id == call_id => {
+            Some(command_from_arguments(arguments).unwrap_or_else(|| name.clone()))
+        }
+        ResponseItem::CustomToolCall {
+            name, call_id: id, ..
+        } if id == call_id => Some(name.clone()),
+        ResponseItem::LocalShellCall {
+            call_id: Some(id),
+            action: LocalShellAction::Exec(exec),
+            ..
+        } if id == call_id => Some(display_command(&exec.command)),
+        _ => None,
+    })
+}
+
+fn command_from_arguments(arguments: &str) -> Option<String> {
+    let arguments: Value = serde_json::from_str(arguments).ok()?;
+    match arguments.get("command").or_else(|| arguments.get("cmd"))? {
+        Value::String(command) => Some(command.clone()),
+        Value::Array(tokens) => {
+            let tokens = tokens
+                .iter()
+                .map(|token| token.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()?;
+            Some(display_command(&tokens))
+        }
+        _ => None,
+    }
+}
+
+fn display_command(command: &[String]) -> String {
+    match extract_shell_command(command) {
+        Some((_, script)) => script.to_string(),
+        None => shlex_join(command),
+    }
+}
+
+/// Splits exec output into its exit code and the command's own output. Both
+/// the JSON and the freeform exec formats are recognized; anything else is
+/// returned as-is.
+fn split_exec_output(text: &str) -> (Option<i64>, String) {
+    if let Ok(Value::Object(payload)) = serde_json::from_str::<Value>(text)
+        && let Some(Value::String(output)) = payload.get("output")
+    {
+        let exit_code = payload
+            .get("metadata")
+            .and_then(|metadata| metadata.get("exit_code"))
+            .and_then(Value::as_i64);
+        return (exit_code, output.clone());
+    }
+
+    let exit_code = text.lines().find_map(|line| {
+        line.strip_prefix("Exit code: ")
+            .or_else(|| line.strip_prefix("Process exited with code "))
+            .and_then(|code| code.trim().parse().ok())
+    });
+    let body = match text.split_once("\nOutput:\n") {
+        Some((_, body)) => body.to_string(),
+        None => text.to_string(),
+    };
+    (exit_code, body)
+}
+
+fn excerpt(body: &str) -> Vec<String> {
+    let lines: Vec<&str> = body.lines().collect();
+    if lines.len() <= HEAD_LINES + TAIL_LINES {
+        return lines.into_iter().map(truncate_line).collect();
+    }
+    let omitted = lines.len() - HEAD_LINES - TAIL_LINES;
+    let mut excerpt: Vec<String> = lines[..HEAD_LINES]
+        .iter()
+        .map(|line| truncate_line(line))
+        .collect();
+    excerpt.push(format!("… {omitted} lines omitted …"));
+    excerpt.extend(
+        lines[lines.len() - TAIL_LINES..]
+            .iter()
+            .map(|line| truncate_line(line)),
+    );
+    excerpt
+}
+
+fn truncate_line(line: &str) -> String {
+    if line.chars().count() <= LINE_MAX_CHARS {
+        return line.to_string();
+    }
+    let mut truncated: String = line.chars().take(LINE_MAX_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
@@ -1,10 +1,12 @@
 use crate::codex::TurnContext;
+use crate::context_manager::aging;
+use crate::context_manager::inspect;
 use crate::context_manager::normalize;
+use crate::instructions::PathInstructions;
 use crate::instructions::SkillInstructions;
 use crate::instructions::UserInstructions;
 use crate::session_prefix::is_session_prefix;
 use crate::truncate::TruncationPolicy;
-use crate::truncate::approx_token_count;
 use crate::truncate::approx_tokens_from_byte_count;
 use crate::truncate::truncate_function_output_items_with_policy;
 use crate::truncate::truncate_text;
@@ -13,9 +15,24 @@ use codex_protocol::models::ContentItem;
 use codex_protocol::models::FunctionCallOutputContentItem;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ContextItemCategory;
+use codex_protocol::protocol::ContextItemSummary;
+use codex_protocol::protocol::ContextPruneAction;
 use codex_protocol::protocol::TokenUsage;
 use codex_protocol::protocol::TokenUsageInfo;
+use codex_utils_tokenizer::Tokenizer;
+use std::collections::HashMap;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::ops::Deref;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// Cached item counts are dropped once there are this many, so the cache stays
+/// bounded as items are replaced.
+const MAX_CACHED_ITEM_COUNTS: usize = 8_192;
 
 /// Transcript of thread history
 #[derive(Debug, Clone, Default)]
@@ -23,6 +40,42 @@ pub(crate) struct ContextManager {
     /// The oldest items are at the beginning of the vector.
     items: Vec<ResponseItem>,
     token_info: Option<TokenUsageInfo>,
+    token_counts: TokenCountCache,
+}
+
+/// Token counts of serialized items keyed by tokenizer and content hash, so
+/// re-estimating a growing history only tokenizes items it has not seen. Clones
+/// of a history share the cache.
+#[derive(Debug, Clone, Default)]
+struct TokenCountCache(Arc<Mutex<HashMap<(Tokenizer, u64), i64>>>);
+
+impl TokenCountCache {
+    fn item_tokens(&self, item: &ResponseItem, tokenizer: Tokenizer) -> i64 {
+        // The byte estimate and the fixed counts are cheaper than a lookup.
+        if !tokenizer.is_exact() || fixed_item_token_count(item).is_some() {
+            return estimate_item_token_count(item, tokenizer);
+        }
+        let serialized = serde_json::to_string(item).unwrap_or_default();
+        let mut hasher = DefaultHasher::new();
+        serialized.hash(&mut hasher);
+        let key = (tokenizer, hasher.finish());
+        if let Some(tokens) = self
+            .0
+            .lock()
+            .ok()
+            .and_then(|counts| counts.get(&key).copied())
+        {
+            return tokens;
+        }
+        let tokens = i64::try_from(tokenizer.count(&serialized)).unwrap_or(i64::MAX);
+        if let Ok(mut counts) = self.0.lock() {
+            if counts.len() >= MAX_CACHED_ITEM_COUNTS {
+                counts.clear();
+            }
+            counts.insert(key, tokens);
+        }
+        tokens
+    }
 }
 
 impl ContextManager {
@@ -30,6 +83,7 @@ impl ContextManager {
         Self {
             items: Vec::new(),
             token_info: TokenUsageInfo::new_or_append(&None, &None, None),
+            token_counts: TokenCountCache::default(),
         }
     }
 
@@ -82,23 +136,140 @@ impl ContextManager {
         &self.items
     }
 
-    // Estimate token usage using byte-based heuristics from the truncation helpers.
-    // This is a coarse lower bound, not a tokenizer-accurate count.
+    // Estimate token usage with the turn's tokenizer. Models without a bundled
+    // vocabulary fall back to a byte-based heuristic, which is a coarse lower bound.
     pub(crate) fn estimate_token_count(&self, turn_context: &TurnContext) -> Option<i64> {
-        let model_info = turn_context.client.get_model_info();
-        let personality = turn_context
-            .personality
-            .or(turn_context.client.config().personality);
-        let base_instructions = model_info.get_model_instructions(personality);
-        let base_tokens = i64::try_from(approx_token_count(&base_instructions)).unwrap_or(i64::MAX);
+        let base_tokens = estimate_base_instructions_token_count(turn_context);
 
         let items_tokens = self.items.iter().fold(0i64, |acc, item| {
-            acc.saturating_add(estimate_item_token_count(item))
+            acc.saturating_add(self.token_counts.item_tokens(item, turn_context.tokenizer))
         });
 
         Some(base_tokens.saturating_add(items_tokens))
     }
 
+    /// Per-item view of the prompt, starting with the base instructions, using the same
+    /// estimates as [`Self::estimate_token_count`]. Ghost snapshots are never sent to the
+    /// model and are left out.
+    pub(crate) fn breakdown(&self, turn_context: &TurnContext) -> Vec<ContextItemSummary> {
+        let base_instructions = ContextItemSummary {
+            id: None,
+            category: ContextItemCategory::BaseInstructions,
+            label: turn_context.client.get_model_info().slug,
+            estimated_tokens: estimate_base_instructions_token_count(turn_context),
+        };
+        let items = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| !matches!(item, ResponseItem::GhostSnapshot { .. }))
+            .map(|(index, item)| ContextItemSummary {
+                id: Some(inspect::item_id(index, item)),
+                category: inspect::categorize(item),
+                label: inspect::label(item, &self.items),
+                estimated_tokens: self.token_counts.item_tokens(item, turn_context.tokenizer),
+            });
+        std::iter::once(base_instructions).chain(items).collect()
+    }
+
+    /// Drops or shortens the items whose ids (as reported by [`Self::breakdown`]) are in
+    /// `ids`, leaving the rest verbatim. Dropping a tool call also drops its output.
+    /// Returns the ids that matched no item.
+    pub(crate) fn prune_items(
+        &mut self,
+        ids: &[String],
+        action: ContextPruneAction,
+    ) -> Vec<String> {
+        let mut indices = Vec::new();
+        let mut unknown = Vec::new();
+        for id in ids {
+            match self
+                .items
+                .iter()
+                .enumerate()
+                .position(|(index, item)| inspect::item_id(index, item) == *id)
+            {
+                Some(index) => indices.push(index),
+                None => unknown.push(id.clone()),
+            }
+        }
+        indices.sort_unstable();
+        indices.dedup();
+
+        // Walk backwards so removals don't shift the indices still to visit. Only calls are
+        // removed together with a counterpart, and their outputs always come later.
+        for index in indices.into_iter().rev() {
+            match inspect::prune(&self.items[index], action) {
+                Some(replacement) => self.items[index] = replacement,
+                None => {
+                    let removed = self.items.remove(index);
+                    normalize::remove_corresponding_for(&mut self.items, &removed);
+                }
+            }
+        }
+        unknown
+    }
+
+    /// Call ids of tool outputs recorded before the last `keep_recent_turns`
+    /// user turns whose stubs would save a meaningful number of tokens, oldest
+    /// first.
+    pub(crate) fn stale_tool_output_call_ids(
+        &self,
+        keep_recent_turns: usize,
+        rollout_path: Option<&Path>,
+        tokenizer: Tokenizer,
+    ) -> Vec<String> {
+        let positions = user_message_positions(&self.items);
+        if positions.len() <= keep_recent_turns {
+            return Vec::new();
+        }
+        let cutoff = match keep_recent_turns {
+            0 => self.items.len(),
+            keep => positions[positions.len() - keep],
+        };
+        self.items[..cutoff]
+            .iter()
+            .filter(|item| {
+                aging::stub(item, &self.items, rollout_path).is_some_and(|stub| {
+                    self.token_counts
+                        .item_tokens(item, tokenizer)
+                        .saturating_sub(estimate_item_token_count(&stub, tokenizer))
+                        >= aging::MIN_SAVED_TOKENS
+                })
+            })
+            .filter_map(aging::output_call_id)
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Replaces the outputs of `call_ids` with stubs pointing at
+    /// `rollout_path`. Returns the estimated number of tokens saved.
+    pub(crate) fn age_tool_outputs(
+        &mut self,
+        call_ids: &[String],
+        rollout_path: Option<&Path>,
+        tokenizer: Tokenizer,
+    ) -> i64 {
+        let mut saved = 0i64;
+        for index in 0..self.items.len() {
+            let is_target = aging::output_call_id(&self.items[index])
+                .is_some_and(|call_id| call_ids.iter().any(|id| id == call_id));
+            if !is_target {
+                continue;
+            }
+            let Some(stub) = aging::stub(&self.items[index], &self.items, rollout_path) else {
+                continue;
+            };
+            saved = saved.saturating_add(
+                self.token_counts
+                    .item_tokens(&self.items[index], tokenizer)
+                    .saturating_sub(estimate_item_token_count(&stub, tokenizer)),
+            );
+            self.items[index] = stub;
+        }
+        saved
+    }
+
     pub(crate) fn remove_first_item(&mut self) {
         if !self.items.is_empty() {
             // Remove the oldest item (front of the list). Items are ordered from
@@ -199,7 +370,7 @@ impl ContextManager {
         );
     }
 
-    fn get_non_last_reasoning_items_tokens(&self) -> i64 {
+    fn get_non_last_reasoning_items_tokens(&self, tokenizer: Tokenizer) -> i64 {
         // Get reasoning items excluding all the ones after the last user message.
         let Some(last_user_index) = self
             .items
@@ -222,35 +393,40 @@ impl ContextManager {
                 )
             })
             .fold(0i64, |acc, item| {
-                acc.saturating_add(estimate_item_token_count(item))
+                acc.saturating_add(self.token_counts.item_tokens(item, tokenizer))
             })
     }
 
-    fn get_trailing_codex_generated_items_tokens(&self) -> i64 {
+    fn get_trailing_codex_generated_items_tokens(&self, tokenizer: Tokenizer) -> i64 {
         let mut total = 0i64;
         for item in self.items.iter().rev() {
             if !is_codex_generated_item(item) {
                 break;
             }
-            total = total.saturating_add(estimate_item_token_count(item));
+            total = total.saturating_add(self.token_counts.item_tokens(item, tokenizer));
         }
         total
     }
 
     /// When true, the server already accounted for past reasoning tokens and
     /// the client should not re-estimate them.
-    pub(crate) fn get_total_token_usage(&self, server_reasoning_included: bool) -> i64 {
+    pub(crate) fn get_total_token_usage(
+        &self,
+        server_reasoning_included: bool,
+        tokenizer: Tokenizer,
+    ) -> i64 {
         let last_tokens = self
             .token_info
             .as_ref()
             .map(|info| info.last_token_usage.total_tokens)
             .unwrap_or(0);
-        let trailing_codex_generated_tokens = self.get_trailing_codex_generated_items_tokens();
+        let trailing_codex_generated_tokens =
+            self.get_trailing_codex_generated_items_tokens(tokenizer);
         if server_reasoning_included {
             last_tokens.saturating_add(trailing_codex_generated_tokens)
         } else {
             last_tokens
-                .saturating_add(self.get_non_last_reasoning_items_tokens())
+                .saturating_add(self.get_non_last_reasoning_items_tokens(tokenizer))
                 .saturating_add(trailing_codex_generated_tokens)
         }
     }
@@ -325,6 +501,15 @@ fn is_api_message(message: &ResponseItem) -> bool {
     }
 }
 
+fn estimate_base_instructions_token_count(turn_context: &TurnContext) -> i64 {
+    let model_info = turn_context.client.get_model_info();
+    let personality = turn_context
+        .personality
+        .or(turn_context.client.config().personality);
+    let base_instructions = model_info.get_model_instructions(personality);
+    i64::try_from(turn_context.tokenizer.count(&base_instructions)).unwrap_or(i64::MAX)
+}
+
 fn estimate_reasoning_length(encoded_len: usize) -> usize {
     encoded_len
         .saturating_mul(3)
@@ -333,9 +518,11 @@ fn estimate_reasoning_length(encoded_len: usize) -> usize {
         .saturating_sub(650)
 }
 
-fn estimate_item_token_count(item: &ResponseItem) -> i64 {
+/// Tokens for items whose count does not depend on the tokenizer, or `None`
+/// when the serialized item has to be counted.
+fn fixed_item_token_count(item: &ResponseItem) -> Option<i64> {
     match item {
-        ResponseItem::GhostSnapshot { .. } => 0,
+        ResponseItem::GhostSnapshot { .. } => Some(0),
         ResponseItem::Reasoning {
             encrypted_content: Some(content),
             ..
@@ -344,15 +531,19 @@ fn estimate_item_token_count(item: &ResponseItem) -> i64 {
             encrypted_content: content,
         } => {
             let reasoning_bytes = estimate_reasoning_length(content.len());
-            i64::try_from(approx_tokens_from_byte_count(reasoning_bytes)).unwrap_or(i64::MAX)
-        }
-        item => {
-            let serialized = serde_json::to_string(item).unwrap_or_default();
-            i64::try_from(approx_token_count(&serialized)).unwrap_or(i64::MAX)
+            Some(i64::try_from(approx_tokens_from_byte_count(reasoning_bytes)).unwrap_or(i64::MAX))
         }
+        _ => None,
     }
 }
 
+fn estimate_item_token_count(item: &ResponseItem, tokenizer: Tokenizer) -> i64 {
+    fixed_item_token_count(item).unwrap_or_else(|| {
+        let serialized = serde_json::to_string(item).unwrap_or_default();
+        i64::try_from(tokenizer.count(&serialized)).unwrap_or(i64::MAX)
+    })
+}
+
 pub(crate) fn is_codex_generated_item(item: &ResponseItem) -> bool {
     matches!(
         item,
@@ -371,6 +562,7 @@ pub(crate) fn is_user_turn_boundary(item: &ResponseItem) -> bool {
 
     if UserInstructions::is_user_instructions(content)
         || SkillInstructions::is_skill_instructions(content)
+        || PathInstructions::is_path_instructions(content)
     {
         return false;
     }
@@ -32,7 +32,7 @@ fn create_history_with_items(items: Vec<ResponseItem>) -> ContextManager {
     let mut h = ContextManager::new();
     // Use a generous but fixed token budget; tests only rely on truncation
     // behavior, not on a specific model's token limit.
-    h.record_items(items.iter(), TruncationPolicy::Tokens(10_000));
+    h.record_items(items.iter(), TruncationPolicy::tokens(10_000));
     h
 }
 
@@ -102,13 +102,13 @@ fn reasoning_with_encrypted_content(len: usize) -> ResponseItem {
 }
 
 fn truncate_exec_output(content: &str) -> String {
-    truncate::truncate_text(content, TruncationPolicy::Tokens(EXEC_FORMAT_MAX_TOKENS))
+    truncate::truncate_text(content, TruncationPolicy::tokens(EXEC_FORMAT_MAX_TOKENS))
 }
 
 #[test]
 fn filters_non_api_messages() {
     let mut h = ContextManager::default();
-    let policy = TruncationPolicy::Tokens(10_000);
+    let policy = TruncationPolicy::tokens(10_000);
     // System message is not API messages; Other is ignored.
     let system = ResponseItem::Message {
         id: None,
@@ -167,7 +167,10 @@ fn filters_non_api_messages() {
 fn non_last_reasoning_tokens_return_zero_when_no_user_messages() {
     let history = create_history_with_items(vec![reasoning_with_encrypted_content(800)]);
 
-    assert_eq!(history.get_non_last_reasoning_items_tokens(), 0);
+    assert_eq!(
+        history.get_non_last_reasoning_items_tokens(Tokenizer::Approximate),
+        0
+    );
 }
 
 #[test]
@@ -182,7 +185,10 @@ fn non_last_reasoning_tokens_ignore_entries_after_last_user() {
     // first: (900 * 0.75 - 650) / 4 = 6.25 tokens
     // second: (1000 * 0.75 - 650) / 4 = 25 tokens
     // first + second = 62.5
-    assert_eq!(history.get_non_last_reasoning_items_tokens(), 32);
+    assert_eq!(
+        history.get_non_last_reasoning_items_tokens(Tokenizer::Approximate),
+        32
+    );
 }
 
 #[test]
@@ -196,15 +202,32 @@ fn trailing_codex_generated_tokens_stop_at_first_non_generated_item() {
         trailing_function_output.clone(),
         trailing_custom_output.clone(),
     ]);
-    let expected_tokens = estimate_item_token_count(&trailing_function_output)
-        .saturating_add(estimate_item_token_count(&trailing_custom_output));
+    let expected_tokens =
+        estimate_item_token_count(&trailing_function_output, Tokenizer::Approximate)
+            .saturating_add(estimate_item_token_count(
+                &trailing_custom_output,
+                Tokenizer::Approximate,
+            ));
 
     assert_eq!(
-        history.get_trailing_codex_generated_items_tokens(),
+        history.get_trailing_codex_generated_items_tokens(Tokenizer::Approximate),
         expected_tokens
     );
 }
 
+#[test]
+fn item_token_counts_are_cached_for_exact_tokenizers() {
+    let cache = TokenCountCache::default();
+    let item = assistant_msg("hello there");
+    let exact = estimate_item_token_count(&item, Tokenizer::O200kBase);
+
+    assert_eq!(cache.item_tokens(&item, Tokenizer::O200kBase), exact);
+    assert_eq!(cache.item_tokens(&item, Tokenizer::O200kBase), exact);
+    // The byte estimate is cheaper than a lookup, so it is not cached.
+    cache.item_tokens(&item, Tokenizer::Approximate);
+    assert_eq!(cache.0.lock().unwrap().len(), 1);
+}
+
 #[test]
 fn trailing_codex_generated_tokens_exclude_function_call_tail() {
     let history = create_history_with_items(vec![ResponseItem::FunctionCall {
@@ -214,7 +237,10 @@ fn trailing_codex_generated_tokens_exclude_function_call_tail() {
         call_id: "call-tail".to_string(),
     }]);
 
-    assert_eq!(history.get_trailing_codex_generated_items_tokens(), 0);
+    assert_eq!(
+        history.get_trailing_codex_generated_items_tokens(Tokenizer::Approximate),
+        0
+    );
 }
 
 #[test]
@@ -237,8 +263,8 @@ fn total_token_usage_includes_only_trailing_codex_generated_items() {
     );
 
     assert_eq!(
-        history.get_total_token_usage(true),
-        100 + estimate_item_token_count(&trailing_output)
+        history.get_total_token_usage(true, Tokenizer::Approximate),
+        100 + estimate_item_token_count(&trailing_output, Tokenizer::Approximate)
     );
 }
 
@@ -577,7 +603,7 @@ fn record_items_truncates_function_call_output_content() {
     let mut history = ContextManager::new();
     // Any reasonably small token budget works; the test only cares that
     // truncation happens and the marker is present.
-    let policy = TruncationPolicy::Tokens(1_000);
+    let policy = TruncationPolicy::tokens(1_000);
     let long_line = "a very long line to trigger truncation\n";
     let long_output = long_line.repeat(2_500);
     let item = ResponseItem::FunctionCallOutput {
@@ -613,7 +639,7 @@ fn record_items_truncates_function_call_output_content() {
 #[test]
 fn record_items_truncates_custom_tool_call_output_content() {
     let mut history = ContextManager::new();
-    let policy = TruncationPolicy::Tokens(1_000);
+    let policy = TruncationPolicy::tokens(1_000);
     let line = "custom output that is very long\n";
     let long_output = line.repeat(2_500);
     let item = ResponseItem::CustomToolCallOutput {
@@ -643,7 +669,7 @@ fn record_items_truncates_custom_tool_call_output_content() {
 #[test]
 fn record_items_respects_custom_token_limit() {
     let mut history = ContextManager::new();
-    let policy = TruncationPolicy::Tokens(10);
+    let policy = TruncationPolicy::tokens(10);
     let long_output = "tokenized content repeated many times ".repeat(200);
     let item = ResponseItem::FunctionCallOutput {
         call_id: "call-custom-limit".to_string(),
@@ -1127,3 +1153,296 @@ fn normalize_mixed_inserts_and_removals_panics_in_debug() {
     let mut h = create_history_with_items(items);
     h.normalize_history();
 }
+
+#[test]
+fn prune_items_drop_replaces_tool_output_and_keeps_call() {
+    let call = ResponseItem::FunctionCall {
+        id: None,
+        name: "shell".to_string(),
+        arguments: "{}".to_string(),
+        call_id: "call-1".to_string(),
+    };
+    let items = vec![
+        user_input_text_msg("run the tests"),
+        call.clone(),
+        function_call_output("call-1", &"test log line\n".repeat(1_000)),
+        assistant_msg("done"),
+    ];
+    let mut history = create_history_with_items(items);
+
+    let unknown = history.prune_items(
+        &["output:call-1".to_string(), "item:99".to_string()],
+        ContextPruneAction::Drop,
+    );
+
+    assert_eq!(unknown, vec!["item:99".to_string()]);
+    assert_eq!(
+        history.raw_items(),
+        vec![
+            user_input_text_msg("run the tests"),
+            call,
+            function_call_output("call-1", inspect::DROPPED_OUTPUT_PLACEHOLDER),
+            assistant_msg("done"),
+        ]
+    );
+}
+
+#[test]
+fn prune_items_drop_call_removes_its_output() {
+    let items = vec![
+        user_input_text_msg("hi"),
+        reasoning_msg("thinking"),
+        ResponseItem::CustomToolCall {
+            id: None,
+            status: None,
+            call_id: "tool-1".to_string(),
+            name: "apply_patch".to_string(),
+            input: "*** Begin Patch".to_string(),
+        },
+        custom_tool_call_output("tool-1", "ok"),
+        assistant_msg("patched"),
+    ];
+    let mut history = create_history_with_items(items);
+
+    // Positional ids refer to the history as listed, even when several are removed at once.
+    let unknown = history.prune_items(
+        &["item:1".to_string(), "call:tool-1".to_string()],
+        ContextPruneAction::Drop,
+    );
+
+    assert_eq!(unknown, Vec::<String>::new());
+    assert_eq!(
+        history.raw_items(),
+        vec![user_input_text_msg("hi"), assistant_msg("patched")]
+    );
+}
+
+#[test]
+fn prune_items_truncate_keeps_head_and_tail() {
+    let log = (0..2_000)
+        .map(|line| format!("line {line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut history = create_history_with_items(vec![function_call_output("call-1", &log)]);
+
+    history.prune_items(&["output:call-1".to_string()], ContextPruneAction::Truncate);
+
+    let [ResponseItem::FunctionCallOutput { output, .. }] = history.raw_items() else {
+        panic!("expected a single function call output");
+    };
+    assert!(output.content.len() < log.len() / 10);
+    assert!(output.content.starts_with("line 0\n"));
+    assert!(output.content.ends_with("line 1999"));
+}
+
+#[test]
+fn prune_items_truncate_keeps_reasoning() {
+    let items = vec![
+        user_input_text_msg("hi"),
+        reasoning_msg("thinking"),
+        assistant_msg("hello"),
+    ];
+    let mut history = create_history_with_items(items.clone());
+
+    let unknown = history.prune_items(&["item:1".to_string()], ContextPruneAction::Truncate);
+
+    assert_eq!(unknown, Vec::<String>::new());
+    assert_eq!(history.raw_items(), items);
+}
+
+#[test]
+fn inspect_categorizes_and_labels_items() {
+    let agents = user_input_text_msg(
+        "# AGENTS.md instructions for /repo\n\n<INSTRUCTIONS>\nbe nice\n</INSTRUCTIONS>",
+    );
+    let skill =
+        user_input_text_msg("<skill>\n<name>pdf</name>\n<path>/s/SKILL.md</path>\nx\n</skill>");
+    let environment =
+        user_input_text_msg("<environment_context>\n<cwd>/repo</cwd>\n</environment_context>");
+    let call = ResponseItem::FunctionCall {
+        id: None,
+        name: "shell".to_string(),
+        arguments: "{}".to_string(),
+        call_id: "call-1".to_string(),
+    };
+    let output = function_call_output("call-1", "ok");
+    let image = ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![
+            ContentItem::InputText {
+                text: "what is this?".to_string(),
+            },
+            ContentItem::InputImage {
+                image_url: "data:image/png;base64,AAAA".to_string(),
+            },
+        ],
+        end_turn: None,
+        phase: None,
+    };
+    let items = vec![
+        agents,
+        skill,
+        environment,
+        call,
+        output,
+        image,
+        reasoning_msg("hmm"),
+    ];
+
+    let described: Vec<_> = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            (
+                inspect::item_id(index, item),
+                inspect::categorize(item),
+                inspect::label(item, &items),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        described,
+        vec![
+            (
+                "item:0".to_string(),
+                ContextItemCategory::UserInstructions,
+                "AGENTS.md for /repo".to_string()
+            ),
+            (
+                "item:1".to_string(),
+                ContextItemCategory::Skill,
+                "pdf".to_string()
+            ),
+            (
+                "item:2".to_string(),
+                ContextItemCategory::SessionContext,
+                "environment_context".to_string()
+            ),
+            (
+                "call:call-1".to_string(),
+                ContextItemCategory::ToolCall,
+                "shell".to_string()
+            ),
+            (
+                "output:call-1".to_string(),
+                ContextItemCategory::ToolOutput,
+                "output of shell".to_string()
+            ),
+            (
+                "item:5".to_string(),
+                ContextItemCategory::Image,
+                "what is this?".to_string()
+            ),
+            (
+                "item:6".to_string(),
+                ContextItemCategory::Reasoning,
+                "summary".to_string()
+            ),
+        ]
+    );
+}
+
+fn shell_call(call_id: &str, script: &str) -> ResponseItem {
+    ResponseItem::FunctionCall {
+        id: None,
+        name: "shell".to_string(),
+        arguments: serde_json::json!({ "command": ["bash", "-lc", script] }).to_string(),
+        call_id: call_id.to_string(),
+    }
+}
+
+fn numbered_lines(count: usize) -> String {
+    (0..count)
+        .map(|n| format!("line {n}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn age_tool_outputs_stubs_outputs_older_than_recent_turns() {
+    let old_output = format!(
+        "Exit code: 101\nWall time: 1.5 seconds\nOutput:\n{}",
+        numbered_lines(200)
+    );
+    let recent_output = numbered_lines(200);
+    let items = vec![
+        user_input_text_msg("run the tests"),
+        shell_call("call-0", "git status"),
+        function_call_output("call-0", "clean"),
+        shell_call("call-1", "cargo test"),
+        function_call_output("call-1", &old_output),
+        assistant_msg("one test fails"),
+        user_input_text_msg("fix it"),
+        shell_call("call-2", "cat src/lib.rs"),
+        function_call_output("call-2", &recent_output),
+    ];
+    let mut history = create_history_with_items(items);
+    let rollout_path = Path::new("/tmp/rollout.jsonl");
+
+    assert_eq!(
+        history.stale_tool_output_call_ids(2, Some(rollout_path), Tokenizer::Approximate),
+        Vec::<String>::new()
+    );
+    // Small outputs are not worth a stub.
+    let call_ids =
+        history.stale_tool_output_call_ids(1, Some(rollout_path), Tokenizer::Approximate);
+    assert_eq!(call_ids, vec!["call-1".to_string()]);
+
+    let saved = history.age_tool_outputs(&call_ids, Some(rollout_path), Tokenizer::Approximate);
+
+    assert!(saved > 0, "expected savings, got {saved}");
+    let expected_stub = [
+        "[output aged out of context; the full output is recorded under call_id `call-1` in /tmp/rollout.jsonl]",
+        "Command: cargo test",
+        "Exit code: 101",
+        "line 0",
+        "line 1",
+        "line 2",
+        "line 3",
+        "line 4",
+        "… 190 lines omitted …",
+        "line 195",
+        "line 196",
+        "line 197",
+        "line 198",
+        "line 199",
+    ]
+    .join("\n");
+    assert_eq!(
+        history.raw_items()[4],
+        function_call_output("call-1", &expected_stub)
+    );
+    assert_eq!(
+        history.raw_items()[8],
+        function_call_output("call-2", &recent_output)
+    );
+    assert_eq!(
+        history.stale_tool_output_call_ids(1, Some(rollout_path), Tokenizer::Approximate),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn aged_stub_reads_json_exec_output() {
+    let output = serde_json::json!({
+        "output": numbered_lines(3),
+        "metadata": { "exit_code": 0, "duration_seconds": 0.2 },
+    })
+    .to_string();
+    let items = vec![
+        shell_call("call-1", "ls"),
+        function_call_output("call-1", &output),
+    ];
+
+    let stub = aging::stub(&items[1], &items, None);
+
+    assert_eq!(
+        stub,
+        Some(function_call_output(
+            "call-1",
+            "[output aged out of context]\nCommand: ls\nExit code: 0\nline 0\nline 1\nline 2"
+        ))
+    );
+}
@@ -0,0 +1,255 @@
+//! Per-item view of the history used by the context inspector: stable ids,
+//! categories and labels, plus the rewrites applied when the user prunes an
+//! item.
+
+use crate::instructions::PathInstructions;
+use crate::instructions::SkillInstructions;
+use crate::instructions::USER_INSTRUCTIONS_PREFIX;
+use crate::instructions::UserInstructions;
+use crate::session_prefix::is_session_prefix_content;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_function_output_items_with_policy;
+use crate::truncate::truncate_text;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ReasoningItemReasoningSummary;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ContextItemCategory;
+use codex_protocol::protocol::ContextPruneAction;
+
+/// Token budget kept (split between head and tail) when truncating an item.
+const TRUNCATE_TOKEN_BUDGET: usize = 256;
+const LABEL_MAX_CHARS: usize = 80;
+
+pub(crate) const DROPPED_OUTPUT_PLACEHOLDER: &str = "[output removed from context by the user]";
+pub(crate) const PRUNED_IMAGE_PLACEHOLDER: &str = "[image removed from context by the user]";
+
+/// Id reported for the item at `index`. Tool calls and outputs are keyed by
+/// their `call_id`, so the id survives other items being dropped; everything
+/// else is positional.
+pub(crate) fn item_id(index: usize, item: &ResponseItem) -> String {
+    match item {
+        ResponseItem::FunctionCall { call_id, .. }
+        | ResponseItem::CustomToolCall { call_id, .. }
+        | ResponseItem::LocalShellCall {
+            call_id: Some(call_id),
+            ..
+        } => format!("call:{call_id}"),
+        ResponseItem::FunctionCallOutput { call_id, .. }
+        | ResponseItem::CustomToolCallOutput { call_id, .. } => format!("output:{call_id}"),
+        _ => format!("item:{index}"),
+    }
+}
+
+pub(crate) fn categorize(item: &ResponseItem) -> ContextItemCategory {
+    match item {
+        ResponseItem::Message { role, content, .. } => {
+            if content
+                .iter()
+                .any(|item| matches!(item, ContentItem::InputImage { .. }))
+            {
+                ContextItemCategory::Image
+            } else if UserInstructions::is_user_instructions(content) {
+                ContextItemCategory::UserInstructions
+            } else if SkillInstructions::is_skill_instructions(content) {
+                ContextItemCategory::Skill
+            } else if PathInstructions::is_path_instructions(content) {
+                ContextItemCategory::PathInstructions
+            } else if is_session_prefix_content(content) {
+                ContextItemCategory::SessionContext
+            } else {
+                match role.as_str() {
+                    "assistant" => ContextItemCategory::AssistantMessage,
+                    "developer" => ContextItemCategory::DeveloperMessage,
+                    _ => ContextItemCategory::UserMessage,
+                }
+            }
+        }
+        ResponseItem::FunctionCallOutput { output, .. } => {
+            let has_image = output.content_items.as_ref().is_some_and(|items| {
+                items
+                    .iter()
+                    .any(|item| matches!(item, FunctionCallOutputContentItem::InputImage { .. }))
+            });
+            if has_image {
+                ContextItemCategory::Image
+            } else {
+                ContextItemCategory::ToolOutput
+            }
+        }
+        ResponseItem::CustomToolCallOutput { .. } => ContextItemCategory::ToolOutput,
+        ResponseItem::FunctionCall { .. }
+        | ResponseItem::CustomToolCall { .. }
+        | ResponseItem::LocalShellCall { .. }
+        | ResponseItem::WebSearchCall { .. } => ContextItemCategory::ToolCall,
+        ResponseItem::Reasoning { .. } => ContextItemCategory::Reasoning,
+        ResponseItem::Compaction { .. }
+        | ResponseItem::GhostSnapshot { .. }
+        | ResponseItem::Other => ContextItemCategory::Compaction,
+    }
+}
+
+/// Short description of `item`. `items` is the full history so tool outputs
+/// can be labelled with the name of the call that produced them.
+pub(crate) fn label(item: &ResponseItem, items: &[ResponseItem]) -> String {
+    match item {
+        ResponseItem::Message { content, .. } => message_label(content),
+        ResponseItem::FunctionCall { name, .. } | ResponseItem::CustomToolCall { name, .. } => {
+            name.clone()
+        }
+        ResponseItem::LocalShellCall { .. } => "local_shell".to_string(),
+        ResponseItem::WebSearchCall { .. } => "web_search".to_string(),
+        ResponseItem::FunctionCallOutput { call_id, .. }
+        | ResponseItem::CustomToolCallOutput { call_id, .. } => match call_name(items, call_id) {
+            Some(name) => format!("output of {name}"),
+            None => "tool output".to_string(),
+        },
+        ResponseItem::Reasoning { summary, .. } => summary
+            .iter()
+            .find_map(|ReasoningItemReasoningSummary::SummaryText { text }| first_line(text))
+            .unwrap_or_else(|| "reasoning".to_string()),
+        ResponseItem::Compaction { .. } => "compacted history".to_string(),
+        ResponseItem::GhostSnapshot { .. } | ResponseItem::Other => String::new(),
+    }
+}
+
+/// Rewrites `item` for `action`. `None` means the item should be removed
+/// from history.
+pub(crate) fn prune(item: &ResponseItem, action: ContextPruneAction) -> Option<ResponseItem> {
+    let policy = TruncationPolicy::tokens(TRUNCATE_TOKEN_BUDGET);
+    match (item, action) {
+        (ResponseItem::FunctionCallOutput { call_id, .. }, ContextPruneAction::Drop) => {
+            Some(ResponseItem::FunctionCallOutput {
+                call_id: call_id.clone(),
+                output: FunctionCallOutputPayload {
+                    content: DROPPED_OUTPUT_PLACEHOLDER.to_string(),
+                    content_items: None,
+                    success: None,
+                },
+            })
+        }
+        (ResponseItem::FunctionCallOutput { call_id, output }, ContextPruneAction::Truncate) => {
+            let content_items = output.content_items.as_ref().map(|items| {
+                truncate_function_output_items_with_policy(items, policy)
+                    .into_iter()
+                    .map(|item| match item {
+                        FunctionCallOutputContentItem::InputImage { .. } => {
+                            FunctionCallOutputContentItem::InputText {
+                                text: PRUNED_IMAGE_PLACEHOLDER.to_string(),
+                            }
+                        }
+                        text => text,
+                    })
+                    .collect()
+            });
+            Some(ResponseItem::FunctionCallOutput {
+                call_id: call_id.clone(),
+                output: FunctionCallOutputPayload {
+                    content: truncate_text(&output.content, policy),
+                    content_items,
+                    success: output.success,
+                },
+            })
+        }
+        (ResponseItem::CustomToolCallOutput { call_id, output }, action) => {
+            let output = match action {
+                ContextPruneAction::Drop => DROPPED_OUTPUT_PLACEHOLDER.to_string(),
+                ContextPruneAction::Truncate => truncate_text(output, policy),
+            };
+            Some(ResponseItem::CustomToolCallOutput {
+                call_id: call_id.clone(),
+                output,
+            })
+        }
+        (
+            ResponseItem::Message {
+                id,
+                role,
+                content,
+                end_turn,
+                phase,
+            },
+            ContextPruneAction::Truncate,
+        ) => Some(ResponseItem::Message {
+            id: id.clone(),
+            role: role.clone(),
+            content: content
+                .iter()
+                .map(|item| match item {
+                    ContentItem::InputText { text } => ContentItem::InputText {
+                        text: truncate_text(text, policy),
+                    },
+                    ContentItem::OutputText { text } => ContentItem::OutputText {
+                        text: truncate_text(text, policy),
+                    },
+                    ContentItem::InputImage { .. } => ContentItem::InputText {
+                        text: PRUNED_IMAGE_PLACEHOLDER.to_string(),
+                    },
+                })
+                .collect(),
+            end_turn: *end_turn,
+            phase: phase.clone(),
+        }),
+        // Calls are small and reasoning is opaque, so truncating keeps them
+        // as-is; dropping removes a call together with its output.
+        (_, ContextPruneAction::Truncate) => Some(item.clone()),
+        (_, ContextPruneAction::Drop) => None,
+    }
+}
+
+fn call_name<'a>(items: &'a [ResponseItem], call_id: &str) -> Option<&'a str> {
+    items.iter().find_map(|item| match item {
+        ResponseItem::FunctionCall {
+            name, call_id: id, ..
+        }
+        | ResponseItem::CustomToolCall {
+            name, call_id: id, ..
+        } if id == call_id => Some(name.as_str()),
+        _ => None,
+    })
+}
+
+fn message_label(content: &[ContentItem]) -> String {
+    if let [ContentItem::InputText { text }] = content {
+        if let Some(rest) = text.strip_prefix(USER_INSTRUCTIONS_PREFIX) {
+            let directory = rest.lines().next().unwrap_or_default();
+            return format!("AGENTS.md for {directory}");
+        }
+        if SkillInstructions::is_skill_instructions(content)
+            && let Some((_, rest)) = text.split_once("<name>")
+            && let Some((name, _)) = rest.split_once("</name>")
+        {
+            return name.to_string();
+        }
+        if let Some(path) = PathInstructions::path_from_message(content) {
+            return path.to_string();
+        }
+        if is_session_prefix_content(content)
+            && let Some(tag) = text
+                .trim_start()
+                .strip_prefix('<')
+                .and_then(|rest| rest.split_once('>'))
+                .map(|(tag, _)| tag)
+        {
+            return tag.to_string();
+        }
+    }
+    content
+        .iter()
+        .find_map(|item| match item {
+            ContentItem::InputText { text } | ContentItem::OutputText { text } => first_line(text),
+            ContentItem::InputImage { .. } => None,
+        })
+        .unwrap_or_else(|| "image".to_string())
+}
+
+fn first_line(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+    if line.chars().count() <= LABEL_MAX_CHARS {
+        return Some(line.to_string());
+    }
+    let mut truncated: String = line.chars().take(LABEL_MAX_CHARS - 1).collect();
+    truncated.push('…');
+    Some(truncated)
+}
@@ -1,4 +1,6 @@
+mod aging;
 mod history;
+mod inspect;
 mod normalize;
 
 pub(crate) use history::ContextManager;
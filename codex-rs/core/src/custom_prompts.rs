@@ -1,15 +1,73 @@
+use crate::config::Config;
+use crate::config_loader::ConfigLayerStack;
+use crate::config_loader::ConfigLayerStackOrdering;
+use crate::exec::ExecExpiration;
+use crate::exec::ExecParams;
+use crate::exec::process_exec_tool_call;
+use crate::exec_env::create_env;
+use crate::is_safe_command::is_known_safe_command;
+use crate::protocol::SandboxPolicy;
+use crate::sandboxing::SandboxPermissions;
+use crate::windows_sandbox::WindowsSandboxLevelExt;
+use codex_app_server_protocol::ConfigLayerSource;
+use codex_protocol::config_types::WindowsSandboxLevel;
 use codex_protocol::custom_prompts::CustomPrompt;
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::Component;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 use tokio::fs;
 
+const PROMPTS_DIR_NAME: &str = "prompts";
+/// Largest file an `@{path}` directive may inline.
+const MAX_INCLUDE_BYTES: u64 = 256 * 1024;
+const DIRECTIVE_COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Return the default prompts directory: `$CODEX_HOME/prompts`.
 /// If `CODEX_HOME` cannot be resolved, returns `None`.
 pub fn default_prompts_dir() -> Option<PathBuf> {
     crate::config::find_codex_home()
         .ok()
-        .map(|home| home.join("prompts"))
+        .map(|home| home.join(PROMPTS_DIR_NAME))
+}
+
+/// Return the `.codex/prompts` directories of trusted project layers, closest
+/// to the working directory first. Untrusted projects contribute no prompts.
+pub fn project_prompts_dirs(config_layer_stack: &ConfigLayerStack) -> Vec<PathBuf> {
+    config_layer_stack
+        .get_layers(ConfigLayerStackOrdering::HighestPrecedenceFirst, false)
+        .into_iter()
+        .filter_map(|layer| match &layer.name {
+            ConfigLayerSource::Project { dot_codex_folder } => {
+                Some(dot_codex_folder.as_path().join(PROMPTS_DIR_NAME))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Discover project prompts from trusted `.codex/prompts` folders followed by
+/// user prompts from `user_dir`. When two prompts share a name, the one
+/// closest to the working directory wins, so project prompts shadow user
+/// prompts. Returns entries sorted by name.
+pub async fn discover_prompts(
+    config_layer_stack: &ConfigLayerStack,
+    user_dir: Option<&Path>,
+) -> Vec<CustomPrompt> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::new();
+    let dirs = project_prompts_dirs(config_layer_stack)
+        .into_iter()
+        .chain(user_dir.map(Path::to_path_buf));
+    for dir in dirs {
+        let prompts = discover_prompts_in_excluding(&dir, &seen).await;
+        seen.extend(prompts.iter().map(|prompt| prompt.name.clone()));
+        out.extend(prompts);
+    }
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    out
 }
 
 /// Discover prompt files in the given directory, returning entries sorted by name.
@@ -144,11 +202,201 @@ fn parse_frontmatter(content: &str) -> (Option<String>, Option<String>, String)
     (desc, hint, body)
 }
 
+/// Where prompt directives are expanded. `!{command}` directives run through
+/// the same sandboxed exec path as the model's shell tool calls.
+#[derive(Debug, Clone)]
+pub struct PromptDirectiveContext {
+    pub cwd: PathBuf,
+    pub env: HashMap<String, String>,
+    pub sandbox_policy: SandboxPolicy,
+    pub windows_sandbox_level: WindowsSandboxLevel,
+    pub codex_linux_sandbox_exe: Option<PathBuf>,
+}
+
+impl PromptDirectiveContext {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            cwd: config.cwd.clone(),
+            env: create_env(&config.shell_environment_policy, None),
+            sandbox_policy: config.sandbox_policy.get().clone(),
+            windows_sandbox_level: WindowsSandboxLevel::from_config(config),
+            codex_linux_sandbox_exe: config.codex_linux_sandbox_exe.clone(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum PromptDirectiveError {
+    #[error("unterminated `{0}{{` directive")]
+    Unterminated(char),
+    #[error("failed to read `{path}`: {message}")]
+    ReadFile { path: String, message: String },
+    #[error("`{path}` is outside the working directory")]
+    PathOutsideCwd { path: String },
+    #[error("`{path}` is larger than {} bytes", MAX_INCLUDE_BYTES)]
+    FileTooLarge { path: String },
+    #[error("`{command}` is not a known read-only command")]
+    CommandNotReadOnly { command: String },
+    #[error("`{command}` failed: {message}")]
+    CommandFailed { command: String, message: String },
+}
+
+/// Whether `content` contains `@{path}` or `!{command}` directives.
+pub fn prompt_has_directives(content: &str) -> bool {
+    find_directive(content).is_some()
+}
+
+/// Expand template directives in a prompt body:
+/// - `@{path}` is replaced by the contents of `path`, a relative path inside
+///   the context's `cwd`.
+/// - `!{command}` is replaced by the stdout of `command`, run in `cwd`. Only
+///   commands recognised as read-only (e.g. `git diff --stat`) are executed;
+///   they run without a shell and under the context's sandbox policy.
+///
+/// A backslash before the sigil (`\@{`, `\!{`) keeps the text literal.
+/// Inserted text is not scanned for further directives.
+pub async fn expand_prompt_directives(
+    content: &str,
+    context: &PromptDirectiveContext,
+) -> Result<String, PromptDirectiveError> {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some((start, sigil)) = find_directive(rest) {
+        if start > 0 && rest.as_bytes()[start - 1] == b'\\' {
+            out.push_str(&rest[..start - 1]);
+            out.push_str(&rest[start..start + 2]);
+            rest = &rest[start + 2..];
+            continue;
+        }
+        out.push_str(&rest[..start]);
+        let body_start = start + 2;
+        let Some(len) = closing_brace(&rest[body_start..]) else {
+            return Err(PromptDirectiveError::Unterminated(sigil));
+        };
+        let body = rest[body_start..body_start + len].trim();
+        match sigil {
+            '@' => out.push_str(&include_file(body, &context.cwd).await?),
+            _ => out.push_str(&run_read_only_command(body, context).await?),
+        }
+        rest = &rest[body_start + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Byte offset and sigil of the first `@{` or `!{` in `text`.
+fn find_directive(text: &str) -> Option<(usize, char)> {
+    text.match_indices(['@', '!'])
+        .find(|(idx, _)| text[idx + 1..].starts_with('{'))
+        .and_then(|(idx, sigil)| sigil.chars().next().map(|sigil| (idx, sigil)))
+}
+
+/// Length of the directive body up to its matching `}`; braces nest.
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (idx, ch) in text.char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(idx),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+async fn include_file(path: &str, cwd: &Path) -> Result<String, PromptDirectiveError> {
+    let outside = || PromptDirectiveError::PathOutsideCwd {
+        path: path.to_string(),
+    };
+    let relative = Path::new(path);
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(outside());
+    }
+    let read_error = |err: std::io::Error| PromptDirectiveError::ReadFile {
+        path: path.to_string(),
+        message: err.to_string(),
+    };
+    // Symlinks may still point elsewhere, so compare canonical paths too.
+    let root = fs::canonicalize(cwd).await.map_err(read_error)?;
+    let resolved = fs::canonicalize(cwd.join(relative))
+        .await
+        .map_err(read_error)?;
+    if !resolved.starts_with(&root) {
+        return Err(outside());
+    }
+    let metadata = fs::metadata(&resolved).await.map_err(read_error)?;
+    if metadata.len() > MAX_INCLUDE_BYTES {
+        return Err(PromptDirectiveError::FileTooLarge {
+            path: path.to_string(),
+        });
+    }
+    let bytes = fs::read(&resolved).await.map_err(read_error)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+async fn run_read_only_command(
+    command: &str,
+    context: &PromptDirectiveContext,
+) -> Result<String, PromptDirectiveError> {
+    let failed = |message: String| PromptDirectiveError::CommandFailed {
+        command: command.to_string(),
+        message,
+    };
+    let argv = shlex::split(command).filter(|argv| !argv.is_empty());
+    let Some(argv) = argv.filter(|argv| is_known_safe_command(argv)) else {
+        return Err(PromptDirectiveError::CommandNotReadOnly {
+            command: command.to_string(),
+        });
+    };
+
+    let params = ExecParams {
+        command: argv,
+        cwd: context.cwd.clone(),
+        expiration: ExecExpiration::Timeout(DIRECTIVE_COMMAND_TIMEOUT),
+        env: context.env.clone(),
+        sandbox_permissions: SandboxPermissions::UseDefault,
+        windows_sandbox_level: context.windows_sandbox_level,
+        justification: None,
+        arg0: None,
+    };
+    let output = process_exec_tool_call(
+        params,
+        &context.sandbox_policy,
+        &context.cwd,
+        &context.codex_linux_sandbox_exe,
+        None,
+    )
+    .await
+    .map_err(|err| failed(err.to_string()))?;
+    if output.exit_code != 0 {
+        let mut message = format!("exited with code {}", output.exit_code);
+        if let Some(line) = output
+            .stderr
+            .text
+            .lines()
+            .find(|line| !line.trim().is_empty())
+        {
+            message.push_str(&format!(": {}", line.trim()));
+        }
+        return Err(failed(message));
+    }
+    Ok(output.stdout.text.trim_end().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config_loader::ConfigLayerEntry;
+    use crate::config_loader::ConfigRequirements;
+    use crate::config_loader::ConfigRequirementsToml;
+    use codex_utils_absolute_path::AbsolutePathBuf;
     use std::fs;
     use tempfile::tempdir;
+    use toml::Value as TomlValue;
 
     #[tokio::test]
     async fn empty_when_dir_missing() {
@@ -241,4 +489,171 @@ mod tests {
         assert_eq!(hint.as_deref(), Some("[arg]"));
         assert_eq!(body, "First line\r\nSecond line\r\n");
     }
+
+    fn project_layer(dot_codex: &Path, trusted: bool) -> ConfigLayerEntry {
+        let source = ConfigLayerSource::Project {
+            dot_codex_folder: AbsolutePathBuf::from_absolute_path(dot_codex).unwrap(),
+        };
+        let config = TomlValue::Table(Default::default());
+        if trusted {
+            ConfigLayerEntry::new(source, config)
+        } else {
+            ConfigLayerEntry::new_disabled(source, config, "untrusted")
+        }
+    }
+
+    #[tokio::test]
+    async fn project_prompts_shadow_user_prompts_when_trusted() {
+        let tmp = tempdir().expect("create TempDir");
+        let root = tmp.path().join("repo");
+        let nested = root.join("pkg");
+        let user = tmp.path().join("user");
+        for (dir, files) in [
+            (
+                root.join(".codex/prompts"),
+                vec![("review.md", "root review"), ("root.md", "root")],
+            ),
+            (
+                nested.join(".codex/prompts"),
+                vec![("review.md", "pkg review")],
+            ),
+            (
+                user.clone(),
+                vec![("review.md", "user review"), ("mine.md", "mine")],
+            ),
+        ] {
+            fs::create_dir_all(&dir).unwrap();
+            for (name, body) in files {
+                fs::write(dir.join(name), body).unwrap();
+            }
+        }
+
+        let stack = ConfigLayerStack::new(
+            vec![
+                project_layer(&root.join(".codex"), true),
+                project_layer(&nested.join(".codex"), true),
+            ],
+            ConfigRequirements::default(),
+            ConfigRequirementsToml::default(),
+        )
+        .unwrap();
+        let found = discover_prompts(&stack, Some(&user)).await;
+        let found: Vec<(String, String)> = found.into_iter().map(|p| (p.name, p.content)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("mine".to_string(), "mine".to_string()),
+                ("review".to_string(), "pkg review".to_string()),
+                ("root".to_string(), "root".to_string()),
+            ]
+        );
+
+        let untrusted = ConfigLayerStack::new(
+            vec![project_layer(&root.join(".codex"), false)],
+            ConfigRequirements::default(),
+            ConfigRequirementsToml::default(),
+        )
+        .unwrap();
+        let contents: Vec<String> = discover_prompts(&untrusted, Some(&user))
+            .await
+            .into_iter()
+            .map(|p| p.content)
+            .collect();
+        assert_eq!(contents, vec!["mine", "user review"]);
+    }
+
+    fn directive_context(cwd: &Path) -> PromptDirectiveContext {
+        PromptDirectiveContext {
+            cwd: cwd.to_path_buf(),
+            env: std::env::vars().collect(),
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            windows_sandbox_level: WindowsSandboxLevel::Disabled,
+            codex_linux_sandbox_exe: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn expands_file_includes_and_escapes() {
+        let tmp = tempdir().expect("create TempDir");
+        fs::write(tmp.path().join("notes.txt"), "line one\n").unwrap();
+
+        let expanded = expand_prompt_directives(
+            "Notes:\n@{ notes.txt }Literal \\@{x} and !",
+            &directive_context(tmp.path()),
+        )
+        .await;
+        assert_eq!(
+            expanded,
+            Ok("Notes:\nline one\nLiteral @{x} and !".to_string())
+        );
+        assert!(prompt_has_directives("see @{a.txt}"));
+        assert!(!prompt_has_directives("mail me@example.com!"));
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_directives() {
+        let tmp = tempdir().expect("create TempDir");
+        let context = directive_context(tmp.path());
+        assert_eq!(
+            expand_prompt_directives("!{git diff", &context).await,
+            Err(PromptDirectiveError::Unterminated('!'))
+        );
+        assert_eq!(
+            expand_prompt_directives("!{rm -rf target}", &context).await,
+            Err(PromptDirectiveError::CommandNotReadOnly {
+                command: "rm -rf target".to_string()
+            })
+        );
+        assert!(matches!(
+            expand_prompt_directives("@{missing.txt}", &context).await,
+            Err(PromptDirectiveError::ReadFile { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_includes_outside_cwd() {
+        let tmp = tempdir().expect("create TempDir");
+        let cwd = tmp.path().join("project");
+        fs::create_dir(&cwd).unwrap();
+        fs::write(tmp.path().join("secret.txt"), "hunter2").unwrap();
+        let context = directive_context(&cwd);
+
+        for path in ["../secret.txt", "sub/../../secret.txt"] {
+            assert_eq!(
+                expand_prompt_directives(&format!("@{{{path}}}"), &context).await,
+                Err(PromptDirectiveError::PathOutsideCwd {
+                    path: path.to_string()
+                })
+            );
+        }
+        let absolute = tmp.path().join("secret.txt").display().to_string();
+        assert_eq!(
+            expand_prompt_directives(&format!("@{{{absolute}}}"), &context).await,
+            Err(PromptDirectiveError::PathOutsideCwd { path: absolute })
+        );
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(tmp.path().join("secret.txt"), cwd.join("link.txt"))
+                .unwrap();
+            assert_eq!(
+                expand_prompt_directives("@{link.txt}", &context).await,
+                Err(PromptDirectiveError::PathOutsideCwd {
+                    path: "link.txt".to_string()
+                })
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn runs_read_only_commands_in_cwd() {
+        let tmp = tempdir().expect("create TempDir");
+        fs::write(tmp.path().join("a.txt"), "").unwrap();
+        fs::write(tmp.path().join("b.txt"), "").unwrap();
+        assert_eq!(
+            expand_prompt_directives("Files:\n!{ls}\nDone", &directive_context(tmp.path())).await,
+            Ok("Files:\na.txt\nb.txt\nDone".to_string())
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use crate::codex::TurnContext;
+use crate::environment_facts::EnvironmentFacts;
 use crate::shell::Shell;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseItem;
@@ -13,11 +14,21 @@ use std::path::PathBuf;
 pub(crate) struct EnvironmentContext {
     pub cwd: Option<PathBuf>,
     pub shell: Shell,
+    pub facts: EnvironmentFacts,
 }
 
 impl EnvironmentContext {
     pub fn new(cwd: Option<PathBuf>, shell: Shell) -> Self {
-        Self { cwd, shell }
+        Self {
+            cwd,
+            shell,
+            facts: EnvironmentFacts::default(),
+        }
+    }
+
+    pub fn with_facts(mut self, facts: EnvironmentFacts) -> Self {
+        self.facts = facts;
+        self
     }
 
     /// Compares two environment contexts, ignoring the shell. Useful when
@@ -28,10 +39,10 @@ impl EnvironmentContext {
             cwd,
             // should compare all fields except shell
             shell: _,
-            ..
+            facts,
         } = other;
 
-        self.cwd == *cwd
+        self.cwd == *cwd && self.facts == *facts
     }
 
     pub fn diff(before: &TurnContext, after: &TurnContext, shell: &Shell) -> Self {
@@ -40,11 +51,13 @@ impl EnvironmentContext {
         } else {
             None
         };
-        EnvironmentContext::new(cwd, shell.clone())
+        let facts = EnvironmentFacts::changed(&before.environment_facts, &after.environment_facts);
+        EnvironmentContext::new(cwd, shell.clone()).with_facts(facts)
     }
 
     pub fn from_turn_context(turn_context: &TurnContext, shell: &Shell) -> Self {
         Self::new(Some(turn_context.cwd.clone()), shell.clone())
+            .with_facts(turn_context.environment_facts.clone())
     }
 }
 
@@ -57,6 +70,8 @@ impl EnvironmentContext {
     /// <environment_context>
     ///   <cwd>...</cwd>
     ///   <shell>...</shell>
+    ///   <git_branch>...</git_branch>
+    ///   ...
     /// </environment_context>
     /// ```
     pub fn serialize_to_xml(self) -> String {
@@ -67,6 +82,7 @@ impl EnvironmentContext {
 
         let shell_name = self.shell.name();
         lines.push(format!("  <shell>{shell_name}</shell>"));
+        lines.extend(self.facts.xml_lines());
         lines.push(ENVIRONMENT_CONTEXT_CLOSE_TAG.to_string());
         lines.join("\n")
     }
@@ -185,6 +201,35 @@ mod tests {
         assert!(!context1.equals_except_shell(&context2));
     }
 
+    #[test]
+    fn serialize_environment_context_with_facts() {
+        let context = EnvironmentContext::new(None, fake_shell()).with_facts(EnvironmentFacts {
+            date: Some("2026-10-19".to_string()),
+            timezone: Some("+02:00".to_string()),
+            ..Default::default()
+        });
+
+        let expected = r#"<environment_context>
+  <shell>bash</shell>
+  <current_date>2026-10-19</current_date>
+  <timezone>+02:00</timezone>
+</environment_context>"#;
+
+        assert_eq!(context.serialize_to_xml(), expected);
+    }
+
+    #[test]
+    fn equals_except_shell_compares_facts() {
+        let context1 = EnvironmentContext::new(Some(PathBuf::from("/repo")), fake_shell());
+        let context2 = EnvironmentContext::new(Some(PathBuf::from("/repo")), fake_shell())
+            .with_facts(EnvironmentFacts {
+                date: Some("2026-10-19".to_string()),
+                ..Default::default()
+            });
+
+        assert!(!context1.equals_except_shell(&context2));
+    }
+
     #[test]
     fn equals_except_shell_ignores_shell() {
         let context1 = EnvironmentContext::new(
@@ -224,6 +224,29 @@ impl CodexErr {
             CodexErr::LandlockRuleset(_) | CodexErr::LandlockPathFd(_) => false,
         }
     }
+
+    /// Whether this error, once retries are exhausted, means the provider is
+    /// unavailable or out of capacity, so a `model_fallbacks` entry may serve
+    /// the turn instead.
+    pub fn is_provider_unavailable(&self) -> bool {
+        match self {
+            CodexErr::Stream(..)
+            | CodexErr::Timeout
+            | CodexErr::ResponseStreamFailed(_)
+            | CodexErr::ConnectionFailed(_)
+            | CodexErr::InternalServerError
+            | CodexErr::RetryLimit(_)
+            | CodexErr::UsageLimitReached(_)
+            | CodexErr::ModelCap(_)
+            | CodexErr::QuotaExceeded => true,
+            CodexErr::UnexpectedStatus(err) => {
+                err.status.is_server_error()
+                    || err.status == StatusCode::TOO_MANY_REQUESTS
+                    || err.status == StatusCode::REQUEST_TIMEOUT
+            }
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -995,6 +1018,27 @@ mod tests {
         });
     }
 
+    #[test]
+    fn provider_unavailable_covers_outages_but_not_bad_requests() {
+        let unexpected = |status| {
+            CodexErr::UnexpectedStatus(UnexpectedResponseError {
+                status,
+                body: String::new(),
+                url: None,
+                cf_ray: None,
+                request_id: None,
+            })
+        };
+
+        assert!(CodexErr::Timeout.is_provider_unavailable());
+        assert!(CodexErr::Stream("disconnected".to_string(), None).is_provider_unavailable());
+        assert!(unexpected(StatusCode::BAD_GATEWAY).is_provider_unavailable());
+        assert!(unexpected(StatusCode::TOO_MANY_REQUESTS).is_provider_unavailable());
+        assert!(!unexpected(StatusCode::NOT_FOUND).is_provider_unavailable());
+        assert!(!CodexErr::ContextWindowExceeded.is_provider_unavailable());
+        assert!(!CodexErr::InvalidRequest("bad".to_string()).is_provider_unavailable());
+    }
+
     #[test]
     fn unexpected_status_cloudflare_html_is_simplified() {
         let err = UnexpectedResponseError {
@@ -127,6 +127,12 @@ pub enum Feature {
     Personality,
     /// Use the Responses API WebSocket transport for OpenAI by default.
     ResponsesWebsockets,
+    /// Persist truncated tool output so the model can read it back.
+    OutputSpill,
+    /// Expose the `service` tool for named long-running processes.
+    BackgroundServices,
+    /// Expose the client-side `fetch_url` tool for reading web pages.
+    FetchUrl,
 }
 
 impl Feature {
@@ -577,6 +583,24 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::UnderDevelopment,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::OutputSpill,
+        key: "output_spill",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::BackgroundServices,
+        key: "background_services",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::FetchUrl,
+        key: "fetch_url",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
 ];
 
 /// Push a warning event if any under-development features are enabled.
@@ -0,0 +1,536 @@
+//! Minimal HTML to markdown conversion for `fetch_url`.
+//!
+//! This is not a spec-compliant HTML parser. It walks the tag stream once,
+//! drops non-content elements (scripts, styles, `<head>` apart from the
+//! title), and renders the common structural tags as markdown so the model
+//! gets readable text instead of raw markup.
+
+use url::Url;
+
+/// Elements whose contents are never rendered. Their bodies are skipped up to
+/// the matching close tag without being tokenized.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "iframe", "object", "canvas", "select",
+];
+
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "header",
+    "main",
+    "nav",
+    "p",
+    "section",
+    "summary",
+    "table",
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct HtmlDocument {
+    pub(crate) title: Option<String>,
+    pub(crate) markdown: String,
+}
+
+/// Convert `html` to markdown, resolving relative links against `base`.
+pub(crate) fn html_to_markdown(html: &str, base: &Url) -> HtmlDocument {
+    let mut writer = MarkdownWriter::new(base);
+    let mut title = None;
+    let mut in_head = false;
+    let mut rest = html;
+
+    while let Some(lt) = rest.find('<') {
+        if !in_head {
+            writer.text(&rest[..lt]);
+        }
+        rest = &rest[lt..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+        let Some((tag, after)) = parse_tag(rest) else {
+            // A stray `<` that does not start a tag is plain text.
+            if !in_head {
+                writer.text("<");
+            }
+            rest = &rest[1..];
+            continue;
+        };
+        rest = after;
+
+        let name = tag.name.as_str();
+        if !tag.closing && (SKIPPED_ELEMENTS.contains(&name) || name == "title") {
+            let (body, after) = split_raw_text(rest, name);
+            if name == "title" && title.is_none() {
+                let text = collapse_whitespace(&decode_entities(body));
+                if !text.is_empty() {
+                    title = Some(text);
+                }
+            }
+            rest = after;
+            continue;
+        }
+        if name == "head" {
+            in_head = !tag.closing;
+            continue;
+        }
+        if name == "body" {
+            in_head = false;
+            continue;
+        }
+        if !in_head {
+            writer.tag(&tag);
+        }
+    }
+    if !in_head {
+        writer.text(rest);
+    }
+
+    HtmlDocument {
+        title,
+        markdown: writer.finish(),
+    }
+}
+
+struct Tag {
+    name: String,
+    closing: bool,
+    attrs: Vec<(String, String)>,
+}
+
+impl Tag {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Parse the tag at the start of `input` (which begins with `<`), returning
+/// the tag and the input after its closing `>`.
+fn parse_tag(input: &str) -> Option<(Tag, &str)> {
+    let body = &input[1..];
+    let (closing, body) = match body.strip_prefix('/') {
+        Some(body) => (true, body),
+        None => (false, body),
+    };
+    let name_len = body
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(body.len());
+    if name_len == 0 || !body.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let name = body[..name_len].to_ascii_lowercase();
+
+    let mut attrs = Vec::new();
+    let mut rest = &body[name_len..];
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if rest.is_empty() {
+            return Some((
+                Tag {
+                    name,
+                    closing,
+                    attrs,
+                },
+                "",
+            ));
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return Some((
+                Tag {
+                    name,
+                    closing,
+                    attrs,
+                },
+                after,
+            ));
+        }
+
+        let key_len = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len())
+            .max(1);
+        let key = rest[..key_len].to_ascii_lowercase();
+        rest = rest[key_len..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            let (raw, after) = match after_eq.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after_eq[1..];
+                    match inner.find(quote) {
+                        Some(end) => (&inner[..end], &inner[end + 1..]),
+                        None => (inner, ""),
+                    }
+                }
+                _ => {
+                    let end = after_eq
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(after_eq.len());
+                    (&after_eq[..end], &after_eq[end..])
+                }
+            };
+            value = decode_entities(raw);
+            rest = after;
+        }
+        attrs.push((key, value));
+    }
+}
+
+/// Split raw element content at its close tag, returning the content and the
+/// input after the close tag.
+fn split_raw_text<'a>(input: &'a str, name: &str) -> (&'a str, &'a str) {
+    let close = format!("</{name}");
+    let lower = input.to_ascii_lowercase();
+    match lower.find(&close) {
+        Some(start) => {
+            let after = &input[start..];
+            let end = after.find('>').map_or(after.len(), |end| end + 1);
+            (&input[..start], &after[end..])
+        }
+        None => (input, ""),
+    }
+}
+
+struct MarkdownWriter<'a> {
+    base: &'a Url,
+    out: String,
+    pending_space: bool,
+    pre_depth: usize,
+    /// One entry per open list: `None` for `<ul>`, the next item number for `<ol>`.
+    lists: Vec<Option<usize>>,
+    /// Output offset and resolved target for each open `<a>`.
+    links: Vec<(usize, Option<String>)>,
+}
+
+impl<'a> MarkdownWriter<'a> {
+    fn new(base: &'a Url) -> Self {
+        Self {
+            base,
+            out: String::new(),
+            pending_space: false,
+            pre_depth: 0,
+            lists: Vec::new(),
+            links: Vec::new(),
+        }
+    }
+
+    fn text(&mut self, raw: &str) {
+        if raw.is_empty() {
+            return;
+        }
+        let text = decode_entities(raw);
+        if self.pre_depth > 0 {
+            self.out.push_str(&text);
+            return;
+        }
+        for ch in text.chars() {
+            if ch.is_whitespace() {
+                self.pending_space = true;
+            } else {
+                self.flush_space();
+                self.out.push(ch);
+            }
+        }
+    }
+
+    fn push(&mut self, s: &str) {
+        self.flush_space();
+        self.out.push_str(s);
+    }
+
+    fn flush_space(&mut self) {
+        if std::mem::take(&mut self.pending_space)
+            && !self.out.is_empty()
+            && !self.out.ends_with(char::is_whitespace)
+        {
+            self.out.push(' ');
+        }
+    }
+
+    /// Ensure the output ends with at least `newlines` line breaks.
+    fn block(&mut self, newlines: usize) {
+        self.pending_space = false;
+        if self.out.is_empty() {
+            return;
+        }
+        let trimmed_len = self.out.trim_end_matches([' ', '\t']).len();
+        self.out.truncate(trimmed_len);
+        let existing = self.out.len() - self.out.trim_end_matches('\n').len();
+        for _ in existing..newlines {
+            self.out.push('\n');
+        }
+    }
+
+    fn tag(&mut self, tag: &Tag) {
+        let name = tag.name.as_str();
+        match (name, tag.closing) {
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                self.block(2);
+                let level = usize::from(name.as_bytes()[1] - b'0');
+                self.push(&format!("{} ", "#".repeat(level)));
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => self.block(2),
+            ("br", _) => {
+                self.pending_space = false;
+                self.out.push('\n');
+            }
+            ("hr", false) => {
+                self.block(2);
+                self.out.push_str("---");
+                self.block(2);
+            }
+            ("pre", false) => {
+                self.block(2);
+                self.out.push_str("```\n");
+                self.pre_depth += 1;
+            }
+            ("pre", true) if self.pre_depth > 0 => {
+                self.pre_depth -= 1;
+                if !self.out.ends_with('\n') {
+                    self.out.push('\n');
+                }
+                self.out.push_str("```");
+                self.block(2);
+            }
+            ("code", _) if self.pre_depth == 0 => self.push("`"),
+            ("strong" | "b", _) if self.pre_depth == 0 => self.push("**"),
+            ("em" | "i", _) if self.pre_depth == 0 => self.push("_"),
+            ("ul", false) => {
+                self.block(1);
+                self.lists.push(None);
+            }
+            ("ol", false) => {
+                self.block(1);
+                let start = tag
+                    .attr("start")
+                    .and_then(|start| start.trim().parse().ok())
+                    .unwrap_or(1);
+                self.lists.push(Some(start));
+            }
+            ("ul" | "ol", true) => {
+                self.lists.pop();
+                self.block(if self.lists.is_empty() { 2 } else { 1 });
+            }
+            ("li", false) => {
+                self.block(1);
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                let marker = match self.lists.last_mut() {
+                    Some(Some(next)) => {
+                        let marker = format!("{next}. ");
+                        *next += 1;
+                        marker
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.out.push_str(&indent);
+                self.out.push_str(&marker);
+            }
+            ("li" | "tr", true) => self.block(1),
+            ("tr", false) => self.block(1),
+            ("td" | "th", false) => {
+                if !self.out.is_empty() && !self.out.ends_with('\n') {
+                    self.pending_space = false;
+                    self.out.push_str(" | ");
+                }
+            }
+            ("a", false) => {
+                let href = tag.attr("href").and_then(|href| self.resolve(href));
+                self.flush_space();
+                self.links.push((self.out.len(), href));
+            }
+            ("a", true) => {
+                if let Some((start, Some(href))) = self.links.pop()
+                    && !self.out[start..].trim().is_empty()
+                {
+                    self.out.insert(start, '[');
+                    self.out.push_str(&format!("]({href})"));
+                }
+            }
+            ("img", false) => {
+                let alt = collapse_whitespace(tag.attr("alt").unwrap_or_default());
+                if let Some(src) = tag.attr("src").and_then(|src| self.resolve(src))
+                    && !alt.is_empty()
+                {
+                    self.push(&format!("![{alt}]({src})"));
+                }
+            }
+            (name, _) if BLOCK_ELEMENTS.contains(&name) => {
+                self.block(if name == "p" || name == "table" { 2 } else { 1 });
+            }
+            _ => {}
+        }
+    }
+
+    fn resolve(&self, href: &str) -> Option<String> {
+        let href = href.trim();
+        if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+            return None;
+        }
+        self.base.join(href).ok().map(String::from)
+    }
+
+    fn finish(self) -> String {
+        let mut markdown = String::with_capacity(self.out.len());
+        let mut blank_lines = 0;
+        for line in self.out.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                blank_lines += 1;
+                if blank_lines > 1 || markdown.is_empty() {
+                    continue;
+                }
+            } else {
+                blank_lines = 0;
+            }
+            markdown.push_str(line);
+            markdown.push('\n');
+        }
+        markdown.trim_end().to_string()
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Decode character references. Unknown named entities are left untouched.
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end]).map(|ch| (ch, end)));
+        match decoded {
+            Some((ch, end)) => {
+                out.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(numeric) = entity.strip_prefix('#') {
+        let code = match numeric.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => numeric.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "times" => '×',
+        "middot" => '·',
+        "bull" => '•',
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn convert(html: &str) -> HtmlDocument {
+        let base = Url::parse("https://docs.example.com/guide/index.html").expect("url");
+        html_to_markdown(html, &base)
+    }
+
+    #[test]
+    fn renders_structure_and_drops_non_content() {
+        let html = r##"<!DOCTYPE html>
+<html><head><title> Install &amp; Setup </title>
+<meta charset="utf-8"><style>body { color: red; }</style></head>
+<body>
+<script>alert("<b>hi</b>")</script>
+<h1>Getting <em>started</em></h1>
+<p>Run the   installer, then read the
+<a href="../api/">API docs</a> or <a href="#top">jump</a>.</p>
+<ul><li>One</li><li>Two<ol start="3"><li>Nested</li></ol></li></ul>
+<pre><code>cargo  build
+cargo test</code></pre>
+<!-- hidden -->
+<p>Cost &lt; 5&nbsp;&#8364; <img src="/logo.png" alt="Logo"></p>
+</body></html>"##;
+
+        assert_eq!(
+            convert(html),
+            HtmlDocument {
+                title: Some("Install & Setup".to_string()),
+                markdown: "# Getting _started_\n\n\
+                    Run the installer, then read the [API docs](https://docs.example.com/api/) or jump.\n\n\
+                    - One\n\
+                    - Two\n  \
+                    3. Nested\n\n\
+                    ```\ncargo  build\ncargo test\n```\n\n\
+                    Cost < 5 € ![Logo](https://docs.example.com/logo.png)"
+                    .to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn tolerates_malformed_markup() {
+        assert_eq!(
+            convert("a < b and <unclosed").markdown,
+            "a < b and".to_string()
+        );
+        assert_eq!(
+            convert("<p>x &bogus; y").markdown,
+            "x &bogus; y".to_string()
+        );
+        assert_eq!(
+            convert("<table><tr><th>k</th><th>v</th></tr><tr><td>a</td><td>1</td></tr></table>")
+                .markdown,
+            "k | v\na | 1".to_string()
+        );
+    }
+}
@@ -0,0 +1,640 @@
+//! Client-side page fetching for the `fetch_url` tool.
+//!
+//! Unlike the hosted web search tool this works with any model provider. The
+//! `[network_proxy]` section of `config.toml` is honoured: when the proxy is
+//! enabled every request is routed through it so its allow/deny lists and
+//! limited mode apply unchanged; otherwise the same domain lists are checked
+//! here and hosts outside `allowed_domains` need user approval. Without the
+//! proxy, domain names are resolved before connecting and the request is
+//! pinned to the addresses that passed the local-address check, so DNS cannot
+//! point an allowed name at a private or metadata address. Fetched pages
+//! are converted to markdown and cached for the rest of the session so paging
+//! through a long document does not refetch it.
+
+mod html;
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::header::CONTENT_TYPE;
+use reqwest::header::LOCATION;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use tracing::warn;
+use url::Host;
+use url::Url;
+use wildmatch::WildMatch;
+
+use crate::config_loader::ConfigLayerStack;
+use crate::default_client::get_codex_user_agent;
+
+pub(crate) const FETCH_URL_TOOL_NAME: &str = "fetch_url";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_BODY_BYTES: usize = 5 * 1024 * 1024;
+/// Characters of markdown returned per page of output.
+const PAGE_CHARS: usize = 20_000;
+/// Header the network proxy sets on responses it blocked.
+const PROXY_ERROR_HEADER: &str = "x-proxy-error";
+
+/// The subset of `[network_proxy]` that applies to `fetch_url`. Parsed from
+/// the merged config the same way `codex-network-proxy` does so both agree on
+/// the effective policy.
+#[derive(Debug, Default, Deserialize)]
+struct NetworkProxyConfigToml {
+    #[serde(default)]
+    network_proxy: NetworkProxyToml,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct NetworkProxyToml {
+    #[serde(default)]
+    enabled: bool,
+    proxy_url: Option<String>,
+    #[serde(default)]
+    policy: NetworkPolicyToml,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct NetworkPolicyToml {
+    #[serde(default)]
+    allowed_domains: Vec<String>,
+    #[serde(default)]
+    denied_domains: Vec<String>,
+    #[serde(default)]
+    allow_local_binding: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HostAccess {
+    Allowed,
+    NeedsApproval,
+    Denied(&'static str),
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct FetchUrlPolicy {
+    proxy_url: Option<String>,
+    allowed_domains: Vec<String>,
+    denied_domains: Vec<String>,
+    allow_local_binding: bool,
+}
+
+impl FetchUrlPolicy {
+    pub(crate) fn from_config_layer_stack(stack: &ConfigLayerStack) -> Self {
+        let config: NetworkProxyConfigToml = match stack.effective_config().try_into() {
+            Ok(config) => config,
+            Err(err) => {
+                warn!("ignoring invalid [network_proxy] config for fetch_url: {err}");
+                NetworkProxyConfigToml::default()
+            }
+        };
+        let NetworkProxyToml {
+            enabled,
+            proxy_url,
+            policy,
+        } = config.network_proxy;
+        Self {
+            proxy_url: enabled
+                .then(|| proxy_url.unwrap_or_else(|| "http://127.0.0.1:3128".to_string())),
+            allowed_domains: normalize_patterns(policy.allowed_domains),
+            denied_domains: normalize_patterns(policy.denied_domains),
+            allow_local_binding: policy.allow_local_binding,
+        }
+    }
+
+    /// Decide whether `url` may be fetched. When the network proxy is enabled
+    /// it is the single source of truth and reports blocks in its response.
+    pub(crate) fn check(&self, url: &Url) -> HostAccess {
+        if self.proxy_url.is_some() {
+            return HostAccess::Allowed;
+        }
+        let Some(host) = url.host() else {
+            return HostAccess::Denied("the URL has no host");
+        };
+        let host_str = match &host {
+            Host::Domain(domain) => domain.trim_end_matches('.').to_ascii_lowercase(),
+            Host::Ipv4(ip) => ip.to_string(),
+            Host::Ipv6(ip) => ip.to_string(),
+        };
+
+        // Same order as the proxy: explicit deny wins, then local addresses
+        // are opt-in, then the allowlist.
+        if matches_any(&self.denied_domains, &host_str) {
+            return HostAccess::Denied("the domain is denied by network_proxy.policy");
+        }
+        if is_local_host(&host) && !self.allows_local(&host_str) {
+            return HostAccess::Denied("local and private addresses are not allowed");
+        }
+        if matches_any(&self.allowed_domains, &host_str) {
+            HostAccess::Allowed
+        } else {
+            HostAccess::NeedsApproval
+        }
+    }
+
+    /// Local addresses are reachable only with `allow_local_binding` or when
+    /// the host is allowlisted by exact name.
+    fn allows_local(&self, host: &str) -> bool {
+        self.allow_local_binding || self.allowed_domains.iter().any(|pattern| pattern == host)
+    }
+
+    /// Reject a domain whose DNS answer includes a non-public address, so a
+    /// public name cannot be used to reach loopback, private or metadata
+    /// services.
+    fn check_resolved(&self, host: &str, addrs: &[SocketAddr]) -> Result<(), String> {
+        if addrs.is_empty() {
+            return Err(format!("{host} did not resolve to any address"));
+        }
+        if self.allows_local(host) {
+            return Ok(());
+        }
+        match addrs.iter().find(|addr| is_non_public_ip(addr.ip())) {
+            Some(addr) => Err(format!(
+                "{host} resolves to {}, and local and private addresses are not allowed",
+                addr.ip()
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+fn normalize_patterns(patterns: Vec<String>) -> Vec<String> {
+    patterns
+        .into_iter()
+        .map(|pattern| {
+            let pattern = pattern.trim().trim_end_matches('.').to_ascii_lowercase();
+            match pattern.strip_prefix('[').and_then(|p| p.strip_suffix(']')) {
+                Some(ip) => ip.to_string(),
+                None => pattern,
+            }
+        })
+        .filter(|pattern| !pattern.is_empty())
+        .collect()
+}
+
+/// Domain patterns follow the proxy: `example.com` is exact, `*.example.com`
+/// matches subdomains only, `**.example.com` matches the apex too, and `*`
+/// matches everything.
+fn matches_any(patterns: &[String], host: &str) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| match pattern.strip_prefix("**.") {
+            Some(apex) => {
+                WildMatch::new(apex).matches(host)
+                    || WildMatch::new(&format!("*.{apex}")).matches(host)
+            }
+            None => WildMatch::new(pattern).matches(host),
+        })
+}
+
+fn is_local_host(host: &Host<&str>) -> bool {
+    match host {
+        Host::Domain(domain) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain == "localhost" || domain.ends_with(".localhost")
+        }
+        Host::Ipv4(ip) => is_non_public_ip(IpAddr::V4(*ip)),
+        Host::Ipv6(ip) => is_non_public_ip(IpAddr::V6(*ip)),
+    }
+}
+
+fn is_non_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                // 0.0.0.0/8 ("this network").
+                || ip.octets()[0] == 0
+                // 100.64.0.0/10 (carrier-grade NAT).
+                || (ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_non_public_ip(IpAddr::V4(v4)),
+            None => {
+                let first = ip.segments()[0];
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80
+            }
+        },
+    }
+}
+
+/// A fetched document, split into pages of markdown.
+#[derive(Debug)]
+pub(crate) struct FetchedPage {
+    pub(crate) url: Url,
+    pub(crate) title: Option<String>,
+    pub(crate) pages: Vec<String>,
+    /// The body was cut at [`MAX_BODY_BYTES`].
+    pub(crate) truncated: bool,
+}
+
+impl FetchedPage {
+    /// Render page `page` (1-indexed) with a header describing where it came
+    /// from and how to continue.
+    pub(crate) fn render(&self, page: usize) -> Result<String, String> {
+        let total = self.pages.len();
+        let Some(body) = self.pages.get(page - 1) else {
+            return Err(format!(
+                "page {page} is out of range; the document has {total} page(s)"
+            ));
+        };
+        let mut out = format!("URL: {}\n", self.url);
+        if let Some(title) = &self.title {
+            out.push_str(&format!("Title: {title}\n"));
+        }
+        out.push_str(&format!("Page {page} of {total}"));
+        if page < total {
+            out.push_str(&format!(" (call again with page={} for more)", page + 1));
+        }
+        if self.truncated {
+            out.push_str(&format!(
+                "\nNote: the response exceeded {} MiB and was truncated.",
+                MAX_BODY_BYTES / (1024 * 1024)
+            ));
+        }
+        out.push_str("\n\n");
+        out.push_str(body);
+        Ok(out)
+    }
+}
+
+/// Outcome of a single HTTP request. Redirects are surfaced rather than
+/// followed so the caller can check the next host against the policy.
+pub(crate) enum FetchStep {
+    Redirect(Url),
+    Page(FetchedPage),
+}
+
+/// Per-session `fetch_url` state: the effective policy, an HTTP client
+/// configured for it, and the page cache.
+pub(crate) struct FetchUrlStore {
+    policy: FetchUrlPolicy,
+    client: reqwest::Client,
+    cache: Mutex<HashMap<String, Arc<FetchedPage>>>,
+}
+
+impl FetchUrlStore {
+    pub(crate) fn new(stack: &ConfigLayerStack) -> Self {
+        Self::with_policy(FetchUrlPolicy::from_config_layer_stack(stack))
+    }
+
+    fn with_policy(policy: FetchUrlPolicy) -> Self {
+        let mut builder = client_builder();
+        if let Some(proxy_url) = &policy.proxy_url {
+            match reqwest::Proxy::all(proxy_url) {
+                Ok(proxy) => builder = builder.proxy(proxy),
+                Err(err) => warn!("invalid network_proxy.proxy_url {proxy_url}: {err}"),
+            }
+        } else {
+            builder = builder.no_proxy();
+        }
+        let client = builder.build().unwrap_or_else(|err| {
+            warn!("failed to build fetch_url client: {err}");
+            reqwest::Client::new()
+        });
+        Self {
+            policy,
+            client,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The client to use for `url`. Domain names are resolved here and the
+    /// returned client only connects to the addresses that were checked, so a
+    /// second lookup at connect time cannot return something else.
+    async fn client_for(&self, url: &Url) -> Result<reqwest::Client, String> {
+        let Some(Host::Domain(domain)) = url.host() else {
+            // IP literals were already checked by `FetchUrlPolicy::check`.
+            return Ok(self.client.clone());
+        };
+        if self.policy.proxy_url.is_some() {
+            return Ok(self.client.clone());
+        }
+        let host = domain.trim_end_matches('.').to_ascii_lowercase();
+        let port = url.port_or_known_default().unwrap_or(80);
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((domain, port))
+            .await
+            .map_err(|err| format!("failed to resolve {host}: {err}"))?
+            .collect();
+        self.policy.check_resolved(&host, &addrs)?;
+        client_builder()
+            .no_proxy()
+            .resolve_to_addrs(domain, &addrs)
+            .build()
+            .map_err(|err| format!("failed to build a client for {url}: {err}"))
+    }
+
+    pub(crate) fn policy(&self) -> &FetchUrlPolicy {
+        &self.policy
+    }
+
+    pub(crate) async fn cached(&self, url: &Url) -> Option<Arc<FetchedPage>> {
+        self.cache.lock().await.get(url.as_str()).cloned()
+    }
+
+    pub(crate) async fn insert(&self, url: &Url, page: FetchedPage) -> Arc<FetchedPage> {
+        let page = Arc::new(page);
+        self.cache
+            .lock()
+            .await
+            .insert(url.to_string(), Arc::clone(&page));
+        page
+    }
+
+    /// Issue one GET for `url`. Errors are messages for the model. Each
+    /// redirect hop is a separate call, so every hop is resolved and checked.
+    pub(crate) async fn fetch_once(&self, url: &Url) -> Result<FetchStep, String> {
+        let client = self.client_for(url).await?;
+        let mut response = client
+            .get(url.clone())
+            .header(
+                reqwest::header::ACCEPT,
+                "text/html,text/markdown,text/plain;q=0.9,*/*;q=0.5",
+            )
+            .send()
+            .await
+            .map_err(|err| format!("request to {url} failed: {err}"))?;
+
+        let status = response.status();
+        if let Some(blocked) = response.headers().get(PROXY_ERROR_HEADER) {
+            let blocked = blocked.to_str().unwrap_or("blocked-by-policy").to_string();
+            let message = response.text().await.unwrap_or_default();
+            return Err(format!(
+                "the network proxy blocked {url} ({blocked}): {}",
+                message.trim()
+            ));
+        }
+        if status.is_redirection()
+            && let Some(location) = response.headers().get(LOCATION)
+        {
+            let location = location
+                .to_str()
+                .map_err(|_| format!("{url} redirected to an invalid location"))?;
+            let next = url
+                .join(location)
+                .map_err(|err| format!("{url} redirected to an invalid location: {err}"))?;
+            return Ok(FetchStep::Redirect(next));
+        }
+        if !status.is_success() {
+            return Err(format!("{url} returned HTTP {status}"));
+        }
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if !is_text_content_type(&content_type) {
+            return Err(format!(
+                "{url} returned unsupported content type `{content_type}`; only text and HTML pages can be read"
+            ));
+        }
+
+        let mut body = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|err| format!("failed to read {url}: {err}"))?
+        {
+            let remaining = MAX_BODY_BYTES - body.len();
+            if chunk.len() > remaining {
+                body.extend_from_slice(&chunk[..remaining]);
+                truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let text = String::from_utf8_lossy(&body);
+
+        let (title, markdown) = if content_type.is_empty() || content_type.contains("html") {
+            let document = html::html_to_markdown(&text, url);
+            (document.title, document.markdown)
+        } else {
+            (None, text.trim().to_string())
+        };
+        Ok(FetchStep::Page(FetchedPage {
+            url: url.clone(),
+            title,
+            pages: paginate(&markdown, PAGE_CHARS),
+            truncated,
+        }))
+    }
+}
+
+fn client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .user_agent(get_codex_user_agent())
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+}
+
+fn is_text_content_type(content_type: &str) -> bool {
+    content_type.is_empty()
+        || content_type.starts_with("text/")
+        || content_type.contains("html")
+        || content_type.contains("json")
+        || content_type.contains("xml")
+        || content_type.contains("markdown")
+}
+
+/// Split `text` into pages of at most `page_chars` characters, preferring
+/// line boundaries. Always returns at least one (possibly empty) page.
+fn paginate(text: &str, page_chars: usize) -> Vec<String> {
+    let mut pages = Vec::new();
+    let mut current = String::new();
+    let mut current_chars = 0;
+    for line in text.split_inclusive('\n') {
+        let mut line = line;
+        loop {
+            let line_chars = line.chars().count();
+            if current_chars + line_chars <= page_chars {
+                current.push_str(line);
+                current_chars += line_chars;
+                break;
+            }
+            if current_chars > 0 {
+                pages.push(std::mem::take(&mut current));
+                current_chars = 0;
+                continue;
+            }
+            // A single line longer than a page: hard-split it.
+            let split = line
+                .char_indices()
+                .nth(page_chars)
+                .map_or(line.len(), |(idx, _)| idx);
+            pages.push(line[..split].to_string());
+            line = &line[split..];
+        }
+    }
+    if !current.is_empty() || pages.is_empty() {
+        pages.push(current);
+    }
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    fn policy(allowed: &[&str], denied: &[&str]) -> FetchUrlPolicy {
+        FetchUrlPolicy {
+            proxy_url: None,
+            allowed_domains: normalize_patterns(allowed.iter().map(ToString::to_string).collect()),
+            denied_domains: normalize_patterns(denied.iter().map(ToString::to_string).collect()),
+            allow_local_binding: false,
+        }
+    }
+
+    fn check(policy: &FetchUrlPolicy, url: &str) -> HostAccess {
+        policy.check(&Url::parse(url).expect("url"))
+    }
+
+    #[test]
+    fn policy_matches_proxy_domain_patterns() {
+        let policy = policy(
+            &["docs.rs", "*.github.io", "**.Rust-Lang.org.", "127.0.0.1"],
+            &["evil.docs.rs", "*.internal.github.io"],
+        );
+
+        assert_eq!(check(&policy, "https://docs.rs/serde"), HostAccess::Allowed);
+        assert_eq!(check(&policy, "https://a.github.io/"), HostAccess::Allowed);
+        assert_eq!(
+            check(&policy, "https://github.io/"),
+            HostAccess::NeedsApproval
+        );
+        assert_eq!(
+            check(&policy, "https://rust-lang.org/"),
+            HostAccess::Allowed
+        );
+        assert_eq!(
+            check(&policy, "https://doc.rust-lang.org/"),
+            HostAccess::Allowed
+        );
+        assert_eq!(
+            check(&policy, "https://example.com/"),
+            HostAccess::NeedsApproval
+        );
+        assert!(matches!(
+            check(&policy, "https://x.internal.github.io/"),
+            HostAccess::Denied(_)
+        ));
+        assert!(matches!(
+            check(&policy, "http://localhost:8080/"),
+            HostAccess::Denied(_)
+        ));
+        assert!(matches!(
+            check(&policy, "http://[::ffff:10.0.0.1]/"),
+            HostAccess::Denied(_)
+        ));
+        assert_eq!(
+            check(&policy, "http://127.0.0.1:8080/"),
+            HostAccess::Allowed
+        );
+
+        let proxied = FetchUrlPolicy {
+            proxy_url: Some("http://127.0.0.1:3128".to_string()),
+            ..policy
+        };
+        assert_eq!(check(&proxied, "http://localhost/"), HostAccess::Allowed);
+    }
+
+    #[test]
+    fn resolved_addresses_must_be_public() {
+        let policy = policy(&["*", "intranet.example.com"], &[]);
+        let addr = |ip: &str| SocketAddr::new(ip.parse().expect("ip"), 443);
+
+        assert_eq!(
+            policy.check_resolved("docs.rs", &[addr("151.101.1.1")]),
+            Ok(())
+        );
+        for ip in ["127.0.0.1", "10.1.2.3", "169.254.169.254", "fd00:ec2::254"] {
+            assert!(
+                policy
+                    .check_resolved("rebind.example.com", &[addr("151.101.1.1"), addr(ip)])
+                    .is_err(),
+                "{ip} should be rejected"
+            );
+        }
+        assert!(policy.check_resolved("docs.rs", &[]).is_err());
+        assert_eq!(
+            policy.check_resolved("intranet.example.com", &[addr("10.1.2.3")]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn paginate_prefers_line_boundaries() {
+        assert_eq!(paginate("", 4), vec![String::new()]);
+        assert_eq!(
+            paginate("ab\ncd\nefghij", 4),
+            vec!["ab\n", "cd\n", "efgh", "ij"]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_once_converts_html_and_reports_redirects() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/old"))
+            .respond_with(ResponseTemplate::new(301).insert_header("location", "/docs"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/docs"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                "<html><head><title>Docs</title></head><body><h2>Usage</h2><p>Call <code>run()</code>.</p></body></html>",
+                "text/html; charset=utf-8",
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/logo.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(vec![0u8; 4], "image/png"))
+            .mount(&server)
+            .await;
+        let store = FetchUrlStore::with_policy(policy(&["127.0.0.1"], &[]));
+        let base = Url::parse(&server.uri()).expect("url");
+
+        let old = base.join("/old").expect("url");
+        let Ok(FetchStep::Redirect(next)) = store.fetch_once(&old).await else {
+            panic!("expected a redirect");
+        };
+        assert_eq!(next, base.join("/docs").expect("url"));
+
+        let Ok(FetchStep::Page(page)) = store.fetch_once(&next).await else {
+            panic!("expected a page");
+        };
+        assert_eq!(
+            page.render(1).expect("render"),
+            format!("URL: {next}\nTitle: Docs\nPage 1 of 1\n\n## Usage\n\nCall `run()`.")
+        );
+        assert!(page.render(2).is_err());
+
+        let err = store
+            .fetch_once(&base.join("/logo.png").expect("url"))
+            .await
+            .err()
+            .expect("binary content is rejected");
+        assert!(err.contains("image/png"), "{err}");
+    }
+}
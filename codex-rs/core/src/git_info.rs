@@ -662,6 +662,35 @@ pub async fn current_branch_name(cwd: &Path) -> Option<String> {
         .filter(|name| !name.is_empty())
 }
 
+/// Returns the number of files with uncommitted changes, including untracked
+/// files, or `None` outside a git repository.
+pub async fn dirty_file_count(cwd: &Path) -> Option<usize> {
+    let out = run_git_command_with_timeout(&["status", "--porcelain"], cwd).await?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count(),
+    )
+}
+
+/// Returns the contents of the file named by `commit.template`, if one is
+/// configured and readable.
+pub async fn commit_template(cwd: &Path) -> Option<String> {
+    let out = run_git_command_with_timeout(&["config", "--path", "--get", "commit.template"], cwd)
+        .await?;
+    if !out.status.success() {
+        return None;
+    }
+    let path = String::from_utf8(out.stdout).ok()?;
+    let path = cwd.join(path.trim());
+    let template = tokio::fs::read_to_string(path).await.ok()?;
+    (!template.trim().is_empty()).then_some(template)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -839,6 +868,38 @@ mod tests {
         (repo_path, branch)
     }
 
+    #[tokio::test]
+    async fn test_dirty_file_count_counts_modified_and_untracked_files() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        assert_eq!(dirty_file_count(temp_dir.path()).await, None);
+
+        let repo_path = create_test_git_repo(&temp_dir).await;
+        assert_eq!(dirty_file_count(&repo_path).await, Some(0));
+
+        fs::write(repo_path.join("test.txt"), "changed").expect("write tracked file");
+        fs::write(repo_path.join("new.txt"), "new").expect("write untracked file");
+        assert_eq!(dirty_file_count(&repo_path).await, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_commit_template_reads_configured_file() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let repo_path = create_test_git_repo(&temp_dir).await;
+        assert_eq!(commit_template(&repo_path).await, None);
+
+        fs::write(repo_path.join(".gitmessage"), "area: summary\n").expect("write template");
+        Command::new("git")
+            .args(["config", "commit.template", ".gitmessage"])
+            .current_dir(&repo_path)
+            .output()
+            .await
+            .expect("Failed to set commit.template");
+        assert_eq!(
+            commit_template(&repo_path).await,
+            Some("area: summary\n".to_string())
+        );
+    }
+
     #[tokio::test]
     async fn test_collect_git_info_non_git_directory() {
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
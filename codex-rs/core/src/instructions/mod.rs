@@ -1,5 +1,6 @@
 mod user_instructions;
 
+pub(crate) use user_instructions::PathInstructions;
 pub(crate) use user_instructions::SkillInstructions;
 pub use user_instructions::USER_INSTRUCTIONS_OPEN_TAG_LEGACY;
 pub use user_instructions::USER_INSTRUCTIONS_PREFIX;
@@ -7,6 +7,7 @@ use codex_protocol::models::ResponseItem;
 pub const USER_INSTRUCTIONS_OPEN_TAG_LEGACY: &str = "<user_instructions>";
 pub const USER_INSTRUCTIONS_PREFIX: &str = "# AGENTS.md instructions for ";
 pub const SKILL_INSTRUCTIONS_PREFIX: &str = "<skill";
+pub const PATH_INSTRUCTIONS_PREFIX: &str = "<path_instructions>";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename = "user_instructions", rename_all = "snake_case")]
@@ -79,6 +80,55 @@ impl From<SkillInstructions> for ResponseItem {
     }
 }
 
+/// Instructions from a path-scoped file under `.codex/instructions`, injected
+/// the first time the agent touches a file matching one of its globs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename = "path_instructions", rename_all = "snake_case")]
+pub(crate) struct PathInstructions {
+    pub path: String,
+    pub applies_to: Vec<String>,
+    pub contents: String,
+}
+
+impl PathInstructions {
+    pub fn is_path_instructions(message: &[ContentItem]) -> bool {
+        if let [ContentItem::InputText { text }] = message {
+            text.starts_with(PATH_INSTRUCTIONS_PREFIX)
+        } else {
+            false
+        }
+    }
+
+    /// Returns the instruction file path recorded in an injected message.
+    pub fn path_from_message(message: &[ContentItem]) -> Option<&str> {
+        let [ContentItem::InputText { text }] = message else {
+            return None;
+        };
+        let rest = text.strip_prefix(PATH_INSTRUCTIONS_PREFIX)?;
+        let rest = rest.trim_start().strip_prefix("<path>")?;
+        rest.split_once("</path>").map(|(path, _)| path)
+    }
+}
+
+impl From<PathInstructions> for ResponseItem {
+    fn from(pi: PathInstructions) -> Self {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: format!(
+                    "{PATH_INSTRUCTIONS_PREFIX}\n<path>{}</path>\n<applies_to>{}</applies_to>\n{}\n</path_instructions>",
+                    pi.path,
+                    pi.applies_to.join(", "),
+                    pi.contents
+                ),
+            }],
+            end_turn: None,
+            phase: None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,4 +216,33 @@ mod tests {
             }
         ]));
     }
+
+    #[test]
+    fn test_path_instructions() {
+        let path_instructions = PathInstructions {
+            path: "/repo/.codex/instructions/migrations.md".to_string(),
+            applies_to: vec!["migrations/**".to_string(), "db/*.sql".to_string()],
+            contents: "Never edit applied migrations.".to_string(),
+        };
+        let response_item: ResponseItem = path_instructions.into();
+
+        let ResponseItem::Message { role, content, .. } = response_item else {
+            panic!("expected ResponseItem::Message");
+        };
+
+        assert_eq!(role, "user");
+        assert!(PathInstructions::is_path_instructions(&content));
+        assert_eq!(
+            PathInstructions::path_from_message(&content),
+            Some("/repo/.codex/instructions/migrations.md")
+        );
+
+        let [ContentItem::InputText { text }] = content.as_slice() else {
+            panic!("expected one InputText content item");
+        };
+        assert_eq!(
+            text,
+            "<path_instructions>\n<path>/repo/.codex/instructions/migrations.md</path>\n<applies_to>migrations/**, db/*.sql</applies_to>\nNever edit applied migrations.\n</path_instructions>",
+        );
+    }
 }
@@ -27,11 +27,13 @@ mod context_manager;
 pub mod custom_prompts;
 pub mod env;
 mod environment_context;
+mod environment_facts;
 pub mod error;
 pub mod exec;
 pub mod exec_env;
 mod exec_policy;
 pub mod features;
+mod fetch_url;
 mod flags;
 pub mod git_info;
 pub mod instructions;
@@ -48,12 +50,14 @@ mod mentions;
 mod message_history;
 mod model_provider_info;
 pub mod parse_command;
+mod path_instructions;
 pub mod path_utils;
 pub mod personality_migration;
 pub mod powershell;
 mod proposed_plan_parser;
 pub mod sandboxing;
 mod session_prefix;
+mod spill;
 mod stream_events_utils;
 mod tagged_block_parser;
 mod text_encoding;
@@ -66,6 +70,7 @@ pub use model_provider_info::DEFAULT_OLLAMA_PORT;
 pub use model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
 pub use model_provider_info::ModelProviderInfo;
 pub use model_provider_info::OLLAMA_OSS_PROVIDER_ID;
+pub use model_provider_info::ProviderAuthCommand;
 pub use model_provider_info::WireApi;
 pub use model_provider_info::built_in_model_providers;
 pub use model_provider_info::create_oss_provider_with_base_url;
@@ -88,6 +93,7 @@ pub use auth::AuthManager;
 pub use auth::CodexAuth;
 pub mod default_client;
 pub mod project_doc;
+mod provider_auth;
 mod rollout;
 pub(crate) mod safety;
 pub mod seatbelt;
@@ -106,6 +112,10 @@ pub use rollout::RolloutRecorder;
 pub use rollout::RolloutRecorderParams;
 pub use rollout::SESSIONS_SUBDIR;
 pub use rollout::SessionMeta;
+pub use rollout::export::TranscriptExportOptions;
+pub use rollout::export::TranscriptFormat;
+pub use rollout::export::export_rollout_transcript;
+pub use rollout::export::render_transcript;
 pub use rollout::find_archived_thread_path_by_id_str;
 #[deprecated(note = "use find_thread_path_by_id_str")]
 pub use rollout::find_conversation_path_by_id_str;
@@ -76,6 +76,11 @@ pub struct ModelProviderInfo {
     /// this may be necessary when using this programmatically.
     pub experimental_bearer_token: Option<String>,
 
+    /// Helper command that prints a bearer token for this provider, for
+    /// gateways that only hand out short-lived credentials. Consulted when
+    /// neither `env_key` nor `experimental_bearer_token` is set.
+    pub auth_command: Option<ProviderAuthCommand>,
+
     /// Which wire protocol this provider expects.
     #[serde(default)]
     pub wire_api: WireApi,
@@ -115,6 +120,27 @@ pub struct ModelProviderInfo {
     pub supports_websockets: bool,
 }
 
+/// Credential helper invoked to obtain a bearer token, similar to git's
+/// credential helpers.
+///
+/// The command prints either the bare token or a JSON object of the form
+/// `{"token": "...", "expires_in": 3600}` (or `"expires_at"` as Unix seconds or
+/// RFC 3339) on stdout. Tokens are cached until shortly before they expire and
+/// the command is run again after the provider rejects a request with 401.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ProviderAuthCommand {
+    /// Program to run.
+    pub command: String,
+
+    /// Arguments passed to `command`.
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// How long to wait for the command before failing the request.
+    pub timeout_ms: Option<u64>,
+}
+
 impl ModelProviderInfo {
     fn build_header_map(&self) -> crate::error::Result<HeaderMap> {
         let mut headers = HeaderMap::new();
@@ -238,6 +264,7 @@ impl ModelProviderInfo {
             env_key: None,
             env_key_instructions: None,
             experimental_bearer_token: None,
+            auth_command: None,
             wire_api: WireApi::Responses,
             query_params: None,
             http_headers: Some(
@@ -327,6 +354,7 @@ pub fn create_oss_provider_with_base_url(base_url: &str, wire_api: WireApi) -> M
         env_key: None,
         env_key_instructions: None,
         experimental_bearer_token: None,
+        auth_command: None,
         wire_api,
         query_params: None,
         http_headers: None,
@@ -356,6 +384,7 @@ base_url = "http://localhost:11434/v1"
             env_key: None,
             env_key_instructions: None,
             experimental_bearer_token: None,
+            auth_command: None,
             wire_api: WireApi::Responses,
             query_params: None,
             http_headers: None,
@@ -385,6 +414,7 @@ query_params = { api-version = "2025-04-01-preview" }
             env_key: Some("AZURE_OPENAI_API_KEY".into()),
             env_key_instructions: None,
             experimental_bearer_token: None,
+            auth_command: None,
             wire_api: WireApi::Responses,
             query_params: Some(maplit::hashmap! {
                 "api-version".to_string() => "2025-04-01-preview".to_string(),
@@ -417,6 +447,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             env_key: Some("API_KEY".into()),
             env_key_instructions: None,
             experimental_bearer_token: None,
+            auth_command: None,
             wire_api: WireApi::Responses,
             query_params: None,
             http_headers: Some(maplit::hashmap! {
@@ -0,0 +1,397 @@
+//! Path-scoped instruction files.
+//!
+//! `AGENTS.md` files are loaded once at session start, so guidance that only
+//! matters for one part of a repository is either always in context or never
+//! seen. Such guidance can instead live in `.codex/instructions/*.md` inside
+//! any directory searched for `AGENTS.md`. Each file lists the paths it covers
+//! in YAML frontmatter:
+//!
+//! ```markdown
+//! ---
+//! paths: ["migrations/**", "db/*.sql"]
+//! ---
+//! Never edit a migration that has already been applied.
+//! ```
+//!
+//! Globs match paths relative to the directory that contains the `.codex`
+//! folder. When the agent reads or edits a matching file, the instructions are
+//! added to the conversation before the next model request. The history itself
+//! records what has been injected: a file is injected once, rollbacks and
+//! resumes stay consistent, and compaction carries injected files over into
+//! the compacted history.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::models::ResponseItem;
+use dunce::canonicalize as normalize_path;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use tracing::warn;
+use wildmatch::WildMatch;
+
+use crate::config::Config;
+use crate::instructions::PathInstructions;
+use crate::project_doc::project_doc_search_dirs;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+
+const INSTRUCTIONS_DIR_NAME: &str = "instructions";
+
+#[derive(Debug, Deserialize)]
+struct PathInstructionsFrontmatter {
+    #[serde(default)]
+    paths: Vec<String>,
+}
+
+#[derive(Debug)]
+struct PathInstructionsFile {
+    path: PathBuf,
+    /// Directory that contains the `.codex` folder; globs are relative to it.
+    base: PathBuf,
+    globs: Vec<String>,
+    contents: String,
+}
+
+impl PathInstructionsFile {
+    fn matches(&self, touched: &Path) -> bool {
+        let Ok(relative) = touched.strip_prefix(&self.base) else {
+            return false;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        self.globs
+            .iter()
+            .any(|glob| WildMatch::new(glob).matches(&relative))
+    }
+}
+
+/// Instruction files discovered at session start plus the paths the agent has
+/// touched since the last model request.
+#[derive(Debug, Default)]
+pub(crate) struct PathInstructionsStore {
+    files: Vec<PathInstructionsFile>,
+    touched: Mutex<Vec<PathBuf>>,
+}
+
+impl PathInstructionsStore {
+    pub(crate) async fn discover(config: &Config) -> Self {
+        let max_bytes = config.project_doc_max_bytes;
+        if max_bytes == 0 {
+            return Self::default();
+        }
+        let search_dirs = match project_doc_search_dirs(config) {
+            Ok(dirs) => dirs,
+            Err(err) => {
+                warn!("failed to locate path-scoped instructions: {err:#}");
+                return Self::default();
+            }
+        };
+
+        let mut files = Vec::new();
+        for base in search_dirs {
+            let base = normalize_path(&base).unwrap_or(base);
+            files.extend(load_instruction_files(&base, max_bytes).await);
+        }
+        Self {
+            files,
+            touched: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record paths the agent read or edited. Relative paths are resolved
+    /// against `cwd`.
+    pub(crate) async fn note_touched(&self, cwd: &Path, paths: impl IntoIterator<Item = PathBuf>) {
+        if self.files.is_empty() {
+            return;
+        }
+        let mut touched = self.touched.lock().await;
+        touched.extend(
+            paths
+                .into_iter()
+                .map(|path| normalize_touched_path(&cwd.join(path))),
+        );
+    }
+
+    pub(crate) async fn take_touched(&self) -> Vec<PathBuf> {
+        std::mem::take(&mut *self.touched.lock().await)
+    }
+
+    /// Messages for instruction files matching `touched` that are not already
+    /// part of `history`.
+    pub(crate) fn injections(
+        &self,
+        touched: &[PathBuf],
+        history: &[ResponseItem],
+    ) -> Vec<ResponseItem> {
+        if touched.is_empty() {
+            return Vec::new();
+        }
+        let injected = injected_paths(history);
+        self.files
+            .iter()
+            .filter(|file| !injected.contains(file.path.to_string_lossy().as_ref()))
+            .filter(|file| touched.iter().any(|path| file.matches(path)))
+            .map(|file| {
+                ResponseItem::from(PathInstructions {
+                    path: file.path.to_string_lossy().into_owned(),
+                    applies_to: file.globs.clone(),
+                    contents: file.contents.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Append the path-scoped instructions injected into `previous` that
+/// `new_history` lost, so compaction does not drop guidance the agent is
+/// still working under.
+pub(crate) fn carry_over_path_instructions(
+    previous: &[ResponseItem],
+    new_history: &mut Vec<ResponseItem>,
+) {
+    let kept: HashSet<String> = injected_paths(new_history)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let carried: Vec<ResponseItem> = previous
+        .iter()
+        .filter(|item| path_instructions_path(item).is_some_and(|path| !kept.contains(path)))
+        .cloned()
+        .collect();
+    new_history.extend(carried);
+}
+
+fn injected_paths(history: &[ResponseItem]) -> HashSet<&str> {
+    history.iter().filter_map(path_instructions_path).collect()
+}
+
+fn path_instructions_path(item: &ResponseItem) -> Option<&str> {
+    match item {
+        ResponseItem::Message { role, content, .. } if role == "user" => {
+            PathInstructions::path_from_message(content)
+        }
+        _ => None,
+    }
+}
+
+async fn load_instruction_files(base: &Path, max_bytes: usize) -> Vec<PathInstructionsFile> {
+    let dir = base.join(".codex").join(INSTRUCTIONS_DIR_NAME);
+    let mut entries = match tokio::fs::read_dir(&dir).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(err) => {
+            warn!("failed to read {}: {err:#}", dir.display());
+            return Vec::new();
+        }
+    };
+
+    let mut paths = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let is_file = entry
+            .file_type()
+            .await
+            .map(|ft| ft.is_file() || ft.is_symlink())
+            .unwrap_or(false);
+        if is_file && path.extension().is_some_and(|ext| ext == "md") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut files = Vec::new();
+    for path in paths {
+        let text = match tokio::fs::read_to_string(&path).await {
+            Ok(text) => text,
+            Err(err) => {
+                warn!("failed to read {}: {err:#}", path.display());
+                continue;
+            }
+        };
+        match parse_instruction_file(&text) {
+            Some((globs, contents)) => {
+                if contents.len() > max_bytes {
+                    warn!(
+                        "Path-scoped instructions `{}` exceed project_doc_max_bytes ({max_bytes} bytes) - truncating.",
+                        path.display()
+                    );
+                }
+                files.push(PathInstructionsFile {
+                    path,
+                    base: base.to_path_buf(),
+                    globs,
+                    contents: truncate_text(contents, TruncationPolicy::Bytes(max_bytes)),
+                });
+            }
+            None => warn!(
+                "ignoring {}: expected YAML frontmatter with a non-empty `paths` list",
+                path.display()
+            ),
+        }
+    }
+    files
+}
+
+/// Split an instruction file into its globs and its body.
+fn parse_instruction_file(text: &str) -> Option<(Vec<String>, &str)> {
+    let rest = text
+        .strip_prefix("---\r\n")
+        .or_else(|| text.strip_prefix("---\n"))?;
+    let mut offset = 0;
+    let mut body = None;
+    for line in rest.split_inclusive('\n') {
+        if line.trim() == "---" {
+            body = Some(&rest[offset + line.len()..]);
+            break;
+        }
+        offset += line.len();
+    }
+    let frontmatter: PathInstructionsFrontmatter = serde_yaml::from_str(&rest[..offset]).ok()?;
+    let globs: Vec<String> = frontmatter
+        .paths
+        .into_iter()
+        .map(|glob| glob.trim().trim_start_matches("./").to_string())
+        .filter(|glob| !glob.is_empty())
+        .collect();
+    if globs.is_empty() {
+        return None;
+    }
+    Some((globs, body?.trim()))
+}
+
+/// Canonicalize so touched paths line up with the canonical search
+/// directories. Files that do not exist yet (a patch adding them) fall back
+/// to their canonical parent.
+fn normalize_touched_path(path: &Path) -> PathBuf {
+    if let Ok(path) = normalize_path(path) {
+        return path;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => normalize_path(parent)
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigBuilder;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::TempDir;
+
+    async fn make_config(root: &TempDir) -> Config {
+        let codex_home = TempDir::new().unwrap();
+        let mut config = ConfigBuilder::default()
+            .codex_home(codex_home.path().to_path_buf())
+            .build()
+            .await
+            .expect("defaults for test should always succeed");
+        config.cwd = root.path().to_path_buf();
+        config
+    }
+
+    fn write_instructions(root: &Path, name: &str, contents: &str) {
+        let dir = root.join(".codex").join(INSTRUCTIONS_DIR_NAME);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(name), contents).unwrap();
+    }
+
+    fn injected_text(item: &ResponseItem) -> &str {
+        let ResponseItem::Message { content, .. } = item else {
+            panic!("expected a message");
+        };
+        let [ContentItem::InputText { text }] = content.as_slice() else {
+            panic!("expected one InputText content item");
+        };
+        text
+    }
+
+    #[test]
+    fn parses_globs_and_body() {
+        assert_eq!(
+            parse_instruction_file(
+                "---\npaths:\n  - ./migrations/**\n  - db/*.sql\n---\n\nUse sqlx.\n"
+            ),
+            Some((
+                vec!["migrations/**".to_string(), "db/*.sql".to_string()],
+                "Use sqlx."
+            ))
+        );
+        assert_eq!(parse_instruction_file("No frontmatter"), None);
+        assert_eq!(parse_instruction_file("---\npaths: []\n---\nbody"), None);
+        assert_eq!(parse_instruction_file("---\npaths: [\"a/**\"]\nbody"), None);
+    }
+
+    #[tokio::test]
+    async fn injects_matching_files_once() {
+        let root = TempDir::new().unwrap();
+        fs::create_dir_all(root.path().join(".git")).unwrap();
+        fs::create_dir_all(root.path().join("migrations")).unwrap();
+        fs::write(root.path().join("migrations/001.sql"), "select 1;").unwrap();
+        write_instructions(
+            root.path(),
+            "migrations.md",
+            "---\npaths: [\"migrations/**\"]\n---\nNever edit applied migrations.\n",
+        );
+        write_instructions(
+            root.path(),
+            "frontend.md",
+            "---\npaths: [\"frontend/**/*.tsx\"]\n---\nUse hooks.\n",
+        );
+
+        let store = PathInstructionsStore::discover(&make_config(&root).await).await;
+        store
+            .note_touched(root.path(), [PathBuf::from("README.md")])
+            .await;
+        assert_eq!(
+            store.injections(&store.take_touched().await, &[]),
+            Vec::new()
+        );
+
+        store
+            .note_touched(root.path(), [PathBuf::from("migrations/001.sql")])
+            .await;
+        let touched = store.take_touched().await;
+        let items = store.injections(&touched, &[]);
+        assert_eq!(items.len(), 1);
+        let text = injected_text(&items[0]);
+        assert!(text.contains("<applies_to>migrations/**</applies_to>"));
+        assert!(text.contains("Never edit applied migrations."));
+
+        // Once the message is in history the file is not injected again.
+        assert_eq!(store.injections(&touched, &items), Vec::new());
+        assert_eq!(store.take_touched().await, Vec::<PathBuf>::new());
+    }
+
+    #[test]
+    fn compaction_carries_injected_instructions_over() {
+        let injected: ResponseItem = PathInstructions {
+            path: "/repo/.codex/instructions/migrations.md".to_string(),
+            applies_to: vec!["migrations/**".to_string()],
+            contents: "Never edit applied migrations.".to_string(),
+        }
+        .into();
+        let user_message = ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "fix the migration".to_string(),
+            }],
+            end_turn: None,
+            phase: None,
+        };
+
+        let mut new_history = vec![user_message.clone()];
+        carry_over_path_instructions(&[user_message.clone(), injected.clone()], &mut new_history);
+        assert_eq!(new_history, vec![user_message.clone(), injected.clone()]);
+
+        // Histories that kept the message (remote compaction) are left alone.
+        carry_over_path_instructions(&[injected.clone()], &mut new_history);
+        assert_eq!(new_history, vec![user_message, injected]);
+    }
+}
@@ -0,0 +1,359 @@
+//! Bearer tokens for model providers configured with an `auth_command`.
+//!
+//! The helper is only run when no cached token is available (or the cached
+//! one is about to expire) and again after the provider rejects a request
+//! with 401. Tokens stay in memory: they are never logged, and neither the
+//! token nor the helper's stdout is included in error messages.
+
+use crate::error::CodexErr;
+use crate::error::RefreshTokenFailedError;
+use crate::error::RefreshTokenFailedReason;
+use crate::error::Result;
+use crate::model_provider_info::ModelProviderInfo;
+use crate::model_provider_info::ProviderAuthCommand;
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tokio::time::timeout;
+
+const DEFAULT_AUTH_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+/// Refresh this long before the reported expiry so in-flight requests do not
+/// race the deadline.
+const EXPIRY_MARGIN_SECS: i64 = 60;
+const MAX_STDERR_CHARS: usize = 500;
+
+/// Shared across sessions so every thread talking to the same provider reuses
+/// one token. Each provider has its own slot; the slot lock is held while its
+/// helper runs so concurrent requests do not each spawn it, while other
+/// providers refresh independently.
+static TOKEN_CACHE: LazyLock<StdMutex<HashMap<ProviderAuthCommand, TokenSlot>>> =
+    LazyLock::new(|| StdMutex::new(HashMap::new()));
+
+type TokenSlot = Arc<Mutex<Option<CachedToken>>>;
+
+/// The cache slot for `command`. The map lock is only held for the lookup.
+fn token_slot(command: &ProviderAuthCommand) -> TokenSlot {
+    let mut cache = TOKEN_CACHE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    Arc::clone(cache.entry(command.clone()).or_default())
+}
+
+struct CachedToken {
+    token: String,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl CachedToken {
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|expires_at| {
+            now + chrono::Duration::seconds(EXPIRY_MARGIN_SECS) < expires_at
+        })
+    }
+}
+
+impl std::fmt::Debug for CachedToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedToken")
+            .field("token", &"<redacted>")
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+#[derive(Deserialize)]
+struct AuthCommandOutput {
+    #[serde(alias = "access_token")]
+    token: String,
+    expires_at: Option<ExpiresAt>,
+    expires_in: Option<i64>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ExpiresAt {
+    UnixSeconds(i64),
+    Rfc3339(String),
+}
+
+/// Returns a bearer token for `command`, running the helper if the cached
+/// token is missing or about to expire.
+pub(crate) async fn auth_command_token(command: &ProviderAuthCommand) -> Result<String> {
+    let slot = token_slot(command);
+    let mut cached = slot.lock().await;
+    if let Some(token) = cached.as_ref()
+        && token.is_fresh(Utc::now())
+    {
+        return Ok(token.token.clone());
+    }
+
+    let fresh = run_auth_command(command).await?;
+    let token = fresh.token.clone();
+    *cached = Some(fresh);
+    Ok(token)
+}
+
+/// Drop the cached token so the next request runs the helper again.
+async fn invalidate_auth_command_token(command: &ProviderAuthCommand) {
+    *token_slot(command).lock().await = None;
+}
+
+/// Refreshes an `auth_command` token once after the provider answers 401.
+pub(crate) struct AuthCommandRecovery {
+    command: ProviderAuthCommand,
+    refreshed: bool,
+}
+
+impl AuthCommandRecovery {
+    /// Returns `None` when `provider` does not authenticate through its
+    /// `auth_command`.
+    pub(crate) fn new(provider: &ModelProviderInfo) -> Option<Self> {
+        if provider.env_key.is_some() || provider.experimental_bearer_token.is_some() {
+            return None;
+        }
+        provider.auth_command.clone().map(|command| Self {
+            command,
+            refreshed: false,
+        })
+    }
+
+    /// Invalidates the cached token and returns whether the caller should
+    /// retry. Only the first 401 triggers a refresh; a fresh token that is
+    /// still rejected is reported to the user.
+    pub(crate) async fn refresh(&mut self) -> bool {
+        if self.refreshed {
+            return false;
+        }
+        self.refreshed = true;
+        invalidate_auth_command_token(&self.command).await;
+        true
+    }
+}
+
+async fn run_auth_command(command: &ProviderAuthCommand) -> Result<CachedToken> {
+    let ProviderAuthCommand {
+        command: program,
+        args,
+        timeout_ms,
+    } = command;
+    let limit = timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_AUTH_COMMAND_TIMEOUT);
+
+    let child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| {
+            auth_command_error(format!("failed to run auth_command `{program}`: {err}"))
+        })?;
+    let output = timeout(limit, child.wait_with_output())
+        .await
+        .map_err(|_| {
+            auth_command_error(format!(
+                "auth_command `{program}` timed out after {}ms",
+                limit.as_millis()
+            ))
+        })?
+        .map_err(|err| {
+            auth_command_error(format!("failed to run auth_command `{program}`: {err}"))
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr: String = stderr.trim().chars().take(MAX_STDERR_CHARS).collect();
+        let mut message = format!("auth_command `{program}` failed with {}", output.status);
+        if !stderr.is_empty() {
+            message.push_str(&format!(": {stderr}"));
+        }
+        return Err(auth_command_error(message));
+    }
+
+    parse_auth_command_output(&String::from_utf8_lossy(&output.stdout), Utc::now())
+        .map_err(|reason| auth_command_error(format!("auth_command `{program}` {reason}")))
+}
+
+/// Accepts either the bare token or a JSON object with `token` and optional
+/// expiry. Errors describe the problem without echoing stdout.
+fn parse_auth_command_output(
+    stdout: &str,
+    now: DateTime<Utc>,
+) -> std::result::Result<CachedToken, String> {
+    let stdout = stdout.trim();
+    if !stdout.starts_with('{') {
+        let token = stdout.lines().next().unwrap_or_default().trim();
+        if token.is_empty() {
+            return Err("printed an empty token".to_string());
+        }
+        return Ok(CachedToken {
+            token: token.to_string(),
+            expires_at: None,
+        });
+    }
+
+    let output: AuthCommandOutput = serde_json::from_str(stdout)
+        .map_err(|_| "printed JSON without a string `token` field".to_string())?;
+    if output.token.trim().is_empty() {
+        return Err("printed an empty token".to_string());
+    }
+    let expires_at = match (output.expires_at, output.expires_in) {
+        (Some(ExpiresAt::UnixSeconds(seconds)), _) => Some(
+            DateTime::<Utc>::from_timestamp(seconds, 0)
+                .ok_or_else(|| "printed an out-of-range `expires_at`".to_string())?,
+        ),
+        (Some(ExpiresAt::Rfc3339(value)), _) => Some(
+            DateTime::parse_from_rfc3339(&value)
+                .map_err(|_| "printed an `expires_at` that is not RFC 3339".to_string())?
+                .with_timezone(&Utc),
+        ),
+        (None, Some(seconds)) => Some(now + chrono::Duration::seconds(seconds)),
+        (None, None) => None,
+    };
+    Ok(CachedToken {
+        token: output.token.trim().to_string(),
+        expires_at,
+    })
+}
+
+fn auth_command_error(message: String) -> CodexErr {
+    CodexErr::RefreshTokenFailed(RefreshTokenFailedError::new(
+        RefreshTokenFailedReason::Other,
+        message,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn parse(stdout: &str, now: DateTime<Utc>) -> (String, Option<DateTime<Utc>>) {
+        let cached = parse_auth_command_output(stdout, now).expect("parse");
+        (cached.token, cached.expires_at)
+    }
+
+    #[test]
+    fn parses_plain_and_json_output() {
+        let now = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).expect("timestamp");
+
+        assert_eq!(parse("tok-plain\n", now), ("tok-plain".to_string(), None));
+        assert_eq!(
+            parse(r#"{"token": "tok-json", "expires_in": 3600}"#, now),
+            (
+                "tok-json".to_string(),
+                Some(now + chrono::Duration::seconds(3600))
+            )
+        );
+        assert_eq!(
+            parse(
+                r#"{"access_token": "tok-unix", "expires_at": 1700000300}"#,
+                now
+            ),
+            (
+                "tok-unix".to_string(),
+                DateTime::<Utc>::from_timestamp(1_700_000_300, 0)
+            )
+        );
+        assert_eq!(
+            parse(
+                r#"{"token": "tok-rfc", "expires_at": "2023-11-14T22:18:20Z"}"#,
+                now
+            ),
+            (
+                "tok-rfc".to_string(),
+                DateTime::<Utc>::from_timestamp(1_700_000_300, 0)
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_bad_output_without_echoing_it() {
+        let now = Utc::now();
+        for stdout in ["", "  \n", r#"{"token": ""}"#, r#"{"secret-looking": "x"}"#] {
+            let err = parse_auth_command_output(stdout, now).expect_err(stdout);
+            assert!(!err.contains("secret-looking"), "{err}");
+        }
+    }
+
+    #[test]
+    fn tokens_near_expiry_are_stale() {
+        let now = Utc::now();
+        let token = |expires_at| CachedToken {
+            token: "tok".to_string(),
+            expires_at,
+        };
+        assert!(token(None).is_fresh(now));
+        assert!(token(Some(now + chrono::Duration::minutes(10))).is_fresh(now));
+        assert!(!token(Some(now + chrono::Duration::seconds(30))).is_fresh(now));
+        assert_eq!(
+            format!("{:?}", token(None)),
+            "CachedToken { token: \"<redacted>\", expires_at: None }"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn slow_helper_does_not_block_other_providers() {
+        let slow = ProviderAuthCommand {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "sleep 5; echo tok-slow".to_string()],
+            timeout_ms: None,
+        };
+        let fast = ProviderAuthCommand {
+            command: "echo".to_string(),
+            args: vec!["tok-fast".to_string()],
+            timeout_ms: None,
+        };
+
+        let slow_task = tokio::spawn(async move { auth_command_token(&slow).await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let token = timeout(Duration::from_secs(2), auth_command_token(&fast))
+            .await
+            .expect("fast provider should not wait for the slow helper")
+            .expect("token");
+        assert_eq!(token, "tok-fast");
+        slow_task.abort();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn caches_token_until_refreshed_after_unauthorized() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let counter = tmp.path().join("count");
+        let command = ProviderAuthCommand {
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                r#"echo x >> "$0"; echo "tok-$(wc -l < "$0" | tr -d ' ')""#.to_string(),
+                counter.to_string_lossy().into_owned(),
+            ],
+            timeout_ms: None,
+        };
+        let provider = ModelProviderInfo {
+            auth_command: Some(command.clone()),
+            ..crate::model_provider_info::create_oss_provider_with_base_url(
+                "http://localhost",
+                crate::model_provider_info::WireApi::Responses,
+            )
+        };
+
+        assert_eq!(auth_command_token(&command).await.expect("token"), "tok-1");
+        assert_eq!(auth_command_token(&command).await.expect("token"), "tok-1");
+
+        let mut recovery = AuthCommandRecovery::new(&provider).expect("recovery");
+        assert!(recovery.refresh().await);
+        assert_eq!(auth_command_token(&command).await.expect("token"), "tok-2");
+        assert!(!recovery.refresh().await);
+    }
+}
@@ -20,6 +20,8 @@ const REVIEW_FALLBACK_MESSAGE: &str = "Reviewer failed to output a response.";
 ///   default to selected.
 /// - When `selection` is `None`, the marker is omitted and a simple bullet is
 ///   rendered ("- Title — path:start-end").
+/// - Findings tagged with a commit are grouped under a "Commit <sha>:" line
+///   whenever the commit changes.
 pub fn format_review_findings_block(
     findings: &[ReviewFinding],
     selection: Option<&[bool]>,
@@ -34,7 +36,17 @@ pub fn format_review_findings_block(
         lines.push("Review comment:".to_string());
     }
 
+    let mut current_commit: Option<&str> = None;
     for (idx, item) in findings.iter().enumerate() {
+        if let Some(sha) = item.commit_sha.as_deref()
+            && current_commit != Some(sha)
+        {
+            current_commit = Some(sha);
+            let short_sha: String = sha.chars().take(7).collect();
+            lines.push(String::new());
+            lines.push(format!("Commit {short_sha}:"));
+        }
+
         lines.push(String::new());
 
         let title = &item.title;
@@ -80,3 +92,57 @@ pub fn render_review_output_text(output: &ReviewOutputEvent) -> String {
         sections.join("\n\n")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ReviewCodeLocation;
+    use crate::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn finding(title: &str, commit_sha: Option<&str>) -> ReviewFinding {
+        ReviewFinding {
+            title: title.to_string(),
+            body: "Details.".to_string(),
+            confidence_score: 0.5,
+            priority: 1,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/repo/lib.rs"),
+                line_range: ReviewLineRange { start: 1, end: 2 },
+            },
+            commit_sha: commit_sha.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn findings_block_groups_findings_by_commit() {
+        let findings = vec![
+            finding("First", Some("1111111aaaa")),
+            finding("Second", Some("1111111aaaa")),
+            finding("Third", Some("2222222bbbb")),
+        ];
+
+        assert_eq!(
+            format_review_findings_block(&findings, None),
+            [
+                "",
+                "Full review comments:",
+                "",
+                "Commit 1111111:",
+                "",
+                "- First — /repo/lib.rs:1-2",
+                "  Details.",
+                "",
+                "- Second — /repo/lib.rs:1-2",
+                "  Details.",
+                "",
+                "Commit 2222222:",
+                "",
+                "- Third — /repo/lib.rs:1-2",
+                "  Details.",
+            ]
+            .join("\n")
+        );
+    }
+}
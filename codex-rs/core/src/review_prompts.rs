@@ -1,3 +1,5 @@
+use codex_git::CommitSummary;
+use codex_git::commits_in_range;
 use codex_git::merge_base_with_head;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::ReviewTarget;
@@ -8,6 +10,17 @@ pub struct ResolvedReviewRequest {
     pub target: ReviewTarget,
     pub prompt: String,
     pub user_facing_hint: String,
+    /// One review per commit, oldest first. Empty unless the target asks for
+    /// a per-commit review.
+    pub commit_reviews: Vec<CommitReview>,
+}
+
+/// Review of a single commit within a per-commit range review.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommitReview {
+    pub sha: String,
+    pub subject: String,
+    pub prompt: String,
 }
 
 const UNCOMMITTED_PROMPT: &str = "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.";
@@ -19,6 +32,8 @@ const COMMIT_PROMPT_WITH_TITLE: &str = "Review the code changes introduced by co
 const COMMIT_PROMPT: &str =
     "Review the code changes introduced by commit {sha}. Provide prioritized, actionable findings.";
 
+const COMMIT_RANGE_PROMPT: &str = "Review the code changes introduced by the commits in {base}..{head}, listed oldest first:\n{commits}\nRun `git diff {base}...{head}` to inspect their combined changes, and `git show <sha>` for a single commit. Provide prioritized, actionable findings.";
+
 pub fn resolve_review_request(
     request: ReviewRequest,
     cwd: &Path,
@@ -28,14 +43,67 @@ pub fn resolve_review_request(
     let user_facing_hint = request
         .user_facing_hint
         .unwrap_or_else(|| user_facing_hint(&target));
+    let commit_reviews = match &target {
+        ReviewTarget::CommitRange {
+            base,
+            head,
+            per_commit: true,
+        } => range_commits(cwd, base, head)?
+            .into_iter()
+            .map(|commit| CommitReview {
+                prompt: commit_prompt(&commit.sha, Some(&commit.subject)),
+                sha: commit.sha,
+                subject: commit.subject,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
 
     Ok(ResolvedReviewRequest {
         target,
         prompt,
         user_facing_hint,
+        commit_reviews,
     })
 }
 
+/// Splits a `base..head` range as accepted by `git log` into its endpoints.
+/// An omitted head means `HEAD`.
+pub fn parse_commit_range(spec: &str) -> anyhow::Result<(String, String)> {
+    let spec = spec.trim();
+    let Some((base, head)) = spec.split_once("..") else {
+        anyhow::bail!("Expected a commit range like main..HEAD, got '{spec}'");
+    };
+    if head.starts_with('.') {
+        anyhow::bail!("Symmetric ranges like '{spec}' are not supported; use base..head");
+    }
+    if base.is_empty() {
+        anyhow::bail!("Commit range '{spec}' is missing a base");
+    }
+    let head = if head.is_empty() { "HEAD" } else { head };
+    Ok((base.to_string(), head.to_string()))
+}
+
+fn range_commits(cwd: &Path, base: &str, head: &str) -> anyhow::Result<Vec<CommitSummary>> {
+    let Some(commits) = commits_in_range(cwd, base, head)? else {
+        anyhow::bail!("Could not resolve commit range {base}..{head}");
+    };
+    if commits.is_empty() {
+        anyhow::bail!("No commits to review in {base}..{head}");
+    }
+    Ok(commits)
+}
+
+fn commit_prompt(sha: &str, title: Option<&str>) -> String {
+    if let Some(title) = title {
+        COMMIT_PROMPT_WITH_TITLE
+            .replace("{sha}", sha)
+            .replace("{title}", title)
+    } else {
+        COMMIT_PROMPT.replace("{sha}", sha)
+    }
+}
+
 pub fn review_prompt(target: &ReviewTarget, cwd: &Path) -> anyhow::Result<String> {
     match target {
         ReviewTarget::UncommittedChanges => Ok(UNCOMMITTED_PROMPT.to_string()),
@@ -48,14 +116,16 @@ pub fn review_prompt(target: &ReviewTarget, cwd: &Path) -> anyhow::Result<String
                 Ok(BASE_BRANCH_PROMPT_BACKUP.replace("{branch}", branch))
             }
         }
-        ReviewTarget::Commit { sha, title } => {
-            if let Some(title) = title {
-                Ok(COMMIT_PROMPT_WITH_TITLE
-                    .replace("{sha}", sha)
-                    .replace("{title}", title))
-            } else {
-                Ok(COMMIT_PROMPT.replace("{sha}", sha))
-            }
+        ReviewTarget::Commit { sha, title } => Ok(commit_prompt(sha, title.as_deref())),
+        ReviewTarget::CommitRange { base, head, .. } => {
+            let commits = range_commits(cwd, base, head)?
+                .iter()
+                .map(|commit| format!("- {} {}\n", commit.sha, commit.subject))
+                .collect::<String>();
+            Ok(COMMIT_RANGE_PROMPT
+                .replace("{base}", base)
+                .replace("{head}", head)
+                .replace("{commits}", &commits))
         }
         ReviewTarget::Custom { instructions } => {
             let prompt = instructions.trim();
@@ -79,6 +149,17 @@ pub fn user_facing_hint(target: &ReviewTarget) -> String {
                 format!("commit {short_sha}")
             }
         }
+        ReviewTarget::CommitRange {
+            base,
+            head,
+            per_commit,
+        } => {
+            if *per_commit {
+                format!("each commit in {base}..{head}")
+            } else {
+                format!("commits {base}..{head}")
+            }
+        }
         ReviewTarget::Custom { instructions } => instructions.trim().to_string(),
     }
 }
@@ -91,3 +172,28 @@ impl From<ResolvedReviewRequest> for ReviewRequest {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_commit_range_defaults_head() {
+        assert_eq!(
+            parse_commit_range(" main.. ").expect("valid range"),
+            ("main".to_string(), "HEAD".to_string())
+        );
+        assert_eq!(
+            parse_commit_range("HEAD~3..feature").expect("valid range"),
+            ("HEAD~3".to_string(), "feature".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_commit_range_rejects_malformed_ranges() {
+        for spec in ["main", "..HEAD", "main...HEAD"] {
+            assert!(parse_commit_range(spec).is_err(), "{spec}");
+        }
+    }
+}
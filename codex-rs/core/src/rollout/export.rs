@@ -0,0 +1,739 @@
+//! Render a recorded rollout as a shareable transcript (Markdown or
+//! self-contained HTML).
+//!
+//! The renderer works on the same [`RolloutItem`] stream that resume uses,
+//! so it sees exactly what was persisted: user and assistant messages,
+//! reasoning summaries, tool calls with their outputs, and review results.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::items::AgentMessageContent;
+use codex_protocol::items::TurnItem;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ReviewOutputEvent;
+use codex_protocol::protocol::RolloutItem;
+use serde::Deserialize;
+
+use super::recorder::RolloutRecorder;
+use crate::event_mapping::parse_turn_item;
+use crate::parse_command::shlex_join;
+
+/// Output format for [`render_transcript`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TranscriptFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl TranscriptFormat {
+    /// File extension (without the leading dot) conventionally used for the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            TranscriptFormat::Markdown => "md",
+            TranscriptFormat::Html => "html",
+        }
+    }
+}
+
+/// Knobs controlling what ends up in an exported transcript.
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptExportOptions {
+    /// Replace the session cwd with `<cwd>` and the home directory with `~`.
+    pub redact_paths: bool,
+    /// Omit command and tool output bodies, keeping only the invocations.
+    pub omit_tool_output: bool,
+}
+
+/// Load the rollout at `path` and render it in `format`.
+pub async fn export_rollout_transcript(
+    path: &Path,
+    format: TranscriptFormat,
+    options: &TranscriptExportOptions,
+) -> std::io::Result<String> {
+    let (items, _thread_id, _parse_errors) = RolloutRecorder::load_rollout_items(path).await?;
+    Ok(render_transcript(&items, format, options))
+}
+
+/// Render already-loaded rollout items in `format`.
+pub fn render_transcript(
+    items: &[RolloutItem],
+    format: TranscriptFormat,
+    options: &TranscriptExportOptions,
+) -> String {
+    let transcript = Transcript::from_rollout_items(items, options);
+    match format {
+        TranscriptFormat::Markdown => transcript.to_markdown(options),
+        TranscriptFormat::Html => transcript.to_html(options),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Entry {
+    User(String),
+    Assistant(String),
+    Reasoning(String),
+    Command {
+        command: String,
+        output: Option<String>,
+    },
+    Patch {
+        patch: String,
+        output: Option<String>,
+    },
+    ToolCall {
+        name: String,
+        arguments: String,
+        output: Option<String>,
+    },
+    Review(ReviewOutputEvent),
+    Compacted,
+}
+
+#[derive(Debug, Default)]
+struct Transcript {
+    title: String,
+    entries: Vec<Entry>,
+}
+
+#[derive(Deserialize)]
+struct CommandArgs {
+    #[serde(alias = "cmd")]
+    command: CommandValue,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CommandValue {
+    Argv(Vec<String>),
+    Script(String),
+}
+
+#[derive(Deserialize)]
+struct PatchArgs {
+    input: String,
+}
+
+impl Transcript {
+    fn from_rollout_items(items: &[RolloutItem], options: &TranscriptExportOptions) -> Self {
+        let mut redactor = PathRedactor::new(options.redact_paths);
+        let mut transcript = Transcript {
+            title: "Codex session".to_string(),
+            entries: Vec::new(),
+        };
+        // Maps a tool call id to the entry index that should receive its output.
+        let mut pending_calls: HashMap<String, usize> = HashMap::new();
+        let mut seen_session_meta = false;
+
+        for item in items {
+            match item {
+                RolloutItem::SessionMeta(meta_line) => {
+                    // Forked rollouts may carry the parent's metadata later on; the
+                    // first line describes this session.
+                    if !seen_session_meta {
+                        seen_session_meta = true;
+                        redactor.set_cwd(meta_line.meta.cwd.clone());
+                        transcript.title = format!(
+                            "Codex session {} ({})",
+                            meta_line.meta.id, meta_line.meta.timestamp
+                        );
+                    }
+                }
+                RolloutItem::ResponseItem(item) => {
+                    transcript.push_response_item(item, &redactor, &mut pending_calls);
+                }
+                RolloutItem::Compacted(_) => transcript.entries.push(Entry::Compacted),
+                RolloutItem::EventMsg(EventMsg::ExitedReviewMode(event)) => {
+                    if let Some(output) = &event.review_output {
+                        let mut output = output.clone();
+                        for finding in &mut output.findings {
+                            let path = finding
+                                .code_location
+                                .absolute_file_path
+                                .display()
+                                .to_string();
+                            finding.code_location.absolute_file_path =
+                                PathBuf::from(redactor.apply(&path));
+                        }
+                        transcript.entries.push(Entry::Review(output));
+                    }
+                }
+                RolloutItem::TurnContext(_) | RolloutItem::EventMsg(_) => {}
+            }
+        }
+        transcript
+    }
+
+    fn push_response_item(
+        &mut self,
+        item: &ResponseItem,
+        redactor: &PathRedactor,
+        pending_calls: &mut HashMap<String, usize>,
+    ) {
+        match item {
+            ResponseItem::Message { .. } | ResponseItem::Reasoning { .. } => {
+                match parse_turn_item(item) {
+                    Some(TurnItem::UserMessage(user)) => {
+                        let text = user.message();
+                        if !text.trim().is_empty() {
+                            self.entries.push(Entry::User(redactor.apply(&text)));
+                        }
+                    }
+                    Some(TurnItem::AgentMessage(agent)) => {
+                        let text = agent
+                            .content
+                            .iter()
+                            .map(|content| match content {
+                                AgentMessageContent::Text { text } => text.as_str(),
+                            })
+                            .collect::<String>();
+                        if !text.trim().is_empty() {
+                            self.entries.push(Entry::Assistant(redactor.apply(&text)));
+                        }
+                    }
+                    Some(TurnItem::Reasoning(reasoning)) => {
+                        let text = reasoning.summary_text.join("\n\n");
+                        if !text.trim().is_empty() {
+                            self.entries.push(Entry::Reasoning(redactor.apply(&text)));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                let entry = function_call_entry(name, arguments, redactor);
+                pending_calls.insert(call_id.clone(), self.entries.len());
+                self.entries.push(entry);
+            }
+            ResponseItem::CustomToolCall {
+                name,
+                input,
+                call_id,
+                ..
+            } => {
+                let entry = if name == "apply_patch" {
+                    Entry::Patch {
+                        patch: redactor.apply(input),
+                        output: None,
+                    }
+                } else {
+                    Entry::ToolCall {
+                        name: name.clone(),
+                        arguments: redactor.apply(input),
+                        output: None,
+                    }
+                };
+                pending_calls.insert(call_id.clone(), self.entries.len());
+                self.entries.push(entry);
+            }
+            ResponseItem::LocalShellCall {
+                call_id, action, ..
+            } => {
+                let LocalShellAction::Exec(exec) = action;
+                if let Some(call_id) = call_id {
+                    pending_calls.insert(call_id.clone(), self.entries.len());
+                }
+                self.entries.push(Entry::Command {
+                    command: redactor.apply(&shlex_join(&exec.command)),
+                    output: None,
+                });
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                self.attach_output(pending_calls, call_id, redactor.apply(&output.content));
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                self.attach_output(pending_calls, call_id, redactor.apply(output));
+            }
+            ResponseItem::WebSearchCall { .. }
+            | ResponseItem::GhostSnapshot { .. }
+            | ResponseItem::Compaction { .. }
+            | ResponseItem::Other => {}
+        }
+    }
+
+    fn attach_output(
+        &mut self,
+        pending_calls: &mut HashMap<String, usize>,
+        call_id: &str,
+        text: String,
+    ) {
+        let Some(index) = pending_calls.remove(call_id) else {
+            return;
+        };
+        match self.entries.get_mut(index) {
+            Some(Entry::Command { output, .. })
+            | Some(Entry::Patch { output, .. })
+            | Some(Entry::ToolCall { output, .. }) => *output = Some(text),
+            _ => {}
+        }
+    }
+
+    fn to_markdown(&self, options: &TranscriptExportOptions) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# {}", self.title);
+        for entry in &self.entries {
+            out.push('\n');
+            match entry {
+                Entry::User(text) => {
+                    let _ = writeln!(out, "## User\n\n{}", text.trim_end());
+                }
+                Entry::Assistant(text) => {
+                    let _ = writeln!(out, "## Assistant\n\n{}", text.trim_end());
+                }
+                Entry::Reasoning(text) => {
+                    out.push_str("<details><summary>Reasoning</summary>\n\n");
+                    let _ = writeln!(out, "{}\n\n</details>", text.trim_end());
+                }
+                Entry::Command { command, output } => {
+                    out.push_str("**Command**\n\n");
+                    push_markdown_fence(&mut out, "sh", command);
+                    push_markdown_output(&mut out, output.as_deref(), options);
+                }
+                Entry::Patch { patch, output } => {
+                    out.push_str("**Patch**\n\n");
+                    push_markdown_fence(&mut out, "diff", patch);
+                    push_markdown_output(&mut out, output.as_deref(), options);
+                }
+                Entry::ToolCall {
+                    name,
+                    arguments,
+                    output,
+                } => {
+                    let _ = writeln!(out, "**Tool call** `{name}`\n");
+                    push_markdown_fence(&mut out, "json", arguments);
+                    push_markdown_output(&mut out, output.as_deref(), options);
+                }
+                Entry::Review(review) => {
+                    out.push_str("## Review\n\n");
+                    let explanation = review.overall_explanation.trim();
+                    if !explanation.is_empty() {
+                        let _ = writeln!(out, "{explanation}\n");
+                    }
+                    for finding in &review.findings {
+                        let location = &finding.code_location;
+                        let _ = writeln!(
+                            out,
+                            "- **{}** (P{}) `{}:{}-{}`",
+                            finding.title,
+                            finding.priority,
+                            location.absolute_file_path.display(),
+                            location.line_range.start,
+                            location.line_range.end
+                        );
+                        for line in finding.body.lines() {
+                            let _ = writeln!(out, "  {line}");
+                        }
+                    }
+                }
+                Entry::Compacted => {
+                    out.push_str("---\n\n_Context compacted._\n");
+                }
+            }
+        }
+        out
+    }
+
+    fn to_html(&self, options: &TranscriptExportOptions) -> String {
+        let mut out = String::new();
+        let title = html_escape(&self.title);
+        let _ = write!(
+            out,
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+        for entry in &self.entries {
+            match entry {
+                Entry::User(text) => push_html_message(&mut out, "user", "User", text),
+                Entry::Assistant(text) => {
+                    push_html_message(&mut out, "assistant", "Assistant", text)
+                }
+                Entry::Reasoning(text) => {
+                    let _ = writeln!(
+                        out,
+                        "<details class=\"reasoning\"><summary>Reasoning</summary><pre>{}</pre></details>",
+                        html_escape(text.trim_end())
+                    );
+                }
+                Entry::Command { command, output } => {
+                    let _ = writeln!(
+                        out,
+                        "<section class=\"tool\"><h3>Command</h3><pre class=\"command\">{}</pre>",
+                        html_escape(command)
+                    );
+                    push_html_output(&mut out, output.as_deref(), options);
+                    out.push_str("</section>\n");
+                }
+                Entry::Patch { patch, output } => {
+                    out.push_str("<section class=\"tool\"><h3>Patch</h3><pre class=\"diff\">");
+                    for line in patch.lines() {
+                        let class = match line.chars().next() {
+                            Some('+') => "add",
+                            Some('-') => "del",
+                            Some('@') | Some('*') => "meta",
+                            _ => "ctx",
+                        };
+                        let _ =
+                            writeln!(out, "<span class=\"{class}\">{}</span>", html_escape(line));
+                    }
+                    out.push_str("</pre>\n");
+                    push_html_output(&mut out, output.as_deref(), options);
+                    out.push_str("</section>\n");
+                }
+                Entry::ToolCall {
+                    name,
+                    arguments,
+                    output,
+                } => {
+                    let _ = writeln!(
+                        out,
+                        "<section class=\"tool\"><h3>Tool call <code>{}</code></h3><pre>{}</pre>",
+                        html_escape(name),
+                        html_escape(arguments)
+                    );
+                    push_html_output(&mut out, output.as_deref(), options);
+                    out.push_str("</section>\n");
+                }
+                Entry::Review(review) => {
+                    out.push_str("<section class=\"review\"><h2>Review</h2>\n");
+                    let explanation = review.overall_explanation.trim();
+                    if !explanation.is_empty() {
+                        let _ = writeln!(out, "<p>{}</p>", html_escape(explanation));
+                    }
+                    out.push_str("<ul>\n");
+                    for finding in &review.findings {
+                        let location = &finding.code_location;
+                        let _ = writeln!(
+                            out,
+                            "<li><strong>{}</strong> (P{}) <code>{}:{}-{}</code><pre>{}</pre></li>",
+                            html_escape(&finding.title),
+                            finding.priority,
+                            html_escape(&location.absolute_file_path.display().to_string()),
+                            location.line_range.start,
+                            location.line_range.end,
+                            html_escape(finding.body.trim_end())
+                        );
+                    }
+                    out.push_str("</ul></section>\n");
+                }
+                Entry::Compacted => {
+                    out.push_str("<hr><p class=\"note\">Context compacted.</p>\n");
+                }
+            }
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+fn function_call_entry(name: &str, arguments: &str, redactor: &PathRedactor) -> Entry {
+    match name {
+        "shell" | "container.exec" | "shell_command" | "exec_command" => {
+            if let Ok(args) = serde_json::from_str::<CommandArgs>(arguments) {
+                let command = match args.command {
+                    CommandValue::Argv(argv) => shlex_join(&argv),
+                    CommandValue::Script(script) => script,
+                };
+                return Entry::Command {
+                    command: redactor.apply(&command),
+                    output: None,
+                };
+            }
+        }
+        "apply_patch" => {
+            if let Ok(args) = serde_json::from_str::<PatchArgs>(arguments) {
+                return Entry::Patch {
+                    patch: redactor.apply(&args.input),
+                    output: None,
+                };
+            }
+        }
+        _ => {}
+    }
+    Entry::ToolCall {
+        name: name.to_string(),
+        arguments: redactor.apply(arguments),
+        output: None,
+    }
+}
+
+/// Rewrites absolute paths that identify the machine or user.
+struct PathRedactor {
+    enabled: bool,
+    cwd: Option<String>,
+    home: Option<String>,
+}
+
+impl PathRedactor {
+    fn new(enabled: bool) -> Self {
+        let home = dirs::home_dir()
+            .map(|home| home.display().to_string())
+            .filter(|home| home.len() > 1);
+        Self {
+            enabled,
+            cwd: None,
+            home,
+        }
+    }
+
+    fn set_cwd(&mut self, cwd: PathBuf) {
+        let cwd = cwd.display().to_string();
+        if cwd.len() > 1 {
+            self.cwd = Some(cwd);
+        }
+    }
+
+    fn apply(&self, text: &str) -> String {
+        if !self.enabled {
+            return text.to_string();
+        }
+        // Replace the cwd first; it is usually nested under the home directory.
+        let mut redacted = match &self.cwd {
+            Some(cwd) => text.replace(cwd.as_str(), "<cwd>"),
+            None => text.to_string(),
+        };
+        if let Some(home) = &self.home {
+            redacted = redacted.replace(home.as_str(), "~");
+        }
+        redacted
+    }
+}
+
+fn push_markdown_fence(out: &mut String, lang: &str, body: &str) {
+    // Use a fence longer than any backtick run in the body so it cannot be closed early.
+    let longest_run = body.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    let _ = writeln!(out, "{fence}{lang}\n{}\n{fence}", body.trim_end());
+}
+
+fn push_markdown_output(out: &mut String, output: Option<&str>, options: &TranscriptExportOptions) {
+    if options.omit_tool_output {
+        return;
+    }
+    if let Some(output) = output {
+        out.push_str("\n<details><summary>Output</summary>\n\n");
+        push_markdown_fence(out, "text", output);
+        out.push_str("\n</details>\n");
+    }
+}
+
+fn push_html_message(out: &mut String, class: &str, label: &str, text: &str) {
+    let _ = writeln!(
+        out,
+        "<section class=\"message {class}\"><h2>{label}</h2><div class=\"body\">{}</div></section>",
+        html_escape(text.trim_end())
+    );
+}
+
+fn push_html_output(out: &mut String, output: Option<&str>, options: &TranscriptExportOptions) {
+    if options.omit_tool_output {
+        return;
+    }
+    if let Some(output) = output {
+        let _ = writeln!(
+            out,
+            "<details><summary>Output</summary><pre>{}</pre></details>",
+            html_escape(output.trim_end())
+        );
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:960px;margin:2em auto;padding:0 1em;line-height:1.5;color:#1f2328}\
+h1{font-size:1.4em}h2{font-size:1.1em;margin:0 0 .4em}h3{font-size:.95em;margin:0 0 .4em}\
+.message{border-left:4px solid #d0d7de;padding:.4em 1em;margin:1em 0}\
+.message.user{border-color:#0969da}.message.assistant{border-color:#1a7f37}\
+.body{white-space:pre-wrap}\
+pre{background:#f6f8fa;padding:.6em;overflow-x:auto;white-space:pre-wrap;word-break:break-word}\
+.tool,.review{margin:1em 0}.reasoning{color:#57606a;margin:1em 0}\
+.diff .add{color:#1a7f37}.diff .del{color:#cf222e}.diff .meta{color:#8250df}\
+.diff span{display:block}.note{color:#57606a;font-style:italic}";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::ThreadId;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use codex_protocol::models::ReasoningItemReasoningSummary;
+    use codex_protocol::protocol::ExitedReviewModeEvent;
+    use codex_protocol::protocol::ReviewCodeLocation;
+    use codex_protocol::protocol::ReviewFinding;
+    use codex_protocol::protocol::ReviewLineRange;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> RolloutItem {
+        let content = if role == "user" {
+            ContentItem::InputText {
+                text: text.to_string(),
+            }
+        } else {
+            ContentItem::OutputText {
+                text: text.to_string(),
+            }
+        };
+        RolloutItem::ResponseItem(ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![content],
+            end_turn: None,
+            phase: None,
+        })
+    }
+
+    fn sample_items() -> Vec<RolloutItem> {
+        vec![
+            RolloutItem::SessionMeta(SessionMetaLine {
+                meta: SessionMeta {
+                    id: ThreadId::default(),
+                    timestamp: "2025-01-01T00:00:00Z".to_string(),
+                    cwd: PathBuf::from("/work/repo"),
+                    ..Default::default()
+                },
+                git: None,
+            }),
+            message("user", "fix the build in /work/repo/src"),
+            RolloutItem::ResponseItem(ResponseItem::Reasoning {
+                id: "r1".to_string(),
+                summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: "Looking at the failure".to_string(),
+                }],
+                content: None,
+                encrypted_content: None,
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["cargo","build"]}"#.to_string(),
+                call_id: "call-1".to_string(),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+                call_id: "call-1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "error[E0308]: mismatched types".to_string(),
+                    ..Default::default()
+                },
+            }),
+            RolloutItem::ResponseItem(ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id: "call-2".to_string(),
+                name: "apply_patch".to_string(),
+                input: "*** Begin Patch\n*** Update File: src/lib.rs\n-let x = 1;\n+let x = 1u8;\n*** End Patch".to_string(),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::CustomToolCallOutput {
+                call_id: "call-2".to_string(),
+                output: "Success".to_string(),
+            }),
+            message("assistant", "Fixed <the> build."),
+            RolloutItem::EventMsg(EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+                review_output: Some(ReviewOutputEvent {
+                    findings: vec![ReviewFinding {
+                        title: "Unchecked cast".to_string(),
+                        body: "Overflow possible.".to_string(),
+                        confidence_score: 0.8,
+                        priority: 1,
+                        code_location: ReviewCodeLocation {
+                            absolute_file_path: PathBuf::from("/work/repo/src/lib.rs"),
+                            line_range: ReviewLineRange { start: 3, end: 4 },
+                        },
+                        commit_sha: None,
+                    }],
+                    overall_correctness: "patch is incorrect".to_string(),
+                    overall_explanation: "One issue.".to_string(),
+                    overall_confidence_score: 0.7,
+                }),
+            })),
+        ]
+    }
+
+    #[test]
+    fn pairs_tool_outputs_with_their_calls() {
+        let transcript =
+            Transcript::from_rollout_items(&sample_items(), &TranscriptExportOptions::default());
+        assert_eq!(
+            transcript.entries[2],
+            Entry::Command {
+                command: "cargo build".to_string(),
+                output: Some("error[E0308]: mismatched types".to_string()),
+            }
+        );
+        assert!(matches!(
+            &transcript.entries[3],
+            Entry::Patch { output: Some(output), .. } if output == "Success"
+        ));
+    }
+
+    #[test]
+    fn markdown_includes_all_sections() {
+        let markdown = render_transcript(
+            &sample_items(),
+            TranscriptFormat::Markdown,
+            &TranscriptExportOptions::default(),
+        );
+        assert!(markdown.contains("## User\n\nfix the build in /work/repo/src"));
+        assert!(markdown.contains("<details><summary>Reasoning</summary>"));
+        assert!(markdown.contains("```sh\ncargo build\n```"));
+        assert!(markdown.contains("```diff\n*** Begin Patch"));
+        assert!(markdown.contains("error[E0308]"));
+        assert!(markdown.contains("- **Unchecked cast** (P1) `/work/repo/src/lib.rs:3-4`"));
+    }
+
+    #[test]
+    fn redacts_cwd_and_omits_tool_output() {
+        let options = TranscriptExportOptions {
+            redact_paths: true,
+            omit_tool_output: true,
+        };
+        let markdown = render_transcript(&sample_items(), TranscriptFormat::Markdown, &options);
+        assert!(!markdown.contains("/work/repo"));
+        assert!(markdown.contains("fix the build in <cwd>/src"));
+        assert!(markdown.contains("`<cwd>/src/lib.rs:3-4`"));
+        assert!(!markdown.contains("error[E0308]"));
+        assert!(markdown.contains("cargo build"));
+    }
+
+    #[test]
+    fn html_escapes_content() {
+        let html = render_transcript(
+            &sample_items(),
+            TranscriptFormat::Html,
+            &TranscriptExportOptions::default(),
+        );
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("Fixed &lt;the&gt; build."));
+        assert!(html.contains("<span class=\"add\">+let x = 1u8;</span>"));
+        assert!(html.contains("<span class=\"del\">-let x = 1;</span>"));
+    }
+
+    #[test]
+    fn markdown_fence_outgrows_backticks_in_body() {
+        let mut out = String::new();
+        push_markdown_fence(&mut out, "text", "a ```` b");
+        assert_eq!(out, "`````text\na ```` b\n`````\n");
+    }
+}
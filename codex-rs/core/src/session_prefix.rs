@@ -8,12 +8,15 @@ use codex_protocol::models::ContentItem;
 /// boundaries.
 pub(crate) const ENVIRONMENT_CONTEXT_OPEN_TAG: &str = "<environment_context>";
 pub(crate) const TURN_ABORTED_OPEN_TAG: &str = "<turn_aborted>";
+pub(crate) const PLAN_CONTEXT_OPEN_TAG: &str = "<plan_context>";
 
 /// Returns true if `text` starts with a session prefix marker (case-insensitive).
 pub(crate) fn is_session_prefix(text: &str) -> bool {
     let trimmed = text.trim_start();
     let lowered = trimmed.to_ascii_lowercase();
-    lowered.starts_with(ENVIRONMENT_CONTEXT_OPEN_TAG) || lowered.starts_with(TURN_ABORTED_OPEN_TAG)
+    lowered.starts_with(ENVIRONMENT_CONTEXT_OPEN_TAG)
+        || lowered.starts_with(TURN_ABORTED_OPEN_TAG)
+        || lowered.starts_with(PLAN_CONTEXT_OPEN_TAG)
 }
 
 /// Returns true if `text` starts with a session prefix marker (case-insensitive).
@@ -24,3 +27,14 @@ pub(crate) fn is_session_prefix_content(content: &[ContentItem]) -> bool {
         false
     }
 }
+
+/// Returns true if `content` is a `<plan_context>` message restoring the latest plan.
+pub(crate) fn is_plan_context_content(content: &[ContentItem]) -> bool {
+    if let [ContentItem::InputText { text }] = content {
+        text.trim_start()
+            .to_ascii_lowercase()
+            .starts_with(PLAN_CONTEXT_OPEN_TAG)
+    } else {
+        false
+    }
+}
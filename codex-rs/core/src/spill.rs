@@ -0,0 +1,464 @@
+//! Per-session storage for tool output that was truncated before being shown
+//! to the model.
+//!
+//! When command output exceeds the model budget, the head/tail that the model
+//! sees is followed by a note naming a spill id. The full text is written to
+//! `$CODEX_HOME/output_spills/<thread id>/<spill id>.log` so the
+//! `read_spilled_output` tool can page through or search it later instead of
+//! the model re-running the command. The directory is removed when the session
+//! ends.
+//!
+//! Unified exec processes keep only a capped head and tail in memory, so their
+//! output is also streamed to a per-process spool as it arrives, and a
+//! truncated poll spills its slice of that spool. Spools are charged to the
+//! same per-session budget as spills until their process goes away.
+
+use std::io::SeekFrom;
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use codex_protocol::ThreadId;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncSeekExt;
+use tokio::io::AsyncWriteExt;
+
+use crate::truncate::TruncationPolicy;
+use crate::truncate::fits_budget;
+
+const SPILL_DIR: &str = "output_spills";
+const SPOOL_DIR: &str = "spool";
+const SPILL_EXTENSION: &str = "log";
+/// Upper bound on the total number of bytes a single session may keep on
+/// disk, across spills and live process spools.
+const MAX_SESSION_SPILL_BYTES: u64 = 256 * 1024 * 1024;
+
+pub(crate) const READ_SPILLED_OUTPUT_TOOL_NAME: &str = "read_spilled_output";
+
+#[derive(Debug)]
+pub(crate) struct SpillStore {
+    dir: PathBuf,
+    /// Bytes currently held by spill files and process spools.
+    spilled_bytes: Arc<AtomicU64>,
+}
+
+impl SpillStore {
+    pub(crate) fn new(codex_home: &Path, thread_id: ThreadId) -> Self {
+        Self {
+            dir: codex_home.join(SPILL_DIR).join(thread_id.to_string()),
+            spilled_bytes: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Persist `content` when truncating it for `policy` drops anything and
+    /// return the note that should be shown to the model next to the
+    /// truncated output.
+    pub(crate) async fn spill_if_truncated(
+        &self,
+        call_id: &str,
+        content: &str,
+        policy: TruncationPolicy,
+    ) -> Option<String> {
+        if fits_budget(content, policy) {
+            return None;
+        }
+        self.spill(call_id, content).await
+    }
+
+    /// Persist `content` unconditionally, for callers that already know the
+    /// model saw a truncated copy.
+    pub(crate) async fn spill(&self, call_id: &str, content: &str) -> Option<String> {
+        let spill_id = spill_id_for(call_id)?;
+        let len = content.len() as u64;
+        if !reserve(&self.spilled_bytes, len) {
+            tracing::debug!("output spill budget exhausted; not persisting {spill_id}");
+            return None;
+        }
+
+        let path = self.path_for(spill_id);
+        let result = async {
+            tokio::fs::create_dir_all(&self.dir).await?;
+            tokio::fs::write(&path, content).await
+        }
+        .await;
+        if let Err(err) = result {
+            self.spilled_bytes.fetch_sub(len, Ordering::Relaxed);
+            tracing::warn!("failed to persist truncated output {spill_id}: {err}");
+            return None;
+        }
+
+        Some(spill_note(spill_id, content.lines().count()))
+    }
+
+    /// Persist the bytes of `slice` from its process spool. Used by unified
+    /// exec, whose in-memory buffer may already have dropped the middle of
+    /// the output.
+    pub(crate) async fn spill_slice(&self, call_id: &str, slice: &SpoolSlice) -> Option<String> {
+        let spill_id = spill_id_for(call_id)?;
+        if !slice.is_complete() {
+            tracing::debug!("output spool does not cover {spill_id}; not persisting");
+            return None;
+        }
+        let len = slice.len();
+        if !reserve(&self.spilled_bytes, len) {
+            tracing::debug!("output spill budget exhausted; not persisting {spill_id}");
+            return None;
+        }
+
+        match self.copy_slice(spill_id, slice).await {
+            Ok(total_lines) => Some(spill_note(spill_id, total_lines)),
+            Err(err) => {
+                self.spilled_bytes.fetch_sub(len, Ordering::Relaxed);
+                tracing::warn!("failed to persist truncated output {spill_id}: {err}");
+                None
+            }
+        }
+    }
+
+    /// A spool for the output of unified exec process `process_id`.
+    pub(crate) fn spool_for(&self, process_id: &str) -> Option<OutputSpool> {
+        let process_id = spill_id_for(process_id)?;
+        Some(OutputSpool::new(
+            self.dir
+                .join(SPOOL_DIR)
+                .join(format!("{process_id}.{SPILL_EXTENSION}")),
+            Arc::clone(&self.spilled_bytes),
+        ))
+    }
+
+    /// Copies the slice into the spill file, returning its line count.
+    async fn copy_slice(&self, spill_id: &str, slice: &SpoolSlice) -> std::io::Result<usize> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let mut source = tokio::fs::File::open(&slice.spool.path).await?;
+        source.seek(SeekFrom::Start(slice.range.start)).await?;
+        let mut source = source.take(slice.len());
+        let mut target = tokio::fs::File::create(self.path_for(spill_id)).await?;
+
+        let mut buf = vec![0; 64 * 1024];
+        let mut newlines = 0;
+        let mut last = None;
+        loop {
+            let n = source.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            newlines += buf[..n].iter().filter(|byte| **byte == b'\n').count();
+            last = Some(buf[n - 1]);
+            target.write_all(&buf[..n]).await?;
+        }
+        target.flush().await?;
+
+        // Match `str::lines`, which does not count a trailing newline as a line.
+        Ok(match last {
+            Some(b'\n') | None => newlines,
+            Some(_) => newlines + 1,
+        })
+    }
+
+    /// Read back a previously spilled output. Spool slices are cut at poll
+    /// boundaries, which may split a multi-byte character, so invalid UTF-8 is
+    /// replaced rather than failing the read.
+    pub(crate) async fn read(&self, spill_id: &str) -> std::io::Result<String> {
+        let spill_id = validate_spill_id(spill_id)?;
+        let bytes = tokio::fs::read(self.path_for(spill_id)).await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn path_for(&self, spill_id: &str) -> PathBuf {
+        self.dir.join(format!("{spill_id}.{SPILL_EXTENSION}"))
+    }
+}
+
+impl Drop for SpillStore {
+    fn drop(&mut self) {
+        if !self.dir.exists() {
+            return;
+        }
+        if let Err(err) = std::fs::remove_dir_all(&self.dir) {
+            tracing::warn!("failed to delete output spills at {:?}: {err:?}", self.dir);
+        }
+    }
+}
+
+fn spill_note(spill_id: &str, total_lines: usize) -> String {
+    format!(
+        "[Full output ({total_lines} lines) saved as spill_id \"{spill_id}\". Call {READ_SPILLED_OUTPUT_TOOL_NAME} to page through or search it instead of re-running the command.]"
+    )
+}
+
+/// Append-only copy of everything a unified exec process printed. Chunks are
+/// written as they arrive, before the capped in-memory buffer sees them, so a
+/// truncated poll can still be spilled in full. The file is removed once the
+/// process and every slice referring to it are gone, and until then its
+/// bytes count against the session's spill budget.
+#[derive(Debug)]
+pub(crate) struct OutputSpool {
+    path: PathBuf,
+    session_bytes: Arc<AtomicU64>,
+    /// `None` before the first write, and again for good once the spool hit
+    /// its cap or a write failed.
+    file: tokio::sync::Mutex<Option<tokio::fs::File>>,
+    written: AtomicU64,
+    stopped: AtomicBool,
+}
+
+impl OutputSpool {
+    fn new(path: PathBuf, session_bytes: Arc<AtomicU64>) -> Self {
+        Self {
+            path,
+            session_bytes,
+            file: tokio::sync::Mutex::new(None),
+            written: AtomicU64::new(0),
+            stopped: AtomicBool::new(false),
+        }
+    }
+
+    /// Append `chunk`. Once a chunk cannot be written, nothing after it is
+    /// either, so `written` always describes a contiguous prefix.
+    pub(crate) async fn append(&self, chunk: &[u8]) {
+        if self.stopped.load(Ordering::Relaxed) {
+            return;
+        }
+        let len = chunk.len() as u64;
+        let mut file = self.file.lock().await;
+        if !reserve(&self.session_bytes, len) {
+            self.stop(&mut file, "session spill budget exhausted");
+            return;
+        }
+        let result = async {
+            if file.is_none() {
+                if let Some(parent) = self.path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                *file = Some(tokio::fs::File::create(&self.path).await?);
+            }
+            let Some(handle) = file.as_mut() else {
+                return Ok(());
+            };
+            handle.write_all(chunk).await?;
+            // Spills read the spool through a separate handle.
+            handle.flush().await
+        }
+        .await;
+        match result {
+            Ok(()) => {
+                self.written.fetch_add(len, Ordering::Relaxed);
+            }
+            Err(err) => {
+                self.session_bytes.fetch_sub(len, Ordering::Relaxed);
+                self.stop(&mut file, &err.to_string());
+            }
+        }
+    }
+
+    fn stop(&self, file: &mut Option<tokio::fs::File>, reason: &str) {
+        tracing::debug!("no longer spooling output to {:?}: {reason}", self.path);
+        self.stopped.store(true, Ordering::Relaxed);
+        *file = None;
+    }
+}
+
+impl Drop for OutputSpool {
+    fn drop(&mut self) {
+        self.session_bytes
+            .fetch_sub(self.written.load(Ordering::Relaxed), Ordering::Relaxed);
+        if self.path.exists()
+            && let Err(err) = std::fs::remove_file(&self.path)
+        {
+            tracing::debug!("failed to delete output spool at {:?}: {err:?}", self.path);
+        }
+    }
+}
+
+/// The bytes one unified exec poll consumed, as offsets into its spool.
+#[derive(Debug, Clone)]
+pub(crate) struct SpoolSlice {
+    spool: Arc<OutputSpool>,
+    range: Range<u64>,
+}
+
+impl SpoolSlice {
+    pub(crate) fn new(spool: Arc<OutputSpool>, range: Range<u64>) -> Self {
+        Self { spool, range }
+    }
+
+    fn len(&self) -> u64 {
+        self.range.end.saturating_sub(self.range.start)
+    }
+
+    fn is_complete(&self) -> bool {
+        self.range.end <= self.spool.written.load(Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for SpoolSlice {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.spool, &other.spool) && self.range == other.range
+    }
+}
+
+/// Charge `len` bytes to `budget`, or leave it untouched when that would
+/// exceed `MAX_SESSION_SPILL_BYTES`.
+fn reserve(budget: &AtomicU64, len: u64) -> bool {
+    let previous = budget.fetch_add(len, Ordering::Relaxed);
+    if previous.saturating_add(len) > MAX_SESSION_SPILL_BYTES {
+        budget.fetch_sub(len, Ordering::Relaxed);
+        return false;
+    }
+    true
+}
+
+/// Spill ids double as file names, so only allow a conservative character set.
+fn validate_spill_id(raw: &str) -> std::io::Result<&str> {
+    if raw.is_empty()
+        || !raw
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "spill ids may only contain ASCII letters, digits, '-' and '_'",
+        ));
+    }
+    Ok(raw)
+}
+
+/// Like `validate_spill_id`, for ids that come from tool calls and processes
+/// rather than the model's read request: an unusable id just means nothing is
+/// spilled.
+fn spill_id_for(raw: &str) -> Option<&str> {
+    match validate_spill_id(raw) {
+        Ok(id) => Some(id),
+        Err(err) => {
+            tracing::debug!("not persisting truncated output: {err}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn spills_only_when_output_exceeds_budget() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let store = SpillStore::new(codex_home.path(), ThreadId::new());
+
+        assert_eq!(
+            store
+                .spill_if_truncated("call_small", "short", TruncationPolicy::Bytes(100))
+                .await,
+            None
+        );
+
+        let content = "line\n".repeat(100);
+        let note = store
+            .spill_if_truncated("call_big", &content, TruncationPolicy::Bytes(10))
+            .await
+            .expect("note");
+        assert!(note.contains("spill_id \"call_big\""));
+        assert!(note.contains("100 lines"));
+        assert_eq!(store.read("call_big").await.expect("read"), content);
+    }
+
+    #[tokio::test]
+    async fn rejects_path_like_spill_ids() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let store = SpillStore::new(codex_home.path(), ThreadId::new());
+        store
+            .spill_if_truncated("call_1", "abcdef", TruncationPolicy::Bytes(1))
+            .await
+            .expect("note");
+
+        assert!(store.read("call_1").await.is_ok());
+        for raw in ["../call_1", "call_1/", "/", ""] {
+            let err = store.read(raw).await.expect_err(raw);
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_non_ascii_slices_cut_mid_character() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let store = SpillStore::new(codex_home.path(), ThreadId::new());
+        let spool = Arc::new(store.spool_for("1000").expect("spool"));
+        // "héllo" with the two bytes of 'é' arriving in separate chunks.
+        spool.append(b"h\xc3").await;
+        spool.append(b"\xa9llo\n").await;
+
+        let whole = SpoolSlice::new(Arc::clone(&spool), 0..7);
+        store.spill_slice("call_1", &whole).await.expect("note");
+        assert_eq!(store.read("call_1").await.expect("read"), "héllo\n");
+
+        let second_poll = SpoolSlice::new(Arc::clone(&spool), 2..7);
+        store
+            .spill_slice("call_2", &second_poll)
+            .await
+            .expect("note");
+        assert_eq!(store.read("call_2").await.expect("read"), "\u{fffd}llo\n");
+    }
+
+    #[tokio::test]
+    async fn spools_count_against_the_session_budget_until_dropped() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let store = SpillStore::new(codex_home.path(), ThreadId::new());
+        let spool = store.spool_for("1000").expect("spool");
+        spool.append(b"output\n").await;
+        assert_eq!(store.spilled_bytes.load(Ordering::Relaxed), 7);
+
+        store
+            .spilled_bytes
+            .fetch_add(MAX_SESSION_SPILL_BYTES - 7, Ordering::Relaxed);
+        spool.append(b"more").await;
+        assert_eq!(spool.written.load(Ordering::Relaxed), 7);
+        store
+            .spilled_bytes
+            .fetch_sub(MAX_SESSION_SPILL_BYTES - 7, Ordering::Relaxed);
+
+        drop(spool);
+        assert_eq!(store.spilled_bytes.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn spills_slice_of_spooled_output() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let store = SpillStore::new(codex_home.path(), ThreadId::new());
+        let spool = Arc::new(store.spool_for("1000").expect("spool"));
+        spool.append(b"first poll\n").await;
+        spool.append(b"second\npoll").await;
+
+        let slice = SpoolSlice::new(Arc::clone(&spool), 11..22);
+        let note = store.spill_slice("call_2", &slice).await.expect("note");
+        assert!(note.contains("2 lines"));
+        assert_eq!(store.read("call_2").await.expect("read"), "second\npoll");
+
+        // Bytes that never reached the spool cannot be spilled.
+        let beyond = SpoolSlice::new(Arc::clone(&spool), 11..40);
+        assert_eq!(store.spill_slice("call_3", &beyond).await, None);
+
+        let path = spool.path.clone();
+        drop((slice, beyond, spool));
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn drop_removes_session_directory() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let store = SpillStore::new(codex_home.path(), ThreadId::new());
+        store
+            .spill_if_truncated("call_1", "abcdef", TruncationPolicy::Bytes(1))
+            .await
+            .expect("note");
+        let dir = store.dir.clone();
+        assert!(dir.exists());
+        drop(store);
+        assert!(!dir.exists());
+    }
+}
@@ -4,13 +4,18 @@ use crate::AuthManager;
 use crate::RolloutRecorder;
 use crate::agent::AgentControl;
 use crate::analytics_client::AnalyticsEventsClient;
+use crate::environment_facts::EnvironmentFactsCollector;
 use crate::exec_policy::ExecPolicyManager;
+use crate::fetch_url::FetchUrlStore;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::models_manager::manager::ModelsManager;
+use crate::path_instructions::PathInstructionsStore;
 use crate::skills::SkillsManager;
+use crate::spill::SpillStore;
 use crate::state_db::StateDbHandle;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::transport_manager::TransportManager;
+use crate::unified_exec::ServiceManager;
 use crate::unified_exec::UnifiedExecProcessManager;
 use crate::user_notification::UserNotifier;
 use codex_otel::OtelManager;
@@ -22,6 +27,7 @@ pub(crate) struct SessionServices {
     pub(crate) mcp_connection_manager: Arc<RwLock<McpConnectionManager>>,
     pub(crate) mcp_startup_cancellation_token: Mutex<CancellationToken>,
     pub(crate) unified_exec_manager: UnifiedExecProcessManager,
+    pub(crate) service_manager: ServiceManager,
     pub(crate) analytics_events_client: AnalyticsEventsClient,
     pub(crate) notifier: UserNotifier,
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
@@ -36,4 +42,8 @@ pub(crate) struct SessionServices {
     pub(crate) agent_control: AgentControl,
     pub(crate) state_db: Option<StateDbHandle>,
     pub(crate) transport_manager: TransportManager,
+    pub(crate) output_spill: Option<SpillStore>,
+    pub(crate) fetch_url: Option<FetchUrlStore>,
+    pub(crate) path_instructions: PathInstructionsStore,
+    pub(crate) environment_facts: EnvironmentFactsCollector,
 }
@@ -1,8 +1,13 @@
 //! Session-wide mutable state.
 
 use codex_protocol::models::ResponseItem;
+use codex_protocol::plan_tool::UpdatePlanArgs;
+use codex_utils_tokenizer::Tokenizer;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
 
 use crate::codex::SessionConfiguration;
 use crate::context_manager::ContextManager;
@@ -19,6 +24,11 @@ pub(crate) struct SessionState {
     pub(crate) server_reasoning_included: bool,
     pub(crate) dependency_env: HashMap<String, String>,
     pub(crate) mcp_dependency_prompted: HashSet<String>,
+    /// Latest `update_plan` checklist, restored from the rollout on resume.
+    pub(crate) plan: Option<UpdatePlanArgs>,
+    /// Absolute paths edited with apply_patch this session and not yet
+    /// committed through `Op::CreateCommits`.
+    pub(crate) touched_paths: BTreeSet<PathBuf>,
     /// Whether the session's initial context has been seeded into history.
     ///
     /// TODO(owen): This is a temporary solution to avoid updating a thread's updated_at
@@ -37,6 +47,8 @@ impl SessionState {
             server_reasoning_included: false,
             dependency_env: HashMap::new(),
             mcp_dependency_prompted: HashSet::new(),
+            plan: None,
+            touched_paths: BTreeSet::new(),
             initial_context_seeded: false,
         }
     }
@@ -92,9 +104,13 @@ impl SessionState {
         self.history.set_token_usage_full(context_window);
     }
 
-    pub(crate) fn get_total_token_usage(&self, server_reasoning_included: bool) -> i64 {
+    pub(crate) fn get_total_token_usage(
+        &self,
+        server_reasoning_included: bool,
+        tokenizer: Tokenizer,
+    ) -> i64 {
         self.history
-            .get_total_token_usage(server_reasoning_included)
+            .get_total_token_usage(server_reasoning_included, tokenizer)
     }
 
     pub(crate) fn set_server_reasoning_included(&mut self, included: bool) {
@@ -125,6 +141,20 @@ impl SessionState {
     pub(crate) fn dependency_env(&self) -> HashMap<String, String> {
         self.dependency_env.clone()
     }
+
+    pub(crate) fn record_touched_paths(&mut self, paths: BTreeSet<PathBuf>) {
+        self.touched_paths.extend(paths);
+    }
+
+    pub(crate) fn touched_paths(&self) -> BTreeSet<PathBuf> {
+        self.touched_paths.clone()
+    }
+
+    pub(crate) fn forget_touched_paths<'a>(&mut self, paths: impl IntoIterator<Item = &'a Path>) {
+        for path in paths {
+            self.touched_paths.remove(path);
+        }
+    }
 }
 
 // Sometimes new snapshots don't include credits or plan information.
@@ -10,6 +10,7 @@ use chrono::Utc;
 use codex_otel::OtelManager;
 use codex_protocol::ThreadId;
 use codex_protocol::dynamic_tools::DynamicToolSpec;
+use codex_protocol::plan_tool::UpdatePlanArgs;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::SessionSource;
 use codex_state::DB_METRIC_COMPARE_ERROR;
@@ -213,6 +214,22 @@ pub async fn get_dynamic_tools(
     }
 }
 
+/// Get the latest `update_plan` checklist for a thread id using SQLite.
+pub async fn get_thread_plan(
+    context: Option<&codex_state::StateRuntime>,
+    thread_id: ThreadId,
+    stage: &str,
+) -> Option<UpdatePlanArgs> {
+    let ctx = context?;
+    match ctx.get_thread_plan(thread_id).await {
+        Ok(plan) => plan,
+        Err(err) => {
+            warn!("state db get_thread_plan failed during {stage}: {err}");
+            None
+        }
+    }
+}
+
 /// Persist dynamic tools for a thread id using SQLite, if none exist yet.
 pub async fn persist_dynamic_tools(
     context: Option<&codex_state::StateRuntime>,
@@ -0,0 +1,476 @@
+//! `/commit`: ask the model to split the uncommitted changes into commits
+//! with drafted messages, then create the commits the user accepted.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_git::ChangedFile;
+use codex_git::changed_files;
+use codex_git::create_commit;
+use codex_git::file_diff;
+use codex_protocol::models::BaseInstructions;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::user_input::UserInput;
+use codex_utils_tokenizer::APPROX_BYTES_PER_TOKEN;
+use futures::prelude::*;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+use crate::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::codex::get_last_assistant_message_from_turn;
+use crate::error::CodexErr;
+use crate::git_info::commit_template;
+use crate::git_info::get_git_repo_root;
+use crate::git_info::recent_commits;
+use crate::protocol::CodexErrorInfo;
+use crate::protocol::CommitsCreatedEvent;
+use crate::protocol::CommitsProposedEvent;
+use crate::protocol::CreatedCommit;
+use crate::protocol::ErrorEvent;
+use crate::protocol::EventMsg;
+use crate::protocol::ProposedCommit;
+use crate::state::TaskKind;
+use crate::tasks::SessionTask;
+use crate::tasks::SessionTaskContext;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+
+const COMMIT_PROMPT: &str = include_str!("../../templates/commit/prompt.md");
+const RECENT_COMMITS: usize = 20;
+const FILE_DIFF_MAX_TOKENS: usize = 2_000;
+const TOTAL_DIFF_MAX_TOKENS: usize = 40_000;
+/// Untracked files are read no further than their diff could be shown.
+const FILE_DIFF_MAX_BYTES: usize = FILE_DIFF_MAX_TOKENS * APPROX_BYTES_PER_TOKEN;
+
+pub(crate) struct ProposeCommitsTask;
+
+impl ProposeCommitsTask {
+    pub(crate) fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl SessionTask for ProposeCommitsTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        _cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let _ = session
+            .session
+            .services
+            .otel_manager
+            .counter("codex.task.propose_commits", 1, &[]);
+        let sess = session.clone_session();
+        let msg = match propose_commits(&sess, ctx.as_ref()).await {
+            Ok(proposed) => EventMsg::CommitsProposed(proposed),
+            Err(err) => EventMsg::Error(err),
+        };
+        sess.send_event(ctx.as_ref(), msg).await;
+        None
+    }
+}
+
+pub(crate) struct CreateCommitsTask {
+    commits: Vec<ProposedCommit>,
+}
+
+impl CreateCommitsTask {
+    pub(crate) fn new(commits: Vec<ProposedCommit>) -> Self {
+        Self { commits }
+    }
+}
+
+#[async_trait]
+impl SessionTask for CreateCommitsTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        _cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let _ = session
+            .session
+            .services
+            .otel_manager
+            .counter("codex.task.create_commits", 1, &[]);
+        let sess = session.clone_session();
+        let repo_root = get_git_repo_root(&ctx.cwd).unwrap_or_else(|| ctx.cwd.clone());
+        let mut created = CommitsCreatedEvent {
+            commits: Vec::new(),
+            error: None,
+        };
+
+        for commit in &self.commits {
+            let subject = commit
+                .message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            let cwd = ctx.cwd.clone();
+            let message = commit.message.clone();
+            let paths = commit.paths.clone();
+            let result = tokio::task::spawn_blocking(move || create_commit(&cwd, &message, &paths))
+                .await
+                .map_err(|err| err.to_string())
+                .and_then(|result| result.map_err(|err| err.to_string()));
+            match result {
+                Ok(sha) => {
+                    let committed: Vec<PathBuf> = commit
+                        .paths
+                        .iter()
+                        .map(|path| repo_root.join(path))
+                        .collect();
+                    sess.forget_touched_paths(&committed).await;
+                    created.commits.push(CreatedCommit { sha, subject });
+                }
+                Err(err) => {
+                    created.error = Some(format!("Failed to create commit \"{subject}\": {err}"));
+                    break;
+                }
+            }
+        }
+
+        if let Some(error) = &created.error {
+            warn!("{error}");
+        }
+        sess.send_event(ctx.as_ref(), EventMsg::CommitsCreated(created))
+            .await;
+        None
+    }
+}
+
+async fn propose_commits(
+    sess: &Session,
+    turn_context: &TurnContext,
+) -> Result<CommitsProposedEvent, ErrorEvent> {
+    let cwd = turn_context.cwd.clone();
+    let changed = tokio::task::spawn_blocking(move || changed_files(&cwd))
+        .await
+        .map_err(|err| commit_error(format!("Failed to read git status: {err}")))?
+        .map_err(|err| commit_error(format!("Cannot commit: {err}")))?;
+    let repo_root =
+        get_git_repo_root(&turn_context.cwd).unwrap_or_else(|| turn_context.cwd.clone());
+    let touched = sess.touched_paths().await;
+    let (candidates, unassigned_paths) = select_candidates(changed, &touched, &repo_root);
+    if candidates.is_empty() {
+        return Ok(CommitsProposedEvent {
+            commits: Vec::new(),
+            unassigned_paths,
+        });
+    }
+
+    let request = commit_request(turn_context, &candidates).await;
+    let prompt = Prompt {
+        input: vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText { text: request }],
+            end_turn: None,
+            phase: None,
+        }],
+        base_instructions: BaseInstructions {
+            text: COMMIT_PROMPT.to_string(),
+        },
+        output_schema: Some(commit_plan_schema()),
+        ..Default::default()
+    };
+    let output = drain_to_completed(sess, turn_context, &prompt)
+        .await
+        .map_err(|err| err.to_error_event(None))?;
+    let drafted = get_last_assistant_message_from_turn(&output)
+        .and_then(|text| serde_json::from_str::<DraftedPlan>(&text).ok())
+        .ok_or_else(|| commit_error("The model did not return a commit plan.".to_string()))?;
+
+    let candidate_paths: Vec<PathBuf> = candidates.into_iter().map(|file| file.path).collect();
+    let (commits, leftover) = normalize_plan(drafted.commits, &candidate_paths);
+    Ok(CommitsProposedEvent {
+        commits,
+        unassigned_paths: leftover.into_iter().chain(unassigned_paths).collect(),
+    })
+}
+
+/// Splits the changed files into the ones to plan commits for and the ones to
+/// leave alone. When the session edited any of the changed files, only those
+/// are planned; otherwise every changed file is.
+fn select_candidates(
+    changed: Vec<ChangedFile>,
+    touched: &BTreeSet<PathBuf>,
+    repo_root: &Path,
+) -> (Vec<ChangedFile>, Vec<PathBuf>) {
+    let touched: BTreeSet<&Path> = touched
+        .iter()
+        .filter_map(|path| path.strip_prefix(repo_root).ok())
+        .collect();
+    if !changed
+        .iter()
+        .any(|file| touched.contains(file.path.as_path()))
+    {
+        return (changed, Vec::new());
+    }
+    let (candidates, others): (Vec<_>, Vec<_>) = changed
+        .into_iter()
+        .partition(|file| touched.contains(file.path.as_path()));
+    (
+        candidates,
+        others.into_iter().map(|file| file.path).collect(),
+    )
+}
+
+async fn commit_request(turn_context: &TurnContext, candidates: &[ChangedFile]) -> String {
+    let cwd = turn_context.cwd.as_path();
+    let mut request = String::new();
+
+    let subjects: Vec<String> = recent_commits(cwd, RECENT_COMMITS)
+        .await
+        .into_iter()
+        .map(|entry| format!("- {}", entry.subject))
+        .collect();
+    if !subjects.is_empty() {
+        request.push_str("## Recent commits\n");
+        request.push_str(&subjects.join("\n"));
+        request.push_str("\n\n");
+    }
+    if let Some(instructions) = &turn_context.client.config().commit_instructions {
+        request.push_str("## Commit conventions\n");
+        request.push_str(instructions);
+        request.push_str("\n\n");
+    }
+    if let Some(template) = commit_template(cwd).await {
+        request.push_str("## Commit template\n");
+        request.push_str(template.trim_end());
+        request.push_str("\n\n");
+    }
+
+    request.push_str("## Changed files\n");
+    for file in candidates {
+        request.push_str(&format!(
+            "- {} ({})\n",
+            file.path.display(),
+            file.status.trim()
+        ));
+    }
+
+    let repo = turn_context.cwd.clone();
+    let files = candidates.to_vec();
+    let diffs = tokio::task::spawn_blocking(move || {
+        files
+            .iter()
+            .map(|file| file_diff(&repo, file, FILE_DIFF_MAX_BYTES).ok())
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+
+    request.push_str("\n## Diffs\n");
+    let tokenizer = turn_context.tokenizer;
+    let policy = TruncationPolicy::tokens(FILE_DIFF_MAX_TOKENS).with_tokenizer(tokenizer);
+    let mut remaining = TOTAL_DIFF_MAX_TOKENS;
+    for (file, diff) in candidates.iter().zip(diffs) {
+        let diff = diff
+            .map(|diff| truncate_text(&diff, policy))
+            .unwrap_or_default();
+        let tokens = tokenizer.count(&diff);
+        if tokens > remaining {
+            request.push_str(&format!("\n### {}\n(diff omitted)\n", file.path.display()));
+            continue;
+        }
+        remaining -= tokens;
+        request.push_str(&format!("\n### {}\n{diff}\n", file.path.display()));
+    }
+    request
+}
+
+#[derive(Debug, Deserialize)]
+struct DraftedPlan {
+    commits: Vec<DraftedCommit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DraftedCommit {
+    message: String,
+    paths: Vec<String>,
+}
+
+fn commit_plan_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "commits": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "message": { "type": "string" },
+                        "paths": { "type": "array", "items": { "type": "string" } },
+                    },
+                    "required": ["message", "paths"],
+                    "additionalProperties": false,
+                },
+            },
+        },
+        "required": ["commits"],
+        "additionalProperties": false,
+    })
+}
+
+/// Keeps the drafted commits that are usable: paths must be candidates and
+/// belong to one commit only, and commits need a message and at least one
+/// path. Returns the commits and the candidates no commit includes.
+fn normalize_plan(
+    drafted: Vec<DraftedCommit>,
+    candidates: &[PathBuf],
+) -> (Vec<ProposedCommit>, Vec<PathBuf>) {
+    let mut assigned = BTreeSet::new();
+    let mut commits = Vec::new();
+    for commit in drafted {
+        let message = commit.message.trim().to_string();
+        let paths: Vec<PathBuf> = commit
+            .paths
+            .iter()
+            .map(|path| PathBuf::from(path.trim().trim_start_matches("./")))
+            .filter(|path| candidates.contains(path) && !assigned.contains(path))
+            .collect();
+        if message.is_empty() || paths.is_empty() {
+            continue;
+        }
+        assigned.extend(paths.iter().cloned());
+        commits.push(ProposedCommit { message, paths });
+    }
+    let leftover = candidates
+        .iter()
+        .filter(|path| !assigned.contains(*path))
+        .cloned()
+        .collect();
+    (commits, leftover)
+}
+
+/// Streams `prompt` without recording anything into the conversation history.
+async fn drain_to_completed(
+    sess: &Session,
+    turn_context: &TurnContext,
+    prompt: &Prompt,
+) -> Result<Vec<ResponseItem>, CodexErr> {
+    let mut client_session = turn_context
+        .client
+        .new_session(Some(turn_context.cwd.clone()));
+    let mut stream = client_session.stream(prompt).await?;
+    let mut output = Vec::new();
+    loop {
+        let Some(event) = stream.next().await else {
+            return Err(CodexErr::Stream(
+                "stream closed before response.completed".into(),
+                None,
+            ));
+        };
+        match event? {
+            ResponseEvent::OutputItemDone(item) => output.push(item),
+            ResponseEvent::RateLimits(snapshot) => {
+                sess.update_rate_limits(turn_context, snapshot).await;
+            }
+            ResponseEvent::Completed { .. } => return Ok(output),
+            _ => {}
+        }
+    }
+}
+
+fn commit_error(message: String) -> ErrorEvent {
+    ErrorEvent {
+        message,
+        codex_error_info: Some(CodexErrorInfo::Other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn changed(path: &str) -> ChangedFile {
+        ChangedFile {
+            path: PathBuf::from(path),
+            original_path: None,
+            status: " M".to_string(),
+        }
+    }
+
+    fn drafted(message: &str, paths: &[&str]) -> DraftedCommit {
+        DraftedCommit {
+            message: message.to_string(),
+            paths: paths.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn select_candidates_prefers_paths_the_session_edited() {
+        let root = Path::new("/repo");
+        let touched = BTreeSet::from([PathBuf::from("/repo/src/lib.rs")]);
+
+        let (candidates, unassigned) = select_candidates(
+            vec![changed("src/lib.rs"), changed("notes.md")],
+            &touched,
+            root,
+        );
+        assert_eq!(candidates, vec![changed("src/lib.rs")]);
+        assert_eq!(unassigned, vec![PathBuf::from("notes.md")]);
+
+        let (candidates, unassigned) = select_candidates(vec![changed("notes.md")], &touched, root);
+        assert_eq!(candidates, vec![changed("notes.md")]);
+        assert_eq!(unassigned, Vec::<PathBuf>::new());
+    }
+
+    #[test]
+    fn normalize_plan_drops_unknown_duplicate_and_empty_entries() {
+        let candidates = vec![
+            PathBuf::from("src/lib.rs"),
+            PathBuf::from("src/lib_tests.rs"),
+            PathBuf::from("README.md"),
+        ];
+        let (commits, leftover) = normalize_plan(
+            vec![
+                drafted(
+                    "Add parser\n\nSplit out of lib.rs.",
+                    &["./src/lib.rs", "src/lib_tests.rs", "src/missing.rs"],
+                ),
+                drafted("Duplicate", &["src/lib.rs"]),
+                drafted("  ", &["README.md"]),
+            ],
+            &candidates,
+        );
+
+        assert_eq!(
+            commits,
+            vec![ProposedCommit {
+                message: "Add parser\n\nSplit out of lib.rs.".to_string(),
+                paths: vec![
+                    PathBuf::from("src/lib.rs"),
+                    PathBuf::from("src/lib_tests.rs"),
+                ],
+            }]
+        );
+        assert_eq!(leftover, vec![PathBuf::from("README.md")]);
+    }
+}
@@ -6,6 +6,7 @@ use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
 use async_trait::async_trait;
 use codex_git::CreateGhostCommitOptions;
+use codex_git::FileSnapshotStore;
 use codex_git::GhostSnapshotReport;
 use codex_git::GitToolingError;
 use codex_git::create_ghost_commit_with_report;
@@ -113,6 +114,12 @@ impl SessionTask for GhostSnapshotTask {
                             info!("ghost commit captured: {}", ghost_commit.id());
                         }
                         Ok(Err(err)) => match err {
+                            GitToolingError::NotAGitRepository { .. }
+                                if ghost_snapshot.file_snapshots =>
+                            {
+                                capture_file_snapshot(&session, &ctx_for_task, warnings_enabled)
+                                    .await;
+                            }
                             GitToolingError::NotAGitRepository { .. } => info!(
                                 sub_id = ctx_for_task.sub_id.as_str(),
                                 "skipping ghost snapshot because current directory is not a Git repository"
@@ -162,6 +169,49 @@ impl GhostSnapshotTask {
     }
 }
 
+/// Outside a git repository, snapshot the workspace into the file store so
+/// undo still has something to restore.
+async fn capture_file_snapshot(
+    session: &SessionTaskContext,
+    ctx: &TurnContext,
+    warnings_enabled: bool,
+) {
+    let store = FileSnapshotStore::new(
+        &session.session.codex_home().await,
+        &session.session.conversation_id.to_string(),
+        &ctx.ghost_snapshot,
+    );
+    let workspace = ctx.cwd.clone();
+    let warning = match tokio::task::spawn_blocking(move || store.capture(&workspace)).await {
+        Ok(Ok((snapshot, report))) => {
+            info!("file snapshot captured: {}", snapshot.id);
+            format_file_snapshot_warning(ctx.ghost_snapshot.ignore_large_untracked_files, &report)
+        }
+        Ok(Err(err)) => {
+            warn!(
+                sub_id = ctx.sub_id.as_str(),
+                "failed to capture file snapshot: {err}"
+            );
+            Some(format!(
+                "Workspace snapshot failed: {err}. Undo is unavailable for this turn."
+            ))
+        }
+        Err(err) => {
+            warn!(
+                sub_id = ctx.sub_id.as_str(),
+                "file snapshot task panicked: {err}"
+            );
+            None
+        }
+    };
+    if warnings_enabled && let Some(message) = warning {
+        session
+            .session
+            .send_event(ctx, EventMsg::Warning(WarningEvent { message }))
+            .await;
+    }
+}
+
 fn format_snapshot_warnings(
     ignore_large_untracked_files: Option<i64>,
     ignore_large_untracked_dirs: Option<i64>,
@@ -211,6 +261,30 @@ fn format_ignored_untracked_files_warning(
         return None;
     }
 
+    Some(format!(
+        "Repository snapshot ignored untracked files larger than {}: {}. These files are preserved during undo cleanup, but their contents are not captured in the snapshot. Adjust `ghost_snapshot.ignore_large_untracked_files` to change this behavior. To avoid this message in the future, update your `.gitignore`.",
+        format_bytes(threshold),
+        format_ignored_files(report)
+    ))
+}
+
+fn format_file_snapshot_warning(
+    ignore_large_untracked_files: Option<i64>,
+    report: &GhostSnapshotReport,
+) -> Option<String> {
+    let threshold = ignore_large_untracked_files?;
+    if report.ignored_untracked_files.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "Workspace snapshot skipped files larger than {}: {}. Undo leaves these files as they are. Adjust `ghost_snapshot.ignore_large_untracked_files` to change this behavior.",
+        format_bytes(threshold),
+        format_ignored_files(report)
+    ))
+}
+
+fn format_ignored_files(report: &GhostSnapshotReport) -> String {
     const MAX_FILES: usize = 3;
     let mut parts: Vec<String> = Vec::new();
     for file in report.ignored_untracked_files.iter().take(MAX_FILES) {
@@ -224,12 +298,7 @@ fn format_ignored_untracked_files_warning(
         let remaining = report.ignored_untracked_files.len() - MAX_FILES;
         parts.push(format!("{remaining} more"));
     }
-
-    Some(format!(
-        "Repository snapshot ignored untracked files larger than {}: {}. These files are preserved during undo cleanup, but their contents are not captured in the snapshot. Adjust `ghost_snapshot.ignore_large_untracked_files` to change this behavior. To avoid this message in the future, update your `.gitignore`.",
-        format_bytes(threshold),
-        parts.join(", ")
-    ))
+    parts.join(", ")
 }
 
 fn format_bytes(bytes: i64) -> String {
@@ -248,6 +317,7 @@ fn format_bytes(bytes: i64) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use codex_git::IgnoredUntrackedFile;
     use codex_git::LargeUntrackedDir;
     use pretty_assertions::assert_eq;
     use std::path::PathBuf;
@@ -278,4 +348,24 @@ mod tests {
 
         assert_eq!(format_large_untracked_warning(None, &report), None);
     }
+
+    #[test]
+    fn file_snapshot_warning_lists_skipped_files() {
+        let report = GhostSnapshotReport {
+            large_untracked_dirs: Vec::new(),
+            ignored_untracked_files: vec![IgnoredUntrackedFile {
+                path: PathBuf::from("data/dump.sql"),
+                byte_size: 20 * 1024 * 1024,
+            }],
+        };
+
+        let message = format_file_snapshot_warning(Some(10 * 1024 * 1024), &report).unwrap();
+        assert!(message.starts_with(
+            "Workspace snapshot skipped files larger than 10 MiB: data/dump.sql (20 MiB)."
+        ));
+        assert_eq!(
+            format_file_snapshot_warning(Some(1024), &GhostSnapshotReport::default()),
+            None
+        );
+    }
 }
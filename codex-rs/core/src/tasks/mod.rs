@@ -1,3 +1,4 @@
+mod commit;
 mod compact;
 mod ghost_snapshot;
 mod regular;
@@ -35,6 +36,8 @@ use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::user_input::UserInput;
 
+pub(crate) use commit::CreateCommitsTask;
+pub(crate) use commit::ProposeCommitsTask;
 pub(crate) use compact::CompactTask;
 pub(crate) use ghost_snapshot::GhostSnapshotTask;
 pub(crate) use regular::RegularTask;
@@ -7,10 +7,12 @@ use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::AgentMessageContentDeltaEvent;
 use codex_protocol::protocol::AgentMessageDeltaEvent;
+use codex_protocol::protocol::BackgroundEventEvent;
 use codex_protocol::protocol::Event;
 use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::ExitedReviewModeEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
+use codex_protocol::protocol::ReviewFinding;
 use codex_protocol::protocol::ReviewOutputEvent;
 use tokio_util::sync::CancellationToken;
 
@@ -19,18 +21,29 @@ use crate::codex::TurnContext;
 use crate::codex_delegate::run_codex_thread_one_shot;
 use crate::review_format::format_review_findings_block;
 use crate::review_format::render_review_output_text;
+use crate::review_prompts::CommitReview;
 use crate::state::TaskKind;
 use codex_protocol::user_input::UserInput;
 
 use super::SessionTask;
 use super::SessionTaskContext;
 
-#[derive(Clone, Copy)]
-pub(crate) struct ReviewTask;
+#[derive(Clone)]
+pub(crate) struct ReviewTask {
+    /// When set, each commit is reviewed by its own reviewer thread and the
+    /// task input is ignored.
+    commit_reviews: Vec<CommitReview>,
+}
 
 impl ReviewTask {
     pub(crate) fn new() -> Self {
-        Self
+        Self {
+            commit_reviews: Vec::new(),
+        }
+    }
+
+    pub(crate) fn per_commit(commit_reviews: Vec<CommitReview>) -> Self {
+        Self { commit_reviews }
     }
 }
 
@@ -53,17 +66,22 @@ impl SessionTask for ReviewTask {
             .otel_manager
             .counter("codex.task.review", 1, &[]);
 
-        // Start sub-codex conversation and get the receiver for events.
-        let output = match start_review_conversation(
-            session.clone(),
-            ctx.clone(),
-            input,
-            cancellation_token.clone(),
-        )
-        .await
-        {
-            Some(receiver) => process_review_events(session.clone(), ctx.clone(), receiver).await,
-            None => None,
+        let output = if self.commit_reviews.is_empty() {
+            run_review(
+                session.clone(),
+                ctx.clone(),
+                input,
+                cancellation_token.clone(),
+            )
+            .await
+        } else {
+            review_each_commit(
+                session.clone(),
+                ctx.clone(),
+                &self.commit_reviews,
+                cancellation_token.clone(),
+            )
+            .await
         };
         if !cancellation_token.is_cancelled() {
             exit_review_mode(session.clone_session(), output.clone(), ctx.clone()).await;
@@ -76,6 +94,108 @@ impl SessionTask for ReviewTask {
     }
 }
 
+/// Runs one reviewer thread over `input` and returns its parsed output, or
+/// `None` if the review was interrupted.
+async fn run_review(
+    session: Arc<SessionTaskContext>,
+    ctx: Arc<TurnContext>,
+    input: Vec<UserInput>,
+    cancellation_token: CancellationToken,
+) -> Option<ReviewOutputEvent> {
+    // Start sub-codex conversation and get the receiver for events.
+    let receiver =
+        start_review_conversation(session.clone(), ctx.clone(), input, cancellation_token).await?;
+    process_review_events(session, ctx, receiver).await
+}
+
+/// Reviews each commit in turn and merges the results, tagging every finding
+/// with the commit it came from. Stops at the first interrupted review.
+async fn review_each_commit(
+    session: Arc<SessionTaskContext>,
+    ctx: Arc<TurnContext>,
+    commit_reviews: &[CommitReview],
+    cancellation_token: CancellationToken,
+) -> Option<ReviewOutputEvent> {
+    let mut reviews = Vec::with_capacity(commit_reviews.len());
+    for (idx, commit) in commit_reviews.iter().enumerate() {
+        let short_sha: String = commit.sha.chars().take(7).collect();
+        session
+            .clone_session()
+            .send_event(
+                ctx.as_ref(),
+                EventMsg::BackgroundEvent(BackgroundEventEvent {
+                    message: format!(
+                        "Reviewing commit {}/{}: {short_sha} {}",
+                        idx + 1,
+                        commit_reviews.len(),
+                        commit.subject
+                    ),
+                }),
+            )
+            .await;
+        let input = vec![UserInput::Text {
+            text: commit.prompt.clone(),
+            text_elements: Vec::new(),
+        }];
+        let output = run_review(
+            session.clone(),
+            ctx.clone(),
+            input,
+            cancellation_token.clone(),
+        )
+        .await?;
+        reviews.push((commit, output));
+    }
+    Some(merge_commit_reviews(reviews))
+}
+
+/// Combines per-commit reviews into one result. The patch is only correct if
+/// every commit is, and the confidence is that of the least confident review.
+fn merge_commit_reviews(reviews: Vec<(&CommitReview, ReviewOutputEvent)>) -> ReviewOutputEvent {
+    const CORRECT: &str = "patch is correct";
+    const INCORRECT: &str = "patch is incorrect";
+
+    let overall_correctness = if reviews
+        .iter()
+        .all(|(_, review)| review.overall_correctness == CORRECT)
+    {
+        CORRECT.to_string()
+    } else if reviews
+        .iter()
+        .any(|(_, review)| review.overall_correctness == INCORRECT)
+    {
+        INCORRECT.to_string()
+    } else {
+        String::new()
+    };
+    let overall_confidence_score = reviews
+        .iter()
+        .map(|(_, review)| review.overall_confidence_score)
+        .reduce(f32::min)
+        .unwrap_or_default();
+
+    let mut explanations = Vec::new();
+    let mut findings = Vec::new();
+    for (commit, review) in reviews {
+        let explanation = review.overall_explanation.trim();
+        if !explanation.is_empty() {
+            let short_sha: String = commit.sha.chars().take(7).collect();
+            explanations.push(format!("{short_sha} {}: {explanation}", commit.subject));
+        }
+        findings.extend(review.findings.into_iter().map(|finding| ReviewFinding {
+            commit_sha: Some(commit.sha.clone()),
+            ..finding
+        }));
+    }
+
+    ReviewOutputEvent {
+        findings,
+        overall_correctness,
+        overall_explanation: explanations.join("\n\n"),
+        overall_confidence_score,
+    }
+}
+
 async fn start_review_conversation(
     session: Arc<SessionTaskContext>,
     ctx: Arc<TurnContext>,
@@ -247,3 +367,75 @@ pub(crate) async fn exit_review_mode(
         )
         .await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::ReviewCodeLocation;
+    use codex_protocol::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn commit(sha: &str, subject: &str) -> CommitReview {
+        CommitReview {
+            sha: sha.to_string(),
+            subject: subject.to_string(),
+            prompt: String::new(),
+        }
+    }
+
+    fn finding(title: &str) -> ReviewFinding {
+        ReviewFinding {
+            title: title.to_string(),
+            body: String::new(),
+            confidence_score: 0.5,
+            priority: 1,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+                line_range: ReviewLineRange { start: 1, end: 2 },
+            },
+            commit_sha: None,
+        }
+    }
+
+    #[test]
+    fn merge_commit_reviews_tags_findings_and_keeps_worst_verdict() {
+        let first = commit("1111111aaaa", "Add parser");
+        let second = commit("2222222bbbb", "Use parser");
+        let merged = merge_commit_reviews(vec![
+            (
+                &first,
+                ReviewOutputEvent {
+                    findings: Vec::new(),
+                    overall_correctness: "patch is correct".to_string(),
+                    overall_explanation: "Looks fine.".to_string(),
+                    overall_confidence_score: 0.9,
+                },
+            ),
+            (
+                &second,
+                ReviewOutputEvent {
+                    findings: vec![finding("Off by one")],
+                    overall_correctness: "patch is incorrect".to_string(),
+                    overall_explanation: "Loop bound is wrong.".to_string(),
+                    overall_confidence_score: 0.6,
+                },
+            ),
+        ]);
+
+        assert_eq!(
+            merged,
+            ReviewOutputEvent {
+                findings: vec![ReviewFinding {
+                    commit_sha: Some("2222222bbbb".to_string()),
+                    ..finding("Off by one")
+                }],
+                overall_correctness: "patch is incorrect".to_string(),
+                overall_explanation:
+                    "1111111 Add parser: Looks fine.\n\n2222222 Use parser: Loop bound is wrong."
+                        .to_string(),
+                overall_confidence_score: 0.6,
+            }
+        );
+    }
+}
@@ -8,6 +8,8 @@ use crate::state::TaskKind;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
 use async_trait::async_trait;
+use codex_git::FileSnapshotStore;
+use codex_git::GitToolingError;
 use codex_git::RestoreGhostCommitOptions;
 use codex_git::restore_ghost_commit_with_options;
 use codex_protocol::models::ResponseItem;
@@ -83,7 +85,7 @@ impl SessionTask for UndoTask {
                     _ => None,
                 })
         else {
-            completed.message = Some("No ghost snapshot available to undo.".to_string());
+            let completed = undo_file_snapshot(&session, ctx.as_ref()).await;
             sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
                 .await;
             return None;
@@ -124,3 +126,54 @@ impl SessionTask for UndoTask {
         None
     }
 }
+
+/// Restores the newest file snapshot of this session, which is how turns are
+/// captured in workspaces that are not git repositories.
+async fn undo_file_snapshot(session: &SessionTaskContext, ctx: &TurnContext) -> UndoCompletedEvent {
+    let mut completed = UndoCompletedEvent {
+        success: false,
+        message: None,
+    };
+    if !ctx.ghost_snapshot.file_snapshots {
+        completed.message = Some("No ghost snapshot available to undo.".to_string());
+        return completed;
+    }
+
+    let store = FileSnapshotStore::new(
+        &session.session.codex_home().await,
+        &session.session.conversation_id.to_string(),
+        &ctx.ghost_snapshot,
+    );
+    let restore_result =
+        tokio::task::spawn_blocking(move || -> Result<Option<u64>, GitToolingError> {
+            let Some(snapshot) = store.latest()? else {
+                return Ok(None);
+            };
+            store.restore(&snapshot)?;
+            store.discard(&snapshot)?;
+            Ok(Some(snapshot.id))
+        })
+        .await;
+
+    match restore_result {
+        Ok(Ok(Some(id))) => {
+            info!(snapshot_id = id, "Undo restored file snapshot");
+            completed.success = true;
+            completed.message = Some(format!("Undo restored file snapshot {id}."));
+        }
+        Ok(Ok(None)) => {
+            completed.message = Some("No ghost snapshot available to undo.".to_string());
+        }
+        Ok(Err(err)) => {
+            let message = format!("Failed to restore file snapshot: {err}");
+            warn!("{message}");
+            completed.message = Some(message);
+        }
+        Err(err) => {
+            let message = format!("Failed to restore file snapshot: {err}");
+            error!("{message}");
+            completed.message = Some(message);
+        }
+    }
+    completed
+}
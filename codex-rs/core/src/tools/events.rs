@@ -146,6 +146,14 @@ impl ToolEmitter {
     }
 
     pub async fn emit(&self, ctx: ToolEventCtx<'_>, stage: ToolEventStage) {
+        if matches!(stage, ToolEventStage::Begin) {
+            let (cwd, paths) = self.touched_paths(ctx.turn);
+            ctx.session
+                .services
+                .path_instructions
+                .note_touched(cwd, paths)
+                .await;
+        }
         match (self, stage) {
             (
                 Self::Shell {
@@ -251,20 +259,57 @@ impl ToolEmitter {
         }
     }
 
+    /// Files this call reads or edits, for path-scoped instructions.
+    fn touched_paths<'a>(&'a self, turn: &'a TurnContext) -> (&'a Path, Vec<PathBuf>) {
+        match self {
+            Self::Shell {
+                cwd, parsed_cmd, ..
+            }
+            | Self::UnifiedExec {
+                cwd, parsed_cmd, ..
+            } => (
+                cwd.as_path(),
+                parsed_cmd
+                    .iter()
+                    .filter_map(|parsed| match parsed {
+                        ParsedCommand::Read { path, .. } => Some(path.clone()),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            Self::ApplyPatch { changes, .. } => {
+                (turn.cwd.as_path(), changes.keys().cloned().collect())
+            }
+        }
+    }
+
     pub async fn begin(&self, ctx: ToolEventCtx<'_>) {
         self.emit(ctx, ToolEventStage::Begin).await;
     }
 
-    fn format_exec_output_for_model(
+    async fn format_exec_output_for_model(
         &self,
         output: &ExecToolCallOutput,
         ctx: ToolEventCtx<'_>,
     ) -> String {
+        let spill_note = super::spill_exec_output(
+            ctx.session.services.output_spill.as_ref(),
+            ctx.call_id,
+            output,
+            ctx.turn.truncation_policy,
+        )
+        .await;
         match self {
-            Self::Shell { freeform: true, .. } => {
-                super::format_exec_output_for_model_freeform(output, ctx.turn.truncation_policy)
-            }
-            _ => super::format_exec_output_for_model_structured(output, ctx.turn.truncation_policy),
+            Self::Shell { freeform: true, .. } => super::format_exec_output_for_model_freeform(
+                output,
+                ctx.turn.truncation_policy,
+                spill_note.as_deref(),
+            ),
+            _ => super::format_exec_output_for_model_structured(
+                output,
+                ctx.turn.truncation_policy,
+                spill_note.as_deref(),
+            ),
         }
     }
 
@@ -275,7 +320,7 @@ impl ToolEmitter {
     ) -> Result<String, FunctionCallError> {
         let (event, result) = match out {
             Ok(output) => {
-                let content = self.format_exec_output_for_model(&output, ctx);
+                let content = self.format_exec_output_for_model(&output, ctx).await;
                 let exit_code = output.exit_code;
                 let event = ToolEventStage::Success(output);
                 let result = if exit_code == 0 {
@@ -287,7 +332,7 @@ impl ToolEmitter {
             }
             Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Timeout { output })))
             | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
-                let response = self.format_exec_output_for_model(&output, ctx);
+                let response = self.format_exec_output_for_model(&output, ctx).await;
                 let event = ToolEventStage::Failure(ToolEventFailure::Output(*output));
                 let result = Err(FunctionCallError::RespondToModel(response));
                 (event, result)
@@ -0,0 +1,183 @@
+use async_trait::async_trait;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::ReviewDecision;
+use serde::Deserialize;
+use serde::Serialize;
+use url::Url;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::fetch_url::FETCH_URL_TOOL_NAME;
+use crate::fetch_url::FetchStep;
+use crate::fetch_url::HostAccess;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::sandboxing::with_cached_approval;
+
+pub struct FetchUrlHandler;
+
+const MAX_REDIRECTS: usize = 5;
+
+fn default_page() -> usize {
+    1
+}
+
+#[derive(Deserialize)]
+struct FetchUrlArgs {
+    url: String,
+    #[serde(default = "default_page")]
+    page: usize,
+}
+
+/// Approval cache key: approving a host for the session covers every page on it.
+#[derive(Serialize)]
+struct FetchUrlApprovalKey {
+    host: String,
+}
+
+#[async_trait]
+impl ToolHandler for FetchUrlHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "{FETCH_URL_TOOL_NAME} handler received unsupported payload"
+                )));
+            }
+        };
+
+        let FetchUrlArgs { url, page } = parse_arguments(&arguments)?;
+        if page == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "page must be 1 or greater".to_string(),
+            ));
+        }
+        let url = Url::parse(url.trim())
+            .map_err(|err| FunctionCallError::RespondToModel(format!("invalid url: {err}")))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "unsupported scheme `{}`; only http and https URLs can be fetched",
+                url.scheme()
+            )));
+        }
+
+        let Some(store) = session.services.fetch_url.as_ref() else {
+            return Err(FunctionCallError::RespondToModel(
+                "fetch_url is not enabled for this session".to_string(),
+            ));
+        };
+
+        let document = match store.cached(&url).await {
+            Some(document) => document,
+            None => {
+                let mut current = url.clone();
+                let mut redirects = 0;
+                let document = loop {
+                    ensure_host_allowed(
+                        &session,
+                        &turn,
+                        &call_id,
+                        store.policy().check(&current),
+                        &current,
+                    )
+                    .await?;
+                    match store
+                        .fetch_once(&current)
+                        .await
+                        .map_err(FunctionCallError::RespondToModel)?
+                    {
+                        FetchStep::Page(document) => break document,
+                        FetchStep::Redirect(next) => {
+                            redirects += 1;
+                            if redirects > MAX_REDIRECTS {
+                                return Err(FunctionCallError::RespondToModel(format!(
+                                    "{url} redirected more than {MAX_REDIRECTS} times"
+                                )));
+                            }
+                            current = next;
+                        }
+                    }
+                };
+                store.insert(&url, document).await
+            }
+        };
+
+        let content = document
+            .render(page)
+            .map_err(FunctionCallError::RespondToModel)?;
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Resolve the policy decision for `url`, prompting the user for hosts that
+/// are not allowlisted. Every redirect hop goes through here too.
+async fn ensure_host_allowed(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+    access: HostAccess,
+    url: &Url,
+) -> Result<(), FunctionCallError> {
+    let host = url.host_str().unwrap_or_default().to_string();
+    match access {
+        HostAccess::Allowed => Ok(()),
+        HostAccess::Denied(reason) => Err(FunctionCallError::RespondToModel(format!(
+            "fetching {url} is blocked: {reason}"
+        ))),
+        HostAccess::NeedsApproval if turn.approval_policy == AskForApproval::Never => {
+            Err(FunctionCallError::RespondToModel(format!(
+                "fetching {url} is blocked: {host} is not in network_proxy.policy.allowed_domains and approvals are disabled"
+            )))
+        }
+        HostAccess::NeedsApproval => {
+            let decision = with_cached_approval(
+                &session.services,
+                FETCH_URL_TOOL_NAME,
+                vec![FetchUrlApprovalKey { host: host.clone() }],
+                || {
+                    session.request_command_approval(
+                        turn,
+                        call_id.to_string(),
+                        vec![FETCH_URL_TOOL_NAME.to_string(), url.to_string()],
+                        turn.cwd.clone(),
+                        Some(format!(
+                            "Fetch {url}? {host} is not in network_proxy.policy.allowed_domains."
+                        )),
+                        None,
+                    )
+                },
+            )
+            .await;
+            match decision {
+                ReviewDecision::Approved
+                | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+                | ReviewDecision::ApprovedForSession => Ok(()),
+                ReviewDecision::Denied | ReviewDecision::Abort => Err(
+                    FunctionCallError::RespondToModel(format!("the user declined fetching {url}")),
+                ),
+            }
+        }
+    }
+}
@@ -1,13 +1,16 @@
 pub mod apply_patch;
 pub(crate) mod collab;
 mod dynamic;
+mod fetch_url;
 mod grep_files;
 mod list_dir;
 mod mcp;
 mod mcp_resource;
 mod plan;
 mod read_file;
+mod read_spilled_output;
 mod request_user_input;
+mod service;
 mod shell;
 mod test_sync;
 mod unified_exec;
@@ -20,14 +23,19 @@ use crate::function_tool::FunctionCallError;
 pub use apply_patch::ApplyPatchHandler;
 pub use collab::CollabHandler;
 pub use dynamic::DynamicToolHandler;
+pub use fetch_url::FetchUrlHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
 pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
 pub use plan::PlanHandler;
+pub(crate) use plan::plan_context_item;
 pub use read_file::ReadFileHandler;
+pub use read_spilled_output::ReadSpilledOutputHandler;
 pub use request_user_input::RequestUserInputHandler;
 pub(crate) use request_user_input::request_user_input_tool_description;
+pub use service::SERVICE_TOOL_NAME;
+pub use service::ServiceHandler;
 pub use shell::ShellCommandHandler;
 pub use shell::ShellHandler;
 pub use test_sync::TestSyncHandler;
@@ -3,6 +3,7 @@ use crate::client_common::tools::ToolSpec;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
+use crate::session_prefix::PLAN_CONTEXT_OPEN_TAG;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -11,6 +12,9 @@ use crate::tools::registry::ToolKind;
 use crate::tools::spec::JsonSchema;
 use async_trait::async_trait;
 use codex_protocol::config_types::ModeKind;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::plan_tool::StepStatus;
 use codex_protocol::plan_tool::UpdatePlanArgs;
 use codex_protocol::protocol::EventMsg;
 use std::collections::BTreeMap;
@@ -95,9 +99,9 @@ impl ToolHandler for PlanHandler {
     }
 }
 
-/// This function doesn't do anything useful. However, it gives the model a structured way to record its plan that clients can read and render.
-/// So it's the _inputs_ to this function that are useful to clients, not the outputs and neither are actually useful for the model other
-/// than forcing it to come up and document a plan (TBD how that affects performance).
+/// Records the model's plan so clients can render it. The latest plan is kept
+/// as session state (and in the rollout via the `PlanUpdate` event) so it can
+/// be restored into context after compaction or when the session is resumed.
 pub(crate) async fn handle_update_plan(
     session: &Session,
     turn_context: &TurnContext,
@@ -110,6 +114,7 @@ pub(crate) async fn handle_update_plan(
         ));
     }
     let args = parse_update_plan_arguments(&arguments)?;
+    session.set_plan(args.clone()).await;
     session
         .send_event(turn_context, EventMsg::PlanUpdate(args))
         .await;
@@ -121,3 +126,81 @@ fn parse_update_plan_arguments(arguments: &str) -> Result<UpdatePlanArgs, Functi
         FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e}"))
     })
 }
+
+/// Context message restoring the latest plan once the `update_plan` calls that
+/// produced it are no longer in history (compaction) or the session restarts.
+pub(crate) fn plan_context_item(plan: &UpdatePlanArgs) -> ResponseItem {
+    let mut lines = vec![
+        PLAN_CONTEXT_OPEN_TAG.to_string(),
+        "Current plan from your last update_plan call. Keep it up to date with update_plan."
+            .to_string(),
+    ];
+    if let Some(explanation) = plan
+        .explanation
+        .as_deref()
+        .filter(|explanation| !explanation.trim().is_empty())
+    {
+        lines.push(format!("Explanation: {}", explanation.trim()));
+    }
+    for item in &plan.plan {
+        let status = match item.status {
+            StepStatus::Pending => "pending",
+            StepStatus::InProgress => "in_progress",
+            StepStatus::Completed => "completed",
+        };
+        lines.push(format!("- [{status}] {}", item.step));
+    }
+    lines.push("</plan_context>".to_string());
+
+    ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: lines.join("\n"),
+        }],
+        end_turn: None,
+        phase: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_prefix::is_session_prefix_content;
+    use codex_protocol::plan_tool::PlanItemArg;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn plan_context_lists_steps_with_status() {
+        let item = plan_context_item(&UpdatePlanArgs {
+            explanation: Some("Migrate the parser".to_string()),
+            plan: vec![
+                PlanItemArg {
+                    step: "Add tokenizer".to_string(),
+                    status: StepStatus::Completed,
+                },
+                PlanItemArg {
+                    step: "Port grammar".to_string(),
+                    status: StepStatus::InProgress,
+                },
+                PlanItemArg {
+                    step: "Delete old parser".to_string(),
+                    status: StepStatus::Pending,
+                },
+            ],
+        });
+
+        let ResponseItem::Message { role, content, .. } = item else {
+            panic!("expected a message");
+        };
+        assert_eq!(role, "user");
+        assert!(is_session_prefix_content(&content));
+        let [ContentItem::InputText { text }] = content.as_slice() else {
+            panic!("expected one InputText content item");
+        };
+        assert_eq!(
+            text,
+            "<plan_context>\nCurrent plan from your last update_plan call. Keep it up to date with update_plan.\nExplanation: Migrate the parser\n- [completed] Add tokenizer\n- [in_progress] Port grammar\n- [pending] Delete old parser\n</plan_context>"
+        );
+    }
+}
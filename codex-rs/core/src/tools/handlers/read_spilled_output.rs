@@ -0,0 +1,166 @@
+use async_trait::async_trait;
+use codex_utils_string::take_bytes_at_char_boundary;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::spill::READ_SPILLED_OUTPUT_TOOL_NAME;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct ReadSpilledOutputHandler;
+
+const MAX_LINE_LENGTH: usize = 500;
+
+fn default_offset() -> usize {
+    1
+}
+
+fn default_limit() -> usize {
+    200
+}
+
+#[derive(Deserialize)]
+struct ReadSpilledOutputArgs {
+    spill_id: String,
+    #[serde(default = "default_offset")]
+    offset: usize,
+    #[serde(default = "default_limit")]
+    limit: usize,
+    #[serde(default)]
+    pattern: Option<String>,
+}
+
+#[async_trait]
+impl ToolHandler for ReadSpilledOutputHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session, payload, ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "{READ_SPILLED_OUTPUT_TOOL_NAME} handler received unsupported payload"
+                )));
+            }
+        };
+
+        let args: ReadSpilledOutputArgs = parse_arguments(&arguments)?;
+        let ReadSpilledOutputArgs {
+            spill_id,
+            offset,
+            limit,
+            pattern,
+        } = args;
+
+        if offset == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "offset must be a 1-indexed line number".to_string(),
+            ));
+        }
+        if limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+        let pattern = match pattern.as_deref().filter(|p| !p.is_empty()) {
+            Some(pattern) => Some(Regex::new(pattern).map_err(|err| {
+                FunctionCallError::RespondToModel(format!("invalid pattern: {err}"))
+            })?),
+            None => None,
+        };
+
+        let Some(store) = session.services.output_spill.as_ref() else {
+            return Err(FunctionCallError::RespondToModel(
+                "output spilling is not enabled for this session".to_string(),
+            ));
+        };
+        let content = store.read(&spill_id).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to read spill {spill_id}: {err}"))
+        })?;
+
+        Ok(ToolOutput::Function {
+            content: read_lines(&content, offset, limit, pattern.as_ref()),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Render up to `limit` lines starting at `offset`, keeping only lines that
+/// match `pattern` when one is given.
+fn read_lines(content: &str, offset: usize, limit: usize, pattern: Option<&Regex>) -> String {
+    let total_lines = content.lines().count();
+    let mut out = Vec::new();
+    let mut next_offset = None;
+    for (idx, line) in content.lines().enumerate().skip(offset - 1) {
+        if pattern.is_some_and(|pattern| !pattern.is_match(line)) {
+            continue;
+        }
+        if out.len() == limit {
+            next_offset = Some(idx + 1);
+            break;
+        }
+        let display = take_bytes_at_char_boundary(line, MAX_LINE_LENGTH);
+        out.push(format!("L{}: {display}", idx + 1));
+    }
+
+    if out.is_empty() {
+        out.push(match pattern {
+            Some(_) => {
+                format!("No matching lines at or after line {offset} (total lines: {total_lines}).")
+            }
+            None => format!("offset {offset} exceeds total lines ({total_lines})."),
+        });
+    } else if let Some(next_offset) = next_offset {
+        out.push(format!(
+            "[More lines available; continue with offset {next_offset}. Total lines: {total_lines}.]"
+        ));
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn pages_through_lines() {
+        let content = "a\nb\nc\nd\n";
+        assert_eq!(
+            read_lines(content, 2, 2, None),
+            "L2: b\nL3: c\n[More lines available; continue with offset 4. Total lines: 4.]"
+        );
+        assert_eq!(read_lines(content, 4, 2, None), "L4: d");
+        assert_eq!(
+            read_lines(content, 9, 2, None),
+            "offset 9 exceeds total lines (4)."
+        );
+    }
+
+    #[test]
+    fn filters_by_pattern() {
+        let content = "ok 1\nFAILED 2\nok 3\nFAILED 4\nFAILED 5\n";
+        let pattern = Regex::new("FAILED").expect("regex");
+        assert_eq!(
+            read_lines(content, 1, 2, Some(&pattern)),
+            "L2: FAILED 2\nL4: FAILED 4\n[More lines available; continue with offset 5. Total lines: 5.]"
+        );
+        let missing = Regex::new("panic").expect("regex");
+        assert_eq!(
+            read_lines(content, 1, 2, Some(&missing)),
+            "No matching lines at or after line 1 (total lines: 5)."
+        );
+    }
+}
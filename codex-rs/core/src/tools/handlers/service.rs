@@ -0,0 +1,294 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::unified_exec::ReadinessProbe;
+use crate::unified_exec::ServiceManager;
+use crate::unified_exec::ServiceSpec;
+use crate::unified_exec::ServiceStatus;
+use crate::unified_exec::UnifiedExecContext;
+use crate::unified_exec::UnifiedExecError;
+
+pub const SERVICE_TOOL_NAME: &str = "service";
+
+pub struct ServiceHandler;
+
+const DEFAULT_READY_TIMEOUT_MS: u64 = 30_000;
+const MAX_READY_TIMEOUT_MS: u64 = 300_000;
+const DEFAULT_LOG_LINES: usize = 50;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ServiceAction {
+    Start,
+    Status,
+    Logs,
+    Restart,
+    Stop,
+    List,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServiceArgs {
+    action: ServiceAction,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    cmd: Option<String>,
+    #[serde(default)]
+    workdir: Option<String>,
+    #[serde(default)]
+    ready_port: Option<u16>,
+    #[serde(default)]
+    ready_url: Option<String>,
+    #[serde(default)]
+    ready_pattern: Option<String>,
+    #[serde(default)]
+    ready_timeout_ms: Option<u64>,
+    #[serde(default)]
+    lines: Option<usize>,
+    #[serde(default)]
+    sandbox_permissions: SandboxPermissions,
+    #[serde(default)]
+    justification: Option<String>,
+}
+
+#[async_trait]
+impl ToolHandler for ServiceHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        let ToolPayload::Function { arguments } = &invocation.payload else {
+            return true;
+        };
+        spawns_process(arguments)
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "{SERVICE_TOOL_NAME} handler received unsupported payload"
+                )));
+            }
+        };
+        let args: ServiceArgs = parse_arguments(&arguments)?;
+        let manager = &session.services.service_manager;
+        let context = UnifiedExecContext::new(session.clone(), turn.clone(), call_id);
+        let to_model = |err: UnifiedExecError| FunctionCallError::RespondToModel(err.to_string());
+
+        let content = match args.action {
+            ServiceAction::List => {
+                let statuses = manager.list().await;
+                if statuses.is_empty() {
+                    "No services running.".to_string()
+                } else {
+                    statuses
+                        .iter()
+                        .map(ServiceStatus::render)
+                        .collect::<Vec<_>>()
+                        .join("\n\n")
+                }
+            }
+            ServiceAction::Start => {
+                let name = required_name(args.name.as_deref())?;
+                let Some(cmd) = args.cmd.as_deref().filter(|cmd| !cmd.trim().is_empty()) else {
+                    return Err(FunctionCallError::RespondToModel(
+                        "cmd is required to start a service".to_string(),
+                    ));
+                };
+                if args.sandbox_permissions.requires_escalated_permissions()
+                    && !matches!(
+                        turn.approval_policy,
+                        codex_protocol::protocol::AskForApproval::OnRequest
+                    )
+                {
+                    let approval_policy = turn.approval_policy;
+                    return Err(FunctionCallError::RespondToModel(format!(
+                        "approval policy is {approval_policy:?}; reject command — you cannot ask for escalated permissions if the approval policy is {approval_policy:?}"
+                    )));
+                }
+                let readiness = readiness_probe(
+                    args.ready_port,
+                    args.ready_url,
+                    args.ready_pattern.as_deref(),
+                )?;
+                let cwd = turn.resolve_path(args.workdir.filter(|dir| !dir.is_empty()));
+                let spec = ServiceSpec {
+                    name: name.to_string(),
+                    command: session.user_shell().derive_exec_args(cmd, true),
+                    cwd,
+                    readiness,
+                    ready_timeout: Duration::from_millis(
+                        args.ready_timeout_ms
+                            .unwrap_or(DEFAULT_READY_TIMEOUT_MS)
+                            .min(MAX_READY_TIMEOUT_MS),
+                    ),
+                    sandbox_permissions: args.sandbox_permissions,
+                    justification: args.justification,
+                };
+                let status = manager.start(spec, &context).await.map_err(to_model)?;
+                with_recent_logs(status.render(), manager, name).await
+            }
+            ServiceAction::Restart => {
+                let name = required_name(args.name.as_deref())?;
+                let status = manager.restart(name, &context).await.map_err(to_model)?;
+                with_recent_logs(status.render(), manager, name).await
+            }
+            ServiceAction::Status => {
+                let name = required_name(args.name.as_deref())?;
+                manager.status(name).await.map_err(to_model)?.render()
+            }
+            ServiceAction::Logs => {
+                let name = required_name(args.name.as_deref())?;
+                let lines = args.lines.unwrap_or(DEFAULT_LOG_LINES).max(1);
+                let logs = manager.logs(name, lines).await.map_err(to_model)?;
+                if logs.is_empty() {
+                    format!("Service {name} has not produced any output.")
+                } else {
+                    logs
+                }
+            }
+            ServiceAction::Stop => {
+                let name = required_name(args.name.as_deref())?;
+                manager.stop(name).await.map_err(to_model)?.render()
+            }
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Starting or restarting a service runs an arbitrary command, so it has to
+/// wait for the turn's snapshot like any other shell command.
+fn spawns_process(arguments: &str) -> bool {
+    serde_json::from_str::<ServiceArgs>(arguments)
+        .map(|args| matches!(args.action, ServiceAction::Start | ServiceAction::Restart))
+        .unwrap_or(true)
+}
+
+fn required_name(name: Option<&str>) -> Result<&str, FunctionCallError> {
+    name.map(str::trim)
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| {
+            FunctionCallError::RespondToModel("name is required for this action".to_string())
+        })
+}
+
+fn readiness_probe(
+    ready_port: Option<u16>,
+    ready_url: Option<String>,
+    ready_pattern: Option<&str>,
+) -> Result<Option<ReadinessProbe>, FunctionCallError> {
+    let probes = [
+        ready_port.is_some(),
+        ready_url.is_some(),
+        ready_pattern.is_some(),
+    ];
+    if probes.into_iter().filter(|set| *set).count() > 1 {
+        return Err(FunctionCallError::RespondToModel(
+            "set at most one of ready_port, ready_url, or ready_pattern".to_string(),
+        ));
+    }
+    if let Some(port) = ready_port {
+        return Ok(Some(ReadinessProbe::Port(port)));
+    }
+    if let Some(url) = ready_url {
+        return ReadinessProbe::http(&url)
+            .map(Some)
+            .map_err(FunctionCallError::RespondToModel);
+    }
+    match ready_pattern {
+        Some(pattern) => Regex::new(pattern)
+            .map(|pattern| Some(ReadinessProbe::LogPattern(pattern)))
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("invalid ready_pattern: {err}"))
+            }),
+        None => Ok(None),
+    }
+}
+
+async fn with_recent_logs(status: String, manager: &ServiceManager, name: &str) -> String {
+    match manager.logs(name, 20).await {
+        Ok(logs) if !logs.is_empty() => format!("{status}\nRecent output:\n{logs}"),
+        _ => status,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn readiness_probe_accepts_a_single_probe() {
+        assert!(matches!(
+            readiness_probe(Some(3000), None, None),
+            Ok(Some(ReadinessProbe::Port(3000)))
+        ));
+        assert!(matches!(
+            readiness_probe(None, None, Some("Listening on")),
+            Ok(Some(ReadinessProbe::LogPattern(_)))
+        ));
+        assert!(matches!(readiness_probe(None, None, None), Ok(None)));
+    }
+
+    #[test]
+    fn readiness_probe_rejects_multiple_probes() {
+        let err = readiness_probe(Some(3000), Some("http://localhost:3000".to_string()), None)
+            .expect_err("expected conflict");
+        assert_eq!(
+            err,
+            FunctionCallError::RespondToModel(
+                "set at most one of ready_port, ready_url, or ready_pattern".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parses_actions() {
+        let args: ServiceArgs =
+            parse_arguments(r#"{"action": "start", "name": "web", "cmd": "npm run dev"}"#)
+                .expect("parse");
+        assert!(matches!(args.action, ServiceAction::Start));
+        assert_eq!(args.name.as_deref(), Some("web"));
+    }
+
+    #[test]
+    fn only_actions_that_spawn_processes_are_mutating() {
+        assert!(spawns_process(
+            r#"{"action": "start", "name": "web", "cmd": "npm run dev"}"#
+        ));
+        assert!(spawns_process(r#"{"action": "restart", "name": "web"}"#));
+        assert!(spawns_process("not json"));
+        assert!(!spawns_process(r#"{"action": "status", "name": "web"}"#));
+        assert!(!spawns_process(r#"{"action": "logs", "name": "web"}"#));
+        assert!(!spawns_process(r#"{"action": "stop", "name": "web"}"#));
+        assert!(!spawns_process(r#"{"action": "list"}"#));
+    }
+}
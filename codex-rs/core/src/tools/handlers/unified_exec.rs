@@ -13,11 +13,14 @@ use crate::tools::handlers::parse_arguments;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 use crate::unified_exec::ExecCommandRequest;
+use crate::unified_exec::MAX_YIELD_TIME_MS;
 use crate::unified_exec::UnifiedExecContext;
 use crate::unified_exec::UnifiedExecProcessManager;
 use crate::unified_exec::UnifiedExecResponse;
+use crate::unified_exec::WaitCondition;
 use crate::unified_exec::WriteStdinRequest;
 use async_trait::async_trait;
+use regex::Regex;
 use serde::Deserialize;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -35,8 +38,14 @@ struct ExecCommandArgs {
     login: bool,
     #[serde(default = "default_tty")]
     tty: bool,
-    #[serde(default = "default_exec_yield_time_ms")]
-    yield_time_ms: u64,
+    #[serde(default)]
+    screen: bool,
+    #[serde(default)]
+    yield_time_ms: Option<u64>,
+    #[serde(default)]
+    wait_for: Option<String>,
+    #[serde(default)]
+    wait_for_exit: bool,
     #[serde(default)]
     max_output_tokens: Option<usize>,
     #[serde(default)]
@@ -53,10 +62,16 @@ struct WriteStdinArgs {
     session_id: i32,
     #[serde(default)]
     chars: String,
-    #[serde(default = "default_write_stdin_yield_time_ms")]
-    yield_time_ms: u64,
+    #[serde(default)]
+    yield_time_ms: Option<u64>,
+    #[serde(default)]
+    wait_for: Option<String>,
+    #[serde(default)]
+    wait_for_exit: bool,
     #[serde(default)]
     max_output_tokens: Option<usize>,
+    #[serde(default)]
+    screen: Option<bool>,
 }
 
 fn default_exec_yield_time_ms() -> u64 {
@@ -67,6 +82,32 @@ fn default_write_stdin_yield_time_ms() -> u64 {
     250
 }
 
+/// Build the wait condition for a call. Calls that wait for a pattern or exit
+/// default to the longest regular yield time instead of the polling default.
+fn resolve_wait_condition(
+    wait_for: Option<&str>,
+    wait_for_exit: bool,
+    yield_time_ms: Option<u64>,
+    default_yield_time_ms: u64,
+) -> Result<(WaitCondition, u64), FunctionCallError> {
+    let pattern = match wait_for.filter(|pattern| !pattern.is_empty()) {
+        Some(pattern) => Some(Regex::new(pattern).map_err(|err| {
+            FunctionCallError::RespondToModel(format!("invalid wait_for pattern: {err}"))
+        })?),
+        None => None,
+    };
+    let wait = WaitCondition {
+        pattern,
+        exit: wait_for_exit,
+    };
+    let yield_time_ms = yield_time_ms.unwrap_or(if wait.is_set() {
+        MAX_YIELD_TIME_MS
+    } else {
+        default_yield_time_ms
+    });
+    Ok((wait, yield_time_ms))
+}
+
 fn default_login() -> bool {
     true
 }
@@ -127,13 +168,24 @@ impl ToolHandler for UnifiedExecHandler {
         let response = match tool_name.as_str() {
             "exec_command" => {
                 let args: ExecCommandArgs = parse_arguments(&arguments)?;
+                let (wait, yield_time_ms) = resolve_wait_condition(
+                    args.wait_for.as_deref(),
+                    args.wait_for_exit,
+                    args.yield_time_ms,
+                    default_exec_yield_time_ms(),
+                )?;
+                if args.screen && !args.tty {
+                    return Err(FunctionCallError::RespondToModel(
+                        "screen=true requires tty=true".to_string(),
+                    ));
+                }
                 let process_id = manager.allocate_process_id().await;
                 let command = get_command(&args, session.user_shell());
 
                 let ExecCommandArgs {
                     workdir,
                     tty,
-                    yield_time_ms,
+                    screen,
                     max_output_tokens,
                     sandbox_permissions,
                     justification,
@@ -189,9 +241,11 @@ impl ToolHandler for UnifiedExecHandler {
                             command,
                             process_id,
                             yield_time_ms,
+                            wait,
                             max_output_tokens,
                             workdir,
                             tty,
+                            screen,
                             sandbox_permissions,
                             justification,
                             prefix_rule,
@@ -205,12 +259,20 @@ impl ToolHandler for UnifiedExecHandler {
             }
             "write_stdin" => {
                 let args: WriteStdinArgs = parse_arguments(&arguments)?;
+                let (wait, yield_time_ms) = resolve_wait_condition(
+                    args.wait_for.as_deref(),
+                    args.wait_for_exit,
+                    args.yield_time_ms,
+                    default_write_stdin_yield_time_ms(),
+                )?;
                 let response = manager
                     .write_stdin(WriteStdinRequest {
                         process_id: &args.session_id.to_string(),
                         input: &args.chars,
-                        yield_time_ms: args.yield_time_ms,
+                        yield_time_ms,
+                        wait,
                         max_output_tokens: args.max_output_tokens,
+                        screen: args.screen,
                     })
                     .await
                     .map_err(|err| {
@@ -235,7 +297,17 @@ impl ToolHandler for UnifiedExecHandler {
             }
         };
 
-        let content = format_response(&response);
+        let spill_note = match (
+            session.services.output_spill.as_ref(),
+            &response.full_output,
+            &response.screen,
+        ) {
+            (Some(store), Some(full_output), None) => {
+                store.spill_slice(&call_id, full_output).await
+            }
+            _ => None,
+        };
+        let content = format_response(&response, spill_note.as_deref());
 
         Ok(ToolOutput::Function {
             content,
@@ -257,7 +329,7 @@ fn get_command(args: &ExecCommandArgs, session_shell: Arc<Shell>) -> Vec<String>
     shell.derive_exec_args(&args.cmd, args.login)
 }
 
-fn format_response(response: &UnifiedExecResponse) -> String {
+fn format_response(response: &UnifiedExecResponse, spill_note: Option<&str>) -> String {
     let mut sections = Vec::new();
 
     if !response.chunk_id.is_empty() {
@@ -267,6 +339,10 @@ fn format_response(response: &UnifiedExecResponse) -> String {
     let wall_time_seconds = response.wall_time.as_secs_f64();
     sections.push(format!("Wall time: {wall_time_seconds:.4} seconds"));
 
+    if let Some(wait_outcome) = response.wait_outcome {
+        sections.push(format!("Wait condition: {}", wait_outcome.as_str()));
+    }
+
     if let Some(exit_code) = response.exit_code {
         sections.push(format!("Process exited with code {exit_code}"));
     }
@@ -280,8 +356,16 @@ fn format_response(response: &UnifiedExecResponse) -> String {
         sections.push(format!("Original token count: {original_token_count}"));
     }
 
-    sections.push("Output:".to_string());
-    sections.push(response.output.clone());
+    match &response.screen {
+        Some(screen) => sections.push(screen.clone()),
+        None => {
+            sections.push("Output:".to_string());
+            sections.push(response.output.clone());
+        }
+    }
+    if let Some(note) = spill_note {
+        sections.push(note.to_string());
+    }
 
     sections.join("\n")
 }
@@ -354,4 +438,30 @@ mod tests {
         assert_eq!(command[2], "echo hello");
         Ok(())
     }
+
+    #[test]
+    fn wait_condition_defaults_to_long_timeout() -> anyhow::Result<()> {
+        let (wait, yield_time_ms) = resolve_wait_condition(None, false, None, 250)?;
+        assert!(!wait.is_set());
+        assert_eq!(yield_time_ms, 250);
+
+        let (wait, yield_time_ms) =
+            resolve_wait_condition(Some("Listening on port \\d+"), false, None, 250)?;
+        assert!(wait.is_set());
+        assert_eq!(yield_time_ms, MAX_YIELD_TIME_MS);
+
+        let (wait, yield_time_ms) = resolve_wait_condition(None, true, Some(60_000), 250)?;
+        assert!(wait.exit);
+        assert_eq!(yield_time_ms, 60_000);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_wait_for_pattern_is_reported() {
+        let err = resolve_wait_condition(Some("("), false, None, 250)
+            .expect_err("expected invalid pattern");
+        assert!(
+            matches!(err, FunctionCallError::RespondToModel(ref msg) if msg.starts_with("invalid wait_for pattern"))
+        );
+    }
 }
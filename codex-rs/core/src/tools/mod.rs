@@ -10,6 +10,7 @@ pub mod sandboxing;
 pub mod spec;
 
 use crate::exec::ExecToolCallOutput;
+use crate::spill::SpillStore;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::formatted_truncate_text;
 use crate::truncate::truncate_text;
@@ -27,6 +28,7 @@ pub(crate) const TELEMETRY_PREVIEW_TRUNCATION_NOTICE: &str =
 pub fn format_exec_output_for_model_structured(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    spill_note: Option<&str>,
 ) -> String {
     let ExecToolCallOutput {
         exit_code,
@@ -49,7 +51,11 @@ pub fn format_exec_output_for_model_structured(
     // round to 1 decimal place
     let duration_seconds = ((duration.as_secs_f32()) * 10.0).round() / 10.0;
 
-    let formatted_output = format_exec_output_str(exec_output, truncation_policy);
+    let mut formatted_output = format_exec_output_str(exec_output, truncation_policy);
+    if let Some(note) = spill_note {
+        formatted_output.push('\n');
+        formatted_output.push_str(note);
+    }
 
     let payload = ExecOutput {
         output: &formatted_output,
@@ -66,6 +72,7 @@ pub fn format_exec_output_for_model_structured(
 pub fn format_exec_output_for_model_freeform(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    spill_note: Option<&str>,
 ) -> String {
     // round to 1 decimal place
     let duration_seconds = ((exec_output.duration.as_secs_f32()) * 10.0).round() / 10.0;
@@ -86,6 +93,9 @@ pub fn format_exec_output_for_model_freeform(
 
     sections.push("Output:".to_string());
     sections.push(formatted_output);
+    if let Some(note) = spill_note {
+        sections.push(note.to_string());
+    }
 
     sections.join("\n")
 }
@@ -100,6 +110,21 @@ pub fn format_exec_output_str(
     formatted_truncate_text(&content, truncation_policy)
 }
 
+/// Persist the full exec output when the model sees a truncated copy, returning
+/// the note that points the model at the spilled copy.
+pub(crate) async fn spill_exec_output(
+    spill: Option<&SpillStore>,
+    call_id: &str,
+    exec_output: &ExecToolCallOutput,
+    truncation_policy: TruncationPolicy,
+) -> Option<String> {
+    let spill = spill?;
+    let content = build_content_with_timeout(exec_output);
+    spill
+        .spill_if_truncated(call_id, &content, truncation_policy)
+        .await
+}
+
 /// Extracts exec output content and prepends a timeout message if the command timed out.
 fn build_content_with_timeout(exec_output: &ExecToolCallOutput) -> String {
     if exec_output.timed_out {
@@ -3,7 +3,10 @@ use crate::client_common::tools::ResponsesApiTool;
 use crate::client_common::tools::ToolSpec;
 use crate::features::Feature;
 use crate::features::Features;
+use crate::fetch_url::FETCH_URL_TOOL_NAME;
+use crate::spill::READ_SPILLED_OUTPUT_TOOL_NAME;
 use crate::tools::handlers::PLAN_TOOL;
+use crate::tools::handlers::SERVICE_TOOL_NAME;
 use crate::tools::handlers::apply_patch::create_apply_patch_freeform_tool;
 use crate::tools::handlers::apply_patch::create_apply_patch_json_tool;
 use crate::tools::handlers::collab::DEFAULT_WAIT_TIMEOUT_MS;
@@ -32,6 +35,9 @@ pub(crate) struct ToolsConfig {
     pub collab_tools: bool,
     pub collaboration_modes_tools: bool,
     pub request_rule_enabled: bool,
+    pub output_spill: bool,
+    pub background_services: bool,
+    pub fetch_url: bool,
     pub experimental_supported_tools: Vec<String>,
 }
 
@@ -52,6 +58,9 @@ impl ToolsConfig {
         let include_collab_tools = features.enabled(Feature::Collab);
         let include_collaboration_modes_tools = features.enabled(Feature::CollaborationModes);
         let request_rule_enabled = features.enabled(Feature::RequestRule);
+        let output_spill = features.enabled(Feature::OutputSpill);
+        let background_services = features.enabled(Feature::BackgroundServices);
+        let fetch_url = features.enabled(Feature::FetchUrl);
 
         let shell_type = if !features.enabled(Feature::ShellTool) {
             ConfigShellToolType::Disabled
@@ -85,6 +94,9 @@ impl ToolsConfig {
             collab_tools: include_collab_tools,
             collaboration_modes_tools: include_collaboration_modes_tools,
             request_rule_enabled,
+            output_spill,
+            background_services,
+            fetch_url,
             experimental_supported_tools: model_info.experimental_supported_tools.clone(),
         }
     }
@@ -232,7 +244,34 @@ fn create_exec_command_tool(include_prefix_rule: bool) -> ToolSpec {
             "yield_time_ms".to_string(),
             JsonSchema::Number {
                 description: Some(
-                    "How long to wait (in milliseconds) for output before yielding.".to_string(),
+                    "How long to wait (in milliseconds) for output before yielding. With wait_for or wait_for_exit this is the timeout."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "wait_for".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional regular expression; return as soon as a new line of output matches it (e.g. a server's ready line or a REPL prompt). Echoed input is ignored."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "wait_for_exit".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Keep waiting until the process exits or the timeout elapses.".to_string(),
+                ),
+            },
+        ),
+        (
+            "screen".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Emulate a terminal and return the rendered screen (with cursor position) instead of raw output. Use for full-screen programs; requires tty=true."
+                        .to_string(),
                 ),
             },
         ),
@@ -280,7 +319,34 @@ fn create_write_stdin_tool() -> ToolSpec {
             "yield_time_ms".to_string(),
             JsonSchema::Number {
                 description: Some(
-                    "How long to wait (in milliseconds) for output before yielding.".to_string(),
+                    "How long to wait (in milliseconds) for output before yielding. With wait_for or wait_for_exit this is the timeout."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "wait_for".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional regular expression; return as soon as a new line of output matches it (e.g. a server's ready line or a REPL prompt). Echoed input is ignored."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "wait_for_exit".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Keep waiting until the process exits or the timeout elapses.".to_string(),
+                ),
+            },
+        ),
+        (
+            "screen".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Return the rendered screen instead of raw output. Defaults to the mode chosen in exec_command."
+                        .to_string(),
                 ),
             },
         ),
@@ -923,6 +989,179 @@ fn create_list_dir_tool() -> ToolSpec {
     })
 }
 
+fn create_service_tool() -> ToolSpec {
+    let mut properties = BTreeMap::from([
+        (
+            "action".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "One of \"start\", \"status\", \"logs\", \"restart\", \"stop\", or \"list\"."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "name".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Service name. Required for every action except \"list\".".to_string(),
+                ),
+            },
+        ),
+        (
+            "cmd".to_string(),
+            JsonSchema::String {
+                description: Some("Shell command that runs the service (start only).".to_string()),
+            },
+        ),
+        (
+            "workdir".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Working directory for the service; defaults to the turn cwd.".to_string(),
+                ),
+            },
+        ),
+        (
+            "ready_port".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Readiness probe: the service is ready once this localhost port accepts connections."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "ready_url".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Readiness probe: the service is ready once a GET to this localhost or loopback URL returns 2xx."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "ready_pattern".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Readiness probe: the service is ready once its output matches this regular expression."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "ready_timeout_ms".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "How long start/restart wait for the readiness probe. Defaults to 30000."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "lines".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Number of trailing log lines to return (logs only). Defaults to 50."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+    properties.extend(create_approval_parameters(false));
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: SERVICE_TOOL_NAME.to_string(),
+        description: "Manages named long-running background services such as dev servers or databases. Services keep running across turns until stopped and are cleaned up when the session ends."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["action".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_read_spilled_output_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "spill_id".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Spill id reported next to a truncated command output.".to_string(),
+                ),
+            },
+        ),
+        (
+            "offset".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "The line number to start reading from. Must be 1 or greater.".to_string(),
+                ),
+            },
+        ),
+        (
+            "limit".to_string(),
+            JsonSchema::Number {
+                description: Some("The maximum number of lines to return.".to_string()),
+            },
+        ),
+        (
+            "pattern".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional regular expression; only matching lines are returned.".to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: READ_SPILLED_OUTPUT_TOOL_NAME.to_string(),
+        description: "Reads the full output of a previous command that was truncated, with 1-indexed line numbers. Use this instead of re-running the command."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["spill_id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_fetch_url_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "url".to_string(),
+            JsonSchema::String {
+                description: Some("Absolute http or https URL to fetch.".to_string()),
+            },
+        ),
+        (
+            "page".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "1-indexed page of the converted document to return. Defaults to 1."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: FETCH_URL_TOOL_NAME.to_string(),
+        description: "Fetches a web page and returns it as markdown, split into pages for long documents. Results are cached for the session, so requesting further pages does not refetch. Hosts outside the configured network allowlist may require user approval."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["url".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_list_mcp_resources_tool() -> ToolSpec {
     let properties = BTreeMap::from([
         (
@@ -1228,13 +1467,16 @@ pub(crate) fn build_specs(
     use crate::tools::handlers::ApplyPatchHandler;
     use crate::tools::handlers::CollabHandler;
     use crate::tools::handlers::DynamicToolHandler;
+    use crate::tools::handlers::FetchUrlHandler;
     use crate::tools::handlers::GrepFilesHandler;
     use crate::tools::handlers::ListDirHandler;
     use crate::tools::handlers::McpHandler;
     use crate::tools::handlers::McpResourceHandler;
     use crate::tools::handlers::PlanHandler;
     use crate::tools::handlers::ReadFileHandler;
+    use crate::tools::handlers::ReadSpilledOutputHandler;
     use crate::tools::handlers::RequestUserInputHandler;
+    use crate::tools::handlers::ServiceHandler;
     use crate::tools::handlers::ShellCommandHandler;
     use crate::tools::handlers::ShellHandler;
     use crate::tools::handlers::TestSyncHandler;
@@ -1357,6 +1599,24 @@ pub(crate) fn build_specs(
         builder.register_handler("test_sync_tool", test_sync_handler);
     }
 
+    if config.background_services {
+        builder.push_spec(create_service_tool());
+        builder.register_handler(SERVICE_TOOL_NAME, Arc::new(ServiceHandler));
+    }
+
+    if config.output_spill {
+        builder.push_spec_with_parallel_support(create_read_spilled_output_tool(), true);
+        builder.register_handler(
+            READ_SPILLED_OUTPUT_TOOL_NAME,
+            Arc::new(ReadSpilledOutputHandler),
+        );
+    }
+
+    if config.fetch_url {
+        builder.push_spec(create_fetch_url_tool());
+        builder.register_handler(FETCH_URL_TOOL_NAME, Arc::new(FetchUrlHandler));
+    }
+
     match config.web_search_mode {
         Some(WebSearchMode::Cached) => {
             builder.push_spec(ToolSpec::WebSearch {
@@ -1669,6 +1929,86 @@ mod tests {
         assert_contains_tool_names(&tools, &["request_user_input"]);
     }
 
+    #[test]
+    fn read_spilled_output_requires_output_spill_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        assert!(
+            !tools
+                .iter()
+                .any(|t| t.spec.name() == READ_SPILLED_OUTPUT_TOOL_NAME),
+            "read_spilled_output should be disabled when output_spill feature is off"
+        );
+
+        features.enable(Feature::OutputSpill);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        assert_contains_tool_names(&tools, &[READ_SPILLED_OUTPUT_TOOL_NAME]);
+    }
+
+    #[test]
+    fn service_tool_requires_background_services_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        assert!(
+            !tools.iter().any(|t| t.spec.name() == SERVICE_TOOL_NAME),
+            "service tool should be disabled when background_services feature is off"
+        );
+
+        features.enable(Feature::BackgroundServices);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        assert_contains_tool_names(&tools, &[SERVICE_TOOL_NAME]);
+    }
+
+    #[test]
+    fn fetch_url_tool_requires_fetch_url_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        assert!(
+            !tools.iter().any(|t| t.spec.name() == FETCH_URL_TOOL_NAME),
+            "fetch_url should be disabled when fetch_url feature is off"
+        );
+
+        features.enable(Feature::FetchUrl);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        assert_contains_tool_names(&tools, &[FETCH_URL_TOOL_NAME]);
+    }
+
     fn assert_model_tools(
         model_slug: &str,
         features: &Features,
@@ -6,20 +6,21 @@ use codex_protocol::models::FunctionCallOutputContentItem;
 use codex_protocol::openai_models::TruncationMode;
 use codex_protocol::openai_models::TruncationPolicyConfig;
 use codex_protocol::protocol::TruncationPolicy as ProtocolTruncationPolicy;
-
-const APPROX_BYTES_PER_TOKEN: usize = 4;
+use codex_utils_tokenizer::APPROX_BYTES_PER_TOKEN;
+use codex_utils_tokenizer::Tokenizer;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TruncationPolicy {
     Bytes(usize),
-    Tokens(usize),
+    /// Token budget, counted with the given tokenizer.
+    Tokens(usize, Tokenizer),
 }
 
 impl From<TruncationPolicy> for ProtocolTruncationPolicy {
     fn from(value: TruncationPolicy) -> Self {
         match value {
             TruncationPolicy::Bytes(bytes) => Self::Bytes(bytes),
-            TruncationPolicy::Tokens(tokens) => Self::Tokens(tokens),
+            TruncationPolicy::Tokens(tokens, _) => Self::Tokens(tokens),
         }
     }
 }
@@ -28,12 +29,25 @@ impl From<TruncationPolicyConfig> for TruncationPolicy {
     fn from(config: TruncationPolicyConfig) -> Self {
         match config.mode {
             TruncationMode::Bytes => Self::Bytes(config.limit as usize),
-            TruncationMode::Tokens => Self::Tokens(config.limit as usize),
+            TruncationMode::Tokens => Self::tokens(config.limit as usize),
         }
     }
 }
 
 impl TruncationPolicy {
+    /// Token budget counted with the bytes-per-token estimate.
+    pub const fn tokens(limit: usize) -> Self {
+        Self::Tokens(limit, Tokenizer::Approximate)
+    }
+
+    /// Counts token budgets with `tokenizer`. Byte budgets are unchanged.
+    pub fn with_tokenizer(self, tokenizer: Tokenizer) -> Self {
+        match self {
+            TruncationPolicy::Bytes(bytes) => TruncationPolicy::Bytes(bytes),
+            TruncationPolicy::Tokens(tokens, _) => TruncationPolicy::Tokens(tokens, tokenizer),
+        }
+    }
+
     /// Returns a token budget derived from this policy.
     ///
     /// - For `Tokens`, this is the explicit token limit.
@@ -44,7 +58,7 @@ impl TruncationPolicy {
             TruncationPolicy::Bytes(bytes) => {
                 usize::try_from(approx_tokens_from_byte_count(*bytes)).unwrap_or(usize::MAX)
             }
-            TruncationPolicy::Tokens(tokens) => *tokens,
+            TruncationPolicy::Tokens(tokens, _) => *tokens,
         }
     }
 
@@ -56,7 +70,7 @@ impl TruncationPolicy {
     pub fn byte_budget(&self) -> usize {
         match self {
             TruncationPolicy::Bytes(bytes) => *bytes,
-            TruncationPolicy::Tokens(tokens) => approx_bytes_for_tokens(*tokens),
+            TruncationPolicy::Tokens(tokens, _) => approx_bytes_for_tokens(*tokens),
         }
     }
 }
@@ -69,15 +83,15 @@ impl std::ops::Mul<f64> for TruncationPolicy {
             TruncationPolicy::Bytes(bytes) => {
                 TruncationPolicy::Bytes((bytes as f64 * multiplier).ceil() as usize)
             }
-            TruncationPolicy::Tokens(tokens) => {
-                TruncationPolicy::Tokens((tokens as f64 * multiplier).ceil() as usize)
+            TruncationPolicy::Tokens(tokens, tokenizer) => {
+                TruncationPolicy::Tokens((tokens as f64 * multiplier).ceil() as usize, tokenizer)
             }
         }
     }
 }
 
 pub(crate) fn formatted_truncate_text(content: &str, policy: TruncationPolicy) -> String {
-    if content.len() <= policy.byte_budget() {
+    if fits_budget(content, policy) {
         return content.to_string();
     }
     let total_lines = content.lines().count();
@@ -88,12 +102,25 @@ pub(crate) fn formatted_truncate_text(content: &str, policy: TruncationPolicy) -
 pub(crate) fn truncate_text(content: &str, policy: TruncationPolicy) -> String {
     match policy {
         TruncationPolicy::Bytes(_) => truncate_with_byte_estimate(content, policy),
-        TruncationPolicy::Tokens(_) => {
+        TruncationPolicy::Tokens(..) => {
             let (truncated, _) = truncate_with_token_budget(content, policy);
             truncated
         }
     }
 }
+
+/// Whether `content` is returned unchanged when truncated for `policy`.
+pub(crate) fn fits_budget(content: &str, policy: TruncationPolicy) -> bool {
+    match policy {
+        TruncationPolicy::Bytes(bytes) => content.len() <= bytes,
+        // A token never covers less than a byte, so short content fits without counting.
+        TruncationPolicy::Tokens(tokens, tokenizer) if tokenizer.is_exact() => {
+            content.len() <= tokens || tokenizer.count(content) <= tokens
+        }
+        TruncationPolicy::Tokens(tokens, _) => content.len() <= approx_bytes_for_tokens(tokens),
+    }
+}
+
 /// Globally truncate function output items to fit within the given
 /// truncation policy's budget, preserving as many text/image items as
 /// possible and appending a summary for any omitted text items.
@@ -104,7 +131,7 @@ pub(crate) fn truncate_function_output_items_with_policy(
     let mut out: Vec<FunctionCallOutputContentItem> = Vec::with_capacity(items.len());
     let mut remaining_budget = match policy {
         TruncationPolicy::Bytes(_) => policy.byte_budget(),
-        TruncationPolicy::Tokens(_) => policy.token_budget(),
+        TruncationPolicy::Tokens(..) => policy.token_budget(),
     };
     let mut omitted_text_items = 0usize;
 
@@ -118,7 +145,7 @@ pub(crate) fn truncate_function_output_items_with_policy(
 
                 let cost = match policy {
                     TruncationPolicy::Bytes(_) => text.len(),
-                    TruncationPolicy::Tokens(_) => approx_token_count(text),
+                    TruncationPolicy::Tokens(_, tokenizer) => tokenizer.count(text),
                 };
 
                 if cost <= remaining_budget {
@@ -127,7 +154,9 @@ pub(crate) fn truncate_function_output_items_with_policy(
                 } else {
                     let snippet_policy = match policy {
                         TruncationPolicy::Bytes(_) => TruncationPolicy::Bytes(remaining_budget),
-                        TruncationPolicy::Tokens(_) => TruncationPolicy::Tokens(remaining_budget),
+                        TruncationPolicy::Tokens(_, tokenizer) => {
+                            TruncationPolicy::Tokens(remaining_budget, tokenizer)
+                        }
                     };
                     let snippet = truncate_text(text, snippet_policy);
                     if snippet.is_empty() {
@@ -165,10 +194,14 @@ fn truncate_with_token_budget(s: &str, policy: TruncationPolicy) -> (String, Opt
     }
     let max_tokens = policy.token_budget();
 
-    let byte_len = s.len();
-    if max_tokens > 0 && byte_len <= approx_bytes_for_tokens(max_tokens) {
+    if max_tokens > 0 && fits_budget(s, policy) {
         return (s.to_string(), None);
     }
+    if let TruncationPolicy::Tokens(_, tokenizer) = policy
+        && tokenizer.is_exact()
+    {
+        return truncate_with_tokenizer(s, max_tokens, tokenizer);
+    }
 
     let truncated = truncate_with_byte_estimate(s, policy);
     let approx_total_usize = approx_token_count(s);
@@ -180,6 +213,28 @@ fn truncate_with_token_budget(s: &str, policy: TruncationPolicy) -> (String, Opt
     }
 }
 
+/// Truncate the middle of `s` on token boundaries counted by `tokenizer`.
+fn truncate_with_tokenizer(
+    s: &str,
+    max_tokens: usize,
+    tokenizer: Tokenizer,
+) -> (String, Option<u64>) {
+    let total = u64::try_from(tokenizer.count(s)).unwrap_or(u64::MAX);
+    let (left_budget, right_budget) = split_budget(max_tokens);
+    let prefix_end = tokenizer.prefix_len(s, left_budget);
+    let suffix_start = tokenizer.suffix_start(s, right_budget).max(prefix_end);
+    if prefix_end == suffix_start && max_tokens > 0 {
+        return (s.to_string(), None);
+    }
+
+    let removed = tokenizer.count(&s[prefix_end..suffix_start]);
+    let marker = format!("…{removed} tokens truncated…");
+    (
+        assemble_truncated_output(&s[..prefix_end], &s[suffix_start..], &marker),
+        Some(total),
+    )
+}
+
 /// Truncate a string using a byte budget derived from the token budget, without
 /// performing any real tokenization. This keeps the logic purely byte-based and
 /// uses a bytes placeholder in the truncated output.
@@ -260,7 +315,7 @@ fn split_string(s: &str, beginning_bytes: usize, end_bytes: usize) -> (usize, &s
 
 fn format_truncation_marker(policy: TruncationPolicy, removed_count: u64) -> String {
     match policy {
-        TruncationPolicy::Tokens(_) => format!("…{removed_count} tokens truncated…"),
+        TruncationPolicy::Tokens(..) => format!("…{removed_count} tokens truncated…"),
         TruncationPolicy::Bytes(_) => format!("…{removed_count} chars truncated…"),
     }
 }
@@ -276,7 +331,7 @@ fn removed_units_for_source(
     removed_chars: usize,
 ) -> u64 {
     match policy {
-        TruncationPolicy::Tokens(_) => approx_tokens_from_byte_count(removed_bytes),
+        TruncationPolicy::Tokens(..) => approx_tokens_from_byte_count(removed_bytes),
         TruncationPolicy::Bytes(_) => u64::try_from(removed_chars).unwrap_or(u64::MAX),
     }
 }
@@ -289,14 +344,8 @@ fn assemble_truncated_output(prefix: &str, suffix: &str, marker: &str) -> String
     out
 }
 
-pub(crate) fn approx_token_count(text: &str) -> usize {
-    let len = text.len();
-    len.saturating_add(APPROX_BYTES_PER_TOKEN.saturating_sub(1)) / APPROX_BYTES_PER_TOKEN
-}
-
-pub(crate) fn approx_bytes_for_tokens(tokens: usize) -> usize {
-    tokens.saturating_mul(APPROX_BYTES_PER_TOKEN)
-}
+pub(crate) use codex_utils_tokenizer::approx_bytes_for_tokens;
+pub(crate) use codex_utils_tokenizer::approx_token_count;
 
 pub(crate) fn approx_tokens_from_byte_count(bytes: usize) -> u64 {
     let bytes_u64 = bytes as u64;
@@ -315,6 +364,7 @@ mod tests {
     use super::truncate_text;
     use super::truncate_with_token_budget;
     use codex_protocol::models::FunctionCallOutputContentItem;
+    use codex_utils_tokenizer::Tokenizer;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -368,7 +418,7 @@ mod tests {
 
         assert_eq!(
             "Total output lines: 1\n\nex…3 tokens truncated…ut",
-            formatted_truncate_text(content, TruncationPolicy::Tokens(1)),
+            formatted_truncate_text(content, TruncationPolicy::tokens(1)),
         );
     }
 
@@ -378,7 +428,7 @@ mod tests {
 
         assert_eq!(
             content,
-            formatted_truncate_text(content, TruncationPolicy::Tokens(10)),
+            formatted_truncate_text(content, TruncationPolicy::tokens(10)),
         );
     }
 
@@ -398,7 +448,7 @@ mod tests {
 
         assert_eq!(
             "Total output lines: 1\n\nthis is an…10 tokens truncated… truncated",
-            formatted_truncate_text(content, TruncationPolicy::Tokens(5)),
+            formatted_truncate_text(content, TruncationPolicy::tokens(5)),
         );
     }
 
@@ -430,7 +480,7 @@ mod tests {
 
         assert_eq!(
             "Total output lines: 2\n\nthis is an example o…11 tokens truncated…also some other line",
-            formatted_truncate_text(content, TruncationPolicy::Tokens(10)),
+            formatted_truncate_text(content, TruncationPolicy::tokens(10)),
         );
     }
 
@@ -438,7 +488,7 @@ mod tests {
     fn truncate_with_token_budget_returns_original_when_under_limit() {
         let s = "short output";
         let limit = 100;
-        let (out, original) = truncate_with_token_budget(s, TruncationPolicy::Tokens(limit));
+        let (out, original) = truncate_with_token_budget(s, TruncationPolicy::tokens(limit));
         assert_eq!(out, s);
         assert_eq!(original, None);
     }
@@ -446,7 +496,7 @@ mod tests {
     #[test]
     fn truncate_with_token_budget_reports_truncation_at_zero_limit() {
         let s = "abcdef";
-        let (out, original) = truncate_with_token_budget(s, TruncationPolicy::Tokens(0));
+        let (out, original) = truncate_with_token_budget(s, TruncationPolicy::tokens(0));
         assert_eq!(out, "…2 tokens truncated…");
         assert_eq!(original, Some(2));
     }
@@ -454,11 +504,28 @@ mod tests {
     #[test]
     fn truncate_middle_tokens_handles_utf8_content() {
         let s = "😀😀😀😀😀😀😀😀😀😀\nsecond line with text\n";
-        let (out, tokens) = truncate_with_token_budget(s, TruncationPolicy::Tokens(8));
+        let (out, tokens) = truncate_with_token_budget(s, TruncationPolicy::tokens(8));
         assert_eq!(out, "😀😀😀😀…8 tokens truncated… line with text\n");
         assert_eq!(tokens, Some(16));
     }
 
+    #[test]
+    fn truncate_tokens_with_exact_tokenizer_keeps_half_the_budget_per_side() {
+        let tokenizer = Tokenizer::O200kBase;
+        let content = "alpha beta 数据库 gamma\n".repeat(40);
+        let policy = TruncationPolicy::tokens(10).with_tokenizer(tokenizer);
+
+        let (out, total) = truncate_with_token_budget(&content, policy);
+
+        let (head, rest) = out.split_once('…').expect("marker start");
+        let (marker, tail) = rest.split_once('…').expect("marker end");
+        assert!(tokenizer.count(head) <= 5, "head too long: {head:?}");
+        assert!(tokenizer.count(tail) <= 5, "tail too long: {tail:?}");
+        let removed = tokenizer.count(&content[head.len()..content.len() - tail.len()]);
+        assert_eq!(marker, format!("{removed} tokens truncated"));
+        assert_eq!(total, u64::try_from(tokenizer.count(&content)).ok());
+    }
+
     #[test]
     fn truncate_middle_bytes_handles_utf8_content() {
         let s = "😀😀😀😀😀😀😀😀😀😀\nsecond line with text\n";
@@ -490,7 +557,7 @@ mod tests {
         ];
 
         let output =
-            truncate_function_output_items_with_policy(&items, TruncationPolicy::Tokens(limit));
+            truncate_function_output_items_with_policy(&items, TruncationPolicy::tokens(limit));
 
         // Expect: t1 (full), t2 (full), image, t3 (truncated), summary mentioning 2 omitted.
         assert_eq!(output.len(), 5);
@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
@@ -219,6 +220,16 @@ impl TurnDiffTracker {
         if s.len() == 40 { Some(s) } else { None }
     }
 
+    /// Every path touched by apply_patch during this turn, including both ends of a rename.
+    pub fn changed_paths(&self) -> BTreeSet<PathBuf> {
+        self.baseline_file_info
+            .values()
+            .map(|info| &info.path)
+            .chain(self.temp_name_to_current_path.values())
+            .cloned()
+            .collect()
+    }
+
     /// Recompute the aggregated unified diff by comparing all of the in-memory snapshots that were
     /// collected before the first time they were touched by apply_patch during this turn with
     /// the current repo state.
@@ -645,6 +656,7 @@ index {left_oid}..{right_oid}
             )
         };
         assert_eq!(out, expected);
+        assert_eq!(acc.changed_paths(), BTreeSet::from([src, dest]));
     }
 
     #[test]
@@ -16,6 +16,18 @@ pub(crate) enum UnifiedExecError {
     StdinClosed,
     #[error("missing command line for unified exec request")]
     MissingCommandLine,
+    #[error("Unknown service {name}")]
+    UnknownService { name: String },
+    #[error("service {name} is already running; restart or stop it first")]
+    ServiceAlreadyRunning { name: String },
+    #[error("service {name} is still starting; check its status or stop it first")]
+    ServiceStarting { name: String },
+    #[error("service {name} was stopped before it finished starting")]
+    ServiceStopped { name: String },
+    #[error(
+        "screen output is not available for this session; rerun exec_command with tty=true and screen=true"
+    )]
+    ScreenUnavailable,
     #[error("Command denied by sandbox: {message}")]
     SandboxDenied {
         message: String,
@@ -1,5 +1,6 @@
 use crate::unified_exec::UNIFIED_EXEC_OUTPUT_MAX_BYTES;
 use std::collections::VecDeque;
+use std::ops::Range;
 
 /// A capped buffer that preserves a stable prefix ("head") and suffix ("tail"),
 /// dropping the middle once it exceeds the configured maximum. The buffer is
@@ -15,6 +16,10 @@ pub(crate) struct HeadTailBuffer {
     head_bytes: usize,
     tail_bytes: usize,
     omitted_bytes: usize,
+    /// Bytes ever pushed, including omitted ones.
+    pushed_bytes: u64,
+    /// `pushed_bytes` as of the last drain.
+    drained_through: u64,
 }
 
 impl Default for HeadTailBuffer {
@@ -40,6 +45,8 @@ impl HeadTailBuffer {
             head_bytes: 0,
             tail_bytes: 0,
             omitted_bytes: 0,
+            pushed_bytes: 0,
+            drained_through: 0,
         }
     }
 
@@ -50,19 +57,24 @@ impl HeadTailBuffer {
         self.head_bytes.saturating_add(self.tail_bytes)
     }
 
-    // Used for tests.
-    #[allow(dead_code)]
     /// Total bytes that were dropped from the middle due to the size cap.
     pub(crate) fn omitted_bytes(&self) -> usize {
         self.omitted_bytes
     }
 
+    /// Stream offsets of the bytes pushed since the last drain, whether they
+    /// were retained or omitted.
+    pub(crate) fn pending_range(&self) -> Range<u64> {
+        self.drained_through..self.pushed_bytes
+    }
+
     /// Append a chunk of bytes to the buffer.
     ///
     /// Bytes are first added to the head until the head budget is full; any
     /// remaining bytes are added to the tail, with older tail bytes being
     /// dropped to preserve the tail budget.
     pub(crate) fn push_chunk(&mut self, chunk: Vec<u8>) {
+        self.pushed_bytes = self.pushed_bytes.saturating_add(chunk.len() as u64);
         if self.max_bytes == 0 {
             self.omitted_bytes = self.omitted_bytes.saturating_add(chunk.len());
             return;
@@ -126,6 +138,7 @@ impl HeadTailBuffer {
         self.head_bytes = 0;
         self.tail_bytes = 0;
         self.omitted_bytes = 0;
+        self.drained_through = self.pushed_bytes;
         out
     }
 
@@ -226,9 +239,11 @@ mod tests {
         let mut buf = HeadTailBuffer::new(10);
         buf.push_chunk(b"0123456789".to_vec());
         buf.push_chunk(b"ab".to_vec());
+        assert_eq!(buf.pending_range(), 0..12);
 
         let drained = buf.drain_chunks();
         assert!(!drained.is_empty());
+        assert_eq!(buf.pending_range(), 12..12);
 
         assert_eq!(buf.retained_bytes(), 0);
         assert_eq!(buf.omitted_bytes(), 0);
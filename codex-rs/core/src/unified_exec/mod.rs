@@ -29,25 +29,36 @@ use std::time::Duration;
 
 use rand::Rng;
 use rand::rng;
+use regex::Regex;
 use tokio::sync::Mutex;
 
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::sandboxing::SandboxPermissions;
+use crate::spill::SpoolSlice;
 
 mod async_watcher;
 mod errors;
 mod head_tail_buffer;
 mod process;
 mod process_manager;
+mod screen;
+mod service;
+mod wait_for;
 
 pub(crate) use errors::UnifiedExecError;
 pub(crate) use process::UnifiedExecProcess;
+pub(crate) use service::ReadinessProbe;
+pub(crate) use service::ServiceManager;
+pub(crate) use service::ServiceSpec;
+pub(crate) use service::ServiceStatus;
 
 pub(crate) const MIN_YIELD_TIME_MS: u64 = 250;
 // Minimum yield time for an empty `write_stdin`.
 pub(crate) const MIN_EMPTY_YIELD_TIME_MS: u64 = 5_000;
 pub(crate) const MAX_YIELD_TIME_MS: u64 = 30_000;
+// Calls with a wait condition return early, so they may block for longer.
+pub(crate) const MAX_WAIT_CONDITION_TIME_MS: u64 = 300_000;
 pub(crate) const DEFAULT_MAX_OUTPUT_TOKENS: usize = 10_000;
 pub(crate) const UNIFIED_EXEC_OUTPUT_MAX_BYTES: usize = 1024 * 1024; // 1 MiB
 pub(crate) const UNIFIED_EXEC_OUTPUT_MAX_TOKENS: usize = UNIFIED_EXEC_OUTPUT_MAX_BYTES / 4;
@@ -72,14 +83,52 @@ impl UnifiedExecContext {
     }
 }
 
+/// Conditions that let a unified exec call return before its yield time
+/// elapses. Without one, a call collects output for the full yield time or
+/// until the process exits.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WaitCondition {
+    /// Return as soon as the output collected by this call matches.
+    pub pattern: Option<Regex>,
+    /// Keep collecting until the process exits.
+    pub exit: bool,
+}
+
+impl WaitCondition {
+    pub(crate) fn is_set(&self) -> bool {
+        self.pattern.is_some() || self.exit
+    }
+}
+
+/// Which condition ended a unified exec call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WaitOutcome {
+    PatternMatched,
+    ProcessExited,
+    TimedOut,
+}
+
+impl WaitOutcome {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            WaitOutcome::PatternMatched => "pattern matched",
+            WaitOutcome::ProcessExited => "process exited",
+            WaitOutcome::TimedOut => "timed out",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct ExecCommandRequest {
     pub command: Vec<String>,
     pub process_id: String,
     pub yield_time_ms: u64,
+    pub wait: WaitCondition,
     pub max_output_tokens: Option<usize>,
     pub workdir: Option<PathBuf>,
     pub tty: bool,
+    /// Emulate a terminal for this process and return the rendered screen.
+    pub screen: bool,
     pub sandbox_permissions: SandboxPermissions,
     pub justification: Option<String>,
     pub prefix_rule: Option<Vec<String>>,
@@ -90,7 +139,11 @@ pub(crate) struct WriteStdinRequest<'a> {
     pub process_id: &'a str,
     pub input: &'a str,
     pub yield_time_ms: u64,
+    pub wait: WaitCondition,
     pub max_output_tokens: Option<usize>,
+    /// Override whether the rendered screen is returned; defaults to the mode
+    /// chosen when the process was started.
+    pub screen: Option<bool>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -101,10 +154,17 @@ pub(crate) struct UnifiedExecResponse {
     pub output: String,
     /// Raw bytes returned for this unified exec call before any truncation.
     pub raw_output: Vec<u8>,
+    /// Where the complete output of this call can be read back from, set
+    /// only when `output` or `raw_output` is missing part of it.
+    pub full_output: Option<SpoolSlice>,
     pub process_id: Option<String>,
     pub exit_code: Option<i32>,
     pub original_token_count: Option<usize>,
     pub session_command: Option<Vec<String>>,
+    /// Set when the call asked for a wait condition.
+    pub wait_outcome: Option<WaitOutcome>,
+    /// Rendered terminal screen, returned instead of the raw output delta.
+    pub screen: Option<String>,
 }
 
 #[derive(Default)]
@@ -145,6 +205,14 @@ pub(crate) fn clamp_yield_time(yield_time_ms: u64) -> u64 {
     yield_time_ms.clamp(MIN_YIELD_TIME_MS, MAX_YIELD_TIME_MS)
 }
 
+pub(crate) fn clamp_wait_time(yield_time_ms: u64, wait: &WaitCondition) -> u64 {
+    if wait.is_set() {
+        yield_time_ms.clamp(MIN_YIELD_TIME_MS, MAX_WAIT_CONDITION_TIME_MS)
+    } else {
+        clamp_yield_time(yield_time_ms)
+    }
+}
+
 pub(crate) fn resolve_max_tokens(max_tokens: Option<usize>) -> usize {
     max_tokens.unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS)
 }
@@ -201,9 +269,11 @@ mod tests {
                     command: vec!["bash".to_string(), "-lc".to_string(), cmd.to_string()],
                     process_id,
                     yield_time_ms,
+                    wait: WaitCondition::default(),
                     max_output_tokens: None,
                     workdir: None,
                     tty: true,
+                    screen: false,
                     sandbox_permissions: SandboxPermissions::UseDefault,
                     justification: None,
                     prefix_rule: None,
@@ -226,7 +296,9 @@ mod tests {
                 process_id,
                 input,
                 yield_time_ms,
+                wait: WaitCondition::default(),
                 max_output_tokens: None,
+                screen: None,
             })
             .await
     }
@@ -399,6 +471,81 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn wait_for_pattern_returns_before_yield_time() -> anyhow::Result<()> {
+        skip_if_sandbox!(Ok(()));
+
+        let (session, turn) = test_session_and_turn().await;
+
+        let open_shell = exec_command(&session, &turn, "bash -i", 2_500).await?;
+        let process_id = open_shell
+            .process_id
+            .as_ref()
+            .expect("expected process id")
+            .as_str();
+
+        // The PTY echoes the input, so match on the expanded value only.
+        let response = session
+            .services
+            .unified_exec_manager
+            .write_stdin(WriteStdinRequest {
+                process_id,
+                input: "sleep 1 && echo ready-$((20 + 22))\n",
+                yield_time_ms: 20_000,
+                wait: WaitCondition {
+                    pattern: Some(Regex::new("ready-42")?),
+                    exit: false,
+                },
+                max_output_tokens: None,
+                screen: None,
+            })
+            .await?;
+
+        assert_eq!(response.wait_outcome, Some(WaitOutcome::PatternMatched));
+        assert!(response.output.contains("ready-42"));
+        assert!(
+            response.wall_time < Duration::from_secs(10),
+            "wait_for should return before the yield time elapses"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn wait_for_exit_reports_exit() -> anyhow::Result<()> {
+        skip_if_sandbox!(Ok(()));
+
+        let (session, turn) = test_session_and_turn().await;
+
+        let open_shell = exec_command(&session, &turn, "bash -i", 2_500).await?;
+        let process_id = open_shell
+            .process_id
+            .as_ref()
+            .expect("expected process id")
+            .as_str();
+
+        let response = session
+            .services
+            .unified_exec_manager
+            .write_stdin(WriteStdinRequest {
+                process_id,
+                input: "exit\n",
+                yield_time_ms: 20_000,
+                wait: WaitCondition {
+                    pattern: None,
+                    exit: true,
+                },
+                max_output_tokens: None,
+                screen: None,
+            })
+            .await?;
+
+        assert_eq!(response.wait_outcome, Some(WaitOutcome::ProcessExited));
+        assert!(response.process_id.is_none());
+
+        Ok(())
+    }
+
     #[tokio::test]
     #[ignore] // Ignored while we have a better way to test this.
     async fn requests_with_large_timeout_are_capped() -> anyhow::Result<()> {
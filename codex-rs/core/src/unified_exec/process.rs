@@ -1,6 +1,7 @@
 #![allow(clippy::module_inception)]
 
 use std::sync::Arc;
+use std::sync::OnceLock;
 use tokio::sync::Mutex;
 use tokio::sync::Notify;
 use tokio::sync::mpsc;
@@ -13,14 +14,17 @@ use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
 use crate::exec::StreamOutput;
 use crate::exec::is_likely_sandbox_denied;
+use crate::spill::OutputSpool;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::formatted_truncate_text;
 use codex_utils_pty::ExecCommandSession;
 use codex_utils_pty::SpawnedPty;
+use codex_utils_tokenizer::Tokenizer;
 
 use super::UNIFIED_EXEC_OUTPUT_MAX_TOKENS;
 use super::UnifiedExecError;
 use super::head_tail_buffer::HeadTailBuffer;
+use super::screen::ScreenHandle;
 
 pub(crate) type OutputBuffer = Arc<Mutex<HeadTailBuffer>>;
 pub(crate) struct OutputHandles {
@@ -38,6 +42,10 @@ pub(crate) struct UnifiedExecProcess {
     output_drained: Arc<Notify>,
     output_task: JoinHandle<()>,
     sandbox_type: SandboxType,
+    screen: ScreenHandle,
+    spool: Arc<OnceLock<Arc<OutputSpool>>>,
+    /// Counts tokens for the model of the turn that started the process.
+    tokenizer: Tokenizer,
 }
 
 impl UnifiedExecProcess {
@@ -45,6 +53,7 @@ impl UnifiedExecProcess {
         process_handle: ExecCommandSession,
         initial_output_rx: tokio::sync::broadcast::Receiver<Vec<u8>>,
         sandbox_type: SandboxType,
+        tokenizer: Tokenizer,
     ) -> Self {
         let output_buffer = Arc::new(Mutex::new(HeadTailBuffer::default()));
         let output_notify = Arc::new(Notify::new());
@@ -53,11 +62,23 @@ impl UnifiedExecProcess {
         let mut receiver = initial_output_rx;
         let buffer_clone = Arc::clone(&output_buffer);
         let notify_clone = Arc::clone(&output_notify);
+        let screen = ScreenHandle::default();
+        let screen_clone = screen.clone();
+        let spool: Arc<OnceLock<Arc<OutputSpool>>> = Arc::new(OnceLock::new());
+        let spool_clone = Arc::clone(&spool);
         let output_task = tokio::spawn(async move {
             loop {
                 match receiver.recv().await {
                     Ok(chunk) => {
                         let mut guard = buffer_clone.lock().await;
+                        // Spool before buffering so every byte a poll drains is
+                        // already on disk if that poll needs to spill.
+                        if let Some(spool) = spool_clone.get() {
+                            spool.append(&chunk).await;
+                        }
+                        // Feed the emulator under the buffer lock so enabling it
+                        // never misses or replays a chunk.
+                        screen_clone.process(&chunk);
                         guard.push_chunk(chunk);
                         drop(guard);
                         notify_clone.notify_waiters();
@@ -76,6 +97,9 @@ impl UnifiedExecProcess {
             output_drained,
             output_task,
             sandbox_type,
+            screen,
+            spool,
+            tokenizer,
         }
     }
 
@@ -91,6 +115,40 @@ impl UnifiedExecProcess {
         }
     }
 
+    pub(super) fn screen(&self) -> ScreenHandle {
+        self.screen.clone()
+    }
+
+    pub(super) fn tokenizer(&self) -> Tokenizer {
+        self.tokenizer
+    }
+
+    /// Start maintaining a rendered screen for this process, seeded with any
+    /// output that has not been collected yet.
+    pub(super) async fn enable_screen(&self) {
+        let guard = self.output_buffer.lock().await;
+        let pending = guard.snapshot_chunks();
+        self.screen.enable(pending.iter().map(Vec::as_slice));
+    }
+
+    /// Start streaming this process's output to `spool`, seeded with the
+    /// output buffered so far. Skipped if the buffer already dropped bytes,
+    /// since the spool could not line up with the stream offsets.
+    pub(super) async fn enable_spool(&self, spool: OutputSpool) {
+        let guard = self.output_buffer.lock().await;
+        if guard.omitted_bytes() > 0 || guard.pending_range().start > 0 {
+            return;
+        }
+        for chunk in guard.snapshot_chunks() {
+            spool.append(&chunk).await;
+        }
+        let _ = self.spool.set(Arc::new(spool));
+    }
+
+    pub(super) fn spool(&self) -> Option<Arc<OutputSpool>> {
+        self.spool.get().cloned()
+    }
+
     pub(super) fn output_receiver(&self) -> tokio::sync::broadcast::Receiver<Vec<u8>> {
         self.process_handle.output_receiver()
     }
@@ -117,6 +175,11 @@ impl UnifiedExecProcess {
         self.output_task.abort();
     }
 
+    /// Retained output that has not been drained by a poll.
+    pub(super) async fn output_snapshot(&self) -> Vec<u8> {
+        self.output_buffer.lock().await.to_bytes()
+    }
+
     async fn snapshot_output(&self) -> Vec<Vec<u8>> {
         let guard = self.output_buffer.lock().await;
         guard.snapshot_chunks()
@@ -161,7 +224,8 @@ impl UnifiedExecProcess {
         if is_likely_sandbox_denied(sandbox_type, &exec_output) {
             let snippet = formatted_truncate_text(
                 text,
-                TruncationPolicy::Tokens(UNIFIED_EXEC_OUTPUT_MAX_TOKENS),
+                TruncationPolicy::tokens(UNIFIED_EXEC_OUTPUT_MAX_TOKENS)
+                    .with_tokenizer(self.tokenizer),
             );
             let message = if snippet.is_empty() {
                 format!("Process exited with code {exit_code}")
@@ -176,13 +240,14 @@ impl UnifiedExecProcess {
     pub(super) async fn from_spawned(
         spawned: SpawnedPty,
         sandbox_type: SandboxType,
+        tokenizer: Tokenizer,
     ) -> Result<Self, UnifiedExecError> {
         let SpawnedPty {
             session: process_handle,
             output_rx,
             mut exit_rx,
         } = spawned;
-        let managed = Self::new(process_handle, output_rx, sandbox_type);
+        let managed = Self::new(process_handle, output_rx, sandbox_type, tokenizer);
 
         let exit_ready = matches!(exit_rx.try_recv(), Ok(_) | Err(TryRecvError::Closed));
 
@@ -2,6 +2,7 @@ use rand::Rng;
 use std::cmp::Reverse;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::ops::Range;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Notify;
@@ -14,6 +15,8 @@ use crate::exec_env::create_env;
 use crate::exec_policy::ExecApprovalRequest;
 use crate::protocol::ExecCommandSource;
 use crate::sandboxing::ExecEnv;
+use crate::spill::OutputSpool;
+use crate::spill::SpoolSlice;
 use crate::tools::events::ToolEmitter;
 use crate::tools::events::ToolEventCtx;
 use crate::tools::events::ToolEventStage;
@@ -22,11 +25,9 @@ use crate::tools::runtimes::unified_exec::UnifiedExecRequest as UnifiedExecToolR
 use crate::tools::runtimes::unified_exec::UnifiedExecRuntime;
 use crate::tools::sandboxing::ToolCtx;
 use crate::truncate::TruncationPolicy;
-use crate::truncate::approx_token_count;
 use crate::truncate::formatted_truncate_text;
 use crate::unified_exec::ExecCommandRequest;
 use crate::unified_exec::MAX_UNIFIED_EXEC_PROCESSES;
-use crate::unified_exec::MAX_YIELD_TIME_MS;
 use crate::unified_exec::MIN_EMPTY_YIELD_TIME_MS;
 use crate::unified_exec::ProcessEntry;
 use crate::unified_exec::ProcessStore;
@@ -35,17 +36,21 @@ use crate::unified_exec::UnifiedExecError;
 use crate::unified_exec::UnifiedExecProcessManager;
 use crate::unified_exec::UnifiedExecResponse;
 use crate::unified_exec::WARNING_UNIFIED_EXEC_PROCESSES;
+use crate::unified_exec::WaitOutcome;
 use crate::unified_exec::WriteStdinRequest;
 use crate::unified_exec::async_watcher::emit_exec_end_for_unified_exec;
 use crate::unified_exec::async_watcher::spawn_exit_watcher;
 use crate::unified_exec::async_watcher::start_streaming_output;
-use crate::unified_exec::clamp_yield_time;
+use crate::unified_exec::clamp_wait_time;
 use crate::unified_exec::generate_chunk_id;
 use crate::unified_exec::head_tail_buffer::HeadTailBuffer;
 use crate::unified_exec::process::OutputBuffer;
 use crate::unified_exec::process::OutputHandles;
 use crate::unified_exec::process::UnifiedExecProcess;
 use crate::unified_exec::resolve_max_tokens;
+use crate::unified_exec::screen::ScreenHandle;
+use crate::unified_exec::wait_for::WaitForScanner;
+use codex_utils_tokenizer::Tokenizer;
 
 const UNIFIED_EXEC_ENV: [(&str, &str); 10] = [
     ("NO_COLOR", "1"),
@@ -60,6 +65,10 @@ const UNIFIED_EXEC_ENV: [(&str, &str); 10] = [
     ("CODEX_CI", "1"),
 ];
 
+// Full-screen programs refuse to draw on a dumb terminal, so sessions with an
+// emulated screen advertise a capable one instead.
+const UNIFIED_EXEC_SCREEN_TERM: &str = "xterm-256color";
+
 fn apply_unified_exec_env(mut env: HashMap<String, String>) -> HashMap<String, String> {
     for (key, value) in UNIFIED_EXEC_ENV {
         env.insert(key.to_string(), value.to_string());
@@ -75,6 +84,9 @@ struct PreparedProcessHandles {
     command: Vec<String>,
     process_id: String,
     tty: bool,
+    screen: ScreenHandle,
+    spool: Option<Arc<OutputSpool>>,
+    tokenizer: Tokenizer,
 }
 
 impl UnifiedExecProcessManager {
@@ -133,6 +145,18 @@ impl UnifiedExecProcessManager {
                 return Err(err);
             }
         };
+        if request.screen {
+            process.enable_screen().await;
+        }
+        if let Some(spool) = context
+            .session
+            .services
+            .output_spill
+            .as_ref()
+            .and_then(|store| store.spool_for(&request.process_id))
+        {
+            process.enable_spool(spool).await;
+        }
 
         let transcript = Arc::new(tokio::sync::Mutex::new(HeadTailBuffer::default()));
         let event_ctx = ToolEventCtx::new(
@@ -152,7 +176,7 @@ impl UnifiedExecProcessManager {
         start_streaming_output(&process, context, Arc::clone(&transcript));
 
         let max_tokens = resolve_max_tokens(request.max_output_tokens);
-        let yield_time_ms = clamp_yield_time(request.yield_time_ms);
+        let yield_time_ms = clamp_wait_time(request.yield_time_ms, &request.wait);
 
         let start = Instant::now();
         // For the initial exec_command call, we both stream output to events
@@ -164,17 +188,29 @@ impl UnifiedExecProcessManager {
             cancellation_token,
         } = process.output_handles();
         let deadline = start + Duration::from_millis(yield_time_ms);
-        let collected = Self::collect_output_until_deadline(
+        let (collected, consumed, wait_outcome) = Self::collect_output_until_deadline(
             &output_buffer,
             &output_notify,
             &cancellation_token,
             deadline,
+            request
+                .wait
+                .pattern
+                .as_ref()
+                .map(|pattern| WaitForScanner::new(pattern, "")),
         )
         .await;
         let wall_time = Instant::now().saturating_duration_since(start);
 
         let text = String::from_utf8_lossy(&collected).to_string();
-        let output = formatted_truncate_text(&text, TruncationPolicy::Tokens(max_tokens));
+        let tokenizer = context.turn.tokenizer;
+        let output = formatted_truncate_text(
+            &text,
+            TruncationPolicy::tokens(max_tokens).with_tokenizer(tokenizer),
+        );
+        let full_output =
+            full_output_slice(process.spool(), consumed, collected.len(), output != text);
+        let screen = process.screen().render();
         let exit_code = process.exit_code();
         let has_exited = process.has_exited() || exit_code.is_some();
         let chunk_id = generate_chunk_id();
@@ -218,13 +254,14 @@ impl UnifiedExecProcessManager {
             .await;
         };
 
-        let original_token_count = approx_token_count(&text);
+        let original_token_count = tokenizer.count(&text);
         let response = UnifiedExecResponse {
             event_call_id: context.call_id.clone(),
             chunk_id,
             wall_time,
             output,
             raw_output: collected,
+            full_output,
             process_id: if has_exited {
                 None
             } else {
@@ -233,6 +270,8 @@ impl UnifiedExecProcessManager {
             exit_code,
             original_token_count: Some(original_token_count),
             session_command: Some(request.command.clone()),
+            wait_outcome: request.wait.is_set().then_some(wait_outcome),
+            screen,
         };
 
         Ok(response)
@@ -252,9 +291,16 @@ impl UnifiedExecProcessManager {
             command: session_command,
             process_id,
             tty,
-            ..
+            screen,
+            spool,
+            tokenizer,
         } = self.prepare_process_handles(process_id.as_str()).await?;
 
+        let return_screen = request.screen.unwrap_or_else(|| screen.is_enabled());
+        if return_screen && !screen.is_enabled() {
+            return Err(UnifiedExecError::ScreenUnavailable);
+        }
+
         if !request.input.is_empty() {
             if !tty {
                 return Err(UnifiedExecError::StdinClosed);
@@ -267,28 +313,38 @@ impl UnifiedExecProcessManager {
 
         let max_tokens = resolve_max_tokens(request.max_output_tokens);
         let yield_time_ms = {
-            let time_ms = clamp_yield_time(request.yield_time_ms);
+            let time_ms = clamp_wait_time(request.yield_time_ms, &request.wait);
             if request.input.is_empty() {
-                time_ms.clamp(MIN_EMPTY_YIELD_TIME_MS, MAX_YIELD_TIME_MS)
+                time_ms.max(MIN_EMPTY_YIELD_TIME_MS)
             } else {
                 time_ms
             }
         };
         let start = Instant::now();
         let deadline = start + Duration::from_millis(yield_time_ms);
-        let collected = Self::collect_output_until_deadline(
+        let (collected, consumed, wait_outcome) = Self::collect_output_until_deadline(
             &output_buffer,
             &output_notify,
             &cancellation_token,
             deadline,
+            request
+                .wait
+                .pattern
+                .as_ref()
+                .map(|pattern| WaitForScanner::new(pattern, request.input)),
         )
         .await;
         let wall_time = Instant::now().saturating_duration_since(start);
 
         let text = String::from_utf8_lossy(&collected).to_string();
-        let output = formatted_truncate_text(&text, TruncationPolicy::Tokens(max_tokens));
-        let original_token_count = approx_token_count(&text);
+        let output = formatted_truncate_text(
+            &text,
+            TruncationPolicy::tokens(max_tokens).with_tokenizer(tokenizer),
+        );
+        let full_output = full_output_slice(spool, consumed, collected.len(), output != text);
+        let original_token_count = tokenizer.count(&text);
         let chunk_id = generate_chunk_id();
+        let screen = if return_screen { screen.render() } else { None };
 
         // After polling, refresh_process_state tells us whether the PTY is
         // still alive or has exited and been removed from the store; we thread
@@ -318,10 +374,13 @@ impl UnifiedExecProcessManager {
             wall_time,
             output,
             raw_output: collected,
+            full_output,
             process_id,
             exit_code,
             original_token_count: Some(original_token_count),
             session_command: Some(session_command.clone()),
+            wait_outcome: request.wait.is_set().then_some(wait_outcome),
+            screen,
         };
 
         Ok(response)
@@ -380,6 +439,9 @@ impl UnifiedExecProcessManager {
             command: entry.command.clone(),
             process_id: entry.process_id.clone(),
             tty: entry.tty,
+            screen: entry.process.screen(),
+            spool: entry.process.spool(),
+            tokenizer: entry.process.tokenizer(),
         })
     }
 
@@ -447,6 +509,7 @@ impl UnifiedExecProcessManager {
         &self,
         env: &ExecEnv,
         tty: bool,
+        tokenizer: Tokenizer,
     ) -> Result<UnifiedExecProcess, UnifiedExecError> {
         let (program, args) = env
             .command
@@ -474,7 +537,7 @@ impl UnifiedExecProcessManager {
         };
         let spawned =
             spawn_result.map_err(|err| UnifiedExecError::create_process(err.to_string()))?;
-        UnifiedExecProcess::from_spawned(spawned, env.sandbox).await
+        UnifiedExecProcess::from_spawned(spawned, env.sandbox, tokenizer).await
     }
 
     pub(super) async fn open_session_with_sandbox(
@@ -483,10 +546,13 @@ impl UnifiedExecProcessManager {
         cwd: PathBuf,
         context: &UnifiedExecContext,
     ) -> Result<UnifiedExecProcess, UnifiedExecError> {
-        let env = apply_unified_exec_env(create_env(
+        let mut env = apply_unified_exec_env(create_env(
             &context.turn.shell_environment_policy,
             Some(context.session.conversation_id),
         ));
+        if request.screen {
+            env.insert("TERM".to_string(), UNIFIED_EXEC_SCREEN_TERM.to_string());
+        }
         let features = context.session.features();
         let mut orchestrator = ToolOrchestrator::new();
         let mut runtime = UnifiedExecRuntime::new(self);
@@ -530,21 +596,31 @@ impl UnifiedExecProcessManager {
             .map_err(|e| UnifiedExecError::create_process(format!("{e:?}")))
     }
 
+    /// Collect output until `deadline`, returning early once the process has
+    /// exited or the collected text matches `wait_for`. Also returns the
+    /// stream offsets consumed, which cover bytes the buffer omitted.
     pub(super) async fn collect_output_until_deadline(
         output_buffer: &OutputBuffer,
         output_notify: &Arc<Notify>,
         cancellation_token: &CancellationToken,
         deadline: Instant,
-    ) -> Vec<u8> {
+        mut wait_for: Option<WaitForScanner<'_>>,
+    ) -> (Vec<u8>, Range<u64>, WaitOutcome) {
         const POST_EXIT_OUTPUT_GRACE: Duration = Duration::from_millis(50);
 
         let mut collected: Vec<u8> = Vec::with_capacity(4096);
+        let mut consumed: Option<Range<u64>> = None;
         let mut exit_signal_received = cancellation_token.is_cancelled();
         loop {
             let drained_chunks: Vec<Vec<u8>>;
             let mut wait_for_output = None;
             {
                 let mut guard = output_buffer.lock().await;
+                let pending = guard.pending_range();
+                consumed = Some(match consumed {
+                    Some(consumed) => consumed.start..pending.end,
+                    None => pending,
+                });
                 drained_chunks = guard.drain_chunks();
                 if drained_chunks.is_empty() {
                     wait_for_output = Some(output_notify.notified());
@@ -578,17 +654,34 @@ impl UnifiedExecProcessManager {
                 continue;
             }
 
+            let mut matched = false;
             for chunk in drained_chunks {
+                if !matched && let Some(scanner) = wait_for.as_mut() {
+                    matched = scanner.scan(&chunk);
+                }
                 collected.extend_from_slice(&chunk);
             }
 
+            if matched {
+                return (
+                    collected,
+                    consumed.unwrap_or_default(),
+                    WaitOutcome::PatternMatched,
+                );
+            }
+
             exit_signal_received |= cancellation_token.is_cancelled();
             if Instant::now() >= deadline {
                 break;
             }
         }
 
-        collected
+        let outcome = if exit_signal_received {
+            WaitOutcome::ProcessExited
+        } else {
+            WaitOutcome::TimedOut
+        };
+        (collected, consumed.unwrap_or_default(), outcome)
     }
 
     fn prune_processes_if_needed(store: &mut ProcessStore) -> bool {
@@ -659,6 +752,21 @@ impl UnifiedExecProcessManager {
     }
 }
 
+/// The spooled copy of a poll's output, when the model saw less than all of
+/// it: either the response was truncated or the buffer dropped the middle.
+fn full_output_slice(
+    spool: Option<Arc<OutputSpool>>,
+    consumed: Range<u64>,
+    collected_len: usize,
+    truncated: bool,
+) -> Option<SpoolSlice> {
+    let omitted = (collected_len as u64) < consumed.end.saturating_sub(consumed.start);
+    if !truncated && !omitted {
+        return None;
+    }
+    Some(SpoolSlice::new(spool?, consumed))
+}
+
 enum ProcessStatus {
     Alive {
         exit_code: Option<i32>,
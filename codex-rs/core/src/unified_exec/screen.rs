@@ -0,0 +1,132 @@
+//! Virtual terminal for unified exec PTY sessions.
+//!
+//! Full-screen programs (`htop`, `less`, curses installers, `git add -p`) paint
+//! the terminal with cursor movement and erase sequences, so the raw byte
+//! stream is unreadable for the model. When a session opts in, every output
+//! chunk is also fed through a VT100 emulator and calls can return the
+//! rendered grid instead of the raw delta.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// Matches the size of the PTY opened by `codex_utils_pty`.
+const SCREEN_ROWS: u16 = 24;
+const SCREEN_COLS: u16 = 80;
+
+/// Shared, lazily enabled terminal emulator for one process.
+#[derive(Clone, Default)]
+pub(crate) struct ScreenHandle {
+    parser: Arc<Mutex<Option<vt100::Parser>>>,
+}
+
+impl std::fmt::Debug for ScreenHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScreenHandle")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+impl ScreenHandle {
+    /// Start emulating the terminal, replaying output that was produced
+    /// before the emulator existed.
+    pub(crate) fn enable<'a>(&self, initial_output: impl IntoIterator<Item = &'a [u8]>) {
+        let Ok(mut guard) = self.parser.lock() else {
+            return;
+        };
+        if guard.is_some() {
+            return;
+        }
+        let mut parser = vt100::Parser::new(SCREEN_ROWS, SCREEN_COLS, 0);
+        for chunk in initial_output {
+            parser.process(chunk);
+        }
+        *guard = Some(parser);
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.parser.lock().is_ok_and(|guard| guard.is_some())
+    }
+
+    pub(crate) fn process(&self, chunk: &[u8]) {
+        if let Ok(mut guard) = self.parser.lock()
+            && let Some(parser) = guard.as_mut()
+        {
+            parser.process(chunk);
+        }
+    }
+
+    /// Render the current screen as plain text, or `None` when the emulator
+    /// is not enabled for this process.
+    pub(crate) fn render(&self) -> Option<String> {
+        let guard = self.parser.lock().ok()?;
+        let parser = guard.as_ref()?;
+        Some(render_screen(parser.screen()))
+    }
+}
+
+fn render_screen(screen: &vt100::Screen) -> String {
+    let (rows, cols) = screen.size();
+    let (cursor_row, cursor_col) = screen.cursor_position();
+    let mut lines: Vec<String> = screen
+        .rows(0, cols)
+        .map(|row| row.trim_end().to_string())
+        .collect();
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+
+    let mode = if screen.alternate_screen() {
+        ", alternate screen"
+    } else {
+        ""
+    };
+    let mut rendered = format!(
+        "Screen {rows}x{cols}, cursor at row {}, column {}{mode}:",
+        cursor_row + 1,
+        cursor_col + 1
+    );
+    for line in lines {
+        rendered.push('\n');
+        rendered.push_str(&line);
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn disabled_handle_renders_nothing() {
+        let handle = ScreenHandle::default();
+        handle.process(b"hello");
+        assert_eq!(handle.render(), None);
+    }
+
+    #[test]
+    fn renders_grid_after_cursor_movement() {
+        let handle = ScreenHandle::default();
+        handle.enable([b"garbage\r\n".as_slice()]);
+        // Clear the screen, then paint two rows out of order.
+        handle.process(b"\x1b[2J\x1b[3;1Hthird row\x1b[1;1Hfirst row\x1b[1;6H");
+
+        assert_eq!(
+            handle.render().expect("screen enabled"),
+            "Screen 24x80, cursor at row 1, column 6:\nfirst row\n\nthird row"
+        );
+    }
+
+    #[test]
+    fn reports_alternate_screen() {
+        let handle = ScreenHandle::default();
+        handle.enable(std::iter::empty());
+        handle.process(b"\x1b[?1049h\x1b[Hmenu");
+
+        assert_eq!(
+            handle.render().expect("screen enabled"),
+            "Screen 24x80, cursor at row 1, column 5, alternate screen:\nmenu"
+        );
+    }
+}
@@ -0,0 +1,603 @@
+//! Named background services built on unified exec processes.
+//!
+//! Processes started with `exec_command` are terminated when the turn that
+//! started them ends. Services are long-running processes (dev servers,
+//! databases, watchers) that the agent starts under a name and that live until
+//! they are stopped or the session shuts down. Each service can carry a
+//! readiness probe so `start`/`restart` return once the service is usable
+//! rather than after a fixed delay.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use regex::Regex;
+use tokio::sync::Mutex;
+use tokio::time::Duration;
+use tokio::time::Instant;
+use url::Host;
+use url::Url;
+
+use crate::exec::ExecToolCallOutput;
+use crate::exec::StreamOutput;
+use crate::parse_command::shlex_join;
+use crate::protocol::ExecCommandSource;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::events::ToolEmitter;
+use crate::tools::events::ToolEventCtx;
+use crate::tools::events::ToolEventStage;
+use crate::unified_exec::ExecCommandRequest;
+use crate::unified_exec::UnifiedExecContext;
+use crate::unified_exec::UnifiedExecError;
+use crate::unified_exec::WaitCondition;
+use crate::unified_exec::process::UnifiedExecProcess;
+
+const READINESS_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const PORT_PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+const HTTP_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+// Give a stopped service a moment to flush its final output before the end
+// event is emitted.
+const EXIT_OUTPUT_GRACE: Duration = Duration::from_millis(100);
+
+/// How to decide that a service is ready to use.
+#[derive(Debug, Clone)]
+pub(crate) enum ReadinessProbe {
+    /// A TCP connection to this localhost port succeeds.
+    Port(u16),
+    /// A GET request to this loopback URL returns a 2xx status. Build it with
+    /// [`ReadinessProbe::http`].
+    Http(Url),
+    /// The service output matches this pattern.
+    LogPattern(Regex),
+}
+
+impl ReadinessProbe {
+    /// An HTTP probe for `url`. Probes run outside the sandbox, so only
+    /// loopback hosts are accepted.
+    pub(crate) fn http(url: &str) -> Result<Self, String> {
+        let url = Url::parse(url).map_err(|err| format!("invalid ready_url: {err}"))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err("ready_url must use http or https".to_string());
+        }
+        if loopback_addr(&url).is_none() {
+            return Err("ready_url must point at localhost or a loopback address".to_string());
+        }
+        Ok(ReadinessProbe::Http(url))
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            ReadinessProbe::Port(port) => format!("port {port}"),
+            ReadinessProbe::Http(url) => format!("GET {url}"),
+            ReadinessProbe::LogPattern(pattern) => format!("log pattern /{}/", pattern.as_str()),
+        }
+    }
+
+    async fn check(&self, process: &UnifiedExecProcess) -> bool {
+        match self {
+            ReadinessProbe::Port(port) => matches!(
+                tokio::time::timeout(
+                    PORT_PROBE_TIMEOUT,
+                    tokio::net::TcpStream::connect(("127.0.0.1", *port)),
+                )
+                .await,
+                Ok(Ok(_))
+            ),
+            ReadinessProbe::Http(url) => http_probe(url).await,
+            ReadinessProbe::LogPattern(pattern) => {
+                let output = process.output_snapshot().await;
+                pattern.is_match(&String::from_utf8_lossy(&output))
+            }
+        }
+    }
+}
+
+/// The loopback address `url` must be reached at, or `None` when its host is
+/// not loopback. `localhost` is pinned to 127.0.0.1 rather than resolved.
+fn loopback_addr(url: &Url) -> Option<SocketAddr> {
+    let ip = match url.host()? {
+        Host::Ipv4(ip) => IpAddr::V4(ip),
+        Host::Ipv6(ip) => IpAddr::V6(ip),
+        Host::Domain(domain) if domain.eq_ignore_ascii_case("localhost") => {
+            IpAddr::V4(Ipv4Addr::LOCALHOST)
+        }
+        Host::Domain(_) => return None,
+    };
+    ip.is_loopback()
+        .then(|| SocketAddr::new(ip, url.port_or_known_default().unwrap_or(80)))
+}
+
+async fn http_probe(url: &Url) -> bool {
+    let Some(addr) = loopback_addr(url) else {
+        return false;
+    };
+    let mut builder = reqwest::Client::builder()
+        .no_proxy()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(HTTP_PROBE_TIMEOUT);
+    if let Some(Host::Domain(domain)) = url.host() {
+        builder = builder.resolve(domain, addr);
+    }
+    let Ok(client) = builder.build() else {
+        return false;
+    };
+    client
+        .get(url.clone())
+        .send()
+        .await
+        .is_ok_and(|response| response.status().is_success())
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ServiceSpec {
+    pub name: String,
+    pub command: Vec<String>,
+    pub cwd: PathBuf,
+    pub readiness: Option<ReadinessProbe>,
+    pub ready_timeout: Duration,
+    pub sandbox_permissions: SandboxPermissions,
+    pub justification: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ServiceState {
+    /// The process is being launched, which may include waiting for approval.
+    Starting,
+    /// Running without a readiness probe.
+    Running,
+    Ready,
+    /// Running, but the readiness probe has not succeeded yet.
+    NotReady,
+    Exited(Option<i32>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ServiceStatus {
+    pub name: String,
+    pub command: Vec<String>,
+    pub state: ServiceState,
+    pub readiness: Option<String>,
+    pub uptime: Duration,
+    pub restarts: u32,
+}
+
+impl ServiceStatus {
+    pub(crate) fn render(&self) -> String {
+        let state = match self.state {
+            ServiceState::Starting => "starting".to_string(),
+            ServiceState::Running => "running".to_string(),
+            ServiceState::Ready => "ready".to_string(),
+            ServiceState::NotReady => "running, not ready".to_string(),
+            ServiceState::Exited(Some(code)) => format!("exited with code {code}"),
+            ServiceState::Exited(None) => "exited".to_string(),
+        };
+        let mut lines = vec![format!("Service {}: {state}", self.name)];
+        lines.push(format!("Command: {}", shlex_join(&self.command)));
+        if let Some(readiness) = &self.readiness {
+            lines.push(format!("Readiness probe: {readiness}"));
+        }
+        lines.push(format!("Uptime: {:.1} seconds", self.uptime.as_secs_f64()));
+        if self.restarts > 0 {
+            lines.push(format!("Restarts: {}", self.restarts));
+        }
+        lines.join("\n")
+    }
+}
+
+struct ServiceEntry {
+    spec: ServiceSpec,
+    /// `None` until the first launch of this service has spawned its process.
+    process: Option<Arc<UnifiedExecProcess>>,
+    started_at: Instant,
+    ready: bool,
+    restarts: u32,
+    /// Set while a start or restart is launching the process or waiting for
+    /// it to become ready.
+    launching: bool,
+    /// Identifies the launch that owns this entry, so a launch that lost its
+    /// entry to `stop` does not touch a newer one.
+    launch_id: u64,
+}
+
+impl ServiceEntry {
+    fn status(&self) -> ServiceStatus {
+        let state = match &self.process {
+            None => ServiceState::Starting,
+            Some(process) if process.has_exited() => ServiceState::Exited(process.exit_code()),
+            Some(_) if self.spec.readiness.is_none() => ServiceState::Running,
+            Some(_) if self.ready => ServiceState::Ready,
+            Some(_) => ServiceState::NotReady,
+        };
+        ServiceStatus {
+            name: self.spec.name.clone(),
+            command: self.spec.command.clone(),
+            state,
+            readiness: self.spec.readiness.as_ref().map(ReadinessProbe::describe),
+            uptime: Instant::now().saturating_duration_since(self.started_at),
+            restarts: self.restarts,
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.process
+            .as_ref()
+            .is_some_and(|process| !process.has_exited())
+    }
+}
+
+/// Session-scoped registry of named services.
+#[derive(Default)]
+pub(crate) struct ServiceManager {
+    services: Mutex<HashMap<String, ServiceEntry>>,
+    next_launch_id: AtomicU64,
+}
+
+impl ServiceManager {
+    pub(crate) async fn start(
+        &self,
+        spec: ServiceSpec,
+        context: &UnifiedExecContext,
+    ) -> Result<ServiceStatus, UnifiedExecError> {
+        let launch_id = self.next_launch_id.fetch_add(1, Ordering::Relaxed);
+        {
+            // Reserve the name before launching so concurrent starts conflict
+            // and status/stop see the service while it comes up.
+            let mut services = self.services.lock().await;
+            if let Some(existing) = services.get(&spec.name) {
+                if existing.launching {
+                    return Err(UnifiedExecError::ServiceStarting {
+                        name: spec.name.clone(),
+                    });
+                }
+                if existing.is_running() {
+                    return Err(UnifiedExecError::ServiceAlreadyRunning {
+                        name: spec.name.clone(),
+                    });
+                }
+            }
+            services.insert(
+                spec.name.clone(),
+                ServiceEntry {
+                    spec: spec.clone(),
+                    process: None,
+                    started_at: Instant::now(),
+                    ready: false,
+                    restarts: 0,
+                    launching: true,
+                    launch_id,
+                },
+            );
+        }
+        self.launch(spec, context, launch_id, false).await
+    }
+
+    pub(crate) async fn restart(
+        &self,
+        name: &str,
+        context: &UnifiedExecContext,
+    ) -> Result<ServiceStatus, UnifiedExecError> {
+        let launch_id = self.next_launch_id.fetch_add(1, Ordering::Relaxed);
+        let (spec, old_process) = {
+            let mut services = self.services.lock().await;
+            let entry = services
+                .get_mut(name)
+                .ok_or_else(|| UnifiedExecError::UnknownService {
+                    name: name.to_string(),
+                })?;
+            if entry.launching {
+                return Err(UnifiedExecError::ServiceStarting {
+                    name: name.to_string(),
+                });
+            }
+            entry.launching = true;
+            entry.launch_id = launch_id;
+            (entry.spec.clone(), entry.process.clone())
+        };
+        // The old process must release its ports before the new one starts,
+        // but its entry stays until the relaunch succeeds or fails.
+        if let Some(process) = old_process {
+            process.terminate();
+        }
+        self.launch(spec, context, launch_id, true).await
+    }
+
+    pub(crate) async fn stop(&self, name: &str) -> Result<ServiceStatus, UnifiedExecError> {
+        let entry = self.services.lock().await.remove(name).ok_or_else(|| {
+            UnifiedExecError::UnknownService {
+                name: name.to_string(),
+            }
+        })?;
+        if let Some(process) = &entry.process {
+            process.terminate();
+        }
+        let mut status = entry.status();
+        if !matches!(status.state, ServiceState::Exited(_)) {
+            status.state = ServiceState::Exited(None);
+        }
+        Ok(status)
+    }
+
+    pub(crate) async fn status(&self, name: &str) -> Result<ServiceStatus, UnifiedExecError> {
+        self.services
+            .lock()
+            .await
+            .get(name)
+            .map(ServiceEntry::status)
+            .ok_or_else(|| UnifiedExecError::UnknownService {
+                name: name.to_string(),
+            })
+    }
+
+    pub(crate) async fn list(&self) -> Vec<ServiceStatus> {
+        let mut statuses: Vec<ServiceStatus> = self
+            .services
+            .lock()
+            .await
+            .values()
+            .map(ServiceEntry::status)
+            .collect();
+        statuses.sort_by(|a, b| a.name.cmp(&b.name));
+        statuses
+    }
+
+    /// Return the last `lines` lines of the service output.
+    pub(crate) async fn logs(&self, name: &str, lines: usize) -> Result<String, UnifiedExecError> {
+        let process = self
+            .services
+            .lock()
+            .await
+            .get(name)
+            .map(|entry| entry.process.clone())
+            .ok_or_else(|| UnifiedExecError::UnknownService {
+                name: name.to_string(),
+            })?;
+        let Some(process) = process else {
+            return Ok(String::new());
+        };
+        let output = process.output_snapshot().await;
+        Ok(tail_lines(&String::from_utf8_lossy(&output), lines))
+    }
+
+    pub(crate) async fn stop_all(&self) {
+        let entries: Vec<ServiceEntry> = self
+            .services
+            .lock()
+            .await
+            .drain()
+            .map(|(_, entry)| entry)
+            .collect();
+        for process in entries.iter().filter_map(|entry| entry.process.as_ref()) {
+            process.terminate();
+        }
+    }
+
+    /// Launch the process for the entry reserved under `launch_id` and wait
+    /// for it to become ready.
+    async fn launch(
+        &self,
+        spec: ServiceSpec,
+        context: &UnifiedExecContext,
+        launch_id: u64,
+        restarted: bool,
+    ) -> Result<ServiceStatus, UnifiedExecError> {
+        let request = ExecCommandRequest {
+            command: spec.command.clone(),
+            process_id: spec.name.clone(),
+            yield_time_ms: 0,
+            wait: WaitCondition::default(),
+            max_output_tokens: None,
+            workdir: Some(spec.cwd.clone()),
+            tty: false,
+            screen: false,
+            sandbox_permissions: spec.sandbox_permissions,
+            justification: spec.justification.clone(),
+            prefix_rule: None,
+        };
+        let process = match context
+            .session
+            .services
+            .unified_exec_manager
+            .open_session_with_sandbox(&request, spec.cwd.clone(), context)
+            .await
+        {
+            Ok(process) => Arc::new(process),
+            Err(err) => {
+                self.abandon_launch(&spec.name, launch_id).await;
+                return Err(err);
+            }
+        };
+        let started_at = Instant::now();
+        {
+            let mut services = self.services.lock().await;
+            match services.get_mut(&spec.name) {
+                Some(entry) if entry.launch_id == launch_id => {
+                    entry.process = Some(Arc::clone(&process));
+                    entry.started_at = started_at;
+                    entry.ready = false;
+                    if restarted {
+                        entry.restarts = entry.restarts.saturating_add(1);
+                    }
+                }
+                _ => {
+                    process.terminate();
+                    return Err(UnifiedExecError::ServiceStopped { name: spec.name });
+                }
+            }
+        }
+
+        let emitter = ToolEmitter::unified_exec(
+            &spec.command,
+            spec.cwd.clone(),
+            ExecCommandSource::Service,
+            Some(spec.name.clone()),
+        );
+        emitter
+            .emit(
+                ToolEventCtx::new(
+                    context.session.as_ref(),
+                    context.turn.as_ref(),
+                    &context.call_id,
+                    None,
+                ),
+                ToolEventStage::Begin,
+            )
+            .await;
+        spawn_service_exit_watcher(Arc::clone(&process), emitter, context, started_at);
+
+        let ready = match &spec.readiness {
+            Some(probe) => wait_until_ready(&process, probe, spec.ready_timeout).await,
+            None => false,
+        };
+        let mut services = self.services.lock().await;
+        match services.get_mut(&spec.name) {
+            Some(entry) if entry.launch_id == launch_id => {
+                entry.ready = ready;
+                entry.launching = false;
+                Ok(entry.status())
+            }
+            // `stop` removed the entry and terminated the process meanwhile.
+            _ => Err(UnifiedExecError::ServiceStopped { name: spec.name }),
+        }
+    }
+
+    /// Release the reservation of a launch that failed to spawn. A fresh
+    /// start disappears again; a restart keeps the entry of the old process.
+    async fn abandon_launch(&self, name: &str, launch_id: u64) {
+        let mut services = self.services.lock().await;
+        let Some(entry) = services.get_mut(name) else {
+            return;
+        };
+        if entry.launch_id != launch_id {
+            return;
+        }
+        if entry.process.is_none() {
+            services.remove(name);
+        } else {
+            entry.launching = false;
+        }
+    }
+}
+
+async fn wait_until_ready(
+    process: &UnifiedExecProcess,
+    probe: &ReadinessProbe,
+    timeout: Duration,
+) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if process.has_exited() {
+            return false;
+        }
+        if probe.check(process).await {
+            return true;
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return false;
+        }
+        tokio::time::sleep(remaining.min(READINESS_POLL_INTERVAL)).await;
+    }
+}
+
+fn spawn_service_exit_watcher(
+    process: Arc<UnifiedExecProcess>,
+    emitter: ToolEmitter,
+    context: &UnifiedExecContext,
+    started_at: Instant,
+) {
+    let session = Arc::clone(&context.session);
+    let turn = Arc::clone(&context.turn);
+    let call_id = context.call_id.clone();
+    let exit_token = process.cancellation_token();
+    tokio::spawn(async move {
+        exit_token.cancelled().await;
+        tokio::time::sleep(EXIT_OUTPUT_GRACE).await;
+
+        let output = String::from_utf8_lossy(&process.output_snapshot().await).to_string();
+        let output = ExecToolCallOutput {
+            exit_code: process.exit_code().unwrap_or(-1),
+            stdout: StreamOutput::new(output.clone()),
+            stderr: StreamOutput::new(String::new()),
+            aggregated_output: StreamOutput::new(output),
+            duration: Instant::now().saturating_duration_since(started_at),
+            timed_out: false,
+        };
+        let ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
+        emitter.emit(ctx, ToolEventStage::Success(output)).await;
+    });
+}
+
+fn tail_lines(text: &str, lines: usize) -> String {
+    let all: Vec<&str> = text.lines().collect();
+    let start = all.len().saturating_sub(lines);
+    all[start..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn tail_lines_returns_last_lines() {
+        assert_eq!(tail_lines("a\nb\nc\n", 2), "b\nc");
+        assert_eq!(tail_lines("a\nb\n", 10), "a\nb");
+        assert_eq!(tail_lines("", 3), "");
+    }
+
+    #[test]
+    fn http_probe_only_accepts_loopback_hosts() {
+        for url in [
+            "http://localhost:3000/health",
+            "http://127.0.0.1:8080",
+            "https://[::1]:8443/ready",
+        ] {
+            assert!(
+                ReadinessProbe::http(url).is_ok(),
+                "{url} should be accepted"
+            );
+        }
+        for url in [
+            "http://example.com/health",
+            "http://169.254.169.254/latest/meta-data",
+            "http://10.0.0.5:3000",
+            "http://localhost.example.com:3000",
+            "file:///etc/passwd",
+        ] {
+            assert!(
+                ReadinessProbe::http(url).is_err(),
+                "{url} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn localhost_is_pinned_to_loopback() {
+        let url = Url::parse("http://localhost:3000/").expect("url");
+        assert_eq!(
+            loopback_addr(&url),
+            Some(SocketAddr::from(([127, 0, 0, 1], 3000)))
+        );
+    }
+
+    #[test]
+    fn status_renders_state_and_probe() {
+        let status = ServiceStatus {
+            name: "web".to_string(),
+            command: vec!["npm".to_string(), "run".to_string(), "dev".to_string()],
+            state: ServiceState::Ready,
+            readiness: Some(ReadinessProbe::Port(3000).describe()),
+            uptime: Duration::from_millis(1500),
+            restarts: 1,
+        };
+        assert_eq!(
+            status.render(),
+            "Service web: ready\nCommand: npm run dev\nReadiness probe: port 3000\nUptime: 1.5 seconds\nRestarts: 1"
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::VecDeque;
+
+use regex::Regex;
+
+/// Longest unterminated line kept for matching. Output that never prints a
+/// newline is matched against its most recent bytes only.
+const MAX_PARTIAL_LINE_BYTES: usize = 8 * 1024;
+
+/// Matches a `wait_for` pattern against output as it arrives, one line at a
+/// time, so each poll only scans the new bytes plus the line they continue.
+/// Lines the PTY echoes back from the input written by this call are skipped,
+/// so a pattern that also appears in the typed command does not match early.
+pub(crate) struct WaitForScanner<'a> {
+    pattern: &'a Regex,
+    /// Output since the last newline.
+    partial: Vec<u8>,
+    /// Input lines still expected to be echoed, in order.
+    echo: VecDeque<String>,
+}
+
+impl<'a> WaitForScanner<'a> {
+    pub(crate) fn new(pattern: &'a Regex, input: &str) -> Self {
+        let echo = input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        Self {
+            pattern,
+            partial: Vec::new(),
+            echo,
+        }
+    }
+
+    /// Feed newly collected output, returning whether the pattern matched.
+    pub(crate) fn scan(&mut self, chunk: &[u8]) -> bool {
+        let mut rest = chunk;
+        while let Some(pos) = rest.iter().position(|byte| *byte == b'\n') {
+            self.push_partial(&rest[..pos]);
+            rest = &rest[pos + 1..];
+            let line = std::mem::take(&mut self.partial);
+            if self.matches_line(&line, true) {
+                return true;
+            }
+        }
+        self.push_partial(rest);
+
+        // Prompts such as `Password:` or `>>> ` arrive without a newline.
+        let partial = std::mem::take(&mut self.partial);
+        let matched = !partial.is_empty() && self.matches_line(&partial, false);
+        self.partial = partial;
+        matched
+    }
+
+    fn push_partial(&mut self, bytes: &[u8]) {
+        self.partial.extend_from_slice(bytes);
+        if self.partial.len() > MAX_PARTIAL_LINE_BYTES {
+            let excess = self.partial.len() - MAX_PARTIAL_LINE_BYTES;
+            self.partial.drain(..excess);
+        }
+    }
+
+    fn matches_line(&mut self, line: &[u8], complete: bool) -> bool {
+        let text = String::from_utf8_lossy(line);
+        let text = text.trim_end_matches('\r');
+        if let Some(expected) = self.echo.front() {
+            // The echo may follow a shell prompt on the same line, and an
+            // unterminated line may hold only the start of it so far.
+            let echoed = if complete {
+                text.trim_end().ends_with(expected.as_str())
+            } else {
+                text.char_indices().any(|(idx, _)| {
+                    let suffix = text[idx..].trim_end();
+                    !suffix.is_empty() && expected.starts_with(suffix)
+                })
+            };
+            if echoed {
+                if complete {
+                    self.echo.pop_front();
+                }
+                return false;
+            }
+        }
+        self.pattern.is_match(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn matches_across_chunk_boundaries() {
+        let pattern = Regex::new("server ready").expect("regex");
+        let mut scanner = WaitForScanner::new(&pattern, "");
+
+        assert!(!scanner.scan(b"booting\nserver re"));
+        assert!(scanner.scan(b"ady on :8080\n"));
+    }
+
+    #[test]
+    fn matches_prompt_without_newline() {
+        let pattern = Regex::new(r">>> $").expect("regex");
+        let mut scanner = WaitForScanner::new(&pattern, "");
+
+        assert!(scanner.scan(b"Python 3.12\n>>> "));
+    }
+
+    #[test]
+    fn skips_echoed_input() {
+        let pattern = Regex::new("done").expect("regex");
+        let mut scanner = WaitForScanner::new(&pattern, "make && echo done\n");
+
+        assert!(!scanner.scan(b"$ make && echo do"));
+        assert!(!scanner.scan(b"ne\r\n"));
+        assert!(!scanner.scan(b"cc -o app main.c\r\n"));
+        assert!(scanner.scan(b"done\r\n"));
+    }
+
+    #[test]
+    fn long_lines_keep_only_recent_bytes() {
+        let pattern = Regex::new("^x+$").expect("regex");
+        let mut scanner = WaitForScanner::new(&pattern, "");
+
+        for _ in 0..4 {
+            scanner.scan(&[b'y'; MAX_PARTIAL_LINE_BYTES]);
+        }
+        assert_eq!(scanner.partial.len(), MAX_PARTIAL_LINE_BYTES);
+        assert!(scanner.scan(&[b'x'; MAX_PARTIAL_LINE_BYTES]));
+    }
+}
@@ -81,6 +81,7 @@ mod json_result;
 mod list_dir;
 mod list_models;
 mod live_cli;
+mod model_fallback;
 mod model_info_overrides;
 mod model_overrides;
 mod model_tools;
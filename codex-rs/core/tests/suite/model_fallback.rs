@@ -0,0 +1,114 @@
+use codex_core::ModelProviderInfo;
+use codex_core::WireApi;
+use codex_core::config::types::ModelFallback;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::load_sse_fixture_with_id;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+const FALLBACK_MODEL: &str = "gpt-5.1-codex-mini";
+
+fn mock_provider(name: &str, server: &MockServer) -> ModelProviderInfo {
+    ModelProviderInfo {
+        name: name.into(),
+        base_url: Some(format!("{}/v1", server.uri())),
+        env_key: Some("PATH".into()),
+        env_key_instructions: None,
+        experimental_bearer_token: None,
+        auth_command: None,
+        wire_api: WireApi::Responses,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(2_000),
+        requires_openai_auth: false,
+        supports_websockets: false,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn turn_falls_back_when_primary_provider_fails() {
+    skip_if_no_network!();
+
+    let primary = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&primary)
+        .await;
+
+    let fallback = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_raw(
+                    load_sse_fixture_with_id("../fixtures/completed_template.json", "resp_fb"),
+                    "text/event-stream",
+                ),
+        )
+        .expect(1)
+        .mount(&fallback)
+        .await;
+
+    let primary_provider = mock_provider("primary", &primary);
+    let fallback_provider = mock_provider("fallback", &fallback);
+    let TestCodex { codex, .. } = test_codex()
+        .with_config(move |config| {
+            config.model_provider_id = "primary".to_string();
+            config.model_provider = primary_provider;
+            config.model_fallbacks = vec![ModelFallback {
+                model_provider_id: "fallback".to_string(),
+                model_provider: fallback_provider,
+                model: Some(FALLBACK_MODEL.to_string()),
+            }];
+        })
+        .build(&primary)
+        .await
+        .unwrap();
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+                text_elements: Vec::new(),
+            }],
+            final_output_json_schema: None,
+        })
+        .await
+        .unwrap();
+
+    let event = wait_for_event_match(&codex, |ev| match ev {
+        EventMsg::ModelFallback(event) => Some(event.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(
+        (
+            event.from_model_provider.as_str(),
+            event.model_provider.as_str(),
+            event.model.as_str()
+        ),
+        ("primary", "fallback", FALLBACK_MODEL)
+    );
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
+
+    let requests = fallback.received_requests().await.unwrap_or_default();
+    let body: serde_json::Value = requests[0].body_json().expect("request body is JSON");
+    assert_eq!(body["model"], FALLBACK_MODEL);
+}
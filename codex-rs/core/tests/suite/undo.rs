@@ -388,6 +388,37 @@ async fn undo_without_snapshot_reports_failure() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn undo_restores_files_outside_git_repo() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let builder = test_codex().with_model("gpt-5.1").with_config(|config| {
+        config.include_apply_patch_tool = true;
+        config.features.enable(Feature::GhostCommit);
+        config.ghost_snapshot.file_snapshots = true;
+    });
+    let harness = TestCodexHarness::with_builder(builder).await?;
+    let notes = harness.path("notes.txt");
+    fs::write(&notes, "original\n")?;
+
+    let patch = "*** Begin Patch\n*** Update File: notes.txt\n@@\n-original\n+modified\n*** Add File: created.txt\n+new\n*** End Patch";
+    run_apply_patch_turn(&harness, "edit outside git", "undo-no-git", patch, "done").await?;
+
+    let created = harness.path("created.txt");
+    assert_eq!(fs::read_to_string(&notes)?, "modified\n");
+    assert!(created.exists());
+
+    let codex = Arc::clone(&harness.test().codex);
+    expect_successful_undo(&codex).await?;
+
+    assert_eq!(fs::read_to_string(&notes)?, "original\n");
+    assert!(!created.exists());
+
+    expect_failed_undo(&codex).await?;
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn undo_restores_moves_and_renames() -> Result<()> {
     skip_if_no_network!(Ok(()));
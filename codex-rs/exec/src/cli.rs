@@ -108,6 +108,11 @@ pub enum Command {
 
     /// Run a code review against the current repository.
     Review(ReviewArgs),
+
+    /// Split the uncommitted changes into commits with drafted messages.
+    /// Continues the latest session in this directory, if any, so only the
+    /// files it edited are planned.
+    Commit(CommitArgs),
 }
 
 #[derive(Args, Debug)]
@@ -207,7 +212,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "uncommitted",
         default_value_t = false,
-        conflicts_with_all = ["base", "commit", "prompt"]
+        conflicts_with_all = ["base", "commit", "range", "last", "prompt"]
     )]
     pub uncommitted: bool,
 
@@ -215,7 +220,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "base",
         value_name = "BRANCH",
-        conflicts_with_all = ["uncommitted", "commit", "prompt"]
+        conflicts_with_all = ["uncommitted", "commit", "range", "last", "prompt"]
     )]
     pub base: Option<String>,
 
@@ -223,7 +228,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "commit",
         value_name = "SHA",
-        conflicts_with_all = ["uncommitted", "base", "prompt"]
+        conflicts_with_all = ["uncommitted", "base", "range", "last", "prompt"]
     )]
     pub commit: Option<String>,
 
@@ -231,11 +236,89 @@ pub struct ReviewArgs {
     #[arg(long = "title", value_name = "TITLE", requires = "commit")]
     pub commit_title: Option<String>,
 
+    /// Review the commits in a range such as `main..HEAD`.
+    #[arg(
+        long = "range",
+        value_name = "BASE..HEAD",
+        conflicts_with_all = ["uncommitted", "base", "commit", "last", "prompt"]
+    )]
+    pub range: Option<String>,
+
+    /// Review the last N commits on the current branch.
+    #[arg(
+        long = "last",
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with_all = ["uncommitted", "base", "commit", "range", "prompt"]
+    )]
+    pub last: Option<u32>,
+
+    /// Review each commit of `--range`, `--last`, or `--base` on its own and
+    /// group findings by commit.
+    #[arg(long = "per-commit", default_value_t = false)]
+    pub per_commit: bool,
+
+    /// Output format. Formats other than `text` print only the review to
+    /// stdout, with paths relative to the repository root.
+    #[arg(long = "format", value_enum, default_value_t = ReviewFormat::Text)]
+    pub format: ReviewFormat,
+
+    /// Exit with status 1 when a finding has this priority or a more severe
+    /// one. P0 is the most severe.
+    #[arg(
+        long = "fail-on-priority",
+        value_name = "0-3",
+        value_parser = clap::value_parser!(u8).range(0..=3)
+    )]
+    pub fail_on_priority: Option<u8>,
+
+    /// Ignore findings with a lower confidence score (0.0 to 1.0) when
+    /// applying `--fail-on-priority`.
+    #[arg(
+        long = "min-confidence",
+        value_name = "SCORE",
+        requires = "fail_on_priority"
+    )]
+    pub min_confidence: Option<f32>,
+
     /// Custom review instructions. If `-` is used, read from stdin.
     #[arg(value_name = "PROMPT", value_hint = clap::ValueHint::Other)]
     pub prompt: Option<String>,
 }
 
+#[derive(Parser, Debug)]
+pub struct CommitArgs {
+    /// Create the proposed commits without asking for confirmation.
+    #[arg(
+        long = "yes",
+        short = 'y',
+        default_value_t = false,
+        conflicts_with = "dry_run"
+    )]
+    pub yes: bool,
+
+    /// Print the proposed commits without creating them.
+    #[arg(long = "dry-run", default_value_t = false)]
+    pub dry_run: bool,
+}
+
+/// Output format for `codex exec review`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum ReviewFormat {
+    /// Human-readable review, as printed without `--format`.
+    #[default]
+    Text,
+    /// The structured review result as JSON.
+    Json,
+    /// SARIF 2.1.0, for code scanning dashboards.
+    Sarif,
+    /// GitHub Actions workflow commands that annotate the diff.
+    GithubAnnotations,
+    /// JUnit XML with one failing test case per finding.
+    Junit,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
 #[value(rename_all = "kebab-case")]
 pub enum Color {
@@ -13,6 +13,8 @@ use codex_core::protocol::CollabCloseBeginEvent;
 use codex_core::protocol::CollabCloseEndEvent;
 use codex_core::protocol::CollabWaitingBeginEvent;
 use codex_core::protocol::CollabWaitingEndEvent;
+use codex_core::protocol::CommitsCreatedEvent;
+use codex_core::protocol::CommitsProposedEvent;
 use codex_core::protocol::DeprecationNoticeEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
@@ -28,6 +30,7 @@ use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::ToolOutputsAgedEvent;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnCompleteEvent;
 use codex_core::protocol::TurnDiffEvent;
@@ -188,6 +191,15 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     "warning:".style(self.yellow).style(self.bold)
                 );
             }
+            EventMsg::ModelFallback(event) => {
+                ts_msg!(
+                    self,
+                    "{} {}",
+                    "model fallback:".style(self.yellow).style(self.bold),
+                    event.summary()
+                );
+                ts_msg!(self, "{}", event.reason.style(self.dimmed));
+            }
             EventMsg::DeprecationNotice(DeprecationNoticeEvent { summary, details }) => {
                 ts_msg!(
                     self,
@@ -610,6 +622,60 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::ContextCompacted(_) => {
                 ts_msg!(self, "context compacted");
             }
+            EventMsg::ToolOutputsAged(ToolOutputsAgedEvent {
+                call_ids,
+                estimated_tokens_saved,
+            }) => {
+                ts_msg!(
+                    self,
+                    "aged {} old tool output(s) out of context (~{estimated_tokens_saved} tokens freed)",
+                    call_ids.len()
+                );
+            }
+            EventMsg::CommitsProposed(CommitsProposedEvent {
+                commits,
+                unassigned_paths,
+            }) => {
+                if commits.is_empty() {
+                    ts_msg!(self, "{}", "no changes to commit".style(self.dimmed));
+                }
+                for (index, commit) in commits.iter().enumerate() {
+                    let subject = commit.message.lines().next().unwrap_or_default();
+                    ts_msg!(
+                        self,
+                        "{} {subject}",
+                        format!("commit {}:", index + 1).style(self.cyan)
+                    );
+                    for path in &commit.paths {
+                        ts_msg!(self, "  {}", path.display().style(self.dimmed));
+                    }
+                }
+                if !unassigned_paths.is_empty() {
+                    ts_msg!(self, "{}", "left uncommitted:".style(self.yellow));
+                    for path in unassigned_paths {
+                        ts_msg!(self, "  {}", path.display().style(self.dimmed));
+                    }
+                }
+            }
+            EventMsg::CommitsCreated(CommitsCreatedEvent { commits, error }) => {
+                for commit in commits {
+                    let short_sha: String = commit.sha.chars().take(7).collect();
+                    ts_msg!(
+                        self,
+                        "{} {} {}",
+                        "committed".style(self.green),
+                        short_sha.style(self.bold),
+                        commit.subject
+                    );
+                }
+                if let Some(error) = error {
+                    ts_msg!(
+                        self,
+                        "{} {error}",
+                        "ERROR:".style(self.red).style(self.bold)
+                    );
+                }
+            }
             EventMsg::CollabAgentSpawnBegin(CollabAgentSpawnBeginEvent {
                 call_id,
                 sender_thread_id: _,
@@ -776,6 +842,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
             | EventMsg::ListSkillsResponse(_)
+            | EventMsg::ContextBreakdown(_)
             | EventMsg::ListRemoteSkillsResponse(_)
             | EventMsg::RemoteSkillDownloaded(_)
             | EventMsg::RawResponseItem(_)
@@ -794,7 +861,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::SkillsUpdateAvailable
             | EventMsg::UndoCompleted(_)
             | EventMsg::UndoStarted(_)
+            | EventMsg::TouchedPathsUpdated(_)
             | EventMsg::ThreadRolledBack(_)
+            | EventMsg::ContextPruned(_)
             | EventMsg::RequestUserInput(_)
             | EventMsg::DynamicToolCallRequest(_) => {}
         }
@@ -1,6 +1,8 @@
 // - In the default output mode, it is paramount that the only thing written to
 //   stdout is the final message (if any).
 // - In --json mode, stdout must be valid JSONL, one event per line.
+// - With `review --format` other than text, stdout holds only the rendered
+//   review.
 // For both modes, any other output must be written to stderr.
 #![deny(clippy::print_stdout)]
 
@@ -9,10 +11,13 @@ mod event_processor;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
+mod review_report;
 
 pub use cli::Cli;
 pub use cli::Command;
+pub use cli::CommitArgs;
 pub use cli::ReviewArgs;
+pub use cli::ReviewFormat;
 use codex_cloud_requirements::cloud_requirements_loader;
 use codex_common::oss::ensure_oss_provider_ready;
 use codex_common::oss::get_default_model_for_oss_provider;
@@ -36,9 +41,12 @@ use codex_core::protocol::AskForApproval;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
+use codex_core::protocol::ProposedCommit;
+use codex_core::protocol::ReviewOutputEvent;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
 use codex_core::protocol::SessionSource;
+use codex_core::review_prompts::parse_commit_range;
 use codex_protocol::approvals::ElicitationAction;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::user_input::UserInput;
@@ -64,6 +72,7 @@ use uuid::Uuid;
 use crate::cli::Command as ExecCommand;
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
+use crate::review_report::ReviewReport;
 use codex_core::default_client::set_default_client_residency_requirement;
 use codex_core::default_client::set_default_originator;
 use codex_core::find_thread_path_by_id_str;
@@ -77,6 +86,17 @@ enum InitialOperation {
     Review {
         review_request: ReviewRequest,
     },
+    Commit {
+        confirmation: CommitConfirmation,
+    },
+}
+
+/// How `codex exec commit` decides whether to create the proposed commits.
+#[derive(Clone, Copy)]
+enum CommitConfirmation {
+    Ask,
+    Yes,
+    DryRun,
 }
 
 #[derive(Clone)]
@@ -112,6 +132,14 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         config_overrides,
     } = cli;
 
+    let review_report = match &command {
+        Some(ExecCommand::Review(review_cli)) => Some(ReviewReport::from_args(review_cli)),
+        _ => None,
+    };
+    if json_mode && review_report.is_some_and(|report| report.format != ReviewFormat::Text) {
+        anyhow::bail!("--format cannot be combined with --json");
+    }
+
     let (stdout_with_ansi, stderr_with_ansi) = match color {
         cli::Color::Always => (true, true),
         cli::Color::Never => (false, false),
@@ -367,6 +395,14 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         } else {
             thread_manager.start_thread(config.clone()).await?
         }
+    } else if let Some(ExecCommand::Commit(_)) = command.as_ref()
+        && let Some(path) = latest_thread_path(&config, false).await
+    {
+        // Continue the latest session in this directory so the plan focuses on
+        // the files it edited rather than every change in the worktree.
+        thread_manager
+            .resume_thread_from_rollout(config.clone(), path, auth_manager.clone())
+            .await?
     } else {
         thread_manager.start_thread(config.clone()).await?
     };
@@ -376,6 +412,19 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
             let summary = codex_core::review_prompts::user_facing_hint(&review_request.target);
             (InitialOperation::Review { review_request }, summary)
         }
+        (Some(ExecCommand::Commit(commit_cli)), _, _) => {
+            let confirmation = if commit_cli.dry_run {
+                CommitConfirmation::DryRun
+            } else if commit_cli.yes {
+                CommitConfirmation::Yes
+            } else {
+                CommitConfirmation::Ask
+            };
+            (
+                InitialOperation::Commit { confirmation },
+                "Split uncommitted changes into commits".to_string(),
+            )
+        }
         (Some(ExecCommand::Resume(args)), root_prompt, imgs) => {
             let prompt_arg = args
                 .prompt
@@ -482,6 +531,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         });
     }
 
+    let commit_confirmation = match &initial_operation {
+        InitialOperation::Commit { confirmation } => Some(*confirmation),
+        _ => None,
+    };
     match initial_operation {
         InitialOperation::UserTurn {
             items,
@@ -509,12 +562,20 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
             info!("Sent review request with event ID: {task_id}");
             task_id
         }
+        InitialOperation::Commit { .. } => {
+            let task_id = thread.submit(Op::ProposeCommits).await?;
+            info!("Sent commit proposal request with event ID: {task_id}");
+            task_id
+        }
     };
 
     // Run the loop until the task is complete.
     // Track whether a fatal error was reported by the server so we can
     // exit with a non-zero status for automation-friendly signaling.
     let mut error_seen = false;
+    // Commits accepted from the proposal, created once the proposal turn ends.
+    let mut pending_commits: Option<Vec<ProposedCommit>> = None;
+    let mut review_output: Option<ReviewOutputEvent> = None;
     while let Some(envelope) = rx.recv().await {
         let ThreadEventEnvelope {
             thread_id,
@@ -534,26 +595,84 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
         }
+        if matches!(&event.msg, EventMsg::CommitsCreated(ev) if ev.error.is_some()) {
+            error_seen = true;
+        }
         if thread_id != primary_thread_id && matches!(&event.msg, EventMsg::TurnComplete(_)) {
             continue;
         }
+        if let EventMsg::ExitedReviewMode(ev) = &event.msg
+            && thread_id == primary_thread_id
+        {
+            review_output = ev.review_output.clone();
+        }
+        let proposed_commits = match &event.msg {
+            EventMsg::CommitsProposed(ev) if thread_id == primary_thread_id => {
+                Some(ev.commits.clone())
+            }
+            _ => None,
+        };
         let shutdown = event_processor.process_event(event);
+        if let (Some(commits), Some(confirmation)) = (proposed_commits, commit_confirmation)
+            && !commits.is_empty()
+            && confirm_commits(confirmation, commits.len()).await
+        {
+            pending_commits = Some(commits);
+        }
         if thread_id != primary_thread_id && matches!(shutdown, CodexStatus::InitiateShutdown) {
             continue;
         }
         match shutdown {
             CodexStatus::Running => continue,
             CodexStatus::InitiateShutdown => {
-                thread.submit(Op::Shutdown).await?;
+                if let Some(commits) = pending_commits.take() {
+                    thread.submit(Op::CreateCommits { commits }).await?;
+                } else {
+                    thread.submit(Op::Shutdown).await?;
+                }
             }
             CodexStatus::Shutdown if thread_id == primary_thread_id => break,
             CodexStatus::Shutdown => continue,
         }
     }
-    event_processor.print_final_output();
+    let rendered_review = match (&review_report, &review_output) {
+        (Some(report), Some(output)) => {
+            let repo_root = get_git_repo_root(&config.cwd).unwrap_or_else(|| config.cwd.clone());
+            report.render(output, &repo_root)
+        }
+        _ => None,
+    };
+    if let Some(report) = &review_report
+        && report.requires_output()
+        && review_output.is_none()
+    {
+        eprintln!("The review ended without output; nothing to report or check.");
+        std::process::exit(1);
+    }
+    #[allow(clippy::print_stdout)]
+    if let Some(rendered) = rendered_review {
+        print!("{rendered}");
+    } else {
+        event_processor.print_final_output();
+    }
     if error_seen {
         std::process::exit(1);
     }
+    if let (Some(report), Some(output)) = (&review_report, &review_output) {
+        let blocking = report.blocking_findings(output);
+        if !blocking.is_empty() {
+            let noun = if blocking.len() == 1 {
+                "finding"
+            } else {
+                "findings"
+            };
+            eprintln!(
+                "{} review {noun} at or above the --fail-on-priority threshold",
+                blocking.len()
+            );
+            std::process::exit(1);
+        }
+    }
 
     Ok(())
 }
@@ -599,30 +718,7 @@ async fn resolve_resume_path(
     args: &crate::cli::ResumeArgs,
 ) -> anyhow::Result<Option<PathBuf>> {
     if args.last {
-        let default_provider_filter = vec![config.model_provider_id.clone()];
-        let filter_cwd = if args.all {
-            None
-        } else {
-            Some(config.cwd.as_path())
-        };
-        match codex_core::RolloutRecorder::find_latest_thread_path(
-            &config.codex_home,
-            1,
-            None,
-            codex_core::ThreadSortKey::UpdatedAt,
-            &[],
-            Some(default_provider_filter.as_slice()),
-            &config.model_provider_id,
-            filter_cwd,
-        )
-        .await
-        {
-            Ok(path) => Ok(path),
-            Err(e) => {
-                error!("Error listing threads: {e}");
-                Ok(None)
-            }
-        }
+        Ok(latest_thread_path(config, args.all).await)
     } else if let Some(id_str) = args.session_id.as_deref() {
         if Uuid::parse_str(id_str).is_ok() {
             let path = find_thread_path_by_id_str(&config.codex_home, id_str).await?;
@@ -636,6 +732,35 @@ async fn resolve_resume_path(
     }
 }
 
+/// Most recently updated session, limited to the current directory unless
+/// `all` is set.
+async fn latest_thread_path(config: &Config, all: bool) -> Option<PathBuf> {
+    let default_provider_filter = vec![config.model_provider_id.clone()];
+    let filter_cwd = if all {
+        None
+    } else {
+        Some(config.cwd.as_path())
+    };
+    match codex_core::RolloutRecorder::find_latest_thread_path(
+        &config.codex_home,
+        1,
+        None,
+        codex_core::ThreadSortKey::UpdatedAt,
+        &[],
+        Some(default_provider_filter.as_slice()),
+        &config.model_provider_id,
+        filter_cwd,
+    )
+    .await
+    {
+        Ok(path) => path,
+        Err(e) => {
+            error!("Error listing threads: {e}");
+            None
+        }
+    }
+}
+
 fn load_output_schema(path: Option<PathBuf>) -> Option<Value> {
     let path = path?;
 
@@ -775,11 +900,63 @@ fn resolve_prompt(prompt_arg: Option<String>) -> String {
     }
 }
 
+/// Whether to create the `count` proposed commits. Without `--yes`, asks on
+/// an interactive terminal and declines otherwise. The prompt is read on a
+/// blocking thread so the runtime keeps draining events while it waits.
+async fn confirm_commits(confirmation: CommitConfirmation, count: usize) -> bool {
+    match confirmation {
+        CommitConfirmation::DryRun => false,
+        CommitConfirmation::Yes => true,
+        CommitConfirmation::Ask if std::io::stdin().is_terminal() => {
+            let noun = if count == 1 { "commit" } else { "commits" };
+            eprint!("Create {count} {noun}? [y/N] ");
+            let answer = tokio::task::spawn_blocking(|| {
+                let mut answer = String::new();
+                std::io::stdin().read_line(&mut answer).map(|_| answer)
+            })
+            .await;
+            let Ok(Ok(answer)) = answer else {
+                return false;
+            };
+            matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+        }
+        CommitConfirmation::Ask => {
+            eprintln!("Not creating commits: pass --yes to create them without a prompt.");
+            false
+        }
+    }
+}
+
 fn build_review_request(args: ReviewArgs) -> anyhow::Result<ReviewRequest> {
+    if args.per_commit && args.range.is_none() && args.last.is_none() && args.base.is_none() {
+        anyhow::bail!("--per-commit requires --range, --last, or --base");
+    }
     let target = if args.uncommitted {
         ReviewTarget::UncommittedChanges
+    } else if let Some(range) = args.range {
+        let (base, head) = parse_commit_range(&range)?;
+        ReviewTarget::CommitRange {
+            base,
+            head,
+            per_commit: args.per_commit,
+        }
+    } else if let Some(count) = args.last {
+        ReviewTarget::CommitRange {
+            base: format!("HEAD~{count}"),
+            head: "HEAD".to_string(),
+            per_commit: args.per_commit,
+        }
     } else if let Some(branch) = args.base {
-        ReviewTarget::BaseBranch { branch }
+        if args.per_commit {
+            // Each commit on the current branch that `branch` does not have.
+            ReviewTarget::CommitRange {
+                base: branch,
+                head: "HEAD".to_string(),
+                per_commit: true,
+            }
+        } else {
+            ReviewTarget::BaseBranch { branch }
+        }
     } else if let Some(sha) = args.commit {
         ReviewTarget::Commit {
             sha,
@@ -795,7 +972,7 @@ fn build_review_request(args: ReviewArgs) -> anyhow::Result<ReviewRequest> {
         }
     } else {
         anyhow::bail!(
-            "Specify --uncommitted, --base, --commit, or provide custom review instructions"
+            "Specify --uncommitted, --base, --commit, --range, --last, or provide custom review instructions"
         );
     };
 
@@ -817,6 +994,12 @@ mod tests {
             base: None,
             commit: None,
             commit_title: None,
+            range: None,
+            last: None,
+            per_commit: false,
+            format: ReviewFormat::Text,
+            fail_on_priority: None,
+            min_confidence: None,
             prompt: None,
         })
         .expect("builds uncommitted review request");
@@ -836,6 +1019,12 @@ mod tests {
             base: None,
             commit: Some("123456789".to_string()),
             commit_title: Some("Add review command".to_string()),
+            range: None,
+            last: None,
+            per_commit: false,
+            format: ReviewFormat::Text,
+            fail_on_priority: None,
+            min_confidence: None,
             prompt: None,
         })
         .expect("builds commit review request");
@@ -851,6 +1040,54 @@ mod tests {
         assert_eq!(request, expected);
     }
 
+    #[test]
+    fn builds_per_commit_review_request_for_last_commits() {
+        let request = build_review_request(ReviewArgs {
+            uncommitted: false,
+            base: None,
+            commit: None,
+            commit_title: None,
+            range: None,
+            last: Some(3),
+            per_commit: true,
+            format: ReviewFormat::Text,
+            fail_on_priority: None,
+            min_confidence: None,
+            prompt: None,
+        })
+        .expect("builds range review request");
+
+        let expected = ReviewRequest {
+            target: ReviewTarget::CommitRange {
+                base: "HEAD~3".to_string(),
+                head: "HEAD".to_string(),
+                per_commit: true,
+            },
+            user_facing_hint: None,
+        };
+
+        assert_eq!(request, expected);
+    }
+
+    #[test]
+    fn per_commit_requires_a_commit_range() {
+        let result = build_review_request(ReviewArgs {
+            uncommitted: true,
+            base: None,
+            commit: None,
+            commit_title: None,
+            range: None,
+            last: None,
+            per_commit: true,
+            format: ReviewFormat::Text,
+            fail_on_priority: None,
+            min_confidence: None,
+            prompt: None,
+        });
+
+        assert!(result.is_err());
+    }
+
     #[test]
     fn builds_custom_review_request_trims_prompt() {
         let request = build_review_request(ReviewArgs {
@@ -858,6 +1095,12 @@ mod tests {
             base: None,
             commit: None,
             commit_title: None,
+            range: None,
+            last: None,
+            per_commit: false,
+            format: ReviewFormat::Text,
+            fail_on_priority: None,
+            min_confidence: None,
             prompt: Some("  custom review instructions  ".to_string()),
         })
         .expect("builds custom review request");
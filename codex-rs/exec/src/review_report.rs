@@ -0,0 +1,395 @@
+//! Machine-readable renderings of a review for `codex exec review --format`.
+//!
+//! Every format reports file paths relative to the repository root so the
+//! output can be uploaded from any checkout.
+
+use std::path::Path;
+
+use codex_core::protocol::ReviewFinding;
+use codex_core::protocol::ReviewOutputEvent;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::cli::ReviewArgs;
+use crate::cli::ReviewFormat;
+
+const TOOL_NAME: &str = "codex-review";
+const TOOL_URI: &str = "https://github.com/openai/codex";
+
+/// How to report a finished review and when to fail the run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ReviewReport {
+    pub(crate) format: ReviewFormat,
+    fail_on_priority: Option<i32>,
+    min_confidence: f32,
+}
+
+impl ReviewReport {
+    pub(crate) fn from_args(args: &ReviewArgs) -> Self {
+        Self {
+            format: args.format,
+            fail_on_priority: args.fail_on_priority.map(i32::from),
+            min_confidence: args.min_confidence.unwrap_or(0.0),
+        }
+    }
+
+    /// Whether the run must fail when the review ends without output, e.g.
+    /// after an interrupt: there is nothing to render or check.
+    pub(crate) fn requires_output(&self) -> bool {
+        self.format != ReviewFormat::Text || self.fail_on_priority.is_some()
+    }
+
+    /// Findings severe and confident enough to fail the run.
+    pub(crate) fn blocking_findings<'a>(
+        &self,
+        output: &'a ReviewOutputEvent,
+    ) -> Vec<&'a ReviewFinding> {
+        let Some(threshold) = self.fail_on_priority else {
+            return Vec::new();
+        };
+        output
+            .findings
+            .iter()
+            .filter(|finding| {
+                finding.priority <= threshold && finding.confidence_score >= self.min_confidence
+            })
+            .collect()
+    }
+
+    /// Renders `output` in the structured format, or `None` for `text`.
+    pub(crate) fn render(&self, output: &ReviewOutputEvent, repo_root: &Path) -> Option<String> {
+        let rendered = match self.format {
+            ReviewFormat::Text => return None,
+            ReviewFormat::Json => render_json(output, repo_root),
+            ReviewFormat::Sarif => render_sarif(output, repo_root),
+            ReviewFormat::GithubAnnotations => render_github_annotations(output, repo_root),
+            ReviewFormat::Junit => render_junit(output, repo_root),
+        };
+        Some(rendered)
+    }
+}
+
+fn relative_path(finding: &ReviewFinding, repo_root: &Path) -> String {
+    let path = finding.code_location.absolute_file_path.as_path();
+    let path = path.strip_prefix(repo_root).unwrap_or(path);
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Line range with both ends clamped to at least 1, as the formats require.
+fn line_range(finding: &ReviewFinding) -> (u32, u32) {
+    let range = &finding.code_location.line_range;
+    let start = range.start.max(1);
+    (start, range.end.max(start))
+}
+
+enum Level {
+    Error,
+    Warning,
+    Notice,
+}
+
+/// P0 and P1 findings are errors, P2 warnings, and anything lower notices.
+fn level(finding: &ReviewFinding) -> Level {
+    match finding.priority {
+        i32::MIN..=1 => Level::Error,
+        2 => Level::Warning,
+        _ => Level::Notice,
+    }
+}
+
+fn render_json(output: &ReviewOutputEvent, repo_root: &Path) -> String {
+    let mut output = output.clone();
+    for finding in &mut output.findings {
+        finding.code_location.absolute_file_path = relative_path(finding, repo_root).into();
+    }
+    let mut rendered = serde_json::to_string_pretty(&output).unwrap_or_default();
+    rendered.push('\n');
+    rendered
+}
+
+fn render_sarif(output: &ReviewOutputEvent, repo_root: &Path) -> String {
+    let rules: Vec<Value> = (0..=3)
+        .map(|priority| {
+            json!({
+                "id": format!("P{priority}"),
+                "shortDescription": { "text": format!("Priority {priority} review finding") },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = output
+        .findings
+        .iter()
+        .map(|finding| {
+            let (start, end) = line_range(finding);
+            let level = match level(finding) {
+                Level::Error => "error",
+                Level::Warning => "warning",
+                Level::Notice => "note",
+            };
+            let mut properties = json!({
+                "priority": finding.priority,
+                "confidence": finding.confidence_score,
+            });
+            if let Some(sha) = &finding.commit_sha {
+                properties["commitSha"] = json!(sha);
+            }
+            json!({
+                "ruleId": format!("P{}", finding.priority.clamp(0, 3)),
+                "level": level,
+                "message": { "text": format!("{}\n\n{}", finding.title, finding.body) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": relative_path(finding, repo_root),
+                            "uriBaseId": "%SRCROOT%",
+                        },
+                        "region": { "startLine": start, "endLine": end },
+                    },
+                }],
+                "properties": properties,
+            })
+        })
+        .collect();
+    let sarif = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "informationUri": TOOL_URI,
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+    let mut rendered = serde_json::to_string_pretty(&sarif).unwrap_or_default();
+    rendered.push('\n');
+    rendered
+}
+
+/// Escapes workflow command data as GitHub Actions expects.
+fn escape_annotation_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a workflow command property, which also may not contain `:` or `,`.
+fn escape_annotation_property(value: &str) -> String {
+    escape_annotation_data(value)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+fn render_github_annotations(output: &ReviewOutputEvent, repo_root: &Path) -> String {
+    let mut rendered = String::new();
+    for finding in &output.findings {
+        let (start, end) = line_range(finding);
+        let command = match level(finding) {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Notice => "notice",
+        };
+        rendered.push_str(&format!(
+            "::{command} file={},line={start},endLine={end},title={}::{}\n",
+            escape_annotation_property(&relative_path(finding, repo_root)),
+            escape_annotation_property(&format!("[P{}] {}", finding.priority, finding.title)),
+            escape_annotation_data(&finding.body),
+        ));
+    }
+    rendered
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// One failing test case per finding, so CI test reports list each one. A
+/// review without findings is a single passing test case.
+fn render_junit(output: &ReviewOutputEvent, repo_root: &Path) -> String {
+    let tests = output.findings.len().max(1);
+    let failures = output.findings.len();
+    let mut rendered = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    rendered.push_str(&format!(
+        "<testsuites name=\"{TOOL_NAME}\" tests=\"{tests}\" failures=\"{failures}\">\n"
+    ));
+    rendered.push_str(&format!(
+        "  <testsuite name=\"{TOOL_NAME}\" tests=\"{tests}\" failures=\"{failures}\">\n"
+    ));
+    if output.findings.is_empty() {
+        rendered.push_str("    <testcase classname=\"review\" name=\"no findings\"/>\n");
+    }
+    for finding in &output.findings {
+        let path = relative_path(finding, repo_root);
+        let (start, end) = line_range(finding);
+        let name = format!("[P{}] {}", finding.priority, finding.title);
+        rendered.push_str(&format!(
+            "    <testcase classname=\"{}\" name=\"{}\" file=\"{}\" line=\"{start}\">\n",
+            escape_xml(&path),
+            escape_xml(&name),
+            escape_xml(&path),
+        ));
+        rendered.push_str(&format!(
+            "      <failure message=\"{}\" type=\"P{}\">{}\n{path}:{start}-{end}</failure>\n",
+            escape_xml(&finding.title),
+            finding.priority,
+            escape_xml(&finding.body),
+            path = escape_xml(&path),
+        ));
+        rendered.push_str("    </testcase>\n");
+    }
+    rendered.push_str("  </testsuite>\n</testsuites>\n");
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::ReviewCodeLocation;
+    use codex_core::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn finding(title: &str, priority: i32, confidence_score: f32) -> ReviewFinding {
+        ReviewFinding {
+            title: title.to_string(),
+            body: "Line one.\nLine two: 50% done".to_string(),
+            confidence_score,
+            priority,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+                line_range: ReviewLineRange { start: 3, end: 5 },
+            },
+            commit_sha: None,
+        }
+    }
+
+    fn output() -> ReviewOutputEvent {
+        ReviewOutputEvent {
+            findings: vec![finding("Off by one", 1, 0.9), finding("Typo", 3, 0.4)],
+            overall_correctness: "patch is incorrect".to_string(),
+            overall_explanation: "Loop bound is wrong.".to_string(),
+            overall_confidence_score: 0.8,
+        }
+    }
+
+    fn report(format: ReviewFormat, fail_on_priority: Option<i32>) -> ReviewReport {
+        ReviewReport {
+            format,
+            fail_on_priority,
+            min_confidence: 0.5,
+        }
+    }
+
+    #[test]
+    fn blocking_findings_respect_priority_and_confidence() {
+        let output = output();
+        let titles = |report: ReviewReport| {
+            report
+                .blocking_findings(&output)
+                .into_iter()
+                .map(|finding| finding.title.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            titles(report(ReviewFormat::Text, None)),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            titles(report(ReviewFormat::Text, Some(0))),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            titles(report(ReviewFormat::Text, Some(3))),
+            vec!["Off by one"]
+        );
+    }
+
+    #[test]
+    fn requires_output_for_structured_formats_and_thresholds() {
+        assert!(!report(ReviewFormat::Text, None).requires_output());
+        assert!(report(ReviewFormat::Text, Some(1)).requires_output());
+        assert!(report(ReviewFormat::Sarif, None).requires_output());
+    }
+
+    #[test]
+    fn json_uses_repo_relative_paths() {
+        let rendered = report(ReviewFormat::Json, None)
+            .render(&output(), Path::new("/repo"))
+            .expect("json output");
+        let parsed: ReviewOutputEvent = serde_json::from_str(&rendered).expect("valid json");
+
+        assert_eq!(
+            parsed.findings[0].code_location.absolute_file_path,
+            PathBuf::from("src/lib.rs")
+        );
+    }
+
+    #[test]
+    fn sarif_reports_results_with_levels_and_regions() {
+        let rendered = report(ReviewFormat::Sarif, None)
+            .render(&output(), Path::new("/repo"))
+            .expect("sarif output");
+        let sarif: Value = serde_json::from_str(&rendered).expect("valid json");
+        let results = &sarif["runs"][0]["results"];
+
+        assert_eq!(sarif["version"], json!("2.1.0"));
+        assert_eq!(results[0]["ruleId"], json!("P1"));
+        assert_eq!(results[0]["level"], json!("error"));
+        assert_eq!(results[1]["level"], json!("note"));
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"],
+            json!({
+                "artifactLocation": { "uri": "src/lib.rs", "uriBaseId": "%SRCROOT%" },
+                "region": { "startLine": 3, "endLine": 5 },
+            })
+        );
+    }
+
+    #[test]
+    fn github_annotations_escape_messages() {
+        let rendered = report(ReviewFormat::GithubAnnotations, None)
+            .render(&output(), Path::new("/repo"))
+            .expect("annotations");
+
+        assert_eq!(
+            rendered,
+            "::error file=src/lib.rs,line=3,endLine=5,title=[P1] Off by one::Line one.%0ALine two: 50%25 done\n\
+             ::notice file=src/lib.rs,line=3,endLine=5,title=[P3] Typo::Line one.%0ALine two: 50%25 done\n"
+        );
+    }
+
+    #[test]
+    fn junit_lists_each_finding_as_a_failure() {
+        let rendered = report(ReviewFormat::Junit, None)
+            .render(&output(), Path::new("/repo"))
+            .expect("junit output");
+
+        assert!(
+            rendered.contains("tests=\"2\" failures=\"2\""),
+            "{rendered}"
+        );
+        assert!(
+            rendered.contains("<failure message=\"Off by one\" type=\"P1\">"),
+            "{rendered}"
+        );
+
+        let empty = ReviewOutputEvent::default();
+        let rendered = report(ReviewFormat::Junit, None)
+            .render(&empty, Path::new("/repo"))
+            .expect("junit output");
+        assert!(
+            rendered.contains("tests=\"1\" failures=\"0\""),
+            "{rendered}"
+        );
+    }
+}
@@ -1267,6 +1267,7 @@ fn task_complete_produces_turn_completed_with_usage() {
         EventMsg::TokenCount(codex_core::protocol::TokenCountEvent {
             info: Some(info),
             rate_limits: None,
+            context_tokens: None,
         }),
     );
     assert!(ep.collect_thread_events(&token_count_event).is_empty());
@@ -0,0 +1,270 @@
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::fs::{self};
+use std::io::Read;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use clap::Parser;
+use serde::Serialize;
+use serde_json::Value;
+use tiny_http::Header;
+use tiny_http::Method;
+use tiny_http::Request;
+use tiny_http::Response;
+use tiny_http::Server;
+use tiny_http::StatusCode;
+
+mod script;
+mod sse;
+pub use script::RequestMatcher;
+pub use script::Rule;
+pub use script::Script;
+pub use script::ScriptEvent;
+pub use script::ScriptState;
+pub use script::ScriptUsage;
+use sse::SseBody;
+use sse::render_rule;
+
+/// CLI arguments for the mock server.
+#[derive(Debug, Clone, Parser)]
+#[command(
+    name = "codex-mock-responses",
+    about = "Scripted stand-in for the OpenAI Responses API"
+)]
+pub struct Args {
+    /// YAML or JSON script describing how to answer requests.
+    #[arg(value_name = "SCRIPT")]
+    pub script: PathBuf,
+
+    /// Port to listen on. If not set, an ephemeral port is used.
+    #[arg(long)]
+    pub port: Option<u16>,
+
+    /// Path to a JSON file to write startup info (single line). Includes {"port": <u16>}.
+    #[arg(long, value_name = "FILE")]
+    pub server_info: Option<PathBuf>,
+
+    /// Enable HTTP shutdown endpoint at GET /shutdown
+    #[arg(long)]
+    pub http_shutdown: bool,
+
+    /// Append a JSON line for every request received to this file.
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct ServerInfo {
+    port: u16,
+    pid: u32,
+}
+
+/// One received request, as appended to the `--record` file.
+#[derive(Serialize)]
+struct RecordedRequest<'a> {
+    method: &'a str,
+    path: &'a str,
+    /// Index of the script rule that answered, if any.
+    rule: Option<usize>,
+    body: &'a Value,
+}
+
+struct MockState {
+    script: Mutex<ScriptState>,
+    record: Option<Mutex<File>>,
+    next_response: Mutex<u64>,
+}
+
+/// Entry point for the library main, for parity with other crates.
+pub fn run_main(args: Args) -> Result<()> {
+    let script = Script::load(&args.script)?;
+    let record = args
+        .record
+        .as_deref()
+        .map(|path| {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("failed to open {}", path.display()))
+                .map(Mutex::new)
+        })
+        .transpose()?;
+    let state = Arc::new(MockState {
+        script: Mutex::new(ScriptState::new(script)),
+        record,
+        next_response: Mutex::new(0),
+    });
+
+    let (listener, bound_addr) = bind_listener(args.port)?;
+    if let Some(path) = args.server_info.as_ref() {
+        write_server_info(path, bound_addr.port())?;
+    }
+    let server = Server::from_listener(listener, None)
+        .map_err(|err| anyhow!("creating HTTP server: {err}"))?;
+
+    eprintln!("codex-mock-responses listening on {bound_addr}");
+
+    let http_shutdown = args.http_shutdown;
+    for request in server.incoming_requests() {
+        let state = state.clone();
+        std::thread::spawn(move || {
+            if http_shutdown && request.method() == &Method::Get && request.url() == "/shutdown" {
+                let _ = request.respond(Response::new_empty(StatusCode(200)));
+                std::process::exit(0);
+            }
+
+            if let Err(e) = handle_request(&state, request) {
+                eprintln!("request error: {e}");
+            }
+        });
+    }
+
+    Err(anyhow!("server stopped unexpectedly"))
+}
+
+fn bind_listener(port: Option<u16>) -> Result<(TcpListener, SocketAddr)> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port.unwrap_or(0)));
+    let listener = TcpListener::bind(addr).with_context(|| format!("failed to bind {addr}"))?;
+    let bound = listener.local_addr().context("failed to read local_addr")?;
+    Ok((listener, bound))
+}
+
+fn write_server_info(path: &Path, port: u16) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    let info = ServerInfo {
+        port,
+        pid: std::process::id(),
+    };
+    let mut data = serde_json::to_string(&info)?;
+    data.push('\n');
+    let mut f = File::create(path)?;
+    f.write_all(data.as_bytes())?;
+    Ok(())
+}
+
+fn handle_request(state: &MockState, mut req: Request) -> Result<()> {
+    let method = req.method().clone();
+    let path = req.url().split('?').next().unwrap_or_default().to_string();
+
+    // Codex may list models before its first turn; an empty list keeps it on the defaults.
+    if method == Method::Get && path.ends_with("/models") {
+        respond_with_json(req, 200, &serde_json::json!({ "models": [] }), &[]);
+        return Ok(());
+    }
+    if method != Method::Post || !path.ends_with("/responses") {
+        let _ = req.respond(Response::new_empty(StatusCode(404)));
+        return Ok(());
+    }
+
+    let mut raw = Vec::new();
+    req.as_reader().read_to_end(&mut raw)?;
+    let body: Value = serde_json::from_slice(&raw).unwrap_or(Value::Null);
+
+    let picked = lock(&state.script).next_rule(&body);
+    record_request(state, method.as_str(), &path, picked.as_ref(), &body)?;
+
+    let Some((idx, rule)) = picked else {
+        eprintln!("no script rule matched request to {path}");
+        respond_with_error(req, 500, "no script rule matched this request");
+        return Ok(());
+    };
+
+    if rule.delay_ms > 0 {
+        std::thread::sleep(Duration::from_millis(rule.delay_ms));
+    }
+    let headers: Vec<Header> = rule
+        .headers
+        .iter()
+        .filter_map(|(name, value)| Header::from_bytes(name.as_bytes(), value.as_bytes()).ok())
+        .collect();
+
+    if let Some(body) = &rule.body {
+        respond_with_json(req, rule.status, body, &headers);
+        return Ok(());
+    }
+
+    let response_id = {
+        let mut next = lock(&state.next_response);
+        *next += 1;
+        format!("resp-{}", *next)
+    };
+    eprintln!("answering {response_id} with rule {idx}");
+    let mut response = Response::new(
+        StatusCode(rule.status),
+        headers,
+        SseBody::new(render_rule(&rule, &response_id)),
+        None,
+        None,
+    );
+    if let Ok(header) = Header::from_bytes("content-type", "text/event-stream") {
+        response.add_header(header);
+    }
+    let _ = req.respond(response);
+    Ok(())
+}
+
+fn record_request(
+    state: &MockState,
+    method: &str,
+    path: &str,
+    picked: Option<&(usize, Rule)>,
+    body: &Value,
+) -> Result<()> {
+    let Some(record) = &state.record else {
+        return Ok(());
+    };
+    let entry = RecordedRequest {
+        method,
+        path,
+        rule: picked.map(|(idx, _)| *idx),
+        body,
+    };
+    let mut line = serde_json::to_string(&entry)?;
+    line.push('\n');
+    lock(record).write_all(line.as_bytes())?;
+    Ok(())
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Answers with an OpenAI-style error body.
+fn respond_with_error(req: Request, status: u16, message: &str) {
+    let body = serde_json::json!({
+        "error": {
+            "message": message,
+            "type": "mock_error",
+        }
+    });
+    respond_with_json(req, status, &body, &[]);
+}
+
+fn respond_with_json(req: Request, status: u16, body: &Value, headers: &[Header]) {
+    let mut response = Response::from_string(body.to_string()).with_status_code(StatusCode(status));
+    if let Ok(header) = Header::from_bytes("content-type", "application/json") {
+        response.add_header(header);
+    }
+    for header in headers {
+        response.add_header(header.clone());
+    }
+    let _ = req.respond(response);
+}
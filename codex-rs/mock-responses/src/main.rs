@@ -0,0 +1,7 @@
+use clap::Parser;
+use codex_mock_responses::Args as MockResponsesArgs;
+
+pub fn main() -> anyhow::Result<()> {
+    let args = MockResponsesArgs::parse();
+    codex_mock_responses::run_main(args)
+}
@@ -0,0 +1,348 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// A script of canned responses. Each request to `/v1/responses` is answered
+/// by the first rule that matches it and has not been used up.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Script {
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    /// Conditions the request must meet. An empty matcher matches every request.
+    #[serde(default, rename = "match")]
+    pub matcher: RequestMatcher,
+    /// How many requests this rule answers before it is skipped.
+    #[serde(default = "default_times")]
+    pub times: u32,
+    /// Answer every matching request, ignoring `times`.
+    #[serde(default)]
+    pub repeat: bool,
+    /// Wait this long before sending the response headers.
+    #[serde(default)]
+    pub delay_ms: u64,
+    #[serde(default = "default_status")]
+    pub status: u16,
+    /// Extra response headers, such as `retry-after` or `x-codex-primary-used-percent`.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Token usage reported in the final `response.completed` event.
+    #[serde(default)]
+    pub usage: ScriptUsage,
+    /// Events streamed as SSE. `response.created` and `response.completed`
+    /// are added automatically.
+    #[serde(default)]
+    pub events: Vec<ScriptEvent>,
+    /// JSON body sent instead of an event stream, typically with an error status.
+    pub body: Option<Value>,
+}
+
+fn default_times() -> u32 {
+    1
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequestMatcher {
+    /// The request's `model` field.
+    pub model: Option<String>,
+    /// Text that appears anywhere in the request body.
+    pub contains: Option<String>,
+    /// Text that appears in the most recent user message.
+    pub user_message_contains: Option<String>,
+    /// The request carries the output of the tool call with this id.
+    pub tool_output_for: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptUsage {
+    #[serde(default)]
+    pub input_tokens: i64,
+    #[serde(default)]
+    pub cached_input_tokens: i64,
+    #[serde(default)]
+    pub output_tokens: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ScriptEvent {
+    /// A complete assistant message.
+    Message { text: String },
+    /// A streamed chunk of assistant text.
+    TextDelta { text: String },
+    /// A reasoning item with the given summary.
+    Reasoning { summary: String },
+    /// A function tool call. `arguments` may be a JSON string or any JSON value.
+    FunctionCall {
+        name: String,
+        arguments: Value,
+        call_id: Option<String>,
+    },
+    /// A `shell_command` tool call running `command`.
+    ShellCommand {
+        command: String,
+        call_id: Option<String>,
+    },
+    /// A freeform tool call, such as `apply_patch`.
+    CustomToolCall {
+        name: String,
+        input: String,
+        call_id: Option<String>,
+    },
+    /// Ends the stream with `response.failed`.
+    Failed { code: String, message: String },
+    /// Pause the stream.
+    Delay { ms: u64 },
+    /// Any other event, sent as-is. It must have a `type` field.
+    Raw { event: Value },
+}
+
+impl Script {
+    /// Loads a script, parsing `.json` files as JSON and anything else as YAML.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let script = if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json(&contents)
+        } else {
+            Self::from_yaml(&contents)
+        };
+        script.with_context(|| format!("invalid script {}", path.display()))
+    }
+
+    pub fn from_json(contents: &str) -> Result<Self> {
+        let script: Script = serde_json::from_str(contents)?;
+        script.validate()?;
+        Ok(script)
+    }
+
+    pub fn from_yaml(contents: &str) -> Result<Self> {
+        let script: Script = serde_yaml::from_str(contents)?;
+        script.validate()?;
+        Ok(script)
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (idx, rule) in self.rules.iter().enumerate() {
+            if rule.body.is_some() && !rule.events.is_empty() {
+                bail!("rule {idx} sets both body and events");
+            }
+            if rule.body.is_none() && rule.status != 200 {
+                bail!("rule {idx} has status {} but no body", rule.status);
+            }
+            for event in &rule.events {
+                if let ScriptEvent::Raw { event } = event
+                    && event.get("type").and_then(Value::as_str).is_none()
+                {
+                    bail!("rule {idx} has a raw event without a type");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RequestMatcher {
+    pub fn matches(&self, body: &Value) -> bool {
+        if let Some(model) = &self.model
+            && body.get("model").and_then(Value::as_str) != Some(model.as_str())
+        {
+            return false;
+        }
+        if let Some(text) = &self.contains
+            && !body.to_string().contains(text.as_str())
+        {
+            return false;
+        }
+        if let Some(text) = &self.user_message_contains
+            && !last_user_message(body).is_some_and(|message| message.contains(text.as_str()))
+        {
+            return false;
+        }
+        if let Some(call_id) = &self.tool_output_for
+            && !input_items(body).any(|item| {
+                matches!(
+                    item.get("type").and_then(Value::as_str),
+                    Some("function_call_output" | "custom_tool_call_output")
+                ) && item.get("call_id").and_then(Value::as_str) == Some(call_id.as_str())
+            })
+        {
+            return false;
+        }
+        true
+    }
+}
+
+fn input_items(body: &Value) -> impl Iterator<Item = &Value> {
+    body.get("input")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+fn last_user_message(body: &Value) -> Option<String> {
+    let message = input_items(body)
+        .filter(|item| {
+            item.get("type").and_then(Value::as_str) == Some("message")
+                && item.get("role").and_then(Value::as_str) == Some("user")
+        })
+        .last()?;
+    let text = message
+        .get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|part| part.get("text").and_then(Value::as_str))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(text)
+}
+
+/// Tracks how often each rule has answered.
+#[derive(Debug)]
+pub struct ScriptState {
+    rules: Vec<Rule>,
+    uses: Vec<u32>,
+}
+
+impl ScriptState {
+    pub fn new(script: Script) -> Self {
+        let uses = vec![0; script.rules.len()];
+        Self {
+            rules: script.rules,
+            uses,
+        }
+    }
+
+    /// Picks the rule that answers `body` and counts it as used.
+    pub fn next_rule(&mut self, body: &Value) -> Option<(usize, Rule)> {
+        let idx = self.rules.iter().enumerate().position(|(idx, rule)| {
+            (rule.repeat || self.uses[idx] < rule.times) && rule.matcher.matches(body)
+        })?;
+        self.uses[idx] += 1;
+        Some((idx, self.rules[idx].clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    const SCRIPT: &str = r#"
+rules:
+  - match:
+      user_message_contains: "list files"
+    events:
+      - type: shell_command
+        command: ls
+        call_id: call-ls
+  - match:
+      tool_output_for: call-ls
+    usage:
+      input_tokens: 120
+      output_tokens: 8
+    events:
+      - type: message
+        text: "Here are your files."
+  - status: 429
+    headers:
+      retry-after: "1"
+    body:
+      error:
+        message: "Rate limit reached"
+    repeat: true
+"#;
+
+    fn request(input: Value) -> Value {
+        json!({ "model": "gpt-test", "input": input })
+    }
+
+    #[test]
+    fn parses_yaml_script() {
+        let script = Script::from_yaml(SCRIPT).expect("parse");
+
+        assert_eq!(script.rules.len(), 3);
+        assert_eq!(
+            script.rules[0].events,
+            vec![ScriptEvent::ShellCommand {
+                command: "ls".to_string(),
+                call_id: Some("call-ls".to_string()),
+            }]
+        );
+        assert_eq!(script.rules[1].usage.input_tokens, 120);
+        assert_eq!(script.rules[2].status, 429);
+        assert_eq!(
+            script.rules[2]
+                .headers
+                .get("retry-after")
+                .map(String::as_str),
+            Some("1")
+        );
+    }
+
+    #[test]
+    fn json_scripts_parse_the_same_way() {
+        let script = Script::from_json(
+            r#"{"rules": [{"match": {"model": "gpt-test"}, "events": [{"type": "message", "text": "hi"}]}]}"#,
+        )
+        .expect("parse");
+
+        assert_eq!(script.rules[0].matcher.model.as_deref(), Some("gpt-test"));
+    }
+
+    #[test]
+    fn rejects_error_status_without_body() {
+        let result = Script::from_yaml("rules:\n  - status: 500\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rules_answer_in_order_until_used_up() {
+        let mut state = ScriptState::new(Script::from_yaml(SCRIPT).expect("parse"));
+        let first = request(json!([
+            {"type": "message", "role": "user", "content": [{"type": "input_text", "text": "please list files"}]}
+        ]));
+        let second = request(json!([
+            {"type": "message", "role": "user", "content": [{"type": "input_text", "text": "please list files"}]},
+            {"type": "function_call", "call_id": "call-ls", "name": "shell_command", "arguments": "{}"},
+            {"type": "function_call_output", "call_id": "call-ls", "output": "README.md"}
+        ]));
+
+        let picked: Vec<Option<usize>> = [&first, &second, &first, &first]
+            .into_iter()
+            .map(|body| state.next_rule(body).map(|(idx, _)| idx))
+            .collect();
+        assert_eq!(picked, vec![Some(0), Some(1), Some(2), Some(2)]);
+    }
+
+    #[test]
+    fn matcher_checks_model_and_body_text() {
+        let matcher = RequestMatcher {
+            model: Some("gpt-test".to_string()),
+            contains: Some("needle".to_string()),
+            ..Default::default()
+        };
+
+        assert!(matcher.matches(&json!({"model": "gpt-test", "instructions": "find the needle"})));
+        assert!(!matcher.matches(&json!({"model": "other", "instructions": "needle"})));
+        assert!(!matcher.matches(&json!({"model": "gpt-test", "instructions": "hay"})));
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::VecDeque;
+use std::io::Read;
+use std::time::Duration;
+
+use serde_json::Value;
+use serde_json::json;
+
+use crate::script::Rule;
+use crate::script::ScriptEvent;
+use crate::script::ScriptUsage;
+
+/// A piece of the event stream: either SSE bytes or a pause.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Chunk {
+    Data(String),
+    Delay(Duration),
+}
+
+/// Turns a rule's events into the SSE stream Codex expects, wrapped in
+/// `response.created` and `response.completed` unless the stream fails.
+pub(crate) fn render_rule(rule: &Rule, response_id: &str) -> Vec<Chunk> {
+    let mut chunks = vec![frame(&json!({
+        "type": "response.created",
+        "response": { "id": response_id },
+    }))];
+    let mut next_call = 0;
+    let mut call_id = |explicit: &Option<String>| {
+        explicit.clone().unwrap_or_else(|| {
+            next_call += 1;
+            format!("{response_id}-call-{next_call}")
+        })
+    };
+
+    for event in &rule.events {
+        let value = match event {
+            ScriptEvent::Message { text } => output_item(json!({
+                "type": "message",
+                "role": "assistant",
+                "content": [{ "type": "output_text", "text": text }],
+            })),
+            ScriptEvent::TextDelta { text } => json!({
+                "type": "response.output_text.delta",
+                "delta": text,
+            }),
+            ScriptEvent::Reasoning { summary } => output_item(json!({
+                "type": "reasoning",
+                "summary": [{ "type": "summary_text", "text": summary }],
+            })),
+            ScriptEvent::FunctionCall {
+                name,
+                arguments,
+                call_id: explicit,
+            } => {
+                let arguments = match arguments {
+                    Value::String(arguments) => arguments.clone(),
+                    other => other.to_string(),
+                };
+                output_item(json!({
+                    "type": "function_call",
+                    "call_id": call_id(explicit),
+                    "name": name,
+                    "arguments": arguments,
+                }))
+            }
+            ScriptEvent::ShellCommand {
+                command,
+                call_id: explicit,
+            } => output_item(json!({
+                "type": "function_call",
+                "call_id": call_id(explicit),
+                "name": "shell_command",
+                "arguments": json!({ "command": command }).to_string(),
+            })),
+            ScriptEvent::CustomToolCall {
+                name,
+                input,
+                call_id: explicit,
+            } => output_item(json!({
+                "type": "custom_tool_call",
+                "call_id": call_id(explicit),
+                "name": name,
+                "input": input,
+            })),
+            ScriptEvent::Failed { code, message } => {
+                chunks.push(frame(&json!({
+                    "type": "response.failed",
+                    "response": {
+                        "id": response_id,
+                        "error": { "code": code, "message": message },
+                    },
+                })));
+                return chunks;
+            }
+            ScriptEvent::Delay { ms } => {
+                chunks.push(Chunk::Delay(Duration::from_millis(*ms)));
+                continue;
+            }
+            ScriptEvent::Raw { event } => event.clone(),
+        };
+        chunks.push(frame(&value));
+    }
+
+    chunks.push(frame(&completed(response_id, rule.usage)));
+    chunks
+}
+
+fn output_item(item: Value) -> Value {
+    json!({ "type": "response.output_item.done", "item": item })
+}
+
+fn completed(response_id: &str, usage: ScriptUsage) -> Value {
+    json!({
+        "type": "response.completed",
+        "response": {
+            "id": response_id,
+            "usage": {
+                "input_tokens": usage.input_tokens,
+                "input_tokens_details": { "cached_tokens": usage.cached_input_tokens },
+                "output_tokens": usage.output_tokens,
+                "output_tokens_details": null,
+                "total_tokens": usage.input_tokens + usage.output_tokens,
+            },
+        },
+    })
+}
+
+fn frame(event: &Value) -> Chunk {
+    let kind = event
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    Chunk::Data(format!("event: {kind}\ndata: {event}\n\n"))
+}
+
+/// Response body that streams chunks, sleeping through delays as it goes.
+pub(crate) struct SseBody {
+    chunks: VecDeque<Chunk>,
+    pending: Vec<u8>,
+    offset: usize,
+}
+
+impl SseBody {
+    pub(crate) fn new(chunks: Vec<Chunk>) -> Self {
+        Self {
+            chunks: chunks.into(),
+            pending: Vec::new(),
+            offset: 0,
+        }
+    }
+}
+
+impl Read for SseBody {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.offset == self.pending.len() {
+            match self.chunks.pop_front() {
+                Some(Chunk::Data(data)) => {
+                    self.pending = data.into_bytes();
+                    self.offset = 0;
+                }
+                Some(Chunk::Delay(delay)) => std::thread::sleep(delay),
+                None => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.pending.len() - self.offset);
+        buf[..n].copy_from_slice(&self.pending[self.offset..self.offset + n]);
+        self.offset += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script::Script;
+    use pretty_assertions::assert_eq;
+
+    fn data_events(chunks: &[Chunk]) -> Vec<Value> {
+        chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Data(data) => data
+                    .lines()
+                    .find_map(|line| line.strip_prefix("data: "))
+                    .and_then(|json| serde_json::from_str(json).ok()),
+                Chunk::Delay(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wraps_events_in_created_and_completed() {
+        let script = Script::from_yaml(
+            r#"
+rules:
+  - usage:
+      input_tokens: 10
+      output_tokens: 2
+    events:
+      - type: shell_command
+        command: ls
+      - type: delay
+        ms: 5
+      - type: message
+        text: done
+"#,
+        )
+        .expect("parse");
+
+        let chunks = render_rule(&script.rules[0], "resp-1");
+        assert_eq!(chunks[2], Chunk::Delay(Duration::from_millis(5)));
+
+        let events = data_events(&chunks);
+        let types: Vec<&str> = events
+            .iter()
+            .filter_map(|event| event["type"].as_str())
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                "response.created",
+                "response.output_item.done",
+                "response.output_item.done",
+                "response.completed",
+            ]
+        );
+        assert_eq!(
+            events[1]["item"],
+            json!({
+                "type": "function_call",
+                "call_id": "resp-1-call-1",
+                "name": "shell_command",
+                "arguments": "{\"command\":\"ls\"}",
+            })
+        );
+        assert_eq!(events[3]["response"]["usage"]["total_tokens"], json!(12));
+    }
+
+    #[test]
+    fn failed_event_ends_the_stream() {
+        let script = Script::from_yaml(
+            r#"
+rules:
+  - events:
+      - type: failed
+        code: server_error
+        message: boom
+      - type: message
+        text: never sent
+"#,
+        )
+        .expect("parse");
+
+        let events = data_events(&render_rule(&script.rules[0], "resp-1"));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1]["type"], json!("response.failed"));
+        assert_eq!(events[1]["response"]["error"]["message"], json!("boom"));
+    }
+
+    #[test]
+    fn body_reads_every_chunk() {
+        let mut body = SseBody::new(vec![
+            Chunk::Data("event: a\n\n".to_string()),
+            Chunk::Delay(Duration::from_millis(1)),
+            Chunk::Data("event: b\n\n".to_string()),
+        ]);
+        let mut out = String::new();
+        body.read_to_string(&mut out).expect("read");
+        assert_eq!(out, "event: a\n\nevent: b\n\n");
+    }
+}
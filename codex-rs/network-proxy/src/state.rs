@@ -205,6 +205,7 @@ fn is_user_controlled_layer(layer: &ConfigLayerSource) -> bool {
     matches!(
         layer,
         ConfigLayerSource::User { .. }
+            | ConfigLayerSource::Conditional { .. }
             | ConfigLayerSource::Project { .. }
             | ConfigLayerSource::SessionFlags
     )
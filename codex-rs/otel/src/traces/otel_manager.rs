@@ -1,5 +1,6 @@
 use crate::metrics::names::API_CALL_COUNT_METRIC;
 use crate::metrics::names::API_CALL_DURATION_METRIC;
+use crate::metrics::names::MODEL_FALLBACK_COUNT_METRIC;
 use crate::metrics::names::SSE_EVENT_COUNT_METRIC;
 use crate::metrics::names::SSE_EVENT_DURATION_METRIC;
 use crate::metrics::names::TOOL_CALL_COUNT_METRIC;
@@ -196,6 +197,35 @@ impl OtelManager {
         );
     }
 
+    /// Records that a turn moved from `from_provider` to `to_provider` after
+    /// the former kept failing. `self` still describes the failed model.
+    pub fn record_model_fallback(&self, from_provider: &str, to_provider: &str, to_model: &str) {
+        self.counter(
+            MODEL_FALLBACK_COUNT_METRIC,
+            1,
+            &[
+                ("from_provider", from_provider),
+                ("to_provider", to_provider),
+            ],
+        );
+        tracing::event!(
+            tracing::Level::INFO,
+            event.name = "codex.model_fallback",
+            event.timestamp = %timestamp(),
+            conversation.id = %self.metadata.conversation_id,
+            app.version = %self.metadata.app_version,
+            auth_mode = self.metadata.auth_mode,
+            user.account_id = self.metadata.account_id,
+            user.email = self.metadata.account_email,
+            terminal.type = %self.metadata.terminal_type,
+            model = %self.metadata.model,
+            slug = %self.metadata.slug,
+            from_provider = %from_provider,
+            to_provider = %to_provider,
+            to_model = %to_model,
+        );
+    }
+
     pub fn record_websocket_request(&self, duration: Duration, error: Option<&str>) {
         let success_str = if error.is_none() { "true" } else { "false" };
         self.counter(
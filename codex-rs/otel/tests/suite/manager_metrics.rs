@@ -103,3 +103,48 @@ fn manager_allows_disabling_metadata_tags() -> Result<()> {
 
     Ok(())
 }
+
+// Ensures model fallbacks are counted per provider pair.
+#[test]
+fn manager_records_model_fallback_counter() -> Result<()> {
+    let (metrics, exporter) = build_metrics_with_defaults(&[])?;
+    let manager = OtelManager::new(
+        ThreadId::new(),
+        "gpt-5.1",
+        "gpt-5.1",
+        Some("account-id".to_string()),
+        None,
+        Some(AuthMode::ApiKey),
+        true,
+        "tty".to_string(),
+        SessionSource::Cli,
+    )
+    .with_metrics_without_metadata_tags(metrics);
+
+    manager.record_model_fallback("openai", "azure", "gpt-5.1");
+    manager.shutdown_metrics()?;
+
+    let resource_metrics = latest_metrics(&exporter);
+    let metric =
+        find_metric(&resource_metrics, "codex.model_fallback").expect("counter metric missing");
+    let attrs = match metric.data() {
+        AggregatedMetrics::U64(data) => match data {
+            MetricData::Sum(sum) => {
+                let points: Vec<_> = sum.data_points().collect();
+                assert_eq!(points.len(), 1);
+                assert_eq!(points[0].value(), 1);
+                attributes_to_map(points[0].attributes())
+            }
+            _ => panic!("unexpected counter aggregation"),
+        },
+        _ => panic!("unexpected counter data type"),
+    };
+
+    let expected = BTreeMap::from([
+        ("from_provider".to_string(), "openai".to_string()),
+        ("to_provider".to_string(), "azure".to_string()),
+    ]);
+    assert_eq!(attrs, expected);
+
+    Ok(())
+}
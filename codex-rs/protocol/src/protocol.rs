@@ -297,9 +297,29 @@ pub enum Op {
     /// responsible for undoing any edits on disk.
     ThreadRollback { num_turns: u32 },
 
+    /// Request a per-item breakdown of the model context with estimated token
+    /// costs. Reply is delivered via `EventMsg::ContextBreakdown`.
+    InspectContext,
+
+    /// Drop or shorten individual history items reported by `InspectContext`
+    /// while keeping every other item verbatim.
+    PruneContextItems {
+        /// Ids from `ContextBreakdownEvent::items`.
+        ids: Vec<String>,
+        action: ContextPruneAction,
+    },
+
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
 
+    /// Ask the model to split the uncommitted changes into commits. Reply is
+    /// delivered via `EventMsg::CommitsProposed`.
+    ProposeCommits,
+
+    /// Create `commits` in order, each from the current worktree contents of
+    /// its paths. Reply is delivered via `EventMsg::CommitsCreated`.
+    CreateCommits { commits: Vec<ProposedCommit> },
+
     /// Request to shut down codex instance.
     Shutdown,
 
@@ -715,6 +735,12 @@ pub enum EventMsg {
     /// Conversation history was rolled back by dropping the last N user turns.
     ThreadRolledBack(ThreadRolledBackEvent),
 
+    /// Individual history items were dropped or shortened by the user.
+    ContextPruned(ContextPrunedEvent),
+
+    /// Old tool outputs were replaced with short stubs to free context space.
+    ToolOutputsAged(ToolOutputsAgedEvent),
+
     /// Agent has started a turn.
     /// v1 wire format uses `task_started`; accept `turn_started` for v2 interop.
     #[serde(rename = "task_started", alias = "turn_started")]
@@ -806,10 +832,24 @@ pub enum EventMsg {
 
     UndoCompleted(UndoCompletedEvent),
 
+    /// Commits drafted in response to `Op::ProposeCommits`.
+    CommitsProposed(CommitsProposedEvent),
+
+    /// Result of `Op::CreateCommits`.
+    CommitsCreated(CommitsCreatedEvent),
+
+    /// Files the session edited that are not committed yet. Recorded in the
+    /// rollout so `Op::ProposeCommits` still focuses on them after a resume.
+    TouchedPathsUpdated(TouchedPathsUpdatedEvent),
+
     /// Notification that a model stream experienced an error or disconnect
     /// and the system is handling it (e.g., retrying with backoff).
     StreamError(StreamErrorEvent),
 
+    /// The rest of the turn is served by a fallback provider or model because
+    /// the previous one kept failing after retries.
+    ModelFallback(ModelFallbackEvent),
+
     /// Notification that the agent is about to apply a code patch. Mirrors
     /// `ExecCommandBegin` so front‑ends can show progress indicators.
     PatchApplyBegin(PatchApplyBeginEvent),
@@ -831,6 +871,9 @@ pub enum EventMsg {
     /// List of skills available to the agent.
     ListSkillsResponse(ListSkillsResponseEvent),
 
+    /// Response to `Op::InspectContext`.
+    ContextBreakdown(ContextBreakdownEvent),
+
     /// List of remote skills available to the agent.
     ListRemoteSkillsResponse(ListRemoteSkillsResponseEvent),
 
@@ -923,6 +966,12 @@ impl From<CollabCloseBeginEvent> for EventMsg {
     }
 }
 
+impl From<ModelFallbackEvent> for EventMsg {
+    fn from(event: ModelFallbackEvent) -> Self {
+        EventMsg::ModelFallback(event)
+    }
+}
+
 impl From<CollabCloseEndEvent> for EventMsg {
     fn from(event: CollabCloseEndEvent) -> Self {
         EventMsg::CollabCloseEnd(event)
@@ -1128,6 +1177,14 @@ pub struct WarningEvent {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ContextCompactedEvent;
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ToolOutputsAgedEvent {
+    /// Call ids whose outputs were replaced with stubs, oldest first.
+    pub call_ids: Vec<String>,
+    /// Estimated number of tokens freed.
+    pub estimated_tokens_saved: i64,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TurnCompleteEvent {
     pub last_agent_message: Option<String>,
@@ -1223,6 +1280,12 @@ impl TokenUsageInfo {
 pub struct TokenCountEvent {
     pub info: Option<TokenUsageInfo>,
     pub rate_limits: Option<RateLimitSnapshot>,
+    /// Tokens the next request is expected to send, counted locally with the
+    /// model's tokenizer: the last reported usage plus items recorded since, or
+    /// the whole history when the provider has not reported usage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub context_tokens: Option<i64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
@@ -1794,6 +1857,19 @@ pub enum ReviewTarget {
         title: Option<String>,
     },
 
+    /// Review the commits reachable from `head` but not from `base`, as in
+    /// `git log base..head`.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    CommitRange {
+        base: String,
+        head: String,
+        /// Review each commit on its own and tag findings with its SHA,
+        /// instead of reviewing the combined diff.
+        #[serde(default)]
+        per_commit: bool,
+    },
+
     /// Arbitrary instructions provided by the user.
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
@@ -1837,6 +1913,11 @@ pub struct ReviewFinding {
     pub confidence_score: f32,
     pub priority: i32,
     pub code_location: ReviewCodeLocation,
+    /// Commit the finding belongs to when each commit of a range is reviewed
+    /// separately.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub commit_sha: Option<String>,
 }
 
 /// Location of the code related to a review finding.
@@ -1863,6 +1944,7 @@ pub enum ExecCommandSource {
     UserShell,
     UnifiedExecStartup,
     UnifiedExecInteraction,
+    Service,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1995,12 +2077,117 @@ pub struct UndoCompletedEvent {
     pub message: Option<String>,
 }
 
+/// One commit in a plan drafted by `Op::ProposeCommits`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ProposedCommit {
+    /// Full commit message: subject line, then an optional body.
+    pub message: String,
+    /// Paths relative to the repository root.
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct CommitsProposedEvent {
+    /// Commits in the order they should be created. Empty when there is
+    /// nothing to commit.
+    pub commits: Vec<ProposedCommit>,
+    /// Changed paths that no proposed commit includes.
+    pub unassigned_paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct CreatedCommit {
+    pub sha: String,
+    pub subject: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct CommitsCreatedEvent {
+    /// Commits created before any failure, oldest first.
+    pub commits: Vec<CreatedCommit>,
+    /// Why the remaining commits were not created, e.g. a rejecting hook.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct TouchedPathsUpdatedEvent {
+    /// Every absolute path the session still tracks, not just the new ones.
+    pub paths: Vec<PathBuf>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ThreadRolledBackEvent {
     /// Number of user turns that were removed from context.
     pub num_turns: u32,
 }
 
+/// How `Op::PruneContextItems` shrinks the selected items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ContextPruneAction {
+    /// Remove the item. Tool outputs are replaced by a one-line placeholder so
+    /// their call stays paired.
+    Drop,
+    /// Keep only the beginning and end of the item's text; images become
+    /// placeholders. Calls and reasoning are left as they are.
+    #[serde(alias = "summarize")]
+    Truncate,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ContextPrunedEvent {
+    /// Ids of the items that were pruned.
+    pub ids: Vec<String>,
+    pub action: ContextPruneAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ContextItemCategory {
+    BaseInstructions,
+    /// AGENTS.md and other user instructions.
+    UserInstructions,
+    Skill,
+    PathInstructions,
+    /// Environment context, restored plans and other session prefixes.
+    SessionContext,
+    UserMessage,
+    AssistantMessage,
+    DeveloperMessage,
+    ToolCall,
+    ToolOutput,
+    Image,
+    Reasoning,
+    Compaction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ContextItemSummary {
+    /// Id to pass to `Op::PruneContextItems`. `None` for items that cannot be
+    /// pruned, such as the base instructions.
+    pub id: Option<String>,
+    pub category: ContextItemCategory,
+    /// Short description: a tool name, the first line of a message, etc.
+    pub label: String,
+    #[ts(type = "number")]
+    pub estimated_tokens: i64,
+}
+
+/// Response payload for `Op::InspectContext`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ContextBreakdownEvent {
+    /// Items in prompt order.
+    pub items: Vec<ContextItemSummary>,
+    #[ts(type = "number")]
+    pub estimated_total_tokens: i64,
+    #[ts(type = "number | null")]
+    pub model_context_window: Option<i64>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct StreamErrorEvent {
     pub message: String,
@@ -2013,6 +2200,26 @@ pub struct StreamErrorEvent {
     pub additional_details: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ModelFallbackEvent {
+    pub from_model_provider: String,
+    pub from_model: String,
+    pub model_provider: String,
+    pub model: String,
+    /// Error from the previous provider that triggered the switch.
+    pub reason: String,
+}
+
+impl ModelFallbackEvent {
+    /// One-line description of the switch for front-ends.
+    pub fn summary(&self) -> String {
+        format!(
+            "`{}` on `{}` is unavailable; using `{}` on `{}` for this turn",
+            self.from_model, self.from_model_provider, self.model, self.model_provider
+        )
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct StreamInfoEvent {
     pub message: String,
@@ -0,0 +1,231 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use anyhow::Context;
+use anyhow::Result;
+use base64::Engine;
+use rand::RngCore;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+
+const TOKEN_PREFIX: &str = "cpx_";
+
+/// Callers the proxy issued tokens to. Only a SHA-256 of each token is stored, so the file
+/// does not grant access by itself.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct ClientRegistry {
+    #[serde(default)]
+    pub(crate) clients: Vec<ClientEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ClientEntry {
+    pub(crate) name: String,
+    token_sha256: String,
+    /// Total tokens (input + output) this client may consume. Unlimited when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) token_budget: Option<u64>,
+}
+
+impl ClientRegistry {
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(raw) => serde_json::from_str(&raw)
+                .with_context(|| format!("parsing clients file {}", path.display())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => {
+                Err(err).with_context(|| format!("reading clients file {}", path.display()))
+            }
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let mut data = serde_json::to_string_pretty(self)?;
+        data.push('\n');
+        std::fs::write(path, data)
+            .with_context(|| format!("writing clients file {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+
+    /// Finds the client a `Bearer` token was issued to.
+    pub(crate) fn authenticate(&self, token: &str) -> Option<&ClientEntry> {
+        let digest = token_digest(token);
+        self.clients
+            .iter()
+            .find(|client| client.token_sha256 == digest)
+    }
+
+    /// Creates (or rotates) the token for `name` and returns it. The plaintext token is only
+    /// available here; the registry keeps its digest.
+    fn issue(&mut self, name: &str, token_budget: Option<u64>) -> String {
+        let mut bytes = [0u8; 32];
+        rand::rng().fill_bytes(&mut bytes);
+        let token = format!(
+            "{TOKEN_PREFIX}{}",
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+        );
+        let entry = ClientEntry {
+            name: name.to_string(),
+            token_sha256: token_digest(&token),
+            token_budget,
+        };
+        match self.clients.iter_mut().find(|client| client.name == name) {
+            Some(existing) => *existing = entry,
+            None => self.clients.push(entry),
+        }
+        token
+    }
+}
+
+/// The clients file as the server sees it. It is re-read whenever its modification time or size
+/// changes, so issuing or rotating a token takes effect without restarting the proxy.
+#[derive(Debug)]
+pub(crate) struct ClientsFile {
+    path: PathBuf,
+    loaded: Mutex<LoadedClients>,
+}
+
+#[derive(Debug)]
+struct LoadedClients {
+    stamp: Option<(SystemTime, u64)>,
+    registry: Arc<ClientRegistry>,
+}
+
+impl ClientsFile {
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let stamp = file_stamp(path);
+        let registry = ClientRegistry::load(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            loaded: Mutex::new(LoadedClients {
+                stamp,
+                registry: Arc::new(registry),
+            }),
+        })
+    }
+
+    /// The current registry. A file that fails to parse (e.g. mid-write) keeps the previous
+    /// registry in place until the next change.
+    pub(crate) fn current(&self) -> Arc<ClientRegistry> {
+        let Ok(mut loaded) = self.loaded.lock() else {
+            return Arc::new(ClientRegistry::default());
+        };
+        let stamp = file_stamp(&self.path);
+        if stamp != loaded.stamp {
+            match ClientRegistry::load(&self.path) {
+                Ok(registry) => {
+                    loaded.registry = Arc::new(registry);
+                    loaded.stamp = stamp;
+                }
+                Err(err) => eprintln!("keeping previous clients: {err:#}"),
+            }
+        }
+        Arc::clone(&loaded.registry)
+    }
+}
+
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Issues a token for `name` in the clients file at `path`, creating the file if needed.
+pub(crate) fn issue_client_token(
+    path: &Path,
+    name: &str,
+    token_budget: Option<u64>,
+) -> Result<String> {
+    let mut registry = ClientRegistry::load(path)?;
+    let token = registry.issue(name, token_budget);
+    registry.save(path)?;
+    Ok(token)
+}
+
+/// Extracts the token from an `Authorization: Bearer <token>` header value.
+pub(crate) fn bearer_token(header_value: &str) -> Option<&str> {
+    header_value
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+fn token_digest(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issued_tokens_authenticate_their_client() {
+        let mut registry = ClientRegistry::default();
+        let alice = registry.issue("alice", Some(1_000));
+        let bob = registry.issue("bob", None);
+
+        assert!(alice.starts_with(TOKEN_PREFIX));
+        assert_eq!(
+            registry
+                .authenticate(&alice)
+                .map(|client| (client.name.as_str(), client.token_budget)),
+            Some(("alice", Some(1_000)))
+        );
+        assert_eq!(
+            registry
+                .authenticate(&bob)
+                .map(|client| client.name.as_str()),
+            Some("bob")
+        );
+        assert!(registry.authenticate("cpx_forged").is_none());
+    }
+
+    #[test]
+    fn reissuing_rotates_the_token() {
+        let mut registry = ClientRegistry::default();
+        let first = registry.issue("alice", None);
+        let second = registry.issue("alice", Some(10));
+
+        assert_eq!(registry.clients.len(), 1);
+        assert!(registry.authenticate(&first).is_none());
+        assert_eq!(
+            registry
+                .authenticate(&second)
+                .and_then(|client| client.token_budget),
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn clients_file_picks_up_new_tokens() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("clients.json");
+        let alice = issue_client_token(&path, "alice", None).unwrap();
+        let clients = ClientsFile::load(&path).unwrap();
+        assert!(clients.current().authenticate(&alice).is_some());
+
+        let bob = issue_client_token(&path, "bob", Some(5)).unwrap();
+        // Adding bob grows the file, so the change is seen even within one mtime tick.
+        let rotated = issue_client_token(&path, "alice", Some(1)).unwrap();
+        let registry = clients.current();
+        assert!(registry.authenticate(&bob).is_some());
+        assert!(registry.authenticate(&alice).is_none());
+        assert!(registry.authenticate(&rotated).is_some());
+    }
+
+    #[test]
+    fn bearer_token_requires_scheme_and_value() {
+        assert_eq!(bearer_token("Bearer cpx_abc"), Some("cpx_abc"));
+        assert_eq!(bearer_token("Bearer "), None);
+        assert_eq!(bearer_token("Basic abc"), None);
+    }
+}
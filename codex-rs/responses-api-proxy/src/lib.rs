@@ -15,6 +15,7 @@ use clap::Parser;
 use reqwest::Url;
 use reqwest::blocking::Client;
 use reqwest::header::AUTHORIZATION;
+use reqwest::header::CONTENT_TYPE;
 use reqwest::header::HOST;
 use reqwest::header::HeaderMap;
 use reqwest::header::HeaderName;
@@ -27,8 +28,20 @@ use tiny_http::Response;
 use tiny_http::Server;
 use tiny_http::StatusCode;
 
+mod clients;
+mod policy;
 mod read_api_key;
+mod usage;
+use clients::ClientEntry;
+use clients::ClientsFile;
+use clients::bearer_token;
+use clients::issue_client_token;
+use policy::RequestPolicy;
 use read_api_key::read_auth_header_from_stdin;
+use usage::ANONYMOUS_CLIENT;
+use usage::BodyKind;
+use usage::MeteredBody;
+use usage::UsageLedger;
 
 /// CLI arguments for the proxy.
 #[derive(Debug, Clone, Parser)]
@@ -49,6 +62,30 @@ pub struct Args {
     /// Absolute URL the proxy should forward requests to (defaults to OpenAI).
     #[arg(long, default_value = "https://api.openai.com/v1/responses")]
     pub upstream_url: String,
+
+    /// Only forward requests for this model. Repeat to allow several; any model when omitted.
+    #[arg(long = "allowed-model", value_name = "MODEL")]
+    pub allowed_models: Vec<String>,
+
+    /// Cap `max_output_tokens` per request. Larger or missing values are replaced with the cap.
+    #[arg(long, value_name = "TOKENS")]
+    pub max_output_tokens: Option<u64>,
+
+    /// JSON file of proxy-issued client tokens. When set, every request must present one.
+    #[arg(long, value_name = "FILE")]
+    pub clients_file: Option<PathBuf>,
+
+    /// Issue a token for NAME in --clients-file, print it, and exit.
+    #[arg(long, value_name = "NAME", requires = "clients_file")]
+    pub issue_token: Option<String>,
+
+    /// Total-token budget for the client created by --issue-token (unlimited when omitted).
+    #[arg(long, value_name = "TOKENS", requires = "issue_token")]
+    pub token_budget: Option<u64>,
+
+    /// Append a JSON line with token usage for every completed response to this file.
+    #[arg(long, value_name = "FILE")]
+    pub usage_log: Option<PathBuf>,
 }
 
 #[derive(Serialize)]
@@ -60,10 +97,19 @@ struct ServerInfo {
 struct ForwardConfig {
     upstream_url: Url,
     host_header: HeaderValue,
+    policy: RequestPolicy,
+    clients: Option<ClientsFile>,
+    ledger: Arc<UsageLedger>,
 }
 
 /// Entry point for the library main, for parity with other crates.
 pub fn run_main(args: Args) -> Result<()> {
+    if let (Some(name), Some(path)) = (args.issue_token.as_deref(), args.clients_file.as_deref()) {
+        let token = issue_client_token(path, name, args.token_budget)?;
+        println!("{token}");
+        return Ok(());
+    }
+
     let auth_header = read_auth_header_from_stdin()?;
 
     let upstream_url = Url::parse(&args.upstream_url).context("parsing --upstream-url")?;
@@ -75,9 +121,20 @@ pub fn run_main(args: Args) -> Result<()> {
     let host_header =
         HeaderValue::from_str(&host).context("constructing Host header from upstream URL")?;
 
+    let clients = args
+        .clients_file
+        .as_deref()
+        .map(ClientsFile::load)
+        .transpose()?;
     let forward_config = Arc::new(ForwardConfig {
         upstream_url,
         host_header,
+        policy: RequestPolicy {
+            allowed_models: args.allowed_models,
+            max_output_tokens: args.max_output_tokens,
+        },
+        clients,
+        ledger: Arc::new(UsageLedger::open(args.usage_log)?),
     });
 
     let (listener, bound_addr) = bind_listener(args.port)?;
@@ -146,10 +203,11 @@ fn forward_request(
     config: &ForwardConfig,
     mut req: Request,
 ) -> Result<()> {
-    // Only allow POST /v1/responses exactly, no query string.
+    // Only allow POST /v1/responses exactly, no query string, plus the usage summary.
     let method = req.method().clone();
     let url_path = req.url().to_string();
-    let allow = method == Method::Post && url_path == "/v1/responses";
+    let is_usage = method == Method::Get && url_path == "/v1/usage";
+    let allow = is_usage || (method == Method::Post && url_path == "/v1/responses");
 
     if !allow {
         let resp = Response::new_empty(StatusCode(403));
@@ -157,11 +215,66 @@ fn forward_request(
         return Ok(());
     }
 
+    // With a clients file, callers authenticate with the token the proxy issued them.
+    let caller = match config.clients.as_ref().map(ClientsFile::current) {
+        Some(registry) => {
+            let token = req
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv("authorization"))
+                .and_then(|header| bearer_token(header.value.as_str()));
+            match token.and_then(|token| registry.authenticate(token)) {
+                Some(client) => Some(client.clone()),
+                None => {
+                    respond_with_error(req, 401, "missing or unknown proxy client token");
+                    return Ok(());
+                }
+            }
+        }
+        None => None,
+    };
+
+    if is_usage {
+        respond_with_usage(req, config, caller.as_ref());
+        return Ok(());
+    }
+
+    let client_name = caller
+        .as_ref()
+        .map_or(ANONYMOUS_CLIENT, |client| client.name.as_str())
+        .to_string();
+    let budget = caller.as_ref().and_then(|client| client.token_budget);
+
     // Read request body
     let mut body = Vec::new();
     let mut reader = req.as_reader();
     std::io::Read::read_to_end(&mut reader, &mut body)?;
 
+    let accepted = match config.policy.apply(body) {
+        Ok(accepted) => accepted,
+        Err(rejection) => {
+            respond_with_error(req, rejection.status, &rejection.message);
+            return Ok(());
+        }
+    };
+
+    // Hold the request's worst case against the budget until its real usage is known.
+    let estimate = usage::reservation_estimate(accepted.body.len(), accepted.max_output_tokens);
+    let reservation = match config.ledger.reserve(&client_name, estimate, budget) {
+        Ok(reservation) => reservation,
+        Err(used) => {
+            let budget = budget.unwrap_or_default();
+            respond_with_error(
+                req,
+                429,
+                &format!(
+                    "token budget exhausted for {client_name} ({used}/{budget} tokens used or reserved)"
+                ),
+            );
+            return Ok(());
+        }
+    };
+
     // Build headers for upstream, forwarding everything from the incoming
     // request except Authorization (we replace it below).
     let mut headers = HeaderMap::new();
@@ -192,7 +305,7 @@ fn forward_request(
     let upstream_resp = client
         .post(config.upstream_url.clone())
         .headers(headers)
-        .body(body)
+        .body(accepted.body)
         .send()
         .context("forwarding request to upstream")?;
 
@@ -224,10 +337,21 @@ fn forward_request(
         }
     });
 
+    let kind = match upstream_resp.headers().get(CONTENT_TYPE) {
+        Some(value) if value.as_bytes().starts_with(b"application/json") => BodyKind::Json,
+        _ => BodyKind::EventStream,
+    };
+    let body = MeteredBody::new(
+        upstream_resp,
+        kind,
+        reservation,
+        accepted.model,
+        status.is_success(),
+    );
     let response = Response::new(
         StatusCode(status.as_u16()),
         response_headers,
-        upstream_resp,
+        body,
         content_length,
         None,
     );
@@ -235,3 +359,43 @@ fn forward_request(
     let _ = req.respond(response);
     Ok(())
 }
+
+/// Answers with an OpenAI-style error body so Codex surfaces the proxy's reason.
+fn respond_with_error(req: Request, status: u16, message: &str) {
+    let body = serde_json::json!({
+        "error": {
+            "message": message,
+            "type": "proxy_error",
+        }
+    });
+    respond_with_json(req, status, &body);
+}
+
+/// Reports accumulated usage: the caller's own totals when clients are configured, otherwise
+/// totals for everyone using the proxy.
+fn respond_with_usage(req: Request, config: &ForwardConfig, caller: Option<&ClientEntry>) {
+    let totals = config.ledger.totals();
+    let clients: Vec<_> = totals
+        .into_iter()
+        .filter(|(name, _)| caller.is_none_or(|caller| &caller.name == name))
+        .map(|(name, usage)| {
+            let token_budget = caller.and_then(|caller| caller.token_budget);
+            serde_json::json!({
+                "client": name,
+                "token_budget": token_budget,
+                "remaining_tokens": token_budget
+                    .map(|budget| budget.saturating_sub(config.ledger.used_tokens(&name))),
+                "usage": usage,
+            })
+        })
+        .collect();
+    respond_with_json(req, 200, &serde_json::json!({ "clients": clients }));
+}
+
+fn respond_with_json(req: Request, status: u16, body: &serde_json::Value) {
+    let mut response = Response::from_string(body.to_string()).with_status_code(StatusCode(status));
+    if let Ok(header) = Header::from_bytes("content-type", "application/json") {
+        response.add_header(header);
+    }
+    let _ = req.respond(response);
+}
@@ -0,0 +1,168 @@
+use serde_json::Value;
+
+/// Per-request limits enforced before a request is forwarded upstream.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RequestPolicy {
+    /// Models callers may request. Empty means any model.
+    pub(crate) allowed_models: Vec<String>,
+    /// Ceiling for `max_output_tokens`.
+    pub(crate) max_output_tokens: Option<u64>,
+}
+
+/// A request body that passed the policy, possibly rewritten.
+#[derive(Debug, PartialEq)]
+pub(crate) struct AcceptedRequest {
+    pub(crate) body: Vec<u8>,
+    pub(crate) model: Option<String>,
+    /// The output ceiling the forwarded body asks for, when it sets one.
+    pub(crate) max_output_tokens: Option<u64>,
+}
+
+/// Why a request was refused, with the HTTP status to answer with.
+#[derive(Debug, PartialEq)]
+pub(crate) struct PolicyRejection {
+    pub(crate) status: u16,
+    pub(crate) message: String,
+}
+
+impl RequestPolicy {
+    fn is_unrestricted(&self) -> bool {
+        self.allowed_models.is_empty() && self.max_output_tokens.is_none()
+    }
+
+    /// Checks the model against the allowlist and caps `max_output_tokens`. A request above the
+    /// cap, or one that leaves it unset, is forwarded with the cap instead.
+    pub(crate) fn apply(&self, body: Vec<u8>) -> Result<AcceptedRequest, PolicyRejection> {
+        let mut payload: Value = match serde_json::from_slice(&body) {
+            Ok(payload) => payload,
+            // Nothing to enforce, so leave malformed bodies for upstream to reject.
+            Err(_) if self.is_unrestricted() => {
+                return Ok(AcceptedRequest {
+                    body,
+                    model: None,
+                    max_output_tokens: None,
+                });
+            }
+            Err(err) => {
+                return Err(PolicyRejection {
+                    status: 400,
+                    message: format!("request body is not valid JSON: {err}"),
+                });
+            }
+        };
+        let model = payload
+            .get("model")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        if !self.allowed_models.is_empty() {
+            match model.as_deref() {
+                Some(model) if self.allowed_models.iter().any(|allowed| allowed == model) => {}
+                Some(model) => {
+                    return Err(PolicyRejection {
+                        status: 403,
+                        message: format!(
+                            "model `{model}` is not allowed by this proxy; allowed models: {}",
+                            self.allowed_models.join(", ")
+                        ),
+                    });
+                }
+                None => {
+                    return Err(PolicyRejection {
+                        status: 400,
+                        message: "request does not name a model".to_string(),
+                    });
+                }
+            }
+        }
+
+        let requested = payload.get("max_output_tokens").and_then(Value::as_u64);
+        let Some(cap) = self.max_output_tokens else {
+            return Ok(AcceptedRequest {
+                body,
+                model,
+                max_output_tokens: requested,
+            });
+        };
+        if requested.is_some_and(|requested| requested <= cap) {
+            return Ok(AcceptedRequest {
+                body,
+                model,
+                max_output_tokens: requested,
+            });
+        }
+        let Some(object) = payload.as_object_mut() else {
+            return Err(PolicyRejection {
+                status: 400,
+                message: "request body must be a JSON object".to_string(),
+            });
+        };
+        object.insert("max_output_tokens".to_string(), Value::from(cap));
+        let body = serde_json::to_vec(&payload).map_err(|err| PolicyRejection {
+            status: 500,
+            message: format!("failed to re-encode request body: {err}"),
+        })?;
+        Ok(AcceptedRequest {
+            body,
+            model,
+            max_output_tokens: Some(cap),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy(models: &[&str], max_output_tokens: Option<u64>) -> RequestPolicy {
+        RequestPolicy {
+            allowed_models: models.iter().map(ToString::to_string).collect(),
+            max_output_tokens,
+        }
+    }
+
+    fn body(value: Value) -> Vec<u8> {
+        serde_json::to_vec(&value).unwrap()
+    }
+
+    #[test]
+    fn unrestricted_policy_forwards_body_untouched() {
+        let raw = b"not json".to_vec();
+        let accepted = policy(&[], None).apply(raw.clone()).unwrap();
+        assert_eq!(accepted.body, raw);
+        assert_eq!(accepted.model, None);
+    }
+
+    #[test]
+    fn rejects_models_outside_allowlist() {
+        let rejection = policy(&["gpt-5.1-codex"], None)
+            .apply(body(json!({"model": "o3"})))
+            .unwrap_err();
+        assert_eq!(rejection.status, 403);
+        assert!(rejection.message.contains("`o3`"));
+
+        let accepted = policy(&["gpt-5.1-codex"], None)
+            .apply(body(json!({"model": "gpt-5.1-codex"})))
+            .unwrap();
+        assert_eq!(accepted.model.as_deref(), Some("gpt-5.1-codex"));
+    }
+
+    #[test]
+    fn caps_max_output_tokens() {
+        let policy = policy(&[], Some(1_000));
+
+        let within = body(json!({"model": "m", "max_output_tokens": 500}));
+        assert_eq!(policy.apply(within.clone()).unwrap().body, within);
+
+        for request in [
+            json!({"model": "m", "max_output_tokens": 5_000}),
+            json!({"model": "m"}),
+        ] {
+            let accepted = policy.apply(body(request)).unwrap();
+            let rewritten: Value = serde_json::from_slice(&accepted.body).unwrap();
+            assert_eq!(rewritten["max_output_tokens"], json!(1_000));
+            assert_eq!(accepted.max_output_tokens, Some(1_000));
+        }
+    }
+}
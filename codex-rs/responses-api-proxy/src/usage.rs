@@ -0,0 +1,585 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use anyhow::Context;
+use anyhow::Result;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Client name recorded when the proxy runs without a clients file.
+pub(crate) const ANONYMOUS_CLIENT: &str = "anonymous";
+/// Output tokens reserved for a request that does not set `max_output_tokens`.
+const DEFAULT_OUTPUT_RESERVATION: u64 = 8_192;
+/// Rough request bytes per input token, used only to size reservations.
+const BYTES_PER_TOKEN: u64 = 4;
+/// Largest non-streaming response body buffered to read its usage.
+const MAX_JSON_BODY_BYTES: usize = 16 * 1024 * 1024;
+/// Response events that end a stream; each may carry the response's usage.
+const TERMINAL_EVENTS: [&str; 3] = [
+    "response.completed",
+    "response.incomplete",
+    "response.failed",
+];
+
+/// One completed response, as appended to the usage log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct UsageRecord {
+    /// Seconds since the Unix epoch.
+    pub(crate) timestamp: u64,
+    pub(crate) client: String,
+    pub(crate) model: Option<String>,
+    pub(crate) response_id: Option<String>,
+    pub(crate) input_tokens: u64,
+    pub(crate) cached_input_tokens: u64,
+    pub(crate) output_tokens: u64,
+    pub(crate) reasoning_output_tokens: u64,
+    pub(crate) total_tokens: u64,
+    /// The response never reported usage (e.g. the caller disconnected mid-stream), so the
+    /// request's reservation was charged instead.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) estimated: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub(crate) struct UsageTotals {
+    pub(crate) requests: u64,
+    pub(crate) input_tokens: u64,
+    pub(crate) cached_input_tokens: u64,
+    pub(crate) output_tokens: u64,
+    pub(crate) total_tokens: u64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, record: &UsageRecord) {
+        self.requests += 1;
+        self.input_tokens += record.input_tokens;
+        self.cached_input_tokens += record.cached_input_tokens;
+        self.output_tokens += record.output_tokens;
+        self.total_tokens += record.total_tokens;
+    }
+}
+
+/// Running per-client totals, optionally backed by an append-only JSONL log that is replayed on
+/// startup so budgets survive restarts. Requests in flight hold a reservation that counts
+/// against the budget until their real usage is known.
+#[derive(Debug, Default)]
+pub(crate) struct UsageLedger {
+    log_path: Option<PathBuf>,
+    state: Mutex<LedgerState>,
+}
+
+#[derive(Debug, Default)]
+struct LedgerState {
+    totals: BTreeMap<String, UsageTotals>,
+    reserved: BTreeMap<String, u64>,
+}
+
+/// Tokens set aside for one in-flight request. Dropping it without settling releases them.
+#[derive(Debug)]
+pub(crate) struct Reservation {
+    ledger: Arc<UsageLedger>,
+    client: String,
+    input_tokens: u64,
+    output_tokens: u64,
+    settled: bool,
+}
+
+impl Reservation {
+    fn tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+
+    /// Replace the reservation with the response's actual usage, if any.
+    fn settle(mut self, record: Option<UsageRecord>) {
+        self.settled = true;
+        self.ledger.release(&self.client, self.tokens());
+        if let Some(record) = record {
+            self.ledger.record(record);
+        }
+    }
+
+    /// Charge the reservation itself when the real usage cannot be known.
+    fn charge(self, model: Option<String>) {
+        let record = UsageRecord {
+            timestamp: now_secs(),
+            client: self.client.clone(),
+            model,
+            response_id: None,
+            input_tokens: self.input_tokens,
+            cached_input_tokens: 0,
+            output_tokens: self.output_tokens,
+            reasoning_output_tokens: 0,
+            total_tokens: self.tokens(),
+            estimated: true,
+        };
+        self.settle(Some(record));
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        if !self.settled {
+            self.ledger.release(&self.client, self.tokens());
+        }
+    }
+}
+
+/// Size of the reservation for a request body of `body_len` bytes.
+pub(crate) fn reservation_estimate(body_len: usize, max_output_tokens: Option<u64>) -> (u64, u64) {
+    let input = u64::try_from(body_len).unwrap_or(u64::MAX) / BYTES_PER_TOKEN;
+    (
+        input,
+        max_output_tokens.unwrap_or(DEFAULT_OUTPUT_RESERVATION),
+    )
+}
+
+impl UsageLedger {
+    pub(crate) fn open(log_path: Option<PathBuf>) -> Result<Self> {
+        let mut totals: BTreeMap<String, UsageTotals> = BTreeMap::new();
+        if let Some(path) = log_path.as_deref() {
+            for record in read_records(path)? {
+                totals
+                    .entry(record.client.clone())
+                    .or_default()
+                    .add(&record);
+            }
+        }
+        Ok(Self {
+            log_path,
+            state: Mutex::new(LedgerState {
+                totals,
+                reserved: BTreeMap::new(),
+            }),
+        })
+    }
+
+    pub(crate) fn record(&self, record: UsageRecord) {
+        if let Some(path) = self.log_path.as_deref()
+            && let Err(err) = append_record(path, &record)
+        {
+            eprintln!("failed to write usage log: {err}");
+        }
+        if let Ok(mut state) = self.state.lock() {
+            state
+                .totals
+                .entry(record.client.clone())
+                .or_default()
+                .add(&record);
+        }
+    }
+
+    /// Reserve `input_tokens + output_tokens` for `client`. Fails with the tokens already used
+    /// or reserved when adding this request would exceed `budget`, so concurrent requests cannot
+    /// all slip in under a nearly spent budget.
+    pub(crate) fn reserve(
+        self: &Arc<Self>,
+        client: &str,
+        (input_tokens, output_tokens): (u64, u64),
+        budget: Option<u64>,
+    ) -> std::result::Result<Reservation, u64> {
+        let Ok(mut state) = self.state.lock() else {
+            return Err(0);
+        };
+        let committed = state.committed(client);
+        let requested = input_tokens + output_tokens;
+        if budget.is_some_and(|budget| committed + requested > budget) {
+            return Err(committed);
+        }
+        *state.reserved.entry(client.to_string()).or_default() += requested;
+        Ok(Reservation {
+            ledger: Arc::clone(self),
+            client: client.to_string(),
+            input_tokens,
+            output_tokens,
+            settled: false,
+        })
+    }
+
+    fn release(&self, client: &str, tokens: u64) {
+        if let Ok(mut state) = self.state.lock()
+            && let Some(reserved) = state.reserved.get_mut(client)
+        {
+            *reserved = reserved.saturating_sub(tokens);
+            if *reserved == 0 {
+                state.reserved.remove(client);
+            }
+        }
+    }
+
+    /// Tokens recorded for `client` plus those reserved by its in-flight requests.
+    pub(crate) fn used_tokens(&self, client: &str) -> u64 {
+        self.state
+            .lock()
+            .map(|state| state.committed(client))
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn totals(&self) -> BTreeMap<String, UsageTotals> {
+        self.state
+            .lock()
+            .map(|state| state.totals.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl LedgerState {
+    fn committed(&self, client: &str) -> u64 {
+        let used = self
+            .totals
+            .get(client)
+            .map_or(0, |usage| usage.total_tokens);
+        used + self.reserved.get(client).copied().unwrap_or(0)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+fn read_records(path: &Path) -> Result<Vec<UsageRecord>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("reading usage log {}", path.display()));
+        }
+    };
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        // Skip partial lines left by an interrupted write rather than refusing to start.
+        if let Ok(record) = serde_json::from_str::<UsageRecord>(&line) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+fn append_record(path: &Path, record: &UsageRecord) -> Result<()> {
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// How the upstream response carries usage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BodyKind {
+    /// An SSE stream (`"stream": true`) whose terminal event holds the response.
+    EventStream,
+    /// A single JSON response object (`"stream": false`).
+    Json,
+}
+
+/// Wraps an upstream Responses body, passing bytes through unchanged while looking for the
+/// response's usage: in the terminal `data:` event of a stream, or in a plain JSON body. The
+/// request's reservation is settled with that usage as soon as it is seen. If the body is
+/// dropped first, for example because the caller disconnected, a successful response is charged
+/// the full reservation since upstream may still have produced those tokens.
+pub(crate) struct MeteredBody<R> {
+    inner: R,
+    kind: BodyKind,
+    reservation: Option<Reservation>,
+    client: String,
+    model: Option<String>,
+    /// Charge the reservation when no usage was seen (the upstream request succeeded).
+    charge_unmetered: bool,
+    pending: Vec<u8>,
+}
+
+impl<R> MeteredBody<R> {
+    pub(crate) fn new(
+        inner: R,
+        kind: BodyKind,
+        reservation: Reservation,
+        model: Option<String>,
+        charge_unmetered: bool,
+    ) -> Self {
+        Self {
+            inner,
+            kind,
+            client: reservation.client.clone(),
+            reservation: Some(reservation),
+            model,
+            charge_unmetered,
+            pending: Vec::new(),
+        }
+    }
+
+    fn observe(&mut self, bytes: &[u8]) {
+        if self.reservation.is_none() {
+            return;
+        }
+        if self.kind == BodyKind::Json {
+            if self.pending.len() + bytes.len() <= MAX_JSON_BODY_BYTES {
+                self.pending.extend_from_slice(bytes);
+            } else {
+                // Too large to parse; settle on the reservation once the body ends.
+                self.pending.clear();
+                self.kind = BodyKind::EventStream;
+            }
+            return;
+        }
+        for &byte in bytes {
+            if byte != b'\n' {
+                self.pending.push(byte);
+                continue;
+            }
+            let line = std::mem::take(&mut self.pending);
+            if let Some(usage) = self.terminal_event_usage(&line) {
+                self.settle(usage);
+                return;
+            }
+        }
+    }
+
+    /// Called at end of body: a JSON response is complete now.
+    fn finish(&mut self) {
+        if self.reservation.is_none() || self.kind != BodyKind::Json {
+            return;
+        }
+        let body = std::mem::take(&mut self.pending);
+        if let Ok(response) = serde_json::from_slice::<Value>(&body)
+            && response.get("object").and_then(Value::as_str) == Some("response")
+        {
+            let usage = self.usage_record(&response);
+            self.settle(usage);
+        }
+    }
+
+    fn settle(&mut self, usage: Option<UsageRecord>) {
+        if let Some(reservation) = self.reservation.take() {
+            reservation.settle(usage);
+        }
+    }
+
+    /// `Some` once a terminal event arrives, holding its usage if the event reported any.
+    fn terminal_event_usage(&self, line: &[u8]) -> Option<Option<UsageRecord>> {
+        let data = line.strip_prefix(b"data:")?;
+        let event: Value = serde_json::from_slice(data.trim_ascii()).ok()?;
+        let kind = event.get("type").and_then(Value::as_str)?;
+        if !TERMINAL_EVENTS.contains(&kind) {
+            return None;
+        }
+        Some(
+            event
+                .get("response")
+                .and_then(|response| self.usage_record(response)),
+        )
+    }
+
+    fn usage_record(&self, response: &Value) -> Option<UsageRecord> {
+        let usage = response.get("usage").filter(|usage| usage.is_object())?;
+        let count = |value: Option<&Value>| value.and_then(Value::as_u64).unwrap_or(0);
+        let input_tokens = count(usage.get("input_tokens"));
+        let output_tokens = count(usage.get("output_tokens"));
+        Some(UsageRecord {
+            timestamp: now_secs(),
+            client: self.client.clone(),
+            model: response
+                .get("model")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .or_else(|| self.model.clone()),
+            response_id: response
+                .get("id")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            input_tokens,
+            cached_input_tokens: count(
+                usage
+                    .get("input_tokens_details")
+                    .and_then(|details| details.get("cached_tokens")),
+            ),
+            output_tokens,
+            reasoning_output_tokens: count(
+                usage
+                    .get("output_tokens_details")
+                    .and_then(|details| details.get("reasoning_tokens")),
+            ),
+            total_tokens: usage
+                .get("total_tokens")
+                .and_then(Value::as_u64)
+                .unwrap_or(input_tokens + output_tokens),
+            estimated: false,
+        })
+    }
+}
+
+impl<R: Read> Read for MeteredBody<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read == 0 {
+            self.finish();
+        } else {
+            self.observe(&buf[..read]);
+        }
+        Ok(read)
+    }
+}
+
+impl<R> Drop for MeteredBody<R> {
+    fn drop(&mut self) {
+        let Some(reservation) = self.reservation.take() else {
+            return;
+        };
+        if self.charge_unmetered {
+            reservation.charge(self.model.clone());
+        } else {
+            reservation.settle(None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPLETED_STREAM: &str = concat!(
+        "event: response.created\n",
+        "data: {\"type\":\"response.created\",\"response\":{\"id\":\"resp_1\"}}\n\n",
+        "event: response.completed\n",
+        "data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_1\",\"model\":\"gpt-5.1-codex\",",
+        "\"usage\":{\"input_tokens\":120,\"input_tokens_details\":{\"cached_tokens\":100},",
+        "\"output_tokens\":30,\"output_tokens_details\":{\"reasoning_tokens\":10},\"total_tokens\":150}}}\n\n",
+    );
+
+    fn metered(
+        body: &'static str,
+        kind: BodyKind,
+        ledger: &Arc<UsageLedger>,
+        client: &str,
+    ) -> MeteredBody<&'static [u8]> {
+        let reservation = ledger.reserve(client, (50, 1_000), None).expect("reserve");
+        MeteredBody::new(
+            body.as_bytes(),
+            kind,
+            reservation,
+            Some("gpt-5.1-codex".to_string()),
+            true,
+        )
+    }
+
+    #[test]
+    fn records_usage_from_completed_event_split_across_reads() {
+        let ledger = Arc::new(UsageLedger::default());
+        let mut body = metered(COMPLETED_STREAM, BodyKind::EventStream, &ledger, "alice");
+        // The reservation counts until the usage arrives.
+        assert_eq!(ledger.used_tokens("alice"), 1_050);
+
+        // Read in small chunks so the completed event straddles several reads.
+        let mut forwarded = Vec::new();
+        let mut chunk = [0u8; 7];
+        loop {
+            let read = body.read(&mut chunk).unwrap();
+            if read == 0 {
+                break;
+            }
+            forwarded.extend_from_slice(&chunk[..read]);
+        }
+        drop(body);
+
+        assert_eq!(forwarded, COMPLETED_STREAM.as_bytes());
+        assert_eq!(ledger.used_tokens("alice"), 150);
+        assert_eq!(
+            ledger.totals().get("alice"),
+            Some(&UsageTotals {
+                requests: 1,
+                input_tokens: 120,
+                cached_input_tokens: 100,
+                output_tokens: 30,
+                total_tokens: 150,
+            })
+        );
+    }
+
+    #[test]
+    fn records_usage_from_incomplete_and_failed_events() {
+        let ledger = Arc::new(UsageLedger::default());
+        for stream in [
+            "data: {\"type\":\"response.incomplete\",\"response\":{\"usage\":{\"input_tokens\":10,\"output_tokens\":5}}}\n\n",
+            "data: {\"type\":\"response.failed\",\"response\":{\"usage\":{\"input_tokens\":7,\"output_tokens\":0}}}\n\n",
+        ] {
+            let mut body = metered(stream, BodyKind::EventStream, &ledger, "alice");
+            std::io::copy(&mut body, &mut std::io::sink()).unwrap();
+        }
+
+        assert_eq!(ledger.used_tokens("alice"), 22);
+        assert_eq!(ledger.totals()["alice"].requests, 2);
+    }
+
+    #[test]
+    fn failed_event_without_usage_releases_the_reservation() {
+        let ledger = Arc::new(UsageLedger::default());
+        let stream = "data: {\"type\":\"response.failed\",\"response\":{\"usage\":null}}\n\n";
+        let mut body = metered(stream, BodyKind::EventStream, &ledger, ANONYMOUS_CLIENT);
+        std::io::copy(&mut body, &mut std::io::sink()).unwrap();
+        drop(body);
+
+        assert!(ledger.totals().is_empty());
+        assert_eq!(ledger.used_tokens(ANONYMOUS_CLIENT), 0);
+    }
+
+    #[test]
+    fn records_usage_from_non_streaming_json() {
+        let ledger = Arc::new(UsageLedger::default());
+        let json = "{\"id\":\"resp_2\",\"object\":\"response\",\"model\":\"gpt-5.1-codex\",\"usage\":{\"input_tokens\":40,\"output_tokens\":2,\"total_tokens\":42}}";
+        let mut body = metered(json, BodyKind::Json, &ledger, "alice");
+        std::io::copy(&mut body, &mut std::io::sink()).unwrap();
+        drop(body);
+
+        assert_eq!(ledger.used_tokens("alice"), 42);
+    }
+
+    #[test]
+    fn early_disconnect_charges_the_reservation() {
+        let ledger = Arc::new(UsageLedger::default());
+        let mut body = metered(COMPLETED_STREAM, BodyKind::EventStream, &ledger, "alice");
+        let mut chunk = [0u8; 16];
+        body.read_exact(&mut chunk).unwrap();
+        drop(body);
+
+        assert_eq!(ledger.used_tokens("alice"), 1_050);
+        assert_eq!(ledger.totals()["alice"].requests, 1);
+    }
+
+    #[test]
+    fn reservations_count_against_the_budget() {
+        let ledger = Arc::new(UsageLedger::default());
+        let first = ledger
+            .reserve("alice", (0, 600), Some(1_000))
+            .expect("fits");
+        assert_eq!(
+            ledger.reserve("alice", (0, 600), Some(1_000)).err(),
+            Some(600)
+        );
+        let second = ledger
+            .reserve("alice", (0, 400), Some(1_000))
+            .expect("fits exactly");
+        assert_eq!(
+            ledger.reserve("alice", (0, 1), Some(1_000)).err(),
+            Some(1_000)
+        );
+
+        drop(first);
+        drop(second);
+        assert_eq!(ledger.used_tokens("alice"), 0);
+        assert!(ledger.reserve("alice", (0, 600), Some(1_000)).is_ok());
+    }
+}
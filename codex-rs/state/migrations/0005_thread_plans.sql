@@ -0,0 +1,7 @@
+CREATE TABLE thread_plans (
+    thread_id TEXT PRIMARY KEY,
+    explanation TEXT,
+    steps TEXT NOT NULL,
+    updated_at INTEGER NOT NULL,
+    FOREIGN KEY(thread_id) REFERENCES threads(id) ON DELETE CASCADE
+);
@@ -17,6 +17,9 @@ use chrono::Utc;
 use codex_otel::OtelManager;
 use codex_protocol::ThreadId;
 use codex_protocol::dynamic_tools::DynamicToolSpec;
+use codex_protocol::plan_tool::PlanItemArg;
+use codex_protocol::plan_tool::UpdatePlanArgs;
+use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::RolloutItem;
 use log::LevelFilter;
 use serde_json::Value;
@@ -151,6 +154,31 @@ ORDER BY position ASC
         Ok(Some(tools))
     }
 
+    /// Get the latest `update_plan` checklist recorded for a thread, if any.
+    pub async fn get_thread_plan(
+        &self,
+        thread_id: ThreadId,
+    ) -> anyhow::Result<Option<UpdatePlanArgs>> {
+        let row = sqlx::query(
+            r#"
+SELECT explanation, steps
+FROM thread_plans
+WHERE thread_id = ?
+            "#,
+        )
+        .bind(thread_id.to_string())
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let steps: String = row.try_get("steps")?;
+        Ok(Some(UpdatePlanArgs {
+            explanation: row.try_get("explanation")?,
+            plan: serde_json::from_str::<Vec<PlanItemArg>>(steps.as_str())?,
+        }))
+    }
+
     /// Find a rollout path by thread id using the underlying database.
     pub async fn find_rollout_path_by_id(
         &self,
@@ -445,6 +473,36 @@ ON CONFLICT(thread_id, position) DO NOTHING
         Ok(())
     }
 
+    /// Replace the stored `update_plan` checklist for a thread.
+    pub async fn persist_thread_plan(
+        &self,
+        thread_id: ThreadId,
+        plan: &UpdatePlanArgs,
+    ) -> anyhow::Result<()> {
+        let steps = serde_json::to_string(&plan.plan)?;
+        sqlx::query(
+            r#"
+INSERT INTO thread_plans (
+    thread_id,
+    explanation,
+    steps,
+    updated_at
+) VALUES (?, ?, ?, ?)
+ON CONFLICT(thread_id) DO UPDATE SET
+    explanation = excluded.explanation,
+    steps = excluded.steps,
+    updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(thread_id.to_string())
+        .bind(plan.explanation.as_deref())
+        .bind(steps)
+        .bind(datetime_to_epoch_seconds(Utc::now()))
+        .execute(self.pool.as_ref())
+        .await?;
+        Ok(())
+    }
+
     /// Apply rollout items incrementally using the underlying database.
     pub async fn apply_rollout_items(
         &self,
@@ -466,8 +524,8 @@ ON CONFLICT(thread_id, position) DO NOTHING
         if let Some(updated_at) = file_modified_time_utc(builder.rollout_path.as_path()).await {
             metadata.updated_at = updated_at;
         }
-        // Keep the thread upsert before dynamic tools to satisfy the foreign key constraint:
-        // thread_dynamic_tools.thread_id -> threads.id.
+        // Keep the thread upsert before dynamic tools and plans to satisfy the foreign key
+        // constraints: thread_dynamic_tools.thread_id and thread_plans.thread_id -> threads.id.
         if let Err(err) = self.upsert_thread(&metadata).await {
             if let Some(otel) = otel {
                 otel.counter(DB_ERROR_METRIC, 1, &[("stage", "apply_rollout_items")]);
@@ -485,6 +543,14 @@ ON CONFLICT(thread_id, position) DO NOTHING
             }
             return Err(err);
         }
+        if let Some(plan) = extract_latest_plan(items)
+            && let Err(err) = self.persist_thread_plan(builder.id, plan).await
+        {
+            if let Some(otel) = otel {
+                otel.counter(DB_ERROR_METRIC, 1, &[("stage", "persist_thread_plan")]);
+            }
+            return Err(err);
+        }
         Ok(())
     }
 
@@ -606,6 +672,17 @@ fn extract_dynamic_tools(items: &[RolloutItem]) -> Option<Option<Vec<DynamicTool
     })
 }
 
+fn extract_latest_plan(items: &[RolloutItem]) -> Option<&UpdatePlanArgs> {
+    items.iter().rev().find_map(|item| match item {
+        RolloutItem::EventMsg(EventMsg::PlanUpdate(plan)) => Some(plan),
+        RolloutItem::SessionMeta(_)
+        | RolloutItem::ResponseItem(_)
+        | RolloutItem::Compacted(_)
+        | RolloutItem::TurnContext(_)
+        | RolloutItem::EventMsg(_) => None,
+    })
+}
+
 async fn open_sqlite(path: &Path) -> anyhow::Result<SqlitePool> {
     let options = SqliteConnectOptions::new()
         .filename(path)
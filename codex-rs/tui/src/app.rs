@@ -53,6 +53,7 @@ use codex_core::protocol::EventMsg;
 use codex_core::protocol::FinalOutput;
 use codex_core::protocol::ListSkillsResponseEvent;
 use codex_core::protocol::Op;
+use codex_core::protocol::ProposedCommit;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::SkillErrorInfo;
@@ -1554,6 +1555,9 @@ impl App {
             AppEvent::FileSearchResult { query, matches } => {
                 self.chat_widget.apply_file_search_result(query, matches);
             }
+            AppEvent::PromptDirectivesExpanded { id, result } => {
+                self.chat_widget.on_prompt_directives_expanded(id, result);
+            }
             AppEvent::RateLimitSnapshotFetched(snapshot) => {
                 self.chat_widget.on_rate_limit_snapshot(Some(snapshot));
             }
@@ -2152,6 +2156,12 @@ impl App {
             AppEvent::OpenReviewCommitPicker(cwd) => {
                 self.chat_widget.show_review_commit_picker(&cwd).await;
             }
+            AppEvent::OpenReviewRangePrompt => {
+                self.chat_widget.show_review_range_prompt();
+            }
+            AppEvent::OpenReviewRangeModePicker(range) => {
+                self.chat_widget.show_review_range_mode_picker(&range);
+            }
             AppEvent::OpenReviewCustomPrompt => {
                 self.chat_widget.show_review_custom_prompt();
             }
@@ -2165,6 +2175,14 @@ impl App {
             AppEvent::ManageSkillsClosed => {
                 self.chat_widget.handle_manage_skills_closed();
             }
+            AppEvent::EditCommitMessage {
+                commits,
+                unassigned_paths,
+                index,
+            } => {
+                self.edit_commit_message(tui, commits, unassigned_paths, index)
+                    .await;
+            }
             AppEvent::FullScreenApprovalRequest(request) => match request {
                 ApprovalRequest::ApplyPatch { cwd, changes, .. } => {
                     let _ = tui.enter_alt_screen();
@@ -2377,6 +2395,59 @@ impl App {
         tui.frame_requester().schedule_frame();
     }
 
+    /// Opens one message of a `/commit` plan in the external editor, then
+    /// reopens the plan with the edited message.
+    async fn edit_commit_message(
+        &mut self,
+        tui: &mut tui::Tui,
+        mut commits: Vec<ProposedCommit>,
+        unassigned_paths: Vec<PathBuf>,
+        index: usize,
+    ) {
+        match external_editor::resolve_editor_command() {
+            Ok(editor_cmd) => {
+                if let Some(commit) = commits.get_mut(index) {
+                    let seed = commit.message.clone();
+                    let editor_result = tui
+                        .with_restored(tui::RestoreMode::KeepRaw, || async {
+                            external_editor::run_editor(&seed, &editor_cmd).await
+                        })
+                        .await;
+                    match editor_result {
+                        Ok(new_message) => {
+                            let new_message = new_message.trim();
+                            if !new_message.is_empty() {
+                                commit.message = new_message.to_string();
+                            }
+                        }
+                        Err(err) => {
+                            self.chat_widget
+                                .add_to_history(history_cell::new_error_event(format!(
+                                    "Failed to open editor: {err}",
+                                )));
+                        }
+                    }
+                }
+            }
+            Err(external_editor::EditorError::MissingEditor) => {
+                self.chat_widget
+                    .add_to_history(history_cell::new_error_event(
+                    "Cannot open external editor: set $VISUAL or $EDITOR before starting Codex."
+                        .to_string(),
+                ));
+            }
+            Err(err) => {
+                self.chat_widget
+                    .add_to_history(history_cell::new_error_event(format!(
+                        "Failed to open editor: {err}",
+                    )));
+            }
+        }
+        self.chat_widget
+            .open_commit_plan(commits, unassigned_paths, index);
+        tui.frame_requester().schedule_frame();
+    }
+
     fn request_external_editor_launch(&mut self, tui: &mut tui::Tui) {
         self.chat_widget
             .set_external_editor_state(ExternalEditorState::Requested);
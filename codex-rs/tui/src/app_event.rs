@@ -13,12 +13,14 @@ use std::path::PathBuf;
 use codex_chatgpt::connectors::AppInfo;
 use codex_common::approval_presets::ApprovalPreset;
 use codex_core::protocol::Event;
+use codex_core::protocol::ProposedCommit;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_file_search::FileMatch;
 use codex_protocol::ThreadId;
 use codex_protocol::openai_models::ModelPreset;
 
 use crate::bottom_pane::ApprovalRequest;
+use crate::bottom_pane::PromptExpansion;
 use crate::history_cell::HistoryCell;
 
 use codex_core::features::Feature;
@@ -92,6 +94,13 @@ pub(crate) enum AppEvent {
         matches: Vec<FileMatch>,
     },
 
+    /// Result of expanding a custom prompt's `@{file}` and `!{command}`
+    /// directives in the background. `id` identifies the composer request.
+    PromptDirectivesExpanded {
+        id: u64,
+        result: Result<PromptExpansion, String>,
+    },
+
     /// Result of refreshing rate limits
     RateLimitSnapshotFetched(RateLimitSnapshot),
 
@@ -258,6 +267,14 @@ pub(crate) enum AppEvent {
     /// Notify that the manage skills popup was closed.
     ManageSkillsClosed,
 
+    /// Edit the message of commit `index` from a `/commit` plan in the
+    /// external editor, then reopen the plan.
+    EditCommitMessage {
+        commits: Vec<ProposedCommit>,
+        unassigned_paths: Vec<PathBuf>,
+        index: usize,
+    },
+
     /// Re-open the permissions presets popup.
     OpenPermissionsPopup,
 
@@ -267,6 +284,13 @@ pub(crate) enum AppEvent {
     /// Open the commit picker option from the review popup.
     OpenReviewCommitPicker(PathBuf),
 
+    /// Open the commit range prompt from the review popup.
+    OpenReviewRangePrompt,
+
+    /// Ask whether to review a typed `base..head` range as one change or
+    /// commit by commit.
+    OpenReviewRangeModePicker(String),
+
     /// Open the custom prompt option from the review popup.
     OpenReviewCustomPrompt,
 
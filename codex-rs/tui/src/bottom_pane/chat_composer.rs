@@ -131,6 +131,7 @@ use super::skill_popup::MentionItem;
 use super::skill_popup::SkillPopup;
 use super::slash_commands;
 use crate::bottom_pane::paste_burst::FlushResult;
+use crate::bottom_pane::prompt_args::PromptExpansion;
 use crate::bottom_pane::prompt_args::expand_custom_prompt;
 use crate::bottom_pane::prompt_args::expand_if_numeric_with_positional_args;
 use crate::bottom_pane::prompt_args::parse_slash_name;
@@ -143,6 +144,9 @@ use crate::render::renderable::Renderable;
 use crate::slash_command::SlashCommand;
 use crate::style::user_message_style;
 use codex_common::fuzzy_match::fuzzy_match;
+use codex_core::custom_prompts::PromptDirectiveContext;
+use codex_core::custom_prompts::expand_prompt_directives;
+use codex_core::custom_prompts::prompt_has_directives;
 use codex_protocol::custom_prompts::CustomPrompt;
 use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 use codex_protocol::models::local_image_label_text;
@@ -278,6 +282,11 @@ pub(crate) struct ChatComposer {
     // When true, disables paste-burst logic and inserts characters immediately.
     disable_paste_burst: bool,
     custom_prompts: Vec<CustomPrompt>,
+    /// Where `@{file}` and `!{command}` prompt directives are expanded.
+    prompt_directive_context: Option<PromptDirectiveContext>,
+    /// Directive expansion running in the background, if any.
+    pending_prompt_expansion: Option<PendingPromptExpansion>,
+    next_prompt_expansion_id: u64,
     footer_mode: FooterMode,
     footer_hint_override: Option<Vec<(String, String)>>,
     footer_flash: Option<FooterFlash>,
@@ -303,6 +312,15 @@ struct FooterFlash {
     expires_at: Instant,
 }
 
+/// A directive expansion started from the composer. `text` is the composer
+/// content at the time so a late result does not clobber later edits.
+#[derive(Debug)]
+struct PendingPromptExpansion {
+    id: u64,
+    command: String,
+    text: String,
+}
+
 /// Popup state – at most one can be visible at any time.
 enum ActivePopup {
     None,
@@ -368,6 +386,9 @@ impl ChatComposer {
             paste_burst: PasteBurst::default(),
             disable_paste_burst: false,
             custom_prompts: Vec::new(),
+            prompt_directive_context: None,
+            pending_prompt_expansion: None,
+            next_prompt_expansion_id: 0,
             footer_mode: FooterMode::ComposerEmpty,
             footer_hint_override: None,
             footer_flash: None,
@@ -1118,6 +1139,18 @@ impl ChatComposer {
                     && let Some(expanded) =
                         expand_if_numeric_with_positional_args(prompt, first_line, &text_elements)
                 {
+                    if prompt_has_directives(&prompt.content) {
+                        let prompt = prompt.clone();
+                        let first_line = first_line.to_string();
+                        self.preview_prompt_with_directives(&prompt, move |resolved| {
+                            Ok(expand_if_numeric_with_positional_args(
+                                resolved,
+                                &first_line,
+                                &text_elements,
+                            ))
+                        });
+                        return (InputResult::None, true);
+                    }
                     self.prune_attached_images_for_submission(
                         &expanded.text,
                         &expanded.text_elements,
@@ -1141,12 +1174,44 @@ impl ChatComposer {
                         }
                         CommandItem::UserPrompt(idx) => {
                             if let Some(prompt) = popup.prompt(idx) {
+                                let prompt = prompt.clone();
                                 match prompt_selection_action(
-                                    prompt,
+                                    &prompt,
                                     first_line,
                                     PromptSelectionMode::Submit,
                                     &self.textarea.text_elements(),
                                 ) {
+                                    PromptSelectionAction::Submit { .. }
+                                        if prompt_has_directives(&prompt.content) =>
+                                    {
+                                        let elements = self.textarea.text_elements();
+                                        let first_line = first_line.to_string();
+                                        self.preview_prompt_with_directives(
+                                            &prompt,
+                                            move |resolved| {
+                                                Ok(
+                                                    match prompt_selection_action(
+                                                        resolved,
+                                                        &first_line,
+                                                        PromptSelectionMode::Submit,
+                                                        &elements,
+                                                    ) {
+                                                        PromptSelectionAction::Submit {
+                                                            text,
+                                                            text_elements,
+                                                        } => Some(PromptExpansion {
+                                                            text,
+                                                            text_elements,
+                                                        }),
+                                                        PromptSelectionAction::Insert {
+                                                            ..
+                                                        } => None,
+                                                    },
+                                                )
+                                            },
+                                        );
+                                        return (InputResult::None, true);
+                                    }
                                     PromptSelectionAction::Submit {
                                         text,
                                         text_elements,
@@ -1914,6 +1979,28 @@ impl ChatComposer {
         }
 
         if self.slash_commands_enabled() {
+            let directive_prompt = parse_slash_name(&text)
+                .and_then(|(name, _rest, _rest_offset)| {
+                    name.strip_prefix(&format!("{PROMPTS_CMD_PREFIX}:"))
+                })
+                .and_then(|name| self.custom_prompts.iter().find(|p| p.name == name))
+                .filter(|prompt| prompt_has_directives(&prompt.content))
+                .cloned();
+            if let Some(prompt) = directive_prompt {
+                // Keep the typed command in place until the expansion arrives.
+                self.set_text_content(
+                    original_input.clone(),
+                    original_text_elements,
+                    original_local_image_paths,
+                );
+                self.pending_pastes.clone_from(&original_pending_pastes);
+                self.textarea.set_cursor(original_input.len());
+                self.preview_prompt_with_directives(&prompt, move |resolved| {
+                    expand_custom_prompt(&text, &text_elements, std::slice::from_ref(resolved))
+                        .map_err(|err| err.user_message())
+                });
+                return None;
+            }
             let expanded_prompt =
                 match expand_custom_prompt(&text, &text_elements, &self.custom_prompts) {
                     Ok(expanded) => expanded,
@@ -2834,6 +2921,96 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn set_prompt_directive_context(&mut self, context: PromptDirectiveContext) {
+        self.prompt_directive_context = Some(context);
+    }
+
+    /// Custom prompts that use `@{file}` or `!{command}` directives are never
+    /// submitted directly. The directives are expanded in the background,
+    /// `expand` fills in the arguments, and the result comes back through
+    /// [`AppEvent::PromptDirectivesExpanded`] to be loaded into the composer
+    /// so the user can review it before pressing Enter again.
+    fn preview_prompt_with_directives(
+        &mut self,
+        prompt: &CustomPrompt,
+        expand: impl FnOnce(&CustomPrompt) -> Result<Option<PromptExpansion>, String> + Send + 'static,
+    ) {
+        let command = format!("/{PROMPTS_CMD_PREFIX}:{}", prompt.name);
+        let Some(context) = self.prompt_directive_context.clone() else {
+            self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                history_cell::new_error_event(format!(
+                    "Failed to expand {command}: prompt directives are not available yet."
+                )),
+            )));
+            return;
+        };
+        self.next_prompt_expansion_id += 1;
+        let id = self.next_prompt_expansion_id;
+        self.pending_prompt_expansion = Some(PendingPromptExpansion {
+            id,
+            command: command.clone(),
+            text: self.textarea.text().to_string(),
+        });
+
+        let prompt = prompt.clone();
+        let tx = self.app_event_tx.clone();
+        tokio::spawn(async move {
+            let result = match expand_prompt_directives(&prompt.content, &context).await {
+                Ok(content) => match expand(&CustomPrompt { content, ..prompt }) {
+                    Ok(Some(expanded)) => Ok(expanded),
+                    Ok(None) => Err(format!("Failed to expand {command}.")),
+                    Err(err) => Err(format!("Failed to expand {command}: {err}")),
+                },
+                Err(err) => Err(format!("Failed to expand {command}: {err}")),
+            };
+            tx.send(AppEvent::PromptDirectivesExpanded { id, result });
+        });
+    }
+
+    /// Apply a finished directive expansion. Results for a superseded request,
+    /// or arriving after the user edited the composer, are dropped.
+    pub(crate) fn on_prompt_directives_expanded(
+        &mut self,
+        id: u64,
+        result: Result<PromptExpansion, String>,
+    ) {
+        let Some(pending) = self
+            .pending_prompt_expansion
+            .take_if(|pending| pending.id == id)
+        else {
+            return;
+        };
+        if self.textarea.text() != pending.text {
+            return;
+        }
+        let expanded = match result {
+            Ok(expanded) => expanded,
+            Err(message) => {
+                self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                    history_cell::new_error_event(message),
+                )));
+                return;
+            }
+        };
+
+        let local_image_paths = self
+            .attached_images
+            .iter()
+            .map(|img| img.path.clone())
+            .collect();
+        self.set_text_content(expanded.text, expanded.text_elements, local_image_paths);
+        self.move_cursor_to_end();
+        self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+            history_cell::new_info_event(
+                format!(
+                    "Expanded {}. Review the prompt and press Enter to send it.",
+                    pending.command
+                ),
+                None,
+            ),
+        )));
+    }
+
     /// Synchronize `self.file_search_popup` with the current text in the textarea.
     /// Note this is only called when self.active_popup is NOT Command.
     fn sync_file_search_popup(&mut self, query: String) {
@@ -3332,6 +3509,8 @@ mod tests {
     use crate::bottom_pane::prompt_args::PromptArg;
     use crate::bottom_pane::prompt_args::extract_positional_args_for_prompt_line;
     use crate::bottom_pane::textarea::TextArea;
+    use codex_core::protocol::SandboxPolicy;
+    use codex_protocol::config_types::WindowsSandboxLevel;
     use tokio::sync::mpsc::unbounded_channel;
 
     #[test]
@@ -6008,6 +6187,75 @@ mod tests {
         assert!(composer.textarea.is_empty());
     }
 
+    #[tokio::test]
+    async fn custom_prompt_with_directives_is_previewed_before_submit() {
+        let tmp = tempdir().expect("create TempDir");
+        std::fs::write(tmp.path().join("notes.md"), "- ship it\n").expect("write notes");
+
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_steer_enabled(true);
+        composer.set_prompt_directive_context(PromptDirectiveContext {
+            cwd: tmp.path().to_path_buf(),
+            env: HashMap::new(),
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            windows_sandbox_level: WindowsSandboxLevel::Disabled,
+            codex_linux_sandbox_exe: None,
+        });
+        composer.set_custom_prompts(vec![CustomPrompt {
+            name: "notes".to_string(),
+            path: "/tmp/notes.md".to_string().into(),
+            content: "Review these notes:\n@{notes.md}".to_string(),
+            description: None,
+            argument_hint: None,
+        }]);
+
+        type_chars_humanlike(
+            &mut composer,
+            &[
+                '/', 'p', 'r', 'o', 'm', 'p', 't', 's', ':', 'n', 'o', 't', 'e', 's',
+            ],
+        );
+
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(result, InputResult::None);
+
+        let (id, expansion) = loop {
+            match rx.recv().await.expect("expansion result") {
+                AppEvent::PromptDirectivesExpanded { id, result } => break (id, result),
+                _ => continue,
+            }
+        };
+        composer.on_prompt_directives_expanded(id, expansion.clone());
+        assert_eq!(composer.textarea.text(), "Review these notes:\n- ship it\n");
+
+        // A repeated result for the same request is ignored.
+        composer.set_text_content("edited".to_string(), Vec::new(), Vec::new());
+        composer.on_prompt_directives_expanded(id, expansion);
+        assert_eq!(composer.textarea.text(), "edited");
+
+        composer.set_text_content(
+            "Review these notes:\n- ship it\n".to_string(),
+            Vec::new(),
+            Vec::new(),
+        );
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert!(matches!(
+            result,
+            InputResult::Submitted { text, .. } if text == "Review these notes:\n- ship it"
+        ));
+    }
+
     #[test]
     fn custom_prompt_submission_expands_arguments() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
@@ -0,0 +1,407 @@
+use std::path::PathBuf;
+
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Block;
+use ratatui::widgets::Widget;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::key_hint;
+use crate::render::Insets;
+use crate::render::RectExt as _;
+use crate::render::renderable::ColumnRenderable;
+use crate::render::renderable::Renderable;
+use crate::style::user_message_style;
+use codex_core::protocol::Op;
+use codex_core::protocol::ProposedCommit;
+
+use super::CancellationEvent;
+use super::bottom_pane_view::BottomPaneView;
+use super::popup_consts::MAX_POPUP_ROWS;
+use super::scroll_state::ScrollState;
+use super::selection_popup_common::GenericDisplayRow;
+use super::selection_popup_common::render_rows_single_line;
+
+/// Review screen for the commits drafted by `/commit`: reorder, edit, or drop
+/// commits, then create them in order.
+pub(crate) struct CommitPlanView {
+    commits: Vec<ProposedCommit>,
+    unassigned_paths: Vec<PathBuf>,
+    state: ScrollState,
+    complete: bool,
+    app_event_tx: AppEventSender,
+}
+
+impl CommitPlanView {
+    pub(crate) fn new(
+        commits: Vec<ProposedCommit>,
+        unassigned_paths: Vec<PathBuf>,
+        selected: usize,
+        app_event_tx: AppEventSender,
+    ) -> Self {
+        let mut view = Self {
+            commits,
+            unassigned_paths,
+            state: ScrollState::new(),
+            complete: false,
+            app_event_tx,
+        };
+        let len = view.commits.len();
+        view.state.selected_idx = Some(selected);
+        view.state.clamp_selection(len);
+        view.state.ensure_visible(len, Self::max_visible_rows(len));
+        view
+    }
+
+    fn max_visible_rows(len: usize) -> usize {
+        MAX_POPUP_ROWS.min(len.max(1))
+    }
+
+    fn header(&self) -> ColumnRenderable<'static> {
+        let mut header = ColumnRenderable::new();
+        header.push(Line::from("Proposed commits".bold()));
+        let subtitle = if self.unassigned_paths.is_empty() {
+            "Commits are created top to bottom.".to_string()
+        } else {
+            let noun = if self.unassigned_paths.len() == 1 {
+                "file"
+            } else {
+                "files"
+            };
+            format!(
+                "Commits are created top to bottom. {} changed {noun} will stay uncommitted.",
+                self.unassigned_paths.len()
+            )
+        };
+        header.push(Line::from(subtitle.dim()));
+        header
+    }
+
+    fn build_rows(&self) -> Vec<GenericDisplayRow> {
+        self.commits
+            .iter()
+            .enumerate()
+            .map(|(idx, commit)| {
+                let prefix = if self.state.selected_idx == Some(idx) {
+                    '›'
+                } else {
+                    ' '
+                };
+                let subject = commit.message.lines().next().unwrap_or_default();
+                let paths = commit
+                    .paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                GenericDisplayRow {
+                    name: format!("{prefix} {}. {subject}", idx + 1),
+                    description: Some(paths),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    fn move_up(&mut self) {
+        let len = self.commits.len();
+        self.state.move_up_wrap(len);
+        self.state.ensure_visible(len, Self::max_visible_rows(len));
+    }
+
+    fn move_down(&mut self) {
+        let len = self.commits.len();
+        self.state.move_down_wrap(len);
+        self.state.ensure_visible(len, Self::max_visible_rows(len));
+    }
+
+    /// Moves the selected commit one slot earlier (`up`) or later.
+    fn reorder_selected(&mut self, up: bool) {
+        let Some(idx) = self.state.selected_idx else {
+            return;
+        };
+        let target = if up {
+            idx.checked_sub(1)
+        } else {
+            (idx + 1 < self.commits.len()).then_some(idx + 1)
+        };
+        let Some(target) = target else {
+            return;
+        };
+        self.commits.swap(idx, target);
+        self.state.selected_idx = Some(target);
+        let len = self.commits.len();
+        self.state.ensure_visible(len, Self::max_visible_rows(len));
+    }
+
+    /// Drops the selected commit; its files stay uncommitted.
+    fn drop_selected(&mut self) {
+        let Some(idx) = self.state.selected_idx else {
+            return;
+        };
+        if idx >= self.commits.len() {
+            return;
+        }
+        let dropped = self.commits.remove(idx);
+        self.unassigned_paths.extend(dropped.paths);
+        let len = self.commits.len();
+        self.state.clamp_selection(len);
+        self.state.ensure_visible(len, Self::max_visible_rows(len));
+    }
+
+    fn edit_selected(&mut self) {
+        let Some(index) = self.state.selected_idx else {
+            return;
+        };
+        self.complete = true;
+        self.app_event_tx.send(AppEvent::EditCommitMessage {
+            commits: std::mem::take(&mut self.commits),
+            unassigned_paths: std::mem::take(&mut self.unassigned_paths),
+            index,
+        });
+    }
+
+    fn accept(&mut self) {
+        if self.commits.is_empty() {
+            return;
+        }
+        self.complete = true;
+        self.app_event_tx.send(AppEvent::CodexOp(Op::CreateCommits {
+            commits: std::mem::take(&mut self.commits),
+        }));
+    }
+}
+
+impl BottomPaneView for CommitPlanView {
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        match key_event {
+            KeyEvent {
+                code: KeyCode::Up,
+                modifiers: KeyModifiers::SHIFT,
+                ..
+            } => self.reorder_selected(true),
+            KeyEvent {
+                code: KeyCode::Down,
+                modifiers: KeyModifiers::SHIFT,
+                ..
+            } => self.reorder_selected(false),
+            KeyEvent {
+                code: KeyCode::Up, ..
+            }
+            | KeyEvent {
+                code: KeyCode::Char('p'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.move_up(),
+            KeyEvent {
+                code: KeyCode::Down,
+                ..
+            }
+            | KeyEvent {
+                code: KeyCode::Char('n'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.move_down(),
+            KeyEvent {
+                code: KeyCode::Char('e'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => self.edit_selected(),
+            KeyEvent {
+                code: KeyCode::Char('d'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            }
+            | KeyEvent {
+                code: KeyCode::Delete,
+                ..
+            } => self.drop_selected(),
+            KeyEvent {
+                code: KeyCode::Enter,
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => self.accept(),
+            KeyEvent {
+                code: KeyCode::Esc, ..
+            } => {
+                self.on_ctrl_c();
+            }
+            _ => {}
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    fn on_ctrl_c(&mut self) -> CancellationEvent {
+        self.complete = true;
+        CancellationEvent::Handled
+    }
+}
+
+impl Renderable for CommitPlanView {
+    fn desired_height(&self, width: u16) -> u16 {
+        let rows_height = Self::max_visible_rows(self.commits.len()) as u16;
+        let header_height = self.header().desired_height(width.saturating_sub(4));
+        // Insets, the gap under the header, and the footer hint.
+        header_height + 1 + rows_height + 2 + 1
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 || area.width == 0 {
+            return;
+        }
+
+        let [content_area, footer_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+
+        Block::default()
+            .style(user_message_style())
+            .render(content_area, buf);
+
+        let header = self.header();
+        let header_height = header.desired_height(content_area.width.saturating_sub(4));
+        let rows = self.build_rows();
+        let rows_height = Self::max_visible_rows(rows.len()) as u16;
+        let [header_area, _, list_area] = Layout::vertical([
+            Constraint::Max(header_height),
+            Constraint::Max(1),
+            Constraint::Length(rows_height),
+        ])
+        .areas(content_area.inset(Insets::vh(1, 2)));
+
+        header.render(header_area, buf);
+
+        if list_area.height > 0 {
+            let render_area = Rect {
+                x: list_area.x.saturating_sub(2),
+                y: list_area.y,
+                width: content_area.width.saturating_sub(2).max(1),
+                height: list_area.height,
+            };
+            render_rows_single_line(
+                render_area,
+                buf,
+                &rows,
+                &self.state,
+                render_area.height as usize,
+                "no commits left",
+            );
+        }
+
+        let hint_area = Rect {
+            x: footer_area.x + 2,
+            y: footer_area.y,
+            width: footer_area.width.saturating_sub(2),
+            height: footer_area.height,
+        };
+        commit_plan_hint_line().dim().render(hint_area, buf);
+    }
+}
+
+fn commit_plan_hint_line() -> Line<'static> {
+    Line::from(vec![
+        key_hint::shift(KeyCode::Up).into(),
+        "/".into(),
+        key_hint::shift(KeyCode::Down).into(),
+        " reorder · ".into(),
+        key_hint::plain(KeyCode::Char('e')).into(),
+        " edit message · ".into(),
+        key_hint::plain(KeyCode::Char('d')).into(),
+        " drop · ".into(),
+        key_hint::plain(KeyCode::Enter).into(),
+        " commit · ".into(),
+        key_hint::plain(KeyCode::Esc).into(),
+        " cancel".into(),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::UnboundedReceiver;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn commit(message: &str, path: &str) -> ProposedCommit {
+        ProposedCommit {
+            message: message.to_string(),
+            paths: vec![PathBuf::from(path)],
+        }
+    }
+
+    fn view() -> (CommitPlanView, UnboundedReceiver<AppEvent>) {
+        let (tx, rx) = unbounded_channel::<AppEvent>();
+        let view = CommitPlanView::new(
+            vec![
+                commit("Add parser", "src/parser.rs"),
+                commit("Document parser", "README.md"),
+                commit("Bump version", "Cargo.toml"),
+            ],
+            Vec::new(),
+            0,
+            AppEventSender::new(tx),
+        );
+        (view, rx)
+    }
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn reorder_drop_and_accept_submit_the_edited_plan() {
+        let (mut view, mut rx) = view();
+
+        view.handle_key_event(key(KeyCode::Down, KeyModifiers::SHIFT));
+        view.handle_key_event(key(KeyCode::Char('d'), KeyModifiers::NONE));
+        assert_eq!(view.unassigned_paths, vec![PathBuf::from("src/parser.rs")]);
+        view.handle_key_event(key(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert!(view.is_complete());
+        let Ok(AppEvent::CodexOp(Op::CreateCommits { commits })) = rx.try_recv() else {
+            panic!("expected CreateCommits");
+        };
+        assert_eq!(
+            commits,
+            vec![
+                commit("Document parser", "README.md"),
+                commit("Bump version", "Cargo.toml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn edit_hands_the_plan_to_the_app() {
+        let (mut view, mut rx) = view();
+
+        view.handle_key_event(key(KeyCode::Down, KeyModifiers::NONE));
+        view.handle_key_event(key(KeyCode::Char('e'), KeyModifiers::NONE));
+
+        assert!(view.is_complete());
+        let Ok(AppEvent::EditCommitMessage { commits, index, .. }) = rx.try_recv() else {
+            panic!("expected EditCommitMessage");
+        };
+        assert_eq!(index, 1);
+        assert_eq!(commits.len(), 3);
+    }
+
+    #[test]
+    fn escape_cancels_without_committing() {
+        let (mut view, mut rx) = view();
+
+        view.handle_key_event(key(KeyCode::Esc, KeyModifiers::NONE));
+
+        assert!(view.is_complete());
+        assert!(rx.try_recv().is_err());
+    }
+}
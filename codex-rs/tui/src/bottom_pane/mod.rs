@@ -27,6 +27,7 @@ use crate::render::renderable::Renderable;
 use crate::render::renderable::RenderableItem;
 use crate::tui::FrameRequester;
 use bottom_pane_view::BottomPaneView;
+use codex_core::custom_prompts::PromptDirectiveContext;
 use codex_core::features::Features;
 use codex_core::skills::model::SkillMetadata;
 use codex_file_search::FileMatch;
@@ -55,15 +56,18 @@ pub(crate) struct LocalImageAttachment {
 mod chat_composer;
 mod chat_composer_history;
 mod command_popup;
+mod commit_plan_view;
 pub mod custom_prompt_view;
 mod experimental_features_view;
 mod file_search_popup;
 mod footer;
 mod list_selection_view;
 mod prompt_args;
+pub(crate) use prompt_args::PromptExpansion;
 mod skill_popup;
 mod skills_toggle_view;
 mod slash_commands;
+pub(crate) use commit_plan_view::CommitPlanView;
 pub(crate) use footer::CollaborationModeIndicator;
 pub(crate) use list_selection_view::SelectionViewParams;
 mod feedback_view;
@@ -648,9 +652,25 @@ impl BottomPane {
         }
     }
 
-    /// Update custom prompts available for the slash popup.
-    pub(crate) fn set_custom_prompts(&mut self, prompts: Vec<CustomPrompt>) {
+    /// Update custom prompts available for the slash popup. Prompt directives
+    /// such as `@{file}` are expanded in `directive_context`.
+    pub(crate) fn set_custom_prompts(
+        &mut self,
+        prompts: Vec<CustomPrompt>,
+        directive_context: PromptDirectiveContext,
+    ) {
         self.composer.set_custom_prompts(prompts);
+        self.composer
+            .set_prompt_directive_context(directive_context);
+        self.request_redraw();
+    }
+
+    pub(crate) fn on_prompt_directives_expanded(
+        &mut self,
+        id: u64,
+        result: Result<PromptExpansion, String>,
+    ) {
+        self.composer.on_prompt_directives_expanded(id, result);
         self.request_redraw();
     }
 
@@ -32,9 +32,13 @@ use std::time::Instant;
 use crate::version::CODEX_CLI_VERSION;
 use codex_backend_client::Client as BackendClient;
 use codex_chatgpt::connectors;
+use codex_core::TranscriptExportOptions;
+use codex_core::TranscriptFormat;
 use codex_core::config::Config;
 use codex_core::config::ConstraintResult;
 use codex_core::config::types::Notifications;
+use codex_core::custom_prompts::PromptDirectiveContext;
+use codex_core::export_rollout_transcript;
 use codex_core::features::FEATURES;
 use codex_core::features::Feature;
 use codex_core::git_info::current_branch_name;
@@ -50,6 +54,10 @@ use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
 use codex_core::protocol::CodexErrorInfo;
+use codex_core::protocol::CommitsCreatedEvent;
+use codex_core::protocol::CommitsProposedEvent;
+use codex_core::protocol::ContextPruneAction;
+use codex_core::protocol::ContextPrunedEvent;
 use codex_core::protocol::CreditsSnapshot;
 use codex_core::protocol::DeprecationNoticeEvent;
 use codex_core::protocol::ErrorEvent;
@@ -71,6 +79,7 @@ use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::ProposedCommit;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
@@ -79,6 +88,7 @@ use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TerminalInteractionEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
+use codex_core::protocol::ToolOutputsAgedEvent;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnCompleteEvent;
 use codex_core::protocol::TurnDiffEvent;
@@ -144,12 +154,14 @@ use crate::bottom_pane::BottomPane;
 use crate::bottom_pane::BottomPaneParams;
 use crate::bottom_pane::CancellationEvent;
 use crate::bottom_pane::CollaborationModeIndicator;
+use crate::bottom_pane::CommitPlanView;
 use crate::bottom_pane::DOUBLE_PRESS_QUIT_SHORTCUT_ENABLED;
 use crate::bottom_pane::ExperimentalFeatureItem;
 use crate::bottom_pane::ExperimentalFeaturesView;
 use crate::bottom_pane::FeedbackAudience;
 use crate::bottom_pane::InputResult;
 use crate::bottom_pane::LocalImageAttachment;
+use crate::bottom_pane::PromptExpansion;
 use crate::bottom_pane::QUIT_SHORTCUT_TIMEOUT;
 use crate::bottom_pane::SelectionAction;
 use crate::bottom_pane::SelectionItem;
@@ -182,6 +194,7 @@ use crate::render::renderable::RenderableExt;
 use crate::render::renderable::RenderableItem;
 use crate::slash_command::SlashCommand;
 use crate::status::RateLimitSnapshotDisplay;
+use crate::status::format_tokens_compact;
 use crate::text_formatting::truncate_text;
 use crate::tui::FrameRequester;
 mod interrupts;
@@ -210,6 +223,7 @@ use codex_core::CodexAuth;
 use codex_core::ThreadManager;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::review_prompts::parse_commit_range;
 use codex_file_search::FileMatch;
 use codex_protocol::openai_models::InputModality;
 use codex_protocol::openai_models::ModelPreset;
@@ -234,6 +248,12 @@ struct UnifiedExecProcessSummary {
     recent_chunks: Vec<String>,
 }
 
+struct ServiceSummary {
+    name: String,
+    call_id: String,
+    command_display: String,
+}
+
 struct UnifiedExecWaitState {
     command_display: String,
 }
@@ -483,6 +503,9 @@ pub(crate) struct ChatWidget {
     session_header: SessionHeader,
     initial_user_message: Option<UserMessage>,
     token_info: Option<TokenUsageInfo>,
+    /// Locally counted size of the next request, from the latest `TokenCount`
+    /// event. Preferred over the last reported usage for the context meter.
+    context_tokens: Option<i64>,
     rate_limit_snapshot: Option<RateLimitSnapshotDisplay>,
     plan_type: Option<PlanType>,
     rate_limit_warnings: RateLimitWarningState,
@@ -501,6 +524,8 @@ pub(crate) struct ChatWidget {
     unified_exec_wait_streak: Option<UnifiedExecWaitStreak>,
     task_complete_pending: bool,
     unified_exec_processes: Vec<UnifiedExecProcessSummary>,
+    // Named services outlive turns, so they are tracked separately.
+    services: Vec<ServiceSummary>,
     /// Tracks whether codex-core currently considers an agent turn to be in progress.
     ///
     /// This is kept separate from `mcp_startup_status` so that MCP startup progress (or completion)
@@ -561,6 +586,8 @@ pub(crate) struct ChatWidget {
     had_work_activity: bool,
     // Whether the current turn emitted a plan update.
     saw_plan_update_this_turn: bool,
+    // Latest `update_plan` checklist, surfaced in `/status`.
+    latest_plan: Option<UpdatePlanArgs>,
     // Whether the current turn emitted a proposed plan item.
     saw_plan_item_this_turn: bool,
     // Incremental buffer for streamed plan content.
@@ -1171,7 +1198,8 @@ impl ChatWidget {
         match info {
             Some(info) => self.apply_token_info(info),
             None => {
-                self.bottom_pane.set_context_window(None, None);
+                self.bottom_pane
+                    .set_context_window(None, self.context_tokens);
                 self.token_info = None;
             }
         }
@@ -1185,10 +1213,17 @@ impl ChatWidget {
     }
 
     fn context_remaining_percent(&self, info: &TokenUsageInfo) -> Option<i64> {
-        info.model_context_window.map(|window| {
-            info.last_token_usage
-                .percent_of_context_window_remaining(window)
-        })
+        info.model_context_window
+            .map(|window| match self.context_tokens {
+                Some(total_tokens) => TokenUsage {
+                    total_tokens,
+                    ..TokenUsage::default()
+                }
+                .percent_of_context_window_remaining(window),
+                None => info
+                    .last_token_usage
+                    .percent_of_context_window_remaining(window),
+            })
     }
 
     fn context_used_tokens(&self, info: &TokenUsageInfo, percent_known: bool) -> Option<i64> {
@@ -1196,11 +1231,17 @@ impl ChatWidget {
             return None;
         }
 
-        Some(info.total_token_usage.tokens_in_context_window())
+        Some(
+            self.context_tokens
+                .unwrap_or_else(|| info.total_token_usage.tokens_in_context_window()),
+        )
     }
 
     fn restore_pre_review_token_info(&mut self) {
         if let Some(saved) = self.pre_review_token_info.take() {
+            // The local count described the review thread; fall back to the
+            // saved usage until the next `TokenCount` event.
+            self.context_tokens = None;
             match saved {
                 Some(info) => self.apply_token_info(info),
                 None => {
@@ -1487,6 +1528,7 @@ impl ChatWidget {
 
     fn on_plan_update(&mut self, update: UpdatePlanArgs) {
         self.saw_plan_update_this_turn = true;
+        self.latest_plan = Some(update.clone());
         self.add_to_history(history_cell::new_plan_update(update));
     }
 
@@ -1526,6 +1568,10 @@ impl ChatWidget {
 
     fn on_exec_command_begin(&mut self, ev: ExecCommandBeginEvent) {
         self.flush_answer_stream_with_separator();
+        if ev.source == ExecCommandSource::Service {
+            self.track_service_begin(&ev);
+            return;
+        }
         if is_unified_exec_source(ev.source) {
             self.track_unified_exec_process_begin(&ev);
             if !is_standard_tool_call(&ev.parsed_cmd) {
@@ -1629,6 +1675,10 @@ impl ChatWidget {
     }
 
     fn on_exec_command_end(&mut self, ev: ExecCommandEndEvent) {
+        if ev.source == ExecCommandSource::Service {
+            self.track_service_end(&ev);
+            return;
+        }
         if is_unified_exec_source(ev.source) {
             if let Some(process_id) = ev.process_id.as_deref()
                 && self
@@ -1717,6 +1767,36 @@ impl ChatWidget {
         }
     }
 
+    fn track_service_begin(&mut self, ev: &ExecCommandBeginEvent) {
+        let name = ev.process_id.clone().unwrap_or_else(|| ev.call_id.clone());
+        let command_display = strip_bash_lc_and_escape(&ev.command);
+        self.services.retain(|service| service.name != name);
+        self.add_to_history(history_cell::new_info_event(
+            format!("Started service {name}"),
+            Some(command_display.clone()),
+        ));
+        self.services.push(ServiceSummary {
+            name,
+            call_id: ev.call_id.clone(),
+            command_display,
+        });
+    }
+
+    fn track_service_end(&mut self, ev: &ExecCommandEndEvent) {
+        // A restart emits the end of the old process after the new one began,
+        // so only forget the service if the end belongs to the tracked run.
+        let Some(idx) = self.services.iter().position(|service| {
+            Some(&service.name) == ev.process_id.as_ref() && service.call_id == ev.call_id
+        }) else {
+            return;
+        };
+        let service = self.services.remove(idx);
+        self.add_to_history(history_cell::new_info_event(
+            format!("Service {} exited with code {}", service.name, ev.exit_code),
+            None,
+        ));
+    }
+
     fn clear_unified_exec_processes(&mut self) {
         if self.unified_exec_processes.is_empty() {
             return;
@@ -2284,6 +2364,7 @@ impl ChatWidget {
             session_header: SessionHeader::new(header_model),
             initial_user_message,
             token_info: None,
+            context_tokens: None,
             rate_limit_snapshot: None,
             plan_type: None,
             rate_limit_warnings: RateLimitWarningState::default(),
@@ -2298,6 +2379,7 @@ impl ChatWidget {
             unified_exec_wait_streak: None,
             task_complete_pending: false,
             unified_exec_processes: Vec::new(),
+            services: Vec::new(),
             agent_turn_running: false,
             mcp_startup_status: None,
             connectors_cache: ConnectorsCacheState::default(),
@@ -2320,6 +2402,7 @@ impl ChatWidget {
             needs_final_message_separator: false,
             had_work_activity: false,
             saw_plan_update_this_turn: false,
+            latest_plan: None,
             saw_plan_item_this_turn: false,
             plan_delta_buffer: String::new(),
             plan_item_active: false,
@@ -2430,6 +2513,7 @@ impl ChatWidget {
             session_header: SessionHeader::new(header_model),
             initial_user_message,
             token_info: None,
+            context_tokens: None,
             rate_limit_snapshot: None,
             plan_type: None,
             rate_limit_warnings: RateLimitWarningState::default(),
@@ -2444,6 +2528,7 @@ impl ChatWidget {
             unified_exec_wait_streak: None,
             task_complete_pending: false,
             unified_exec_processes: Vec::new(),
+            services: Vec::new(),
             agent_turn_running: false,
             mcp_startup_status: None,
             connectors_cache: ConnectorsCacheState::default(),
@@ -2456,6 +2541,7 @@ impl ChatWidget {
             thread_name: None,
             forked_from: None,
             saw_plan_update_this_turn: false,
+            latest_plan: None,
             saw_plan_item_this_turn: false,
             plan_delta_buffer: String::new(),
             plan_item_active: false,
@@ -2565,6 +2651,7 @@ impl ChatWidget {
             session_header: SessionHeader::new(header_model),
             initial_user_message,
             token_info: None,
+            context_tokens: None,
             rate_limit_snapshot: None,
             plan_type: None,
             rate_limit_warnings: RateLimitWarningState::default(),
@@ -2579,6 +2666,7 @@ impl ChatWidget {
             unified_exec_wait_streak: None,
             task_complete_pending: false,
             unified_exec_processes: Vec::new(),
+            services: Vec::new(),
             agent_turn_running: false,
             mcp_startup_status: None,
             connectors_cache: ConnectorsCacheState::default(),
@@ -2601,6 +2689,7 @@ impl ChatWidget {
             needs_final_message_separator: false,
             had_work_activity: false,
             saw_plan_update_this_turn: false,
+            latest_plan: None,
             saw_plan_item_this_turn: false,
             plan_delta_buffer: String::new(),
             plan_item_active: false,
@@ -2871,6 +2960,12 @@ impl ChatWidget {
                 self.clear_token_usage();
                 self.app_event_tx.send(AppEvent::CodexOp(Op::Compact));
             }
+            SlashCommand::Context => {
+                self.submit_op(Op::InspectContext);
+            }
+            SlashCommand::Commit => {
+                self.submit_op(Op::ProposeCommits);
+            }
             SlashCommand::Review => {
                 self.open_review_popup();
             }
@@ -3014,6 +3109,9 @@ impl ChatWidget {
             SlashCommand::Apps => {
                 self.add_connectors_output();
             }
+            SlashCommand::Export => {
+                self.export_transcript(TranscriptFormat::Markdown);
+            }
             SlashCommand::Rollout => {
                 if let Some(path) = self.rollout_path() {
                     self.add_info_message(
@@ -3147,10 +3245,102 @@ impl ChatWidget {
                 });
                 self.bottom_pane.drain_pending_submission_state();
             }
+            SlashCommand::Context if !trimmed.is_empty() => {
+                self.bottom_pane.drain_pending_submission_state();
+                let mut words = trimmed.split_whitespace();
+                let action = match words.next().map(str::to_ascii_lowercase).as_deref() {
+                    Some("drop") => ContextPruneAction::Drop,
+                    Some("truncate") => ContextPruneAction::Truncate,
+                    _ => {
+                        self.add_error_message(
+                            "Usage: /context [drop|truncate <id>...]".to_string(),
+                        );
+                        return;
+                    }
+                };
+                let ids: Vec<String> = words.map(str::to_string).collect();
+                if ids.is_empty() {
+                    self.add_error_message(
+                        "Pass the ids shown by /context, e.g. /context drop output:call_123."
+                            .to_string(),
+                    );
+                    return;
+                }
+                self.submit_op(Op::PruneContextItems { ids, action });
+            }
+            SlashCommand::Export if !trimmed.is_empty() => {
+                let (format, path) = trimmed
+                    .split_once(char::is_whitespace)
+                    .map_or((trimmed, ""), |(format, path)| (format, path.trim()));
+                let format = match format.to_ascii_lowercase().as_str() {
+                    "md" | "markdown" => TranscriptFormat::Markdown,
+                    "html" => TranscriptFormat::Html,
+                    other => {
+                        self.add_error_message(format!(
+                            "Unknown export format '{other}'. Use 'md' or 'html', optionally followed by a path."
+                        ));
+                        self.bottom_pane.drain_pending_submission_state();
+                        return;
+                    }
+                };
+                self.export_transcript_to(format, (!path.is_empty()).then(|| PathBuf::from(path)));
+                self.bottom_pane.drain_pending_submission_state();
+            }
             _ => self.dispatch_command(cmd),
         }
     }
 
+    fn export_transcript(&mut self, format: TranscriptFormat) {
+        self.export_transcript_to(format, None);
+    }
+
+    /// Render the current rollout and write it to `requested_path` (relative
+    /// to the session cwd), or under `$CODEX_HOME/exports` when none was given.
+    fn export_transcript_to(&mut self, format: TranscriptFormat, requested_path: Option<PathBuf>) {
+        let Some(rollout_path) = self.rollout_path() else {
+            self.add_info_message("Nothing to export yet.".to_string(), None);
+            return;
+        };
+        let output_path = transcript_export_path(
+            &self.config.codex_home,
+            &self.config.cwd,
+            self.thread_id,
+            format,
+            requested_path,
+        );
+        let tx = self.app_event_tx.clone();
+        tokio::spawn(async move {
+            let result = match export_rollout_transcript(
+                &rollout_path,
+                format,
+                &TranscriptExportOptions::default(),
+            )
+            .await
+            {
+                Ok(transcript) => {
+                    let written = async {
+                        if let Some(parent) = output_path.parent() {
+                            tokio::fs::create_dir_all(parent).await?;
+                        }
+                        tokio::fs::write(&output_path, transcript).await
+                    };
+                    written.await
+                }
+                Err(err) => Err(err),
+            };
+            let cell = match result {
+                Ok(()) => history_cell::new_info_event(
+                    format!("Exported session to {}", output_path.display()),
+                    None,
+                ),
+                Err(err) => {
+                    history_cell::new_error_event(format!("Failed to export session: {err}"))
+                }
+            };
+            tx.send(AppEvent::InsertHistoryCell(Box::new(cell)));
+        });
+    }
+
     fn show_rename_prompt(&mut self) {
         let tx = self.app_event_tx.clone();
         let has_name = self
@@ -3484,10 +3674,12 @@ impl ChatWidget {
                 self.on_task_complete(last_agent_message, from_replay)
             }
             EventMsg::TokenCount(ev) => {
+                self.context_tokens = ev.context_tokens;
                 self.set_token_info(ev.info);
                 self.on_rate_limit_snapshot(ev.rate_limits);
             }
             EventMsg::Warning(WarningEvent { message }) => self.on_warning(message),
+            EventMsg::ModelFallback(ev) => self.on_warning(ev.summary()),
             EventMsg::Error(ErrorEvent {
                 message,
                 codex_error_info,
@@ -3591,7 +3783,15 @@ impl ChatWidget {
             EventMsg::CollabCloseBegin(_) => {}
             EventMsg::CollabCloseEnd(ev) => self.on_collab_event(collab::close_end(ev)),
             EventMsg::ThreadRolledBack(_) => {}
+            EventMsg::ContextBreakdown(ev) => {
+                self.add_to_history(history_cell::new_context_breakdown(&ev));
+            }
+            EventMsg::ContextPruned(ev) => self.on_context_pruned(ev),
+            EventMsg::ToolOutputsAged(ev) => self.on_tool_outputs_aged(ev),
+            EventMsg::CommitsProposed(ev) => self.on_commits_proposed(ev),
+            EventMsg::CommitsCreated(ev) => self.on_commits_created(ev),
             EventMsg::RawResponseItem(_)
+            | EventMsg::TouchedPathsUpdated(_)
             | EventMsg::ItemStarted(_)
             | EventMsg::AgentMessageContentDelta(_)
             | EventMsg::ReasoningContentDelta(_)
@@ -3777,6 +3977,7 @@ impl ChatWidget {
             self.model_display_name(),
             collaboration_mode,
             reasoning_effort_override,
+            self.latest_plan.as_ref(),
         ));
     }
 
@@ -3789,7 +3990,15 @@ impl ChatWidget {
                 recent_chunks: process.recent_chunks.clone(),
             })
             .collect();
-        self.add_to_history(history_cell::new_unified_exec_processes_output(processes));
+        let services = self
+            .services
+            .iter()
+            .map(|service| history_cell::ServiceDetails {
+                name: service.name.clone(),
+                command_display: service.command_display.clone(),
+            })
+            .collect();
+        self.add_to_history(history_cell::new_ps_output(processes, services));
     }
 
     fn stop_rate_limit_poller(&mut self) {
@@ -5780,6 +5989,14 @@ impl ChatWidget {
         self.bottom_pane.on_file_search_result(query, matches);
     }
 
+    pub(crate) fn on_prompt_directives_expanded(
+        &mut self,
+        id: u64,
+        result: Result<PromptExpansion, String>,
+    ) {
+        self.bottom_pane.on_prompt_directives_expanded(id, result);
+    }
+
     /// Handles a Ctrl+C press at the chat-widget layer.
     ///
     /// The first press arms a time-bounded quit shortcut and shows a footer hint via the bottom
@@ -5952,7 +6169,89 @@ impl ChatWidget {
         let len = ev.custom_prompts.len();
         debug!("received {len} custom prompts");
         // Forward to bottom pane so the slash popup can show them now.
-        self.bottom_pane.set_custom_prompts(ev.custom_prompts);
+        self.bottom_pane.set_custom_prompts(
+            ev.custom_prompts,
+            PromptDirectiveContext::from_config(&self.config),
+        );
+    }
+
+    fn on_context_pruned(&mut self, ev: ContextPrunedEvent) {
+        let verb = match ev.action {
+            ContextPruneAction::Drop => "Dropped",
+            ContextPruneAction::Truncate => "Truncated",
+        };
+        let noun = if ev.ids.len() == 1 { "item" } else { "items" };
+        self.add_info_message(
+            format!(
+                "{verb} {} context {noun}: {}",
+                ev.ids.len(),
+                ev.ids.join(", ")
+            ),
+            Some("Run /context to see the updated breakdown.".to_string()),
+        );
+    }
+
+    fn on_tool_outputs_aged(&mut self, ev: ToolOutputsAgedEvent) {
+        let noun = if ev.call_ids.len() == 1 {
+            "output"
+        } else {
+            "outputs"
+        };
+        self.add_info_message(
+            format!(
+                "Aged {} old tool {noun} out of context (~{} tokens freed)",
+                ev.call_ids.len(),
+                format_tokens_compact(ev.estimated_tokens_saved)
+            ),
+            Some(
+                "Full outputs remain in the session rollout; run /context to see the breakdown."
+                    .to_string(),
+            ),
+        );
+    }
+
+    fn on_commits_proposed(&mut self, ev: CommitsProposedEvent) {
+        let CommitsProposedEvent {
+            commits,
+            unassigned_paths,
+        } = ev;
+        if commits.is_empty() {
+            let hint = (!unassigned_paths.is_empty()).then(|| {
+                format!(
+                    "{} changed file(s) could not be grouped into a commit.",
+                    unassigned_paths.len()
+                )
+            });
+            self.add_info_message("Nothing to commit.".to_string(), hint);
+            return;
+        }
+        self.open_commit_plan(commits, unassigned_paths, 0);
+    }
+
+    pub(crate) fn open_commit_plan(
+        &mut self,
+        commits: Vec<ProposedCommit>,
+        unassigned_paths: Vec<PathBuf>,
+        selected: usize,
+    ) {
+        let view = CommitPlanView::new(
+            commits,
+            unassigned_paths,
+            selected,
+            self.app_event_tx.clone(),
+        );
+        self.bottom_pane.show_view(Box::new(view));
+        self.request_redraw();
+    }
+
+    fn on_commits_created(&mut self, ev: CommitsCreatedEvent) {
+        for commit in &ev.commits {
+            let short_sha: String = commit.sha.chars().take(7).collect();
+            self.add_info_message(format!("Committed {short_sha} {}", commit.subject), None);
+        }
+        if let Some(error) = ev.error {
+            self.add_error_message(error);
+        }
     }
 
     fn on_list_skills(&mut self, ev: ListSkillsResponseEvent) {
@@ -6015,6 +6314,16 @@ impl ChatWidget {
             ..Default::default()
         });
 
+        items.push(SelectionItem {
+            name: "Review a commit range".to_string(),
+            description: Some("(as one change or commit by commit)".into()),
+            actions: vec![Box::new(move |tx| {
+                tx.send(AppEvent::OpenReviewRangePrompt);
+            })],
+            dismiss_on_select: false,
+            ..Default::default()
+        });
+
         items.push(SelectionItem {
             name: "Custom review instructions".to_string(),
             actions: vec![Box::new(move |tx| {
@@ -6107,6 +6416,74 @@ impl ChatWidget {
         });
     }
 
+    pub(crate) fn show_review_range_prompt(&mut self) {
+        let tx = self.app_event_tx.clone();
+        let view = CustomPromptView::new(
+            "Commit range".to_string(),
+            "Type a range like main..HEAD or HEAD~3.. and press Enter".to_string(),
+            None,
+            Box::new(move |range: String| {
+                let trimmed = range.trim().to_string();
+                if trimmed.is_empty() {
+                    return;
+                }
+                tx.send(AppEvent::OpenReviewRangeModePicker(trimmed));
+            }),
+        );
+        self.bottom_pane.show_view(Box::new(view));
+    }
+
+    pub(crate) fn show_review_range_mode_picker(&mut self, range: &str) {
+        let (base, head) = match parse_commit_range(range) {
+            Ok(endpoints) => endpoints,
+            Err(err) => {
+                self.add_error_message(err.to_string());
+                return;
+            }
+        };
+
+        let modes = [
+            ("Review as one change", None, false),
+            (
+                "Review each commit separately",
+                Some("(findings grouped by commit)"),
+                true,
+            ),
+        ];
+        let items = modes
+            .into_iter()
+            .map(|(name, description, per_commit)| {
+                let base = base.clone();
+                let head = head.clone();
+                SelectionItem {
+                    name: name.to_string(),
+                    description: description.map(Into::into),
+                    actions: vec![Box::new(move |tx: &AppEventSender| {
+                        tx.send(AppEvent::CodexOp(Op::Review {
+                            review_request: ReviewRequest {
+                                target: ReviewTarget::CommitRange {
+                                    base: base.clone(),
+                                    head: head.clone(),
+                                    per_commit,
+                                },
+                                user_facing_hint: None,
+                            },
+                        }));
+                    })],
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(format!("Review {base}..{head}")),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
     pub(crate) fn show_review_custom_prompt(&mut self) {
         let tx = self.app_event_tx.clone();
         let view = CustomPromptView::new(
@@ -6188,6 +6565,7 @@ impl ChatWidget {
 
     pub(crate) fn clear_token_usage(&mut self) {
         self.token_info = None;
+        self.context_tokens = None;
     }
 
     fn as_renderable(&self) -> RenderableItem<'_> {
@@ -6404,5 +6782,26 @@ fn format_duration_short(seconds: u64) -> String {
     }
 }
 
+/// Where `/export` writes: `requested` resolved against `cwd` when the user
+/// named a path, otherwise `$CODEX_HOME/exports/codex-<thread id>.<ext>` so
+/// exports do not land in the project tree.
+fn transcript_export_path(
+    codex_home: &Path,
+    cwd: &Path,
+    thread_id: Option<ThreadId>,
+    format: TranscriptFormat,
+    requested: Option<PathBuf>,
+) -> PathBuf {
+    if let Some(requested) = requested {
+        return cwd.join(requested);
+    }
+    let stem = thread_id
+        .map(|id| format!("codex-{id}"))
+        .unwrap_or_else(|| "codex-session".to_string());
+    codex_home
+        .join("exports")
+        .join(format!("{stem}.{}", format.extension()))
+}
+
 #[cfg(test)]
 pub(crate) mod tests;
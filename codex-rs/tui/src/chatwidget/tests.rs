@@ -641,6 +641,7 @@ async fn review_restores_context_window_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(pre_review_tokens, context_window)),
             rate_limits: None,
+            context_tokens: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
@@ -660,6 +661,7 @@ async fn review_restores_context_window_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(review_tokens, context_window)),
             rate_limits: None,
+            context_tokens: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(97));
@@ -689,6 +691,7 @@ async fn token_count_none_resets_context_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(pre_compact_tokens, context_window)),
             rate_limits: None,
+            context_tokens: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
@@ -698,11 +701,30 @@ async fn token_count_none_resets_context_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: None,
             rate_limits: None,
+            context_tokens: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), None);
 }
 
+/// The locally counted request size wins over the last reported usage, which
+/// misses items recorded since.
+#[tokio::test]
+async fn context_indicator_prefers_local_context_tokens() {
+    let (mut chat, _rx, _ops) = make_chatwidget_manual(None).await;
+
+    let context_window = 13_000;
+    chat.handle_codex_event(Event {
+        id: "token-local".into(),
+        msg: EventMsg::TokenCount(TokenCountEvent {
+            info: Some(make_token_info(12_030, context_window)),
+            rate_limits: None,
+            context_tokens: Some(12_700),
+        }),
+    });
+    assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
+}
+
 #[tokio::test]
 async fn context_indicator_shows_used_tokens_when_window_unknown() {
     let (mut chat, _rx, _ops) = make_chatwidget_manual(Some("unknown-model")).await;
@@ -728,6 +750,7 @@ async fn context_indicator_shows_used_tokens_when_window_unknown() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(token_info),
             rate_limits: None,
+            context_tokens: None,
         }),
     });
 
@@ -847,6 +870,7 @@ async fn make_chatwidget_manual(
         session_header: SessionHeader::new(resolved_model.clone()),
         initial_user_message: None,
         token_info: None,
+        context_tokens: None,
         rate_limit_snapshot: None,
         plan_type: None,
         rate_limit_warnings: RateLimitWarningState::default(),
@@ -863,6 +887,7 @@ async fn make_chatwidget_manual(
         unified_exec_wait_streak: None,
         task_complete_pending: false,
         unified_exec_processes: Vec::new(),
+        services: Vec::new(),
         agent_turn_running: false,
         mcp_startup_status: None,
         connectors_cache: ConnectorsCacheState::default(),
@@ -886,6 +911,7 @@ async fn make_chatwidget_manual(
         needs_final_message_separator: false,
         had_work_activity: false,
         saw_plan_update_this_turn: false,
+        latest_plan: None,
         saw_plan_item_this_turn: false,
         plan_delta_buffer: String::new(),
         plan_item_active: false,
@@ -2289,7 +2315,8 @@ async fn review_popup_custom_prompt_action_sends_event() {
     // Open the preset selection popup
     chat.open_review_popup();
 
-    // Move selection down to the fourth item: "Custom review instructions"
+    // Move selection down to the fifth item: "Custom review instructions"
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
     chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
     chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
     chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
@@ -2932,6 +2959,59 @@ async fn custom_prompt_submit_sends_review_op() {
     }
 }
 
+/// Submitting a commit range asks how to review it, and choosing the
+/// per-commit mode sends a per-commit range review.
+#[tokio::test]
+async fn review_range_prompt_then_per_commit_sends_review_op() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.show_review_range_prompt();
+    chat.handle_paste(" main.. ".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let range = match rx.try_recv().expect("expected one app event") {
+        AppEvent::OpenReviewRangeModePicker(range) => range,
+        other => panic!("unexpected app event: {other:?}"),
+    };
+    assert_eq!(range, "main..");
+
+    chat.show_review_range_mode_picker(&range);
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    match rx.try_recv().expect("expected review op") {
+        AppEvent::CodexOp(Op::Review { review_request }) => {
+            assert_eq!(
+                review_request,
+                ReviewRequest {
+                    target: ReviewTarget::CommitRange {
+                        base: "main".to_string(),
+                        head: "HEAD".to_string(),
+                        per_commit: true,
+                    },
+                    user_facing_hint: None,
+                }
+            );
+        }
+        other => panic!("unexpected app event: {other:?}"),
+    }
+}
+
+/// A malformed range is reported instead of opening the mode picker.
+#[tokio::test]
+async fn review_range_mode_picker_rejects_malformed_range() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.show_review_range_mode_picker("main");
+
+    let cells = drain_insert_history(&mut rx);
+    let rendered = lines_to_single_string(cells.last().expect("error cell"));
+    assert!(
+        rendered.contains("Expected a commit range like main..HEAD"),
+        "unexpected error: {rendered}"
+    );
+}
+
 /// Hitting Enter on an empty custom prompt view does not submit.
 #[tokio::test]
 async fn custom_prompt_enter_empty_does_not_send() {
@@ -5207,3 +5287,24 @@ async fn review_queues_user_messages_snapshot() {
     .unwrap();
     assert_snapshot!(term.backend().vt100().screen().contents());
 }
+
+#[test]
+fn transcript_exports_default_to_codex_home() {
+    let codex_home = Path::new("/home/me/.codex");
+    let cwd = Path::new("/work/repo");
+
+    assert_eq!(
+        transcript_export_path(codex_home, cwd, None, TranscriptFormat::Html, None),
+        PathBuf::from("/home/me/.codex/exports/codex-session.html")
+    );
+    assert_eq!(
+        transcript_export_path(
+            codex_home,
+            cwd,
+            None,
+            TranscriptFormat::Markdown,
+            Some(PathBuf::from("notes/session.md")),
+        ),
+        PathBuf::from("/work/repo/notes/session.md")
+    );
+}
@@ -25,6 +25,7 @@ use crate::render::line_utils::line_to_static;
 use crate::render::line_utils::prefix_lines;
 use crate::render::line_utils::push_owned_lines;
 use crate::render::renderable::Renderable;
+use crate::status::format_tokens_compact;
 use crate::style::proposed_plan_style;
 use crate::style::user_message_style;
 use crate::text_formatting::format_and_truncate_tool_result;
@@ -40,6 +41,8 @@ use base64::Engine;
 use codex_common::format_env_display::format_env_display;
 use codex_core::config::Config;
 use codex_core::config::types::McpServerTransportConfig;
+use codex_core::protocol::ContextBreakdownEvent;
+use codex_core::protocol::ContextItemCategory;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpAuthStatus;
 use codex_core::protocol::McpInvocation;
@@ -688,10 +691,68 @@ impl HistoryCell for UnifiedExecProcessesCell {
 
 pub(crate) fn new_unified_exec_processes_output(
     processes: Vec<UnifiedExecProcessDetails>,
+) -> CompositeHistoryCell {
+    new_ps_output(processes, Vec::new())
+}
+
+/// `/ps` output listing background terminals and, when any are running, named
+/// services.
+pub(crate) fn new_ps_output(
+    processes: Vec<UnifiedExecProcessDetails>,
+    services: Vec<ServiceDetails>,
 ) -> CompositeHistoryCell {
     let command = PlainHistoryCell::new(vec!["/ps".magenta().into()]);
     let summary = UnifiedExecProcessesCell::new(processes);
-    CompositeHistoryCell::new(vec![Box::new(command), Box::new(summary)])
+    let mut parts: Vec<Box<dyn HistoryCell>> = vec![Box::new(command), Box::new(summary)];
+    if !services.is_empty() {
+        parts.push(Box::new(ServicesCell { services }));
+    }
+    CompositeHistoryCell::new(parts)
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ServiceDetails {
+    pub(crate) name: String,
+    pub(crate) command_display: String,
+}
+
+#[derive(Debug)]
+struct ServicesCell {
+    services: Vec<ServiceDetails>,
+}
+
+impl HistoryCell for ServicesCell {
+    fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+        if width == 0 {
+            return Vec::new();
+        }
+        let mut out: Vec<Line<'static>> = vec!["".into(), vec!["Services".bold()].into()];
+        out.push("".into());
+        let prefix = "  • ";
+        let separator = " · ";
+        for service in &self.services {
+            let budget = (width as usize)
+                .saturating_sub(UnicodeWidthStr::width(prefix))
+                .saturating_sub(UnicodeWidthStr::width(service.name.as_str()))
+                .saturating_sub(UnicodeWidthStr::width(separator));
+            let first_line = service.command_display.lines().next().unwrap_or_default();
+            let (command, _, _) = take_prefix_by_width(first_line, budget);
+            out.push(
+                vec![
+                    prefix.dim(),
+                    service.name.clone().cyan(),
+                    separator.dim(),
+                    command.dim(),
+                ]
+                .into(),
+            );
+        }
+        out
+    }
+
+    fn desired_height(&self, width: u16) -> u16 {
+        self.display_lines(width).len() as u16
+    }
 }
 
 fn truncate_exec_snippet(full_cmd: &str) -> String {
@@ -1758,6 +1819,108 @@ pub(crate) fn new_mcp_tools_output(
 
     PlainHistoryCell { lines }
 }
+/// Number of individual items listed by `/context`; the rest are only counted
+/// in the per-category totals.
+const CONTEXT_LARGEST_ITEMS: usize = 10;
+
+/// `/context` output: estimated token cost per category plus the largest
+/// items, with the ids `/context drop` and `/context truncate` accept.
+pub(crate) fn new_context_breakdown(event: &ContextBreakdownEvent) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec!["/context".magenta().into(), "".into()];
+    let mut header: Vec<Span<'static>> = vec![
+        "Context".bold(),
+        format!(
+            "  ~{} tokens",
+            format_tokens_compact(event.estimated_total_tokens)
+        )
+        .into(),
+    ];
+    if let Some(window) = event.model_context_window {
+        header.push(format!(" of {}", format_tokens_compact(window)).into());
+    }
+    header.push(" (estimated)".dim());
+    lines.push(header.into());
+    lines.push("".into());
+
+    let mut categories: Vec<(ContextItemCategory, usize, i64)> = Vec::new();
+    for item in &event.items {
+        match categories
+            .iter_mut()
+            .find(|(category, _, _)| *category == item.category)
+        {
+            Some((_, count, tokens)) => {
+                *count += 1;
+                *tokens = tokens.saturating_add(item.estimated_tokens);
+            }
+            None => categories.push((item.category, 1, item.estimated_tokens)),
+        }
+    }
+    categories.sort_by(|a, b| b.2.cmp(&a.2));
+    for (category, count, tokens) in categories {
+        let noun = if count == 1 { "item" } else { "items" };
+        lines.push(
+            vec![
+                "  • ".dim(),
+                format!("{:<18}", context_category_label(category)).into(),
+                format!("{:>7}", format_tokens_compact(tokens)).into(),
+                format!("  ({count} {noun})").dim(),
+            ]
+            .into(),
+        );
+    }
+
+    let mut largest: Vec<_> = event
+        .items
+        .iter()
+        .filter_map(|item| item.id.as_ref().map(|id| (id, item)))
+        .collect();
+    largest.sort_by(|a, b| b.1.estimated_tokens.cmp(&a.1.estimated_tokens));
+    largest.truncate(CONTEXT_LARGEST_ITEMS);
+    if !largest.is_empty() {
+        lines.push("".into());
+        lines.push(vec!["Largest items".bold()].into());
+        for (id, item) in largest {
+            lines.push(
+                vec![
+                    "  • ".dim(),
+                    format!("{:>7}", format_tokens_compact(item.estimated_tokens)).into(),
+                    "  ".into(),
+                    id.clone().cyan(),
+                    "  ".into(),
+                    item.label.clone().dim(),
+                ]
+                .into(),
+            );
+        }
+        lines.push("".into());
+        lines.push(
+            "Use /context drop <id> or /context truncate <id> to shrink individual items."
+                .dim()
+                .into(),
+        );
+    }
+
+    PlainHistoryCell::new(lines)
+}
+
+fn context_category_label(category: ContextItemCategory) -> &'static str {
+    match category {
+        ContextItemCategory::BaseInstructions => "Base instructions",
+        ContextItemCategory::UserInstructions => "AGENTS.md",
+        ContextItemCategory::Skill => "Skills",
+        ContextItemCategory::PathInstructions => "Path instructions",
+        ContextItemCategory::SessionContext => "Session context",
+        ContextItemCategory::UserMessage => "User messages",
+        ContextItemCategory::AssistantMessage => "Assistant messages",
+        ContextItemCategory::DeveloperMessage => "Developer messages",
+        ContextItemCategory::ToolCall => "Tool calls",
+        ContextItemCategory::ToolOutput => "Tool outputs",
+        ContextItemCategory::Image => "Images",
+        ContextItemCategory::Reasoning => "Reasoning",
+        ContextItemCategory::Compaction => "Compacted history",
+    }
+}
+
 pub(crate) fn new_info_event(message: String, hint: Option<String>) -> PlainHistoryCell {
     let mut line = vec!["• ".dim(), message.into()];
     if let Some(hint) = hint {
@@ -2403,6 +2566,74 @@ mod tests {
         assert!(rendered[0].contains("Worked for"));
     }
 
+    #[test]
+    fn ps_output_lists_services() {
+        let cell = new_ps_output(
+            Vec::new(),
+            vec![ServiceDetails {
+                name: "web".to_string(),
+                command_display: "npm run dev".to_string(),
+            }],
+        );
+        let rendered = render_lines(&cell.display_lines(60));
+        assert!(rendered.iter().any(|line| line == "Services"));
+        assert!(rendered.iter().any(|line| line == "  • web · npm run dev"));
+    }
+
+    #[test]
+    fn context_breakdown_groups_categories_and_lists_largest_items() {
+        use codex_core::protocol::ContextItemSummary;
+
+        let item = |id: Option<&str>, category, label: &str, estimated_tokens| ContextItemSummary {
+            id: id.map(str::to_string),
+            category,
+            label: label.to_string(),
+            estimated_tokens,
+        };
+        let event = ContextBreakdownEvent {
+            items: vec![
+                item(
+                    None,
+                    ContextItemCategory::BaseInstructions,
+                    "gpt-5.1-codex",
+                    3_000,
+                ),
+                item(
+                    Some("item:0"),
+                    ContextItemCategory::UserInstructions,
+                    "AGENTS.md for /repo",
+                    500,
+                ),
+                item(
+                    Some("output:call-1"),
+                    ContextItemCategory::ToolOutput,
+                    "output of shell",
+                    40_000,
+                ),
+                item(
+                    Some("output:call-2"),
+                    ContextItemCategory::ToolOutput,
+                    "output of shell",
+                    200,
+                ),
+            ],
+            estimated_total_tokens: 43_700,
+            model_context_window: Some(272_000),
+        };
+
+        let rendered = render_lines(&new_context_breakdown(&event).display_lines(100));
+
+        assert_eq!(rendered[2], "Context  ~43.7K tokens of 272K (estimated)");
+        assert_eq!(rendered[4], "  • Tool outputs        40.2K  (2 items)");
+        assert!(
+            rendered
+                .iter()
+                .any(|line| line == "  •     40K  output:call-1  output of shell")
+        );
+        // The base instructions cannot be pruned, so they are not listed individually.
+        assert!(!rendered.iter().any(|line| line.contains("gpt-5.1-codex")));
+    }
+
     #[test]
     fn ps_output_empty_snapshot() {
         let cell = new_unified_exec_processes_output(Vec::new());
@@ -26,11 +26,13 @@ pub enum SlashCommand {
     Fork,
     Init,
     Compact,
+    Context,
     Plan,
     Collab,
     Agent,
     // Undo,
     Diff,
+    Commit,
     Mention,
     Status,
     Mcp,
@@ -39,6 +41,7 @@ pub enum SlashCommand {
     Quit,
     Exit,
     Feedback,
+    Export,
     Rollout,
     Ps,
     Personality,
@@ -53,6 +56,7 @@ impl SlashCommand {
             SlashCommand::New => "start a new chat during a conversation",
             SlashCommand::Init => "create an AGENTS.md file with instructions for Codex",
             SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
+            SlashCommand::Context => "show what fills the context window and prune large items",
             SlashCommand::Review => "review my current changes and find issues",
             SlashCommand::Rename => "rename the current thread",
             SlashCommand::Resume => "resume a saved chat",
@@ -60,6 +64,7 @@ impl SlashCommand {
             // SlashCommand::Undo => "ask Codex to undo a turn",
             SlashCommand::Quit | SlashCommand::Exit => "exit Codex",
             SlashCommand::Diff => "show git diff (including untracked files)",
+            SlashCommand::Commit => "split my changes into commits with drafted messages",
             SlashCommand::Mention => "mention a file",
             SlashCommand::Skills => "use skills to improve how Codex performs specific tasks",
             SlashCommand::Status => "show current session configuration and token usage",
@@ -76,6 +81,7 @@ impl SlashCommand {
             SlashCommand::Mcp => "list configured MCP tools",
             SlashCommand::Apps => "manage apps",
             SlashCommand::Logout => "log out of Codex",
+            SlashCommand::Export => "export this session as Markdown (or `/export html [path]`)",
             SlashCommand::Rollout => "print the rollout file path",
             SlashCommand::TestApproval => "test approval request",
         }
@@ -91,7 +97,11 @@ impl SlashCommand {
     pub fn supports_inline_args(self) -> bool {
         matches!(
             self,
-            SlashCommand::Review | SlashCommand::Rename | SlashCommand::Plan
+            SlashCommand::Review
+                | SlashCommand::Rename
+                | SlashCommand::Plan
+                | SlashCommand::Export
+                | SlashCommand::Context
         )
     }
 
@@ -112,16 +122,19 @@ impl SlashCommand {
             | SlashCommand::Experimental
             | SlashCommand::Review
             | SlashCommand::Plan
+            | SlashCommand::Commit
             | SlashCommand::Logout => false,
             SlashCommand::Diff
             | SlashCommand::Rename
             | SlashCommand::Mention
             | SlashCommand::Skills
             | SlashCommand::Status
+            | SlashCommand::Context
             | SlashCommand::Ps
             | SlashCommand::Mcp
             | SlashCommand::Apps
             | SlashCommand::Feedback
+            | SlashCommand::Export
             | SlashCommand::Quit
             | SlashCommand::Exit => true,
             SlashCommand::Rollout => true,
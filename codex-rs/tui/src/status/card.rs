@@ -15,6 +15,7 @@ use codex_core::protocol::TokenUsageInfo;
 use codex_protocol::ThreadId;
 use codex_protocol::account::PlanType;
 use codex_protocol::openai_models::ReasoningEffort;
+use codex_protocol::plan_tool::UpdatePlanArgs;
 use ratatui::prelude::*;
 use ratatui::style::Stylize;
 use std::collections::BTreeSet;
@@ -29,6 +30,7 @@ use super::format::truncate_line_to_width;
 use super::helpers::compose_account_display;
 use super::helpers::compose_agents_summary;
 use super::helpers::compose_model_display;
+use super::helpers::compose_plan_summary;
 use super::helpers::format_directory_display;
 use super::helpers::format_tokens_compact;
 use super::rate_limits::RateLimitSnapshotDisplay;
@@ -71,6 +73,7 @@ struct StatusHistoryCell {
     thread_name: Option<String>,
     session_id: Option<String>,
     forked_from: Option<String>,
+    task_plan: Option<String>,
     token_usage: StatusTokenUsageData,
     rate_limits: StatusRateLimitData,
 }
@@ -90,6 +93,7 @@ pub(crate) fn new_status_output(
     model_name: &str,
     collaboration_mode: Option<&str>,
     reasoning_effort_override: Option<Option<ReasoningEffort>>,
+    task_plan: Option<&UpdatePlanArgs>,
 ) -> CompositeHistoryCell {
     let command = PlainHistoryCell::new(vec!["/status".magenta().into()]);
     let card = StatusHistoryCell::new(
@@ -106,6 +110,7 @@ pub(crate) fn new_status_output(
         model_name,
         collaboration_mode,
         reasoning_effort_override,
+        task_plan,
     );
 
     CompositeHistoryCell::new(vec![Box::new(command), Box::new(card)])
@@ -127,6 +132,7 @@ impl StatusHistoryCell {
         model_name: &str,
         collaboration_mode: Option<&str>,
         reasoning_effort_override: Option<Option<ReasoningEffort>>,
+        task_plan: Option<&UpdatePlanArgs>,
     ) -> Self {
         let mut config_entries = vec![
             ("workdir", config.cwd.display().to_string()),
@@ -172,6 +178,7 @@ impl StatusHistoryCell {
         let account = compose_account_display(auth_manager, plan_type);
         let session_id = session_id.as_ref().map(std::string::ToString::to_string);
         let forked_from = forked_from.map(|id| id.to_string());
+        let task_plan = task_plan.and_then(compose_plan_summary);
         let default_usage = TokenUsage::default();
         let (context_usage, context_window) = match token_info {
             Some(info) => (&info.last_token_usage, info.model_context_window),
@@ -204,6 +211,7 @@ impl StatusHistoryCell {
             thread_name,
             session_id,
             forked_from,
+            task_plan,
             token_usage,
             rate_limits,
         }
@@ -399,6 +407,9 @@ impl HistoryCell for StatusHistoryCell {
         if self.session_id.is_some() && self.forked_from.is_some() {
             push_label(&mut labels, &mut seen, "Forked from");
         }
+        if self.task_plan.is_some() {
+            push_label(&mut labels, &mut seen, "Plan");
+        }
         if self.collaboration_mode.is_some() {
             push_label(&mut labels, &mut seen, "Collaboration mode");
         }
@@ -465,6 +476,9 @@ impl HistoryCell for StatusHistoryCell {
         {
             lines.push(formatter.line("Forked from", vec![Span::from(forked_from.clone())]));
         }
+        if let Some(task_plan) = self.task_plan.as_ref() {
+            lines.push(formatter.line("Plan", vec![Span::from(task_plan.clone())]));
+        }
 
         lines.push(Line::from(Vec::<Span<'static>>::new()));
         // Hide token usage only for ChatGPT subscribers
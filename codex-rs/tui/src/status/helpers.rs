@@ -7,6 +7,8 @@ use codex_core::CodexAuth;
 use codex_core::config::Config;
 use codex_core::project_doc::discover_project_doc_paths;
 use codex_protocol::account::PlanType;
+use codex_protocol::plan_tool::StepStatus;
+use codex_protocol::plan_tool::UpdatePlanArgs;
 use std::path::Path;
 use unicode_width::UnicodeWidthStr;
 
@@ -102,6 +104,28 @@ pub(crate) fn compose_account_display(
     }
 }
 
+/// Summarizes the latest `update_plan` checklist as progress plus the step in flight.
+pub(crate) fn compose_plan_summary(plan: &UpdatePlanArgs) -> Option<String> {
+    if plan.plan.is_empty() {
+        return None;
+    }
+    let total = plan.plan.len();
+    let completed = plan
+        .plan
+        .iter()
+        .filter(|item| matches!(item.status, StepStatus::Completed))
+        .count();
+    let summary = format!("{completed}/{total} steps completed");
+    match plan
+        .plan
+        .iter()
+        .find(|item| matches!(item.status, StepStatus::InProgress))
+    {
+        Some(current) => Some(format!("{summary} (current: {})", current.step)),
+        None => Some(summary),
+    }
+}
+
 pub(crate) fn format_tokens_compact(value: i64) -> String {
     let value = value.max(0);
     if value == 0 {
@@ -17,6 +17,9 @@ use codex_core::protocol::TokenUsageInfo;
 use codex_protocol::ThreadId;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::openai_models::ReasoningEffort;
+use codex_protocol::plan_tool::PlanItemArg;
+use codex_protocol::plan_tool::StepStatus;
+use codex_protocol::plan_tool::UpdatePlanArgs;
 use insta::assert_snapshot;
 use ratatui::prelude::*;
 use std::path::PathBuf;
@@ -155,6 +158,7 @@ async fn status_snapshot_includes_reasoning_details() {
         &model_slug,
         None,
         reasoning_effort_override,
+        None,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -209,6 +213,7 @@ async fn status_snapshot_includes_forked_from() {
         &model_slug,
         None,
         None,
+        None,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -269,6 +274,7 @@ async fn status_snapshot_includes_monthly_limit() {
         &model_slug,
         None,
         None,
+        None,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -317,6 +323,7 @@ async fn status_snapshot_shows_unlimited_credits() {
         &model_slug,
         None,
         None,
+        None,
     );
     let rendered = render_lines(&composite.display_lines(120));
     assert!(
@@ -364,6 +371,7 @@ async fn status_snapshot_shows_positive_credits() {
         &model_slug,
         None,
         None,
+        None,
     );
     let rendered = render_lines(&composite.display_lines(120));
     assert!(
@@ -411,6 +419,7 @@ async fn status_snapshot_hides_zero_credits() {
         &model_slug,
         None,
         None,
+        None,
     );
     let rendered = render_lines(&composite.display_lines(120));
     assert!(
@@ -456,6 +465,7 @@ async fn status_snapshot_hides_when_has_no_credits_flag() {
         &model_slug,
         None,
         None,
+        None,
     );
     let rendered = render_lines(&composite.display_lines(120));
     assert!(
@@ -501,6 +511,7 @@ async fn status_card_token_usage_excludes_cached_tokens() {
         &model_slug,
         None,
         None,
+        None,
     );
     let rendered = render_lines(&composite.display_lines(120));
 
@@ -510,6 +521,63 @@ async fn status_card_token_usage_excludes_cached_tokens() {
     );
 }
 
+#[tokio::test]
+async fn status_card_shows_plan_progress() {
+    let temp_home = TempDir::new().expect("temp home");
+    let mut config = test_config(&temp_home).await;
+    config.model = Some("gpt-5.1-codex-max".to_string());
+    config.cwd = PathBuf::from("/workspace/tests");
+
+    let auth_manager = test_auth_manager(&config);
+    let usage = TokenUsage::default();
+    let now = chrono::Local
+        .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+        .single()
+        .expect("timestamp");
+    let plan = UpdatePlanArgs {
+        explanation: None,
+        plan: vec![
+            PlanItemArg {
+                step: "Read the parser".to_string(),
+                status: StepStatus::Completed,
+            },
+            PlanItemArg {
+                step: "Fix escaping".to_string(),
+                status: StepStatus::InProgress,
+            },
+            PlanItemArg {
+                step: "Add tests".to_string(),
+                status: StepStatus::Pending,
+            },
+        ],
+    };
+
+    let model_slug = ModelsManager::get_model_offline(config.model.as_deref());
+    let composite = new_status_output(
+        &config,
+        &auth_manager,
+        None,
+        &usage,
+        &None,
+        None,
+        None,
+        None,
+        None,
+        now,
+        &model_slug,
+        None,
+        None,
+        Some(&plan),
+    );
+    let rendered = render_lines(&composite.display_lines(120));
+
+    assert!(
+        rendered.iter().any(|line| line.contains("Plan:")
+            && line.contains("1/3 steps completed (current: Fix escaping)")),
+        "expected plan progress row, got: {rendered:?}"
+    );
+}
+
 #[tokio::test]
 async fn status_snapshot_truncates_in_narrow_terminal() {
     let temp_home = TempDir::new().expect("temp home");
@@ -561,6 +629,7 @@ async fn status_snapshot_truncates_in_narrow_terminal() {
         &model_slug,
         None,
         reasoning_effort_override,
+        None,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(70));
     if cfg!(windows) {
@@ -610,6 +679,7 @@ async fn status_snapshot_shows_missing_limits_message() {
         &model_slug,
         None,
         None,
+        None,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -677,6 +747,7 @@ async fn status_snapshot_includes_credits_and_limits() {
         &model_slug,
         None,
         None,
+        None,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -732,6 +803,7 @@ async fn status_snapshot_shows_empty_limits_message() {
         &model_slug,
         None,
         None,
+        None,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -796,6 +868,7 @@ async fn status_snapshot_shows_stale_limits_message() {
         &model_slug,
         None,
         None,
+        None,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -864,6 +937,7 @@ async fn status_snapshot_cached_limits_hide_credits_without_flag() {
         &model_slug,
         None,
         None,
+        None,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -922,6 +996,7 @@ async fn status_context_window_uses_last_usage() {
         &model_slug,
         None,
         None,
+        None,
     );
     let rendered_lines = render_lines(&composite.display_lines(80));
     let context_line = rendered_lines
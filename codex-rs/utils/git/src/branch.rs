@@ -47,6 +47,77 @@ pub fn merge_base_with_head(
     Ok(Some(merge_base))
 }
 
+/// A commit listed by [`commits_in_range`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitSummary {
+    pub sha: String,
+    /// First line of the commit message.
+    pub subject: String,
+}
+
+/// Lists the non-merge commits reachable from `head` but not from `base`,
+/// oldest first, mirroring `git log --reverse --no-merges base..head`.
+///
+/// Returns `Ok(None)` when either revision does not name a commit.
+pub fn commits_in_range(
+    repo_path: &Path,
+    base: &str,
+    head: &str,
+) -> Result<Option<Vec<CommitSummary>>, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    let Some(base_sha) = resolve_commit(repo_root.as_path(), base)? else {
+        return Ok(None);
+    };
+    let Some(head_sha) = resolve_commit(repo_root.as_path(), head)? else {
+        return Ok(None);
+    };
+
+    let log = run_git_for_stdout(
+        repo_root.as_path(),
+        vec![
+            OsString::from("log"),
+            OsString::from("--reverse"),
+            OsString::from("--no-merges"),
+            OsString::from("--format=%H%x1f%s"),
+            OsString::from(format!("{base_sha}..{head_sha}")),
+        ],
+        None,
+    )?;
+
+    let commits = log
+        .lines()
+        .filter_map(|line| {
+            let (sha, subject) = line.split_once('\u{1f}')?;
+            Some(CommitSummary {
+                sha: sha.to_string(),
+                subject: subject.to_string(),
+            })
+        })
+        .collect();
+    Ok(Some(commits))
+}
+
+fn resolve_commit(repo_root: &Path, rev: &str) -> Result<Option<String>, GitToolingError> {
+    // Keep revisions from being parsed as options.
+    if rev.is_empty() || rev.starts_with('-') {
+        return Ok(None);
+    }
+    match run_git_for_stdout(
+        repo_root,
+        vec![
+            OsString::from("rev-parse"),
+            OsString::from("--verify"),
+            OsString::from(format!("{rev}^{{commit}}")),
+        ],
+        None,
+    ) {
+        Ok(sha) => Ok(Some(sha)),
+        Err(GitToolingError::GitCommand { .. }) => Ok(None),
+        Err(other) => Err(other),
+    }
+}
+
 fn resolve_branch_ref(repo_root: &Path, branch: &str) -> Result<Option<String>, GitToolingError> {
     let rev = run_git_for_stdout(
         repo_root,
@@ -118,6 +189,8 @@ fn resolve_upstream_if_remote_ahead(
 
 #[cfg(test)]
 mod tests {
+    use super::CommitSummary;
+    use super::commits_in_range;
     use super::merge_base_with_head;
     use crate::GitToolingError;
     use pretty_assertions::assert_eq;
@@ -253,4 +326,41 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn commits_in_range_lists_branch_commits_oldest_first() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+
+        std::fs::write(repo.join("base.txt"), "base\n")?;
+        run_git_in(repo, &["add", "base.txt"]);
+        commit(repo, "base commit");
+
+        run_git_in(repo, &["checkout", "-b", "feature"]);
+        for name in ["one", "two"] {
+            std::fs::write(repo.join(format!("{name}.txt")), "change\n")?;
+            run_git_in(repo, &["add", "."]);
+            commit(repo, &format!("add {name}"));
+        }
+
+        let commits = commits_in_range(repo, "main", "HEAD")?;
+        assert_eq!(
+            commits,
+            Some(vec![
+                CommitSummary {
+                    sha: run_git_stdout(repo, &["rev-parse", "HEAD~1"]),
+                    subject: "add one".to_string(),
+                },
+                CommitSummary {
+                    sha: run_git_stdout(repo, &["rev-parse", "HEAD"]),
+                    subject: "add two".to_string(),
+                },
+            ])
+        );
+        assert_eq!(commits_in_range(repo, "missing", "HEAD")?, None);
+        assert_eq!(commits_in_range(repo, "--all", "HEAD")?, None);
+
+        Ok(())
+    }
 }
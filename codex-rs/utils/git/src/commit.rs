@@ -0,0 +1,289 @@
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::GitToolingError;
+use crate::operations::ensure_git_repository;
+use crate::operations::normalize_relative_path;
+use crate::operations::resolve_head;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_status;
+use crate::operations::run_git_for_stdout;
+use crate::operations::run_git_for_stdout_all;
+
+/// A path with uncommitted changes, relative to the repository root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedFile {
+    pub path: PathBuf,
+    /// Source path of a rename or copy.
+    pub original_path: Option<PathBuf>,
+    /// Two-letter `git status --porcelain` code, e.g. ` M` or `??`.
+    pub status: String,
+}
+
+impl ChangedFile {
+    pub fn is_untracked(&self) -> bool {
+        self.status == "??"
+    }
+}
+
+/// Lists every path with staged, unstaged or untracked changes, in the order
+/// `git status` reports them.
+pub fn changed_files(repo_path: &Path) -> Result<Vec<ChangedFile>, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    let output = run_git_for_stdout_all(
+        repo_root.as_path(),
+        [
+            "status",
+            "--porcelain=v1",
+            "-z",
+            "--untracked-files=all",
+            "--no-renames",
+        ],
+        None,
+    )?;
+    Ok(parse_porcelain_z(&output))
+}
+
+fn parse_porcelain_z(output: &str) -> Vec<ChangedFile> {
+    let mut files = Vec::new();
+    let mut entries = output.split('\0').filter(|entry| !entry.is_empty());
+    while let Some(entry) = entries.next() {
+        if entry.len() < 4 {
+            continue;
+        }
+        let (status, path) = entry.split_at(3);
+        let status = status[..2].to_string();
+        // Renames and copies are followed by their source path.
+        let original_path = if status.starts_with('R') || status.starts_with('C') {
+            entries.next().map(PathBuf::from)
+        } else {
+            None
+        };
+        files.push(ChangedFile {
+            path: PathBuf::from(path),
+            original_path,
+            status,
+        });
+    }
+    files
+}
+
+/// Unified diff of `file` against `HEAD`. Untracked files are shown as added,
+/// reading at most `max_bytes` of their contents.
+pub fn file_diff(
+    repo_path: &Path,
+    file: &ChangedFile,
+    max_bytes: usize,
+) -> Result<String, GitToolingError> {
+    let repo_root = resolve_repository_root(repo_path)?;
+    let path = normalize_relative_path(&file.path)?;
+    if file.is_untracked() {
+        let mut contents = Vec::new();
+        File::open(repo_root.join(&path))?
+            .take(max_bytes as u64 + 1)
+            .read_to_end(&mut contents)?;
+        let truncated = contents.len() > max_bytes;
+        contents.truncate(max_bytes);
+        let contents = match String::from_utf8(contents) {
+            Ok(contents) => contents,
+            // The limit may split the last character; keep what precedes it.
+            Err(err) if truncated && err.utf8_error().error_len().is_none() => {
+                let valid = err.utf8_error().valid_up_to();
+                let mut bytes = err.into_bytes();
+                bytes.truncate(valid);
+                String::from_utf8(bytes).unwrap_or_default()
+            }
+            Err(_) => return Ok(format!("Binary file {} added\n", path.display())),
+        };
+        let mut diff = format!("--- /dev/null\n+++ b/{}\n", path.display());
+        for line in contents.lines() {
+            diff.push('+');
+            diff.push_str(line);
+            diff.push('\n');
+        }
+        if truncated {
+            diff.push_str(&format!("\\ Truncated after {max_bytes} bytes\n"));
+        }
+        return Ok(diff);
+    }
+
+    let mut args = vec![OsString::from("diff")];
+    if resolve_head(repo_root.as_path())?.is_some() {
+        args.push(OsString::from("HEAD"));
+    } else {
+        args.push(OsString::from("--cached"));
+    }
+    args.push(OsString::from("--"));
+    args.push(path.into_os_string());
+    run_git_for_stdout_all(repo_root.as_path(), args, None)
+}
+
+/// Commits exactly `paths` with `message` and returns the new commit id.
+/// Untracked and deleted paths are staged first. Anything else already in the
+/// index stays staged but is left out of the commit. Hooks such as
+/// `commit-msg` run as they would for `git commit`.
+pub fn create_commit(
+    repo_path: &Path,
+    message: &str,
+    paths: &[PathBuf],
+) -> Result<String, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    let paths = paths
+        .iter()
+        .map(|path| normalize_relative_path(path).map(PathBuf::into_os_string))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut add_args = vec![
+        OsString::from("add"),
+        OsString::from("-A"),
+        OsString::from("--"),
+    ];
+    add_args.extend(paths.iter().cloned());
+    run_git_for_status(repo_root.as_path(), add_args, None)?;
+
+    let mut commit_args = vec![
+        OsString::from("commit"),
+        OsString::from("--quiet"),
+        OsString::from("-m"),
+        OsString::from(message),
+        OsString::from("--"),
+    ];
+    commit_args.extend(paths);
+    run_git_for_status(repo_root.as_path(), commit_args, None)?;
+
+    run_git_for_stdout(repo_root.as_path(), ["rev-parse", "HEAD"], None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn run_git_in(repo_path: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    }
+
+    fn run_git_stdout(repo_path: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .output()
+            .expect("git command");
+        assert!(output.status.success(), "git command failed: {args:?}");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn init_test_repo(repo_path: &Path) {
+        run_git_in(repo_path, &["init", "--initial-branch=main"]);
+        run_git_in(repo_path, &["config", "core.autocrlf", "false"]);
+        run_git_in(repo_path, &["config", "user.name", "Tester"]);
+        run_git_in(repo_path, &["config", "user.email", "test@example.com"]);
+    }
+
+    #[test]
+    fn parse_porcelain_z_reads_renames_and_untracked() {
+        let output = " M src/lib.rs\0R  new.rs\0old.rs\0?? notes.md\0";
+        assert_eq!(
+            parse_porcelain_z(output),
+            vec![
+                ChangedFile {
+                    path: PathBuf::from("src/lib.rs"),
+                    original_path: None,
+                    status: " M".to_string(),
+                },
+                ChangedFile {
+                    path: PathBuf::from("new.rs"),
+                    original_path: Some(PathBuf::from("old.rs")),
+                    status: "R ".to_string(),
+                },
+                ChangedFile {
+                    path: PathBuf::from("notes.md"),
+                    original_path: None,
+                    status: "??".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn create_commit_includes_only_requested_paths() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        std::fs::write(repo.join("a.txt"), "a\n")?;
+        std::fs::write(repo.join("b.txt"), "b\n")?;
+        run_git_in(repo, &["add", "."]);
+        run_git_in(repo, &["commit", "-m", "initial"]);
+
+        std::fs::write(repo.join("a.txt"), "a changed\n")?;
+        std::fs::write(repo.join("b.txt"), "b changed\n")?;
+        std::fs::write(repo.join("c.txt"), "c\n")?;
+
+        let changed: Vec<PathBuf> = changed_files(repo)?.into_iter().map(|f| f.path).collect();
+        assert_eq!(
+            changed,
+            vec![
+                PathBuf::from("a.txt"),
+                PathBuf::from("b.txt"),
+                PathBuf::from("c.txt"),
+            ]
+        );
+
+        let sha = create_commit(
+            repo,
+            "Update a and add c",
+            &[PathBuf::from("a.txt"), PathBuf::from("c.txt")],
+        )?;
+
+        assert_eq!(sha, run_git_stdout(repo, &["rev-parse", "HEAD"]));
+        assert_eq!(
+            run_git_stdout(repo, &["show", "--name-only", "--format=%s", "HEAD"]),
+            "Update a and add c\n\na.txt\nc.txt"
+        );
+        let remaining: Vec<PathBuf> = changed_files(repo)?.into_iter().map(|f| f.path).collect();
+        assert_eq!(remaining, vec![PathBuf::from("b.txt")]);
+        Ok(())
+    }
+
+    #[test]
+    fn file_diff_shows_untracked_files_as_added() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        std::fs::write(repo.join("new.txt"), "one\ntwo\n")?;
+
+        let file = changed_files(repo)?.remove(0);
+        assert_eq!(
+            file_diff(repo, &file, 1024)?,
+            "--- /dev/null\n+++ b/new.txt\n+one\n+two\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn file_diff_reads_untracked_files_up_to_the_limit() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        std::fs::write(repo.join("new.txt"), "one\ntwo\nthree\n")?;
+
+        let file = changed_files(repo)?.remove(0);
+        assert_eq!(
+            file_diff(repo, &file, 6)?,
+            "--- /dev/null\n+++ b/new.txt\n+one\n+tw\n\\ Truncated after 6 bytes\n"
+        );
+        Ok(())
+    }
+}
@@ -26,6 +26,8 @@ pub enum GitToolingError {
     NonRelativePath { path: PathBuf },
     #[error("path {path:?} escapes the repository root")]
     PathEscapesRepository { path: PathBuf },
+    #[error("{path:?} has more than {limit} files, too many to snapshot")]
+    WorkspaceTooLarge { path: PathBuf, limit: usize },
     #[error("failed to process path inside worktree")]
     PathPrefix(#[from] std::path::StripPrefixError),
     #[error(transparent)]
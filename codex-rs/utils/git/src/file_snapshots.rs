@@ -0,0 +1,913 @@
+//! Snapshots for workspaces that are not git repositories.
+//!
+//! Ghost commits need a repository, so outside one each turn instead scans the
+//! workspace and copies every new or changed file into a content-addressed
+//! object store at `$CODEX_HOME/file_snapshots/objects/<hash prefix>/<sha256>`.
+//! The scan runs before any tool call of the turn, so the store holds the
+//! pre-modification copy of whatever `apply_patch` or a command changes. Each
+//! snapshot is a manifest of relative path, size, mtime, and object hash under
+//! `$CODEX_HOME/file_snapshots/sessions/<session id>/<seq>.json`. Unchanged
+//! files (same size and mtime as in the previous manifest) are not re-read,
+//! unless their mtime is too close to the previous snapshot to be trusted.
+//!
+//! Restoring a manifest rewrites files whose contents differ and deletes files
+//! created since. Symlinks that took the place of recorded files or
+//! directories are removed, never written through.
+//!
+//! Each session keeps the hashes its manifests reference in `refs.json` next
+//! to them, so garbage collection reads one index per session instead of
+//! every manifest. When the objects a session references grow past the size
+//! limit, that session's oldest manifests are dropped; other sessions are
+//! left alone. Objects no session references are removed.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::fs::File;
+use std::fs::FileTimes;
+use std::io::Write;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use walkdir::WalkDir;
+
+use crate::GhostSnapshotConfig;
+use crate::GhostSnapshotReport;
+use crate::GitToolingError;
+use crate::IgnoredUntrackedFile;
+use crate::ghost_commits::DEFAULT_IGNORED_DIR_NAMES;
+
+const FILE_SNAPSHOT_DIR: &str = "file_snapshots";
+const OBJECTS_DIR: &str = "objects";
+const SESSIONS_DIR: &str = "sessions";
+const REFS_FILE: &str = "refs.json";
+/// Workspaces with more files than this are not snapshotted, so starting
+/// Codex in a home directory does not copy the whole disk.
+const MAX_SCANNED_FILES: usize = 50_000;
+/// Oldest snapshots of a session are dropped past this count.
+const MAX_SNAPSHOTS_PER_SESSION: usize = 100;
+/// Unreferenced objects younger than this are kept, since another session may
+/// have written them for a manifest it has not saved yet.
+const GC_GRACE_PERIOD: Duration = Duration::from_secs(10 * 60);
+/// Coarsest mtime resolution in common use (FAT). A file whose mtime is this
+/// close to a snapshot may have changed again within the same tick.
+const MTIME_GRANULARITY: Duration = Duration::from_secs(2);
+/// Version control metadata of other systems is left alone.
+const VCS_DIR_NAMES: &[&str] = &[".svn", ".hg", ".bzr", ".jj", "CVS"];
+
+/// A file recorded in a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileSnapshotEntry {
+    /// Path relative to the workspace root.
+    pub path: PathBuf,
+    pub size: u64,
+    pub mtime_ns: u128,
+    #[serde(default)]
+    pub executable: bool,
+    /// Content hash, or `None` when the file was too large to capture.
+    pub hash: Option<String>,
+}
+
+/// The state of a workspace at the start of a turn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileSnapshot {
+    pub id: u64,
+    pub workspace: PathBuf,
+    /// Seconds since the epoch when the scan started.
+    pub created_at: u64,
+    pub files: Vec<FileSnapshotEntry>,
+    pub dirs: Vec<PathBuf>,
+}
+
+/// Snapshots taken by one session, backed by a store shared across sessions.
+#[derive(Debug, Clone)]
+pub struct FileSnapshotStore {
+    root: PathBuf,
+    session_dir: PathBuf,
+    max_file_bytes: Option<u64>,
+    max_store_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FileMeta {
+    size: u64,
+    mtime_ns: u128,
+    executable: bool,
+}
+
+struct WorkspaceScan {
+    files: BTreeMap<PathBuf, FileMeta>,
+    dirs: BTreeSet<PathBuf>,
+}
+
+impl FileSnapshotStore {
+    pub fn new(codex_home: &Path, session_id: &str, config: &GhostSnapshotConfig) -> Self {
+        let root = codex_home.join(FILE_SNAPSHOT_DIR);
+        let session_dir = root.join(SESSIONS_DIR).join(session_id);
+        Self {
+            root,
+            session_dir,
+            max_file_bytes: config
+                .ignore_large_untracked_files
+                .and_then(|bytes| u64::try_from(bytes).ok()),
+            max_store_bytes: config
+                .file_snapshot_max_bytes
+                .and_then(|bytes| u64::try_from(bytes).ok()),
+        }
+    }
+
+    /// Records the current state of `workspace` as a new snapshot.
+    pub fn capture(
+        &self,
+        workspace: &Path,
+    ) -> Result<(FileSnapshot, GhostSnapshotReport), GitToolingError> {
+        // Taken before the scan so every recorded mtime can be compared to it.
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let scan = scan_workspace(workspace)?;
+        let (previous, previous_created_at): (BTreeMap<PathBuf, FileSnapshotEntry>, u64) =
+            match self.latest()? {
+                Some(snapshot) if snapshot.workspace.as_path() == workspace => (
+                    snapshot
+                        .files
+                        .into_iter()
+                        .map(|entry| (entry.path.clone(), entry))
+                        .collect(),
+                    snapshot.created_at,
+                ),
+                _ => (BTreeMap::new(), 0),
+            };
+
+        let mut report = GhostSnapshotReport::default();
+        let mut files = Vec::with_capacity(scan.files.len());
+        for (path, meta) in scan.files {
+            let reused = previous.get(&path).and_then(|entry| {
+                let unchanged = entry.size == meta.size
+                    && entry.mtime_ns == meta.mtime_ns
+                    && mtime_settled(entry.mtime_ns, previous_created_at);
+                entry
+                    .hash
+                    .clone()
+                    .filter(|hash| unchanged && self.touch_object(hash))
+            });
+            let hash = match reused {
+                Some(hash) => Some(hash),
+                None if self.max_file_bytes.is_some_and(|max| meta.size > max) => {
+                    report.ignored_untracked_files.push(IgnoredUntrackedFile {
+                        path: path.clone(),
+                        byte_size: i64::try_from(meta.size).unwrap_or(i64::MAX),
+                    });
+                    None
+                }
+                None => Some(self.store_object(&workspace.join(&path))?),
+            };
+            files.push(FileSnapshotEntry {
+                path,
+                size: meta.size,
+                mtime_ns: meta.mtime_ns,
+                executable: meta.executable,
+                hash,
+            });
+        }
+
+        let snapshot = FileSnapshot {
+            id: self.snapshot_ids()?.last().map_or(1, |id| id + 1),
+            workspace: workspace.to_path_buf(),
+            created_at,
+            files,
+            dirs: scan.dirs.into_iter().collect(),
+        };
+        std::fs::create_dir_all(&self.session_dir)?;
+        // The index is updated first so it never misses an object a saved
+        // manifest needs, even if another session collects garbage meanwhile.
+        let mut refs = self.session_refs(&self.session_dir)?;
+        refs.extend(snapshot_refs(&snapshot));
+        self.write_refs(&refs)?;
+        let manifest = serde_json::to_vec(&snapshot)
+            .map_err(|err| GitToolingError::Io(std::io::Error::other(err)))?;
+        write_atomically(&self.manifest_path(snapshot.id), &manifest)?;
+
+        let ids = self.snapshot_ids()?;
+        if ids.len() > MAX_SNAPSHOTS_PER_SESSION {
+            for id in &ids[..ids.len() - MAX_SNAPSHOTS_PER_SESSION] {
+                remove_if_exists(&self.manifest_path(*id))?;
+            }
+        }
+        self.collect_garbage()?;
+        Ok((snapshot, report))
+    }
+
+    /// The most recent snapshot of this session, if any.
+    pub fn latest(&self) -> Result<Option<FileSnapshot>, GitToolingError> {
+        let Some(id) = self.snapshot_ids()?.last().copied() else {
+            return Ok(None);
+        };
+        let contents = std::fs::read(self.manifest_path(id))?;
+        let snapshot = serde_json::from_slice(&contents)
+            .map_err(|err| GitToolingError::Io(std::io::Error::other(err)))?;
+        Ok(Some(snapshot))
+    }
+
+    /// Puts the workspace back into the state recorded by `snapshot`.
+    ///
+    /// Files that were too large to capture are left as they are. Nothing is
+    /// touched unless every object the snapshot needs is still stored.
+    pub fn restore(&self, snapshot: &FileSnapshot) -> Result<(), GitToolingError> {
+        let workspace = snapshot.workspace.as_path();
+        for dir in &snapshot.dirs {
+            ensure_relative(dir)?;
+        }
+        for entry in &snapshot.files {
+            ensure_relative(&entry.path)?;
+            if let Some(hash) = &entry.hash
+                && !self.object_path(hash).is_file()
+            {
+                return Err(GitToolingError::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!(
+                        "snapshot content of {} is no longer stored",
+                        entry.path.display()
+                    ),
+                )));
+            }
+        }
+        let current = scan_workspace(workspace)?;
+        let recorded: BTreeMap<&Path, &FileSnapshotEntry> = snapshot
+            .files
+            .iter()
+            .map(|entry| (entry.path.as_path(), entry))
+            .collect();
+
+        for path in current.files.keys() {
+            if !recorded.contains_key(path.as_path()) {
+                remove_if_exists(&workspace.join(path))?;
+            }
+        }
+
+        for dir in &snapshot.dirs {
+            create_dir_all_no_follow(workspace, dir)?;
+        }
+        let canonical_workspace = workspace.canonicalize()?;
+        for entry in &snapshot.files {
+            let Some(hash) = &entry.hash else {
+                continue;
+            };
+            if let Some(meta) = current.files.get(&entry.path)
+                && meta.size == entry.size
+                && meta.mtime_ns == entry.mtime_ns
+                && mtime_settled(entry.mtime_ns, snapshot.created_at)
+            {
+                continue;
+            }
+            let target = workspace.join(&entry.path);
+            if let Some(parent) = entry.path.parent() {
+                create_dir_all_no_follow(workspace, parent)?;
+            }
+            if let Some(parent) = target.parent()
+                && !parent.canonicalize()?.starts_with(&canonical_workspace)
+            {
+                return Err(GitToolingError::PathEscapesRepository {
+                    path: entry.path.clone(),
+                });
+            }
+            // The scan skips symlinks, so one may have replaced the recorded
+            // file since. Writing through it could reach outside the workspace.
+            match std::fs::symlink_metadata(&target) {
+                Ok(metadata) if metadata.file_type().is_symlink() => remove_symlink(&target)?,
+                Ok(_) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+            // Written in place so the file keeps its other permission bits.
+            std::fs::write(&target, std::fs::read(self.object_path(hash))?)?;
+            set_executable(&target, entry.executable)?;
+            let modified = UNIX_EPOCH
+                + Duration::from_nanos(u64::try_from(entry.mtime_ns).unwrap_or(u64::MAX));
+            File::options()
+                .write(true)
+                .open(&target)?
+                .set_times(FileTimes::new().set_modified(modified))?;
+        }
+
+        // Drop directories created since the snapshot, deepest first, once empty.
+        let recorded_dirs: HashSet<&Path> = snapshot.dirs.iter().map(PathBuf::as_path).collect();
+        for dir in current.dirs.iter().rev() {
+            if !recorded_dirs.contains(dir.as_path()) {
+                let _ = std::fs::remove_dir(workspace.join(dir));
+            }
+        }
+        Ok(())
+    }
+
+    /// Forgets `snapshot` after it has been restored.
+    pub fn discard(&self, snapshot: &FileSnapshot) -> Result<(), GitToolingError> {
+        remove_if_exists(&self.manifest_path(snapshot.id))?;
+        let refs = manifests_refs(&self.session_manifests()?);
+        self.write_refs(&refs)?;
+        self.collect_garbage()
+    }
+
+    /// Drops this session's oldest manifests while the objects it references
+    /// exceed the size limit, then removes objects no session references. The
+    /// session's newest manifest is always kept.
+    fn collect_garbage(&self) -> Result<(), GitToolingError> {
+        let object_sizes = self.object_sizes()?;
+        let over_budget = |refs: &BTreeSet<String>| {
+            let total: u64 = refs
+                .iter()
+                .filter_map(|hash| object_sizes.get(hash).map(|(size, _)| size))
+                .sum();
+            self.max_store_bytes.is_some_and(|max| total > max)
+        };
+
+        // The index may still list objects of manifests pruned by count, so
+        // the exact set is only worked out once the index is over budget.
+        let mut refs = self.session_refs(&self.session_dir)?;
+        if over_budget(&refs) {
+            let mut manifests = self.session_manifests()?;
+            refs = manifests_refs(&manifests);
+            while manifests.len() > 1 && over_budget(&refs) {
+                let snapshot = manifests.remove(0);
+                remove_if_exists(&self.manifest_path(snapshot.id))?;
+                refs = manifests_refs(&manifests);
+            }
+            self.write_refs(&refs)?;
+        }
+
+        let sessions = self.root.join(SESSIONS_DIR);
+        for entry in std::fs::read_dir(&sessions)? {
+            let dir = entry?.path();
+            if dir.is_dir() && dir != self.session_dir {
+                refs.extend(self.session_refs(&dir)?);
+            }
+        }
+        let now = SystemTime::now();
+        for (hash, (_, modified)) in &object_sizes {
+            let recent = now
+                .duration_since(*modified)
+                .is_ok_and(|age| age < GC_GRACE_PERIOD);
+            if !refs.contains(hash) && !recent {
+                remove_if_exists(&self.object_path(hash))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Hashes referenced by the manifests in `session_dir`, from its index.
+    /// Sessions written before the index existed have their manifests read.
+    fn session_refs(&self, session_dir: &Path) -> Result<BTreeSet<String>, GitToolingError> {
+        match std::fs::read(session_dir.join(REFS_FILE)) {
+            Ok(contents) => serde_json::from_slice(&contents)
+                .map_err(|err| GitToolingError::Io(std::io::Error::other(err))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                Ok(manifests_refs(&read_manifests(session_dir)?))
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn write_refs(&self, refs: &BTreeSet<String>) -> Result<(), GitToolingError> {
+        let contents = serde_json::to_vec(refs)
+            .map_err(|err| GitToolingError::Io(std::io::Error::other(err)))?;
+        write_atomically(&self.session_dir.join(REFS_FILE), &contents)
+    }
+
+    /// This session's manifests, oldest first.
+    fn session_manifests(&self) -> Result<Vec<FileSnapshot>, GitToolingError> {
+        let mut manifests = read_manifests(&self.session_dir)?;
+        manifests.sort_by_key(|snapshot| snapshot.id);
+        Ok(manifests)
+    }
+
+    fn object_sizes(&self) -> Result<BTreeMap<String, (u64, SystemTime)>, GitToolingError> {
+        let objects = self.root.join(OBJECTS_DIR);
+        if !objects.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let mut sizes = BTreeMap::new();
+        for entry in WalkDir::new(&objects).min_depth(2).max_depth(2) {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                sizes.insert(name, (metadata.len(), metadata.modified()?));
+            }
+        }
+        Ok(sizes)
+    }
+
+    fn snapshot_ids(&self) -> Result<Vec<u64>, GitToolingError> {
+        if !self.session_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut ids = Vec::new();
+        for entry in std::fs::read_dir(&self.session_dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json")
+                && let Some(id) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse().ok())
+            {
+                ids.push(id);
+            }
+        }
+        ids.sort_unstable();
+        Ok(ids)
+    }
+
+    fn store_object(&self, path: &Path) -> Result<String, GitToolingError> {
+        let contents = std::fs::read(path)?;
+        let hash = format!("{:x}", Sha256::digest(&contents));
+        if !self.touch_object(&hash) {
+            let object = self.object_path(&hash);
+            if let Some(parent) = object.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            write_atomically(&object, &contents)?;
+        }
+        Ok(hash)
+    }
+
+    /// Bumps the mtime of an existing object so garbage collection in another
+    /// session treats it as recently used until the manifest that needs it is
+    /// indexed. Returns false when the object is not stored.
+    fn touch_object(&self, hash: &str) -> bool {
+        File::options()
+            .write(true)
+            .open(self.object_path(hash))
+            .and_then(|file| file.set_modified(SystemTime::now()))
+            .is_ok()
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        let prefix = hash.get(..2).unwrap_or(hash);
+        self.root.join(OBJECTS_DIR).join(prefix).join(hash)
+    }
+
+    fn manifest_path(&self, id: u64) -> PathBuf {
+        self.session_dir.join(format!("{id}.json"))
+    }
+}
+
+fn read_manifests(session_dir: &Path) -> Result<Vec<FileSnapshot>, GitToolingError> {
+    if !session_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut manifests = Vec::new();
+    for entry in std::fs::read_dir(session_dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json")
+            || path.file_name().is_some_and(|name| name == REFS_FILE)
+        {
+            continue;
+        }
+        // Skip manifests that are unreadable, e.g. from a newer version.
+        let Ok(contents) = std::fs::read(&path) else {
+            continue;
+        };
+        if let Ok(snapshot) = serde_json::from_slice::<FileSnapshot>(&contents) {
+            manifests.push(snapshot);
+        }
+    }
+    Ok(manifests)
+}
+
+fn snapshot_refs(snapshot: &FileSnapshot) -> impl Iterator<Item = String> + '_ {
+    snapshot.files.iter().filter_map(|entry| entry.hash.clone())
+}
+
+fn manifests_refs(manifests: &[FileSnapshot]) -> BTreeSet<String> {
+    manifests.iter().flat_map(snapshot_refs).collect()
+}
+
+/// Whether an mtime recorded by a snapshot taken at `created_at` (seconds)
+/// is old enough that a later edit would have moved it.
+fn mtime_settled(mtime_ns: u128, created_at: u64) -> bool {
+    let settled_before = created_at.saturating_sub(MTIME_GRANULARITY.as_secs());
+    mtime_ns < u128::from(settled_before) * 1_000_000_000
+}
+
+fn scan_workspace(workspace: &Path) -> Result<WorkspaceScan, GitToolingError> {
+    let mut files = BTreeMap::new();
+    let mut dirs = BTreeSet::new();
+    let walker = WalkDir::new(workspace)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| {
+            !(entry.file_type().is_dir() && is_ignored_dir_name(entry.file_name().to_str()))
+        });
+    for entry in walker {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(workspace)?.to_path_buf();
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            dirs.insert(relative);
+        } else if file_type.is_file() {
+            if files.len() >= MAX_SCANNED_FILES {
+                return Err(GitToolingError::WorkspaceTooLarge {
+                    path: workspace.to_path_buf(),
+                    limit: MAX_SCANNED_FILES,
+                });
+            }
+            let metadata = entry.metadata()?;
+            let mtime_ns = metadata
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_nanos())
+                .unwrap_or_default();
+            files.insert(
+                relative,
+                FileMeta {
+                    size: metadata.len(),
+                    mtime_ns,
+                    executable: is_executable(&metadata),
+                },
+            );
+        }
+        // Symlinks are neither followed nor recorded.
+    }
+    Ok(WorkspaceScan { files, dirs })
+}
+
+fn is_ignored_dir_name(name: Option<&str>) -> bool {
+    name.is_some_and(|name| {
+        DEFAULT_IGNORED_DIR_NAMES.contains(&name) || VCS_DIR_NAMES.contains(&name)
+    })
+}
+
+fn ensure_relative(path: &Path) -> Result<(), GitToolingError> {
+    if path.is_absolute() {
+        return Err(GitToolingError::NonRelativePath {
+            path: path.to_path_buf(),
+        });
+    }
+    if path
+        .components()
+        .any(|component| matches!(component, Component::ParentDir))
+    {
+        return Err(GitToolingError::PathEscapesRepository {
+            path: path.to_path_buf(),
+        });
+    }
+    Ok(())
+}
+
+/// Creates `relative` and its missing parents under `workspace`. A symlink
+/// found where a directory belongs is removed rather than followed.
+fn create_dir_all_no_follow(workspace: &Path, relative: &Path) -> Result<(), GitToolingError> {
+    let mut dir = workspace.to_path_buf();
+    for component in relative.components() {
+        dir.push(component);
+        match std::fs::symlink_metadata(&dir) {
+            Ok(metadata) if metadata.is_dir() => continue,
+            Ok(metadata) if metadata.file_type().is_symlink() => remove_symlink(&dir)?,
+            Ok(_) => {
+                return Err(GitToolingError::Io(std::io::Error::new(
+                    std::io::ErrorKind::AlreadyExists,
+                    format!("{} is not a directory", dir.display()),
+                )));
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+        std::fs::create_dir(&dir)?;
+    }
+    Ok(())
+}
+
+/// Removes the link itself. Windows directory symlinks need `remove_dir`.
+fn remove_symlink(path: &Path) -> Result<(), GitToolingError> {
+    if std::fs::remove_file(path).is_err() {
+        std::fs::remove_dir(path)?;
+    }
+    Ok(())
+}
+
+fn write_atomically(path: &Path, contents: &[u8]) -> Result<(), GitToolingError> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    tmp.write_all(contents)?;
+    tmp.persist(path).map_err(|err| err.error)?;
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> Result<(), GitToolingError> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &std::fs::Metadata) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path, executable: bool) -> Result<(), GitToolingError> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = std::fs::metadata(path)?.permissions();
+    let mode = permissions.mode();
+    let mode = if executable {
+        mode | ((mode & 0o444) >> 2)
+    } else {
+        mode & !0o111
+    };
+    permissions.set_mode(mode);
+    std::fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path, _executable: bool) -> Result<(), GitToolingError> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn store(codex_home: &Path, config: &GhostSnapshotConfig) -> FileSnapshotStore {
+        FileSnapshotStore::new(codex_home, "session", config)
+    }
+
+    #[test]
+    fn restore_reverts_edits_deletions_and_new_files() -> Result<(), GitToolingError> {
+        let codex_home = TempDir::new()?;
+        let workspace = TempDir::new()?;
+        let root = workspace.path();
+        std::fs::create_dir(root.join("src"))?;
+        std::fs::write(root.join("src/lib.rs"), "before\n")?;
+        std::fs::write(root.join("notes.txt"), "keep me\n")?;
+
+        let store = store(codex_home.path(), &GhostSnapshotConfig::default());
+        let (snapshot, report) = store.capture(root)?;
+        assert_eq!(report, GhostSnapshotReport::default());
+
+        std::fs::write(root.join("src/lib.rs"), "after\n")?;
+        std::fs::remove_file(root.join("notes.txt"))?;
+        std::fs::create_dir(root.join("generated"))?;
+        std::fs::write(root.join("generated/out.txt"), "new\n")?;
+
+        store.restore(&snapshot)?;
+        store.discard(&snapshot)?;
+
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/lib.rs"))?,
+            "before\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("notes.txt"))?,
+            "keep me\n"
+        );
+        assert!(!root.join("generated").exists());
+        assert_eq!(store.latest()?, None);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn restore_replaces_symlinks_instead_of_writing_through_them() -> Result<(), GitToolingError> {
+        let codex_home = TempDir::new()?;
+        let workspace = TempDir::new()?;
+        let outside = TempDir::new()?;
+        let root = workspace.path();
+        std::fs::create_dir(root.join("src"))?;
+        std::fs::write(root.join("src/lib.rs"), "lib\n")?;
+        std::fs::write(root.join("notes.txt"), "notes\n")?;
+
+        let store = store(codex_home.path(), &GhostSnapshotConfig::default());
+        let (snapshot, _) = store.capture(root)?;
+
+        std::fs::write(outside.path().join("secret.txt"), "secret\n")?;
+        std::fs::remove_file(root.join("notes.txt"))?;
+        std::os::unix::fs::symlink(outside.path().join("secret.txt"), root.join("notes.txt"))?;
+        std::fs::remove_dir_all(root.join("src"))?;
+        std::os::unix::fs::symlink(outside.path(), root.join("src"))?;
+
+        store.restore(&snapshot)?;
+
+        assert_eq!(
+            std::fs::read_to_string(outside.path().join("secret.txt"))?,
+            "secret\n"
+        );
+        assert!(!outside.path().join("lib.rs").exists());
+        assert!(!std::fs::symlink_metadata(root.join("notes.txt"))?.is_symlink());
+        assert_eq!(std::fs::read_to_string(root.join("notes.txt"))?, "notes\n");
+        assert!(std::fs::symlink_metadata(root.join("src"))?.is_dir());
+        assert_eq!(std::fs::read_to_string(root.join("src/lib.rs"))?, "lib\n");
+        Ok(())
+    }
+
+    #[test]
+    fn snapshots_stack_per_turn() -> Result<(), GitToolingError> {
+        let codex_home = TempDir::new()?;
+        let workspace = TempDir::new()?;
+        let root = workspace.path();
+        let file = root.join("state.txt");
+        let store = store(codex_home.path(), &GhostSnapshotConfig::default());
+
+        std::fs::write(&file, "one\n")?;
+        store.capture(root)?;
+        std::fs::write(&file, "two, longer\n")?;
+        store.capture(root)?;
+        std::fs::write(&file, "three, longest\n")?;
+
+        for expected in ["two, longer\n", "one\n"] {
+            let snapshot = store.latest()?.expect("snapshot");
+            store.restore(&snapshot)?;
+            store.discard(&snapshot)?;
+            assert_eq!(std::fs::read_to_string(&file)?, expected);
+        }
+        assert_eq!(store.latest()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn large_files_and_dependency_dirs_are_left_alone() -> Result<(), GitToolingError> {
+        let codex_home = TempDir::new()?;
+        let workspace = TempDir::new()?;
+        let root = workspace.path();
+        std::fs::write(root.join("big.bin"), "0123456789")?;
+        std::fs::create_dir(root.join("node_modules"))?;
+        std::fs::write(root.join("node_modules/dep.js"), "dep\n")?;
+
+        let config = GhostSnapshotConfig {
+            ignore_large_untracked_files: Some(4),
+            ..GhostSnapshotConfig::default()
+        };
+        let store = store(codex_home.path(), &config);
+        let (snapshot, report) = store.capture(root)?;
+        assert_eq!(
+            report.ignored_untracked_files,
+            vec![IgnoredUntrackedFile {
+                path: PathBuf::from("big.bin"),
+                byte_size: 10,
+            }]
+        );
+        assert_eq!(snapshot.files.len(), 1);
+        assert_eq!(snapshot.files[0].hash, None);
+
+        std::fs::write(root.join("big.bin"), "changed")?;
+        std::fs::write(root.join("node_modules/dep.js"), "changed\n")?;
+        store.restore(&snapshot)?;
+
+        assert_eq!(std::fs::read_to_string(root.join("big.bin"))?, "changed");
+        assert_eq!(
+            std::fs::read_to_string(root.join("node_modules/dep.js"))?,
+            "changed\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn garbage_collection_drops_oldest_snapshots_over_budget() -> Result<(), GitToolingError> {
+        let codex_home = TempDir::new()?;
+        let workspace = TempDir::new()?;
+        let root = workspace.path();
+        let config = GhostSnapshotConfig {
+            file_snapshot_max_bytes: Some(16),
+            ..GhostSnapshotConfig::default()
+        };
+        let store = store(codex_home.path(), &config);
+
+        std::fs::write(root.join("a.txt"), "first version\n")?;
+        let (first, _) = store.capture(root)?;
+        std::fs::write(root.join("a.txt"), "second version\n")?;
+        let (second, _) = store.capture(root)?;
+
+        assert_eq!(store.snapshot_ids()?, vec![second.id]);
+        assert!(first.id < second.id);
+        Ok(())
+    }
+
+    #[test]
+    fn garbage_collection_leaves_other_sessions_alone() -> Result<(), GitToolingError> {
+        let codex_home = TempDir::new()?;
+        let workspace = TempDir::new()?;
+        let root = workspace.path();
+        let config = GhostSnapshotConfig {
+            file_snapshot_max_bytes: Some(16),
+            ..GhostSnapshotConfig::default()
+        };
+        let other = FileSnapshotStore::new(codex_home.path(), "other", &config);
+        let store = store(codex_home.path(), &config);
+
+        std::fs::write(root.join("a.txt"), "other session\n")?;
+        let (kept, _) = other.capture(root)?;
+        std::fs::write(root.join("a.txt"), "first version\n")?;
+        store.capture(root)?;
+        std::fs::write(root.join("a.txt"), "second version\n")?;
+        let (second, _) = store.capture(root)?;
+
+        assert_eq!(store.snapshot_ids()?, vec![second.id]);
+        assert_eq!(other.snapshot_ids()?, vec![kept.id]);
+        let kept_hash = kept.files[0].hash.as_deref().expect("hash");
+        assert!(other.object_path(kept_hash).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn edits_within_mtime_granularity_are_hashed_again() -> Result<(), GitToolingError> {
+        let codex_home = TempDir::new()?;
+        let workspace = TempDir::new()?;
+        let root = workspace.path();
+        let file = root.join("state.txt");
+        let store = store(codex_home.path(), &GhostSnapshotConfig::default());
+
+        std::fs::write(&file, "one\n")?;
+        let (first, _) = store.capture(root)?;
+        // Same size and the same mtime, as on a filesystem with coarse times.
+        std::fs::write(&file, "two\n")?;
+        let modified =
+            UNIX_EPOCH + Duration::from_nanos(u64::try_from(first.files[0].mtime_ns).unwrap_or(0));
+        File::options()
+            .write(true)
+            .open(&file)?
+            .set_times(FileTimes::new().set_modified(modified))?;
+        let (second, _) = store.capture(root)?;
+
+        assert_ne!(first.files[0].hash, second.files[0].hash);
+        store.restore(&first)?;
+        assert_eq!(std::fs::read_to_string(&file)?, "one\n");
+        Ok(())
+    }
+
+    #[test]
+    fn restore_changes_nothing_when_an_object_is_missing() -> Result<(), GitToolingError> {
+        let codex_home = TempDir::new()?;
+        let workspace = TempDir::new()?;
+        let root = workspace.path();
+        std::fs::write(root.join("a.txt"), "a\n")?;
+        std::fs::write(root.join("b.txt"), "b\n")?;
+        let store = store(codex_home.path(), &GhostSnapshotConfig::default());
+        let (snapshot, _) = store.capture(root)?;
+
+        std::fs::write(root.join("a.txt"), "edited\n")?;
+        std::fs::write(root.join("new.txt"), "new\n")?;
+        let Some(hash) = snapshot.files[1].hash.as_deref() else {
+            panic!("b.txt should have been captured");
+        };
+        std::fs::remove_file(store.object_path(hash))?;
+
+        assert!(store.restore(&snapshot).is_err());
+        assert_eq!(std::fs::read_to_string(root.join("a.txt"))?, "edited\n");
+        assert!(root.join("new.txt").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn reused_objects_are_touched() -> Result<(), GitToolingError> {
+        let codex_home = TempDir::new()?;
+        let workspace = TempDir::new()?;
+        let root = workspace.path();
+        std::fs::write(root.join("state.txt"), "state\n")?;
+        let store = store(codex_home.path(), &GhostSnapshotConfig::default());
+        let (first, _) = store.capture(root)?;
+        let Some(hash) = first.files[0].hash.as_deref() else {
+            panic!("state.txt should have been captured");
+        };
+        let object = store.object_path(hash);
+        let stale = SystemTime::now() - 2 * GC_GRACE_PERIOD;
+        File::options()
+            .write(true)
+            .open(&object)?
+            .set_modified(stale)?;
+
+        store.capture(root)?;
+
+        let age = SystemTime::now()
+            .duration_since(std::fs::metadata(&object)?.modified()?)
+            .unwrap_or_default();
+        assert!(age < GC_GRACE_PERIOD);
+        Ok(())
+    }
+
+    #[test]
+    fn mtimes_close_to_the_snapshot_are_not_trusted() {
+        let second = 1_000_000_000;
+        assert!(mtime_settled(97 * second, 100));
+        assert!(!mtime_settled(98 * second, 100));
+        assert!(!mtime_settled(100 * second + 5, 100));
+    }
+}
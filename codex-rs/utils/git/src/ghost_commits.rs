@@ -27,12 +27,14 @@ const DEFAULT_COMMIT_MESSAGE: &str = "codex snapshot";
 const DEFAULT_IGNORE_LARGE_UNTRACKED_DIRS: i64 = 200;
 /// Default threshold (10 MiB) for excluding large untracked files from ghost snapshots.
 const DEFAULT_IGNORE_LARGE_UNTRACKED_FILES: i64 = 10 * 1024 * 1024;
+/// Default size limit (1 GiB) for the file snapshot store used outside git repositories.
+const DEFAULT_FILE_SNAPSHOT_MAX_BYTES: i64 = 1024 * 1024 * 1024;
 /// Directories that should always be ignored when capturing ghost snapshots,
 /// even if they are not listed in .gitignore.
 ///
 /// These are typically large dependency or build trees that are not useful
 /// for undo and can cause snapshots to grow without bound.
-const DEFAULT_IGNORED_DIR_NAMES: &[&str] = &[
+pub(crate) const DEFAULT_IGNORED_DIR_NAMES: &[&str] = &[
     "node_modules",
     ".venv",
     "venv",
@@ -66,6 +68,11 @@ pub struct GhostSnapshotConfig {
     pub ignore_large_untracked_files: Option<i64>,
     pub ignore_large_untracked_dirs: Option<i64>,
     pub disable_warnings: bool,
+    /// Snapshot workspaces that are not git repositories with a file store.
+    /// Off unless enabled, since it copies the workspace under CODEX_HOME.
+    pub file_snapshots: bool,
+    /// Size limit for the files one session keeps in the snapshot store.
+    pub file_snapshot_max_bytes: Option<i64>,
 }
 
 impl Default for GhostSnapshotConfig {
@@ -74,6 +81,8 @@ impl Default for GhostSnapshotConfig {
             ignore_large_untracked_files: Some(DEFAULT_IGNORE_LARGE_UNTRACKED_FILES),
             ignore_large_untracked_dirs: Some(DEFAULT_IGNORE_LARGE_UNTRACKED_DIRS),
             disable_warnings: false,
+            file_snapshots: false,
+            file_snapshot_max_bytes: Some(DEFAULT_FILE_SNAPSHOT_MAX_BYTES),
         }
     }
 }
@@ -1170,6 +1179,8 @@ mod tests {
             ignore_large_untracked_files: Some(DEFAULT_IGNORE_LARGE_UNTRACKED_FILES),
             ignore_large_untracked_dirs: Some(threshold),
             disable_warnings: false,
+            file_snapshots: true,
+            file_snapshot_max_bytes: Some(DEFAULT_FILE_SNAPSHOT_MAX_BYTES),
         };
         let (ghost, _report) = create_ghost_commit_with_report(
             &CreateGhostCommitOptions::new(repo).ghost_snapshot(snapshot_config),
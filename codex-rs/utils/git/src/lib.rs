@@ -3,7 +3,9 @@ use std::path::PathBuf;
 
 mod apply;
 mod branch;
+mod commit;
 mod errors;
+mod file_snapshots;
 mod ghost_commits;
 mod operations;
 mod platform;
@@ -14,8 +16,17 @@ pub use apply::apply_git_patch;
 pub use apply::extract_paths_from_patch;
 pub use apply::parse_git_apply_output;
 pub use apply::stage_paths;
+pub use branch::CommitSummary;
+pub use branch::commits_in_range;
 pub use branch::merge_base_with_head;
+pub use commit::ChangedFile;
+pub use commit::changed_files;
+pub use commit::create_commit;
+pub use commit::file_diff;
 pub use errors::GitToolingError;
+pub use file_snapshots::FileSnapshot;
+pub use file_snapshots::FileSnapshotEntry;
+pub use file_snapshots::FileSnapshotStore;
 pub use ghost_commits::CreateGhostCommitOptions;
 pub use ghost_commits::GhostSnapshotConfig;
 pub use ghost_commits::GhostSnapshotReport;
@@ -0,0 +1,232 @@
+//! Token counting for context accounting and truncation.
+//!
+//! The BPE vocabularies for OpenAI model families ship inside the binary, so
+//! counting never touches the network. Models without a known vocabulary fall
+//! back to a bytes-per-token estimate.
+
+use std::sync::OnceLock;
+
+use tiktoken_rs::CoreBPE;
+
+/// Bytes per token assumed by [`Tokenizer::Approximate`].
+pub const APPROX_BYTES_PER_TOKEN: usize = 4;
+
+/// Largest average token length, in bytes, considered when searching for a
+/// prefix or suffix that fits a token budget. Real text averages well under
+/// this, so it only bounds how much of a huge input gets tokenized.
+const MAX_SEARCH_BYTES_PER_TOKEN: usize = 32;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Tokenizer {
+    /// `o200k_base`: GPT-4o, GPT-4.1, GPT-5, the o-series and gpt-oss.
+    O200kBase,
+    /// `cl100k_base`: GPT-4 and GPT-3.5.
+    Cl100kBase,
+    /// Bytes-per-token estimate for models without a bundled vocabulary.
+    #[default]
+    Approximate,
+}
+
+impl Tokenizer {
+    /// Tokenizer for `model`, keyed on its family. Provider prefixes such as
+    /// `openai/` are ignored.
+    pub fn for_model(model: &str) -> Self {
+        let model = model
+            .rsplit('/')
+            .next()
+            .unwrap_or(model)
+            .to_ascii_lowercase();
+        const O200K_PREFIXES: &[&str] = &[
+            "gpt-5",
+            "gpt-4.1",
+            "gpt-4.5",
+            "gpt-4o",
+            "chatgpt-4o",
+            "gpt-oss",
+            "codex-",
+            "o1",
+            "o3",
+            "o4",
+        ];
+        const CL100K_PREFIXES: &[&str] = &["gpt-4", "gpt-3.5", "text-embedding-"];
+        if O200K_PREFIXES
+            .iter()
+            .any(|prefix| model.starts_with(prefix))
+        {
+            Self::O200kBase
+        } else if CL100K_PREFIXES
+            .iter()
+            .any(|prefix| model.starts_with(prefix))
+        {
+            Self::Cl100kBase
+        } else {
+            Self::Approximate
+        }
+    }
+
+    /// Whether counts come from a real vocabulary rather than the estimate.
+    pub fn is_exact(self) -> bool {
+        self.bpe().is_some()
+    }
+
+    pub fn count(self, text: &str) -> usize {
+        match self.bpe() {
+            Some(bpe) => bpe.encode_ordinary(text).len(),
+            None => approx_token_count(text),
+        }
+    }
+
+    /// Byte length of the longest prefix of `text` that ends on a char
+    /// boundary and fits in `max_tokens`.
+    pub fn prefix_len(self, text: &str, max_tokens: usize) -> usize {
+        let Some(bpe) = self.bpe() else {
+            return floor_char_boundary(text, approx_bytes_for_tokens(max_tokens));
+        };
+        // Every token covers at least one byte.
+        if text.len() <= max_tokens {
+            return text.len();
+        }
+
+        let mut fits = 0;
+        let mut too_long =
+            floor_char_boundary(text, max_tokens.saturating_mul(MAX_SEARCH_BYTES_PER_TOKEN));
+        if bpe.encode_ordinary(&text[..too_long]).len() <= max_tokens {
+            return too_long;
+        }
+        while let Some(mid) = char_boundary_between(text, fits, too_long) {
+            if bpe.encode_ordinary(&text[..mid]).len() <= max_tokens {
+                fits = mid;
+            } else {
+                too_long = mid;
+            }
+        }
+        fits
+    }
+
+    /// Byte offset where the longest suffix of `text` that starts on a char
+    /// boundary and fits in `max_tokens` begins.
+    pub fn suffix_start(self, text: &str, max_tokens: usize) -> usize {
+        let len = text.len();
+        let Some(bpe) = self.bpe() else {
+            return ceil_char_boundary(
+                text,
+                len.saturating_sub(approx_bytes_for_tokens(max_tokens)),
+            );
+        };
+        if len <= max_tokens {
+            return 0;
+        }
+
+        let mut fits = len;
+        let mut too_long = ceil_char_boundary(
+            text,
+            len.saturating_sub(max_tokens.saturating_mul(MAX_SEARCH_BYTES_PER_TOKEN)),
+        );
+        if bpe.encode_ordinary(&text[too_long..]).len() <= max_tokens {
+            return too_long;
+        }
+        while let Some(mid) = char_boundary_between(text, too_long, fits) {
+            if bpe.encode_ordinary(&text[mid..]).len() <= max_tokens {
+                fits = mid;
+            } else {
+                too_long = mid;
+            }
+        }
+        fits
+    }
+
+    fn bpe(self) -> Option<&'static CoreBPE> {
+        static O200K_BASE: OnceLock<Option<CoreBPE>> = OnceLock::new();
+        static CL100K_BASE: OnceLock<Option<CoreBPE>> = OnceLock::new();
+        match self {
+            Self::O200kBase => O200K_BASE
+                .get_or_init(|| tiktoken_rs::o200k_base().ok())
+                .as_ref(),
+            Self::Cl100kBase => CL100K_BASE
+                .get_or_init(|| tiktoken_rs::cl100k_base().ok())
+                .as_ref(),
+            Self::Approximate => None,
+        }
+    }
+}
+
+pub fn approx_token_count(text: &str) -> usize {
+    let len = text.len();
+    len.saturating_add(APPROX_BYTES_PER_TOKEN.saturating_sub(1)) / APPROX_BYTES_PER_TOKEN
+}
+
+pub fn approx_bytes_for_tokens(tokens: usize) -> usize {
+    tokens.saturating_mul(APPROX_BYTES_PER_TOKEN)
+}
+
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+/// A char boundary strictly between `low` and `high`, near the middle.
+fn char_boundary_between(text: &str, low: usize, high: usize) -> Option<usize> {
+    let mid = floor_char_boundary(text, low + (high - low) / 2);
+    if mid > low {
+        return Some(mid);
+    }
+    let mid = ceil_char_boundary(text, low + 1);
+    (mid < high).then_some(mid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn for_model_picks_the_family_vocabulary() {
+        assert_eq!(
+            Tokenizer::for_model("gpt-5.1-codex-max"),
+            Tokenizer::O200kBase
+        );
+        assert_eq!(
+            Tokenizer::for_model("openai/gpt-oss-120b"),
+            Tokenizer::O200kBase
+        );
+        assert_eq!(Tokenizer::for_model("o4-mini"), Tokenizer::O200kBase);
+        assert_eq!(Tokenizer::for_model("gpt-4-turbo"), Tokenizer::Cl100kBase);
+        assert_eq!(Tokenizer::for_model("qwen3-coder"), Tokenizer::Approximate);
+    }
+
+    #[test]
+    fn counts_with_the_bundled_vocabulary() {
+        assert_eq!(Tokenizer::O200kBase.count("hello world"), 2);
+        assert_eq!(Tokenizer::Approximate.count("hello world"), 3);
+        assert!(Tokenizer::O200kBase.is_exact());
+        assert!(!Tokenizer::Approximate.is_exact());
+    }
+
+    #[test]
+    fn prefix_and_suffix_fit_the_budget_on_char_boundaries() {
+        let text = "数据库连接失败 error: connection refused\n".repeat(50);
+        for tokenizer in [Tokenizer::O200kBase, Tokenizer::Approximate] {
+            let prefix_len = tokenizer.prefix_len(&text, 40);
+            let suffix_start = tokenizer.suffix_start(&text, 40);
+
+            assert!(text.is_char_boundary(prefix_len));
+            assert!(text.is_char_boundary(suffix_start));
+            assert!(tokenizer.count(&text[..prefix_len]) <= 40);
+            assert!(tokenizer.count(&text[suffix_start..]) <= 40);
+            assert!(prefix_len > 0 && suffix_start < text.len());
+        }
+        assert_eq!(Tokenizer::O200kBase.prefix_len("short", 40), 5);
+        assert_eq!(Tokenizer::O200kBase.suffix_start("short", 40), 0);
+    }
+}